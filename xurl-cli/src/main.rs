@@ -1,25 +1,207 @@
+mod mcp;
+mod serve;
+
+use std::io::{self, Write};
 use std::path::{Path, PathBuf};
 use std::process::ExitCode;
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
-use clap::Parser;
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use serde::Serialize;
+use xurl_core::time::parse_rfc3339_epoch;
 use xurl_core::{
-    ProviderRoots, ThreadUri, XurlError, render_subagent_view_markdown,
-    render_thread_head_markdown, render_thread_markdown, resolve_subagent_view, resolve_thread,
+    FollowEvent, MessageRole, ProviderKind, ProviderRoots, SearchIndex, SearchQuery,
+    SearchResultsView, SessionListQuery, ThreadFollower, ThreadUri, ThreadView, TimelineOptions,
+    UriFormat, XurlError, default_index_path, index_status, list_running_agents, list_sessions,
+    render_entry_markdown, render_index_status_markdown, render_running_agents_markdown,
+    render_search_markdown, render_session_list_markdown, render_subagent_view_markdown,
+    render_thread_diff, render_thread_head_markdown, render_thread_html, render_thread_markdown,
+    render_tool_call_markdown, render_touched_files_markdown, resolve_subagent_view,
+    resolve_thread, resolve_thread_diff, resolve_thread_head, resolve_thread_view,
+    resolve_touched_files, resolve_uri, search_threads,
 };
 
+const FOLLOW_POLL_INTERVAL: Duration = Duration::from_millis(500);
+/// How stale the search index may get before `xurl search` refreshes it.
+const INDEX_MAX_AGE: Duration = Duration::from_secs(5 * 60);
+
 #[derive(Debug, Parser)]
-#[command(name = "xurl", version, about = "Resolve and read code-agent threads")]
+#[command(
+    name = "xurl",
+    version,
+    about = "Resolve and read code-agent threads",
+    args_conflicts_with_subcommands = true,
+    subcommand_negates_reqs = true
+)]
 struct Cli {
-    /// Thread URI like agents://codex/<session_id>, agents://claude/<session_id>, agents://pi/<session_id>/<entry_id>, or legacy forms like codex://<session_id>
-    uri: String,
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    /// Thread URI like agents://codex/<session_id>, agents://claude/latest?cwd=., agents://pi/<session_id>/<entry_id>, legacy forms like codex://<session_id>, or a bare session id
+    #[arg(required = true)]
+    uri: Option<String>,
 
     /// Output frontmatter only (header mode)
     #[arg(short = 'I', long)]
     head: bool,
 
+    /// Keep printing new entries and tool calls as the thread grows (Ctrl-C to stop)
+    #[arg(short = 'f', long, conflicts_with = "head")]
+    follow: bool,
+
+    /// Show tool calls and their results inline in the timeline
+    #[arg(long, conflicts_with = "follow")]
+    tools: bool,
+
+    /// Truncate each tool output to this many characters (with --tools) [default: 2000]
+    #[arg(long, value_name = "CHARS", conflicts_with = "follow")]
+    max_tool_output: Option<usize>,
+
+    /// Only show messages from this role: user or assistant
+    #[arg(long, value_name = "ROLE", value_parser = parse_role, conflicts_with = "follow")]
+    role: Option<MessageRole>,
+
+    /// Leave reasoning out of message text
+    #[arg(long, conflicts_with = "follow")]
+    no_thinking: bool,
+
     /// Write output to a file instead of stdout
-    #[arg(short = 'o', long = "output", value_name = "PATH")]
+    #[arg(short = 'o', long = "output", value_name = "PATH", global = true)]
     output: Option<PathBuf>,
+
+    /// Output format; `jsonl` emits one JSON record per timeline entry or list item [default: markdown]
+    #[arg(long, value_enum, global = true)]
+    format: Option<OutputFormat>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+enum OutputFormat {
+    #[default]
+    Markdown,
+    Json,
+    Jsonl,
+    /// Self-contained HTML page; only when reading a thread
+    Html,
+}
+
+impl From<UriFormat> for OutputFormat {
+    fn from(format: UriFormat) -> Self {
+        match format {
+            UriFormat::Markdown => Self::Markdown,
+            UriFormat::Json => Self::Json,
+            UriFormat::Jsonl => Self::Jsonl,
+            UriFormat::Html => Self::Html,
+        }
+    }
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// List threads across providers, newest first
+    Ls(LsArgs),
+    /// List files the agent read, created, modified or deleted in a thread
+    Files(ThreadArgs),
+    /// Reconstruct the edits made in a thread as a unified diff
+    Diff(ThreadArgs),
+    /// Search message text across all local threads
+    Search(SearchArgs),
+    /// Manage the optional on-disk search index
+    #[command(subcommand)]
+    Index(IndexCommand),
+    /// Run a Model Context Protocol server over stdio
+    Mcp,
+    /// Serve threads over HTTP as HTML, markdown or JSON
+    Serve(ServeArgs),
+    /// List running agent processes and the threads they are writing
+    Ps(PsArgs),
+}
+
+#[derive(Debug, Args)]
+struct PsArgs {
+    /// Flag threads updated within this window that stopped mid-turn with no process, e.g. 30m, 2h
+    #[arg(long, value_name = "DURATION", value_parser = parse_duration_secs, default_value = "30m")]
+    stale_within: u64,
+}
+
+#[derive(Debug, Args)]
+struct ServeArgs {
+    /// Address to listen on; use port 0 to pick a free port
+    #[arg(long, value_name = "ADDR", default_value = "127.0.0.1:8787")]
+    bind: String,
+    /// Allow a non-loopback --bind; anyone who can reach it can read every thread
+    #[arg(long)]
+    allow_remote: bool,
+}
+
+#[derive(Debug, Subcommand)]
+enum IndexCommand {
+    /// Build the search index from scratch
+    Rebuild,
+    /// Index new and changed threads now
+    Refresh,
+    /// Show where the index lives, what it holds and how many threads are pending
+    Status,
+}
+
+#[derive(Debug, Args)]
+struct LsArgs {
+    /// Only list threads from this provider (repeatable)
+    #[arg(short = 'p', long = "provider", value_name = "PROVIDER", value_parser = parse_provider)]
+    providers: Vec<ProviderKind>,
+
+    /// Only list threads updated within this window, e.g. 30m, 12h, 7d, 2w
+    #[arg(long, value_name = "DURATION", value_parser = parse_duration_secs)]
+    since: Option<u64>,
+
+    /// Only list threads whose working directory is inside this directory
+    #[arg(long, value_name = "DIR")]
+    cwd: Option<PathBuf>,
+
+    /// Maximum number of threads to list
+    #[arg(short = 'n', long, value_name = "N")]
+    limit: Option<usize>,
+}
+
+#[derive(Debug, Args)]
+struct ThreadArgs {
+    /// Thread URI or bare session id, as accepted by the top-level command
+    uri: String,
+}
+
+#[derive(Debug, Args)]
+struct SearchArgs {
+    /// Terms that must all appear in one message; wrap phrases in double quotes
+    #[arg(required = true, value_name = "QUERY")]
+    query: Vec<String>,
+
+    /// Only search threads from this provider (repeatable)
+    #[arg(short = 'p', long = "provider", value_name = "PROVIDER", value_parser = parse_provider)]
+    providers: Vec<ProviderKind>,
+
+    /// Only match messages from this role: user or assistant (repeatable)
+    #[arg(long = "role", value_name = "ROLE", value_parser = parse_role)]
+    roles: Vec<MessageRole>,
+
+    /// Only search threads updated after this point: a window like 7d or a date like 2026-01-31
+    #[arg(long, value_name = "WHEN", value_parser = parse_time_bound)]
+    since: Option<u64>,
+
+    /// Only search threads started before this point: a window like 7d or a date like 2026-01-31
+    #[arg(long, value_name = "WHEN", value_parser = parse_time_bound)]
+    until: Option<u64>,
+
+    /// Only search threads whose working directory is inside this directory
+    #[arg(long, value_name = "DIR")]
+    cwd: Option<PathBuf>,
+
+    /// Maximum number of matching messages
+    #[arg(short = 'n', long, value_name = "N")]
+    limit: Option<usize>,
+
+    /// Scan every thread even when a search index exists
+    #[arg(long)]
+    no_index: bool,
 }
 
 fn main() -> ExitCode {
@@ -35,34 +217,522 @@ fn main() -> ExitCode {
 }
 
 fn run(cli: Cli) -> xurl_core::Result<()> {
-    let Cli { uri, head, output } = cli;
+    let Cli {
+        command,
+        uri,
+        head,
+        follow,
+        tools,
+        max_tool_output,
+        role,
+        no_thinking,
+        output,
+        format: explicit_format,
+    } = cli;
     let roots = ProviderRoots::from_env_or_home()?;
-    let uri = ThreadUri::parse(&uri)?;
-
     let output = output.as_deref();
+    let format = explicit_format.unwrap_or_default();
+
+    match command {
+        Some(Command::Ls(args)) => return run_ls(args, &roots, output, format),
+        Some(Command::Files(args)) => return run_files(args, &roots, output, format),
+        Some(Command::Diff(args)) => return run_diff(args, &roots, output, format),
+        Some(Command::Search(args)) => return run_search(args, &roots, output, format),
+        Some(Command::Index(command)) => return run_index(command, &roots, output, format),
+        Some(Command::Mcp) => return mcp::serve(&roots),
+        Some(Command::Serve(args)) => return serve::serve(&args.bind, args.allow_remote, roots),
+        Some(Command::Ps(args)) => return run_ps(args, &roots, output, format),
+        None => {}
+    }
+
+    let uri = resolve_target(uri.as_deref().unwrap_or_default(), &roots)?;
+    // Flags win over the URI query, which wins over defaults.
+    let format = explicit_format
+        .or(uri.query.format.map(OutputFormat::from))
+        .unwrap_or_default();
+    let head = head || uri.query.head.unwrap_or(false);
+
+    if follow {
+        return run_follow(&uri, &roots, output, format);
+    }
+
+    let mut options = uri.query.timeline_options(TimelineOptions::default());
+    options.tools |= tools;
+    if let Some(max_tool_output) = max_tool_output {
+        if !options.tools {
+            return Err(XurlError::InvalidMode(
+                "--max-tool-output requires --tools or a `tools=1` URI query".to_string(),
+            ));
+        }
+        options.max_tool_output = max_tool_output;
+    }
+    if role.is_some() {
+        options.role = role;
+    }
+    if no_thinking {
+        options.thinking = false;
+    }
+
+    match (format, head) {
+        (OutputFormat::Markdown, _) => {}
+        (OutputFormat::Json, true) => {
+            let head = resolve_thread_head(&uri, &roots)?;
+            return write_output(output, &json_document(&head)?);
+        }
+        (OutputFormat::Jsonl, true) => {
+            let head = resolve_thread_head(&uri, &roots)?;
+            return write_output(output, &json_lines([&head])?);
+        }
+        (OutputFormat::Json, false) => {
+            let view = resolve_thread_view(&uri, &roots, &options)?;
+            return write_output(output, &json_document(&view)?);
+        }
+        (OutputFormat::Jsonl, false) => {
+            let view = resolve_thread_view(&uri, &roots, &options)?;
+            let content = match &view.timeline {
+                Some(timeline) => json_lines(timeline)?,
+                None => json_lines([&view])?,
+            };
+            return write_output(output, &content);
+        }
+        (OutputFormat::Html, true) => {
+            let view = ThreadView {
+                head: resolve_thread_head(&uri, &roots)?,
+                timeline: None,
+                subagent: None,
+            };
+            return write_output(output, &render_thread_html(&view));
+        }
+        (OutputFormat::Html, false) => {
+            let view = resolve_thread_view(&uri, &roots, &options)?;
+            return write_output(output, &render_thread_html(&view));
+        }
+    }
 
     if head {
         let head = render_thread_head_markdown(&uri, &roots)?;
         return write_output(output, &head);
     }
 
-    let markdown = if matches!(
+    let markdown = render_thread_document(&uri, &roots, &options)?;
+    write_output(output, &markdown)
+}
+
+/// Frontmatter plus body, as printed by `xurl <uri>`. A `?head=1` query
+/// stops after the frontmatter.
+fn render_thread_document(
+    uri: &ThreadUri,
+    roots: &ProviderRoots,
+    options: &TimelineOptions,
+) -> xurl_core::Result<String> {
+    let head = render_thread_head_markdown(uri, roots)?;
+    if uri.query.head == Some(true) {
+        return Ok(head);
+    }
+    let body = if matches!(
         uri.provider,
         xurl_core::ProviderKind::Codex | xurl_core::ProviderKind::Claude
     ) && uri.agent_id.is_some()
     {
-        let head = render_thread_head_markdown(&uri, &roots)?;
-        let view = resolve_subagent_view(&uri, &roots, false)?;
-        let body = render_subagent_view_markdown(&view);
-        format!("{head}\n{body}")
+        let view = resolve_subagent_view(uri, roots, false)?;
+        render_subagent_view_markdown(&view)
     } else {
-        let head = render_thread_head_markdown(&uri, &roots)?;
-        let resolved = resolve_thread(&uri, &roots)?;
-        let body = render_thread_markdown(&uri, &resolved)?;
-        format!("{head}\n{body}")
+        let resolved = resolve_thread(uri, roots)?;
+        render_thread_markdown(uri, &resolved, options)?
     };
+    Ok(format!("{head}\n{body}"))
+}
 
-    write_output(output, &markdown)
+fn run_ls(
+    args: LsArgs,
+    roots: &ProviderRoots,
+    output: Option<&Path>,
+    format: OutputFormat,
+) -> xurl_core::Result<()> {
+    let updated_after = args.since.map(|window| {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|now| now.as_secs())
+            .unwrap_or_default()
+            .saturating_sub(window)
+    });
+    let cwd = args
+        .cwd
+        .map(|cwd| std::fs::canonicalize(&cwd).unwrap_or(cwd));
+
+    let view = list_sessions(
+        roots,
+        &SessionListQuery {
+            providers: args.providers,
+            updated_after,
+            cwd,
+            limit: args.limit,
+        },
+    );
+    for warning in &view.warnings {
+        eprintln!("warning: {warning}");
+    }
+
+    let content = match format {
+        OutputFormat::Markdown => render_session_list_markdown(&view),
+        OutputFormat::Json => json_document(&view)?,
+        OutputFormat::Jsonl => json_lines(&view.sessions)?,
+        OutputFormat::Html => return Err(html_unsupported()),
+    };
+    write_output(output, &content)
+}
+
+fn run_search(
+    args: SearchArgs,
+    roots: &ProviderRoots,
+    output: Option<&Path>,
+    format: OutputFormat,
+) -> xurl_core::Result<()> {
+    let cwd = args
+        .cwd
+        .map(|cwd| std::fs::canonicalize(&cwd).unwrap_or(cwd));
+
+    let query = SearchQuery {
+        text: args.query.join(" "),
+        providers: args.providers,
+        roles: args.roles,
+        updated_after: args.since,
+        started_before: args.until,
+        cwd,
+        limit: args.limit,
+    };
+    let view = if args.no_index {
+        None
+    } else {
+        search_index(roots, &query)
+    }
+    .unwrap_or_else(|| search_threads(roots, &query));
+    for warning in &view.warnings {
+        eprintln!("warning: {warning}");
+    }
+
+    let content = match format {
+        OutputFormat::Markdown => render_search_markdown(&view),
+        OutputFormat::Json => json_document(&view)?,
+        OutputFormat::Jsonl => json_lines(&view.hits)?,
+        OutputFormat::Html => return Err(html_unsupported()),
+    };
+    write_output(output, &content)
+}
+
+/// Search through the index when one exists, refreshing it first if the
+/// last refresh is older than [`INDEX_MAX_AGE`].
+/// Returns `None` to fall back to scanning.
+fn search_index(roots: &ProviderRoots, query: &SearchQuery) -> Option<SearchResultsView> {
+    let path = default_index_path().ok().filter(|path| path.exists())?;
+    let result = SearchIndex::open(path).and_then(|mut index| {
+        let refresh = index.refresh_if_stale(roots, INDEX_MAX_AGE)?;
+        let mut view = index.search(query)?;
+        view.warnings = refresh.map(|refresh| refresh.warnings).unwrap_or_default();
+        Ok(view)
+    });
+
+    match result {
+        Ok(view) => Some(view),
+        Err(err) => {
+            eprintln!("warning: search index unavailable, scanning instead: {err}");
+            None
+        }
+    }
+}
+
+fn run_index(
+    command: IndexCommand,
+    roots: &ProviderRoots,
+    output: Option<&Path>,
+    format: OutputFormat,
+) -> xurl_core::Result<()> {
+    let path = default_index_path()?;
+    let status = match command {
+        IndexCommand::Rebuild => {
+            let (index, refresh) = SearchIndex::rebuild(&path, roots)?;
+            for warning in &refresh.warnings {
+                eprintln!("warning: {warning}");
+            }
+            index.status(roots)?
+        }
+        IndexCommand::Refresh => {
+            let mut index = SearchIndex::open(&path)?;
+            for warning in &index.refresh(roots)?.warnings {
+                eprintln!("warning: {warning}");
+            }
+            index.status(roots)?
+        }
+        IndexCommand::Status => index_status(&path, roots)?,
+    };
+
+    let content = match format {
+        OutputFormat::Markdown => render_index_status_markdown(&status),
+        OutputFormat::Json => json_document(&status)?,
+        OutputFormat::Jsonl => json_lines(&status.providers)?,
+        OutputFormat::Html => return Err(html_unsupported()),
+    };
+    write_output(output, &content)
+}
+
+fn run_ps(
+    args: PsArgs,
+    roots: &ProviderRoots,
+    output: Option<&Path>,
+    format: OutputFormat,
+) -> xurl_core::Result<()> {
+    let view = list_running_agents(roots, Duration::from_secs(args.stale_within));
+    for warning in &view.warnings {
+        eprintln!("warning: {warning}");
+    }
+
+    let content = match format {
+        OutputFormat::Markdown => render_running_agents_markdown(&view),
+        OutputFormat::Json => json_document(&view)?,
+        OutputFormat::Jsonl => json_lines(&view.agents)? + &json_lines(&view.stale)?,
+        OutputFormat::Html => return Err(html_unsupported()),
+    };
+    write_output(output, &content)
+}
+
+fn run_files(
+    args: ThreadArgs,
+    roots: &ProviderRoots,
+    output: Option<&Path>,
+    format: OutputFormat,
+) -> xurl_core::Result<()> {
+    let uri = resolve_target(&args.uri, roots)?;
+    let view = resolve_touched_files(&uri, roots)?;
+
+    let content = match format {
+        OutputFormat::Markdown => render_touched_files_markdown(&view),
+        OutputFormat::Json => json_document(&view)?,
+        OutputFormat::Jsonl => json_lines(&view.files)?,
+        OutputFormat::Html => return Err(html_unsupported()),
+    };
+    write_output(output, &content)
+}
+
+fn run_diff(
+    args: ThreadArgs,
+    roots: &ProviderRoots,
+    output: Option<&Path>,
+    format: OutputFormat,
+) -> xurl_core::Result<()> {
+    let uri = resolve_target(&args.uri, roots)?;
+    let view = resolve_thread_diff(&uri, roots)?;
+
+    // The patch is already readable text; markdown output is the patch itself
+    // so it can be piped straight into `git apply`.
+    let content = match format {
+        OutputFormat::Markdown => render_thread_diff(&view),
+        OutputFormat::Json => json_document(&view)?,
+        OutputFormat::Jsonl => json_lines(&view.files)?,
+        OutputFormat::Html => return Err(html_unsupported()),
+    };
+    write_output(output, &content)
+}
+
+fn run_follow(
+    uri: &ThreadUri,
+    roots: &ProviderRoots,
+    output: Option<&Path>,
+    format: OutputFormat,
+) -> xurl_core::Result<()> {
+    if matches!(format, OutputFormat::Json | OutputFormat::Html) {
+        return Err(XurlError::InvalidMode(
+            "--follow streams one record at a time; use --format markdown or jsonl".to_string(),
+        ));
+    }
+    // Like the flags they mirror, these query keys conflict with --follow.
+    let query = &uri.query;
+    let view_keys = [
+        ("head", query.head.is_some()),
+        ("max_tool_output", query.max_tool_output.is_some()),
+        ("roles", query.role.is_some()),
+        ("thinking", query.thinking.is_some()),
+        ("tools", query.tools.is_some()),
+    ];
+    if let Some((key, _)) = view_keys.iter().find(|(_, set)| *set) {
+        return Err(XurlError::InvalidMode(format!(
+            "the URI query key `{key}` cannot be used with --follow"
+        )));
+    }
+
+    let (mut follower, entries) = ThreadFollower::start(uri, roots)?;
+    let mut sink = FollowSink::open(output)?;
+
+    let initial = match format {
+        OutputFormat::Markdown => {
+            let head = render_thread_head_markdown(uri, roots)?;
+            let mut content = format!("{head}\n# Thread\n\n## Timeline\n\n");
+            for entry in &entries {
+                content.push_str(&render_entry_markdown(entry));
+            }
+            content
+        }
+        _ => json_lines(
+            &entries
+                .into_iter()
+                .map(FollowEvent::Entry)
+                .collect::<Vec<_>>(),
+        )?,
+    };
+    if !sink.write(&initial)? {
+        return Ok(());
+    }
+
+    loop {
+        thread::sleep(FOLLOW_POLL_INTERVAL);
+        let events = follower.poll()?;
+        if events.is_empty() {
+            continue;
+        }
+
+        let content = match format {
+            OutputFormat::Markdown => events
+                .iter()
+                .map(|event| match event {
+                    FollowEvent::Entry(entry) => render_entry_markdown(entry),
+                    FollowEvent::ToolCall(call) => render_tool_call_markdown(call),
+                })
+                .collect(),
+            _ => json_lines(&events)?,
+        };
+        if !sink.write(&content)? {
+            return Ok(());
+        }
+    }
+}
+
+/// Destination for follow output, flushed after every batch.
+enum FollowSink {
+    Stdout(io::Stdout),
+    File(PathBuf, std::fs::File),
+}
+
+impl FollowSink {
+    fn open(path: Option<&Path>) -> xurl_core::Result<Self> {
+        let Some(path) = path else {
+            return Ok(Self::Stdout(io::stdout()));
+        };
+        let file = std::fs::File::create(path).map_err(|source| XurlError::Io {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        Ok(Self::File(path.to_path_buf(), file))
+    }
+
+    /// Write and flush; returns `false` once the reader has gone away.
+    fn write(&mut self, content: &str) -> xurl_core::Result<bool> {
+        let (path, result) = match self {
+            Self::Stdout(stdout) => {
+                let mut lock = stdout.lock();
+                let result = lock
+                    .write_all(content.as_bytes())
+                    .and_then(|()| lock.flush());
+                (PathBuf::from("<stdout>"), result)
+            }
+            Self::File(path, file) => (
+                path.clone(),
+                file.write_all(content.as_bytes())
+                    .and_then(|()| file.flush()),
+            ),
+        };
+
+        match result {
+            Ok(()) => Ok(true),
+            Err(err) if err.kind() == io::ErrorKind::BrokenPipe => Ok(false),
+            Err(source) => Err(XurlError::Io { path, source }),
+        }
+    }
+}
+
+/// [`resolve_uri`] with the stores that failed to read reported on stderr.
+fn resolve_target(input: &str, roots: &ProviderRoots) -> xurl_core::Result<ThreadUri> {
+    let mut warnings = Vec::new();
+    let uri = resolve_uri(input, roots, &mut warnings);
+    for warning in &warnings {
+        eprintln!("warning: {warning}");
+    }
+    uri
+}
+
+fn html_unsupported() -> XurlError {
+    XurlError::InvalidMode("--format html is only supported when reading a thread".to_string())
+}
+
+fn json_document<T: Serialize>(value: &T) -> xurl_core::Result<String> {
+    let mut encoded = serde_json::to_string_pretty(value)
+        .map_err(|err| XurlError::Serialization(err.to_string()))?;
+    encoded.push('\n');
+    Ok(encoded)
+}
+
+fn json_lines<'a, T: Serialize + 'a>(
+    records: impl IntoIterator<Item = &'a T>,
+) -> xurl_core::Result<String> {
+    let mut output = String::new();
+    for record in records {
+        let encoded = serde_json::to_string(record)
+            .map_err(|err| XurlError::Serialization(err.to_string()))?;
+        output.push_str(&encoded);
+        output.push('\n');
+    }
+    Ok(output)
+}
+
+fn parse_provider(value: &str) -> Result<ProviderKind, String> {
+    value
+        .parse()
+        .map_err(|_| format!("unknown provider `{value}`"))
+}
+
+fn parse_role(value: &str) -> Result<MessageRole, String> {
+    match value {
+        "user" => Ok(MessageRole::User),
+        "assistant" => Ok(MessageRole::Assistant),
+        _ => Err(format!(
+            "unknown role `{value}`; expected user or assistant"
+        )),
+    }
+}
+
+/// Accept either a date/timestamp or a window back from now.
+fn parse_time_bound(value: &str) -> Result<u64, String> {
+    if let Some(epoch) = parse_rfc3339_epoch(value) {
+        return Ok(epoch);
+    }
+    let window = parse_duration_secs(value)?;
+    Ok(SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|now| now.as_secs())
+        .unwrap_or_default()
+        .saturating_sub(window))
+}
+
+fn parse_duration_secs(value: &str) -> Result<u64, String> {
+    let value = value.trim();
+    let split = value
+        .find(|ch: char| !ch.is_ascii_digit())
+        .unwrap_or(value.len());
+    let (amount, unit) = value.split_at(split);
+    let amount = amount
+        .parse::<u64>()
+        .map_err(|_| format!("invalid duration `{value}`; expected e.g. 30m, 12h, 7d"))?;
+    let scale = match unit {
+        "s" => 1,
+        "m" => 60,
+        "h" => 3600,
+        "d" => 86_400,
+        "w" => 604_800,
+        _ => {
+            return Err(format!(
+                "invalid duration unit `{unit}`; expected one of s, m, h, d, w"
+            ));
+        }
+    };
+    Ok(amount.saturating_mul(scale))
 }
 
 fn write_output(path: Option<&Path>, content: &str) -> xurl_core::Result<()> {
@@ -0,0 +1,307 @@
+//! `xurl mcp`: a Model Context Protocol server over stdio.
+//!
+//! Messages are newline-delimited JSON-RPC 2.0. Threads are exposed as
+//! `agents://` resources, and a few tools return the same markdown the CLI
+//! prints, so agents can read each other's threads without shelling out.
+
+use std::cmp::Reverse;
+use std::io::{self, BufRead, Write};
+use std::path::PathBuf;
+use std::time::Duration;
+
+use serde_json::{Value, json};
+use xurl_core::{
+    ProviderKind, ProviderRoots, SessionListQuery, ThreadUri, TimelineOptions, XurlError,
+    list_session_summaries, render_subagent_view_markdown, render_thread_head_markdown,
+    resolve_subagent_view,
+};
+
+use crate::{render_thread_document, resolve_target};
+
+/// Protocol revisions this server speaks, newest first.
+const PROTOCOL_VERSIONS: [&str; 3] = ["2025-06-18", "2025-03-26", "2024-11-05"];
+/// Default window for `list_active_sessions`, matching process discovery.
+const DEFAULT_ACTIVE_AGE_SECS: u64 = 300;
+/// Resources per `resources/list` page.
+const RESOURCES_PAGE_SIZE: usize = 100;
+
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+const RESOURCE_NOT_FOUND: i64 = -32002;
+
+/// Serve requests from stdin until it is closed.
+pub fn serve(roots: &ProviderRoots) -> xurl_core::Result<()> {
+    let stdin = io::stdin();
+    let mut stdout = io::stdout().lock();
+    for line in stdin.lock().lines() {
+        let line = line.map_err(|source| XurlError::Io {
+            path: PathBuf::from("<stdin>"),
+            source,
+        })?;
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        if let Some(response) = handle_message(&line, roots) {
+            writeln!(stdout, "{response}")
+                .and_then(|()| stdout.flush())
+                .map_err(|source| XurlError::Io {
+                    path: PathBuf::from("<stdout>"),
+                    source,
+                })?;
+        }
+    }
+    Ok(())
+}
+
+/// Handle one JSON-RPC message. Notifications get no response.
+fn handle_message(line: &str, roots: &ProviderRoots) -> Option<Value> {
+    let message = match serde_json::from_str::<Value>(line) {
+        Ok(message) => message,
+        Err(err) => return Some(error_response(Value::Null, PARSE_ERROR, &err.to_string())),
+    };
+    let id = message.get("id").cloned();
+    let Some(method) = message.get("method").and_then(Value::as_str) else {
+        return id.map(|id| error_response(id, INVALID_REQUEST, "missing method"));
+    };
+    let id = id?;
+    let params = message.get("params").cloned().unwrap_or(Value::Null);
+
+    let result = match method {
+        "initialize" => Ok(initialize(&params)),
+        "ping" => Ok(json!({})),
+        "tools/list" => Ok(json!({ "tools": tool_definitions() })),
+        "tools/call" => call_tool(&params, roots),
+        "resources/list" => list_resources(&params, roots),
+        "resources/templates/list" => Ok(json!({ "resourceTemplates": resource_templates() })),
+        "resources/read" => read_resource(&params, roots),
+        _ => Err((METHOD_NOT_FOUND, format!("unknown method: {method}"))),
+    };
+
+    Some(match result {
+        Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+        Err((code, message)) => error_response(id, code, &message),
+    })
+}
+
+fn error_response(id: Value, code: i64, message: &str) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": { "code": code, "message": message },
+    })
+}
+
+fn initialize(params: &Value) -> Value {
+    let requested = params.get("protocolVersion").and_then(Value::as_str);
+    let version = requested
+        .filter(|version| PROTOCOL_VERSIONS.contains(version))
+        .unwrap_or(PROTOCOL_VERSIONS[0]);
+
+    json!({
+        "protocolVersion": version,
+        "capabilities": {
+            "tools": {},
+            "resources": {},
+        },
+        "serverInfo": {
+            "name": "xurl",
+            "version": env!("CARGO_PKG_VERSION"),
+        },
+        "instructions": "Read code-agent threads by agents:// URI. Use thread_head for metadata and discovery before read_thread on long threads.",
+    })
+}
+
+fn tool_definitions() -> Value {
+    let uri_schema = |description: &str| {
+        json!({
+            "type": "object",
+            "properties": {
+                "uri": { "type": "string", "description": description },
+            },
+            "required": ["uri"],
+        })
+    };
+
+    let mut read_thread = uri_schema(
+        "Thread URI such as agents://codex/<session_id>; view options like ?roles=user&thinking=0 apply",
+    );
+    read_thread["properties"]["tools"] = json!({
+        "type": "boolean",
+        "description": "Include tool calls and their truncated results in the timeline",
+    });
+
+    json!([
+        {
+            "name": "read_thread",
+            "description": "Render a thread as markdown: frontmatter followed by the timeline.",
+            "inputSchema": read_thread,
+        },
+        {
+            "name": "thread_head",
+            "description": "Render only the YAML frontmatter of a thread, including subagent and entry discovery lists.",
+            "inputSchema": uri_schema("Thread URI such as agents://claude/<session_id>"),
+        },
+        {
+            "name": "list_subagents",
+            "description": "List the subagents spawned by a Codex or Claude main thread.",
+            "inputSchema": uri_schema("Main thread URI such as agents://codex/<session_id>"),
+        },
+        {
+            "name": "list_active_sessions",
+            "description": "List unarchived threads across providers updated recently, newest first.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "max_age_secs": {
+                        "type": "integer",
+                        "minimum": 0,
+                        "description": format!("Only include threads updated within this many seconds (default {DEFAULT_ACTIVE_AGE_SECS})"),
+                    },
+                },
+            },
+        },
+    ])
+}
+
+/// Tool failures are reported in the result with `isError` so the model
+/// sees them; only malformed calls are protocol errors.
+fn call_tool(params: &Value, roots: &ProviderRoots) -> Result<Value, (i64, String)> {
+    let name = params
+        .get("name")
+        .and_then(Value::as_str)
+        .ok_or((INVALID_PARAMS, "missing tool name".to_string()))?;
+    let args = params.get("arguments").cloned().unwrap_or(Value::Null);
+
+    let output = match name {
+        "read_thread" => parse_uri_arg(&args, roots).and_then(|uri| {
+            let mut options = uri.query.timeline_options(TimelineOptions::default());
+            if let Some(tools) = args.get("tools").and_then(Value::as_bool) {
+                options.tools = tools;
+            }
+            render_thread_document(&uri, roots, &options)
+        }),
+        "thread_head" => {
+            parse_uri_arg(&args, roots).and_then(|uri| render_thread_head_markdown(&uri, roots))
+        }
+        "list_subagents" => parse_uri_arg(&args, roots).and_then(|uri| {
+            let view = resolve_subagent_view(&uri, roots, true)?;
+            Ok(render_subagent_view_markdown(&view))
+        }),
+        "list_active_sessions" => {
+            let max_age = args
+                .get("max_age_secs")
+                .and_then(Value::as_u64)
+                .unwrap_or(DEFAULT_ACTIVE_AGE_SECS);
+            let mut sessions = roots.list_active_sessions(Duration::from_secs(max_age));
+            sessions.sort_by_key(|session| Reverse(session.mtime_epoch));
+            serde_json::to_string_pretty(&sessions)
+                .map_err(|err| XurlError::Serialization(err.to_string()))
+        }
+        _ => return Err((INVALID_PARAMS, format!("unknown tool: {name}"))),
+    };
+
+    Ok(match output {
+        Ok(text) => json!({
+            "content": [{ "type": "text", "text": text }],
+            "isError": false,
+        }),
+        Err(err) => json!({
+            "content": [{ "type": "text", "text": format!("error: {err}") }],
+            "isError": true,
+        }),
+    })
+}
+
+fn parse_uri_arg(args: &Value, roots: &ProviderRoots) -> xurl_core::Result<ThreadUri> {
+    let uri = args
+        .get("uri")
+        .and_then(Value::as_str)
+        .ok_or_else(|| XurlError::InvalidUri("missing `uri` argument".to_string()))?;
+    resolve_target(uri, roots)
+}
+
+/// One page of threads, newest first. The cursor is the offset of the
+/// page's first thread; it is opaque to clients.
+fn list_resources(params: &Value, roots: &ProviderRoots) -> Result<Value, (i64, String)> {
+    let offset = match params.get("cursor") {
+        None | Some(Value::Null) => 0,
+        Some(cursor) => cursor
+            .as_str()
+            .and_then(|cursor| cursor.parse::<usize>().ok())
+            .ok_or((INVALID_PARAMS, format!("invalid cursor: {cursor}")))?,
+    };
+    let view = list_session_summaries(roots, &SessionListQuery::default());
+    let resources = view
+        .sessions
+        .iter()
+        .skip(offset)
+        .take(RESOURCES_PAGE_SIZE)
+        .map(|session| {
+            json!({
+                "uri": session.uri(),
+                "name": session.title.clone().unwrap_or_else(|| session.session_id.clone()),
+                "description": format!("{} thread", session.provider),
+                "mimeType": "text/markdown",
+            })
+        })
+        .collect::<Vec<_>>();
+
+    let next = offset + resources.len();
+    let mut result = json!({ "resources": resources });
+    if next < view.sessions.len() {
+        result["nextCursor"] = json!(next.to_string());
+    }
+    Ok(result)
+}
+
+fn resource_templates() -> Vec<Value> {
+    let mut templates = Vec::new();
+    for provider in ProviderKind::ALL {
+        templates.push(json!({
+            "uriTemplate": format!("agents://{provider}/{{session_id}}"),
+            "name": format!("{provider} thread"),
+            "mimeType": "text/markdown",
+        }));
+        let child = match provider {
+            ProviderKind::Codex | ProviderKind::Claude => Some(("agent_id", "subagent thread")),
+            ProviderKind::Pi => Some(("entry_id", "thread branch at an entry")),
+            _ => None,
+        };
+        if let Some((segment, label)) = child {
+            templates.push(json!({
+                "uriTemplate": format!("agents://{provider}/{{session_id}}/{{{segment}}}"),
+                "name": format!("{provider} {label}"),
+                "mimeType": "text/markdown",
+            }));
+        }
+    }
+    templates
+}
+
+fn read_resource(params: &Value, roots: &ProviderRoots) -> Result<Value, (i64, String)> {
+    let raw = params
+        .get("uri")
+        .and_then(Value::as_str)
+        .ok_or((INVALID_PARAMS, "missing resource uri".to_string()))?;
+    let text = resolve_target(raw, roots)
+        .and_then(|uri| {
+            let options = uri.query.timeline_options(TimelineOptions::default());
+            render_thread_document(&uri, roots, &options)
+        })
+        .map_err(|err| {
+            let code = match &err {
+                XurlError::ThreadNotFound { .. }
+                | XurlError::NoMatchingThread(_)
+                | XurlError::EntryNotFound { .. } => RESOURCE_NOT_FOUND,
+                _ => INVALID_PARAMS,
+            };
+            (code, err.to_string())
+        })?;
+
+    Ok(json!({
+        "contents": [{ "uri": raw, "mimeType": "text/markdown", "text": text }],
+    }))
+}
@@ -0,0 +1,538 @@
+//! `xurl serve`: a small local HTTP server for browsing threads.
+//!
+//! Each connection is handled on its own thread and closed after one
+//! response, which keeps the server dependency-free. Views are negotiated
+//! from the `Accept` header: the first of `text/html`, `application/json`
+//! or `text/markdown` listed wins, and markdown is the default.
+//!
+//! Routes:
+//! - `GET /` and `GET /agents`: thread listing (`?provider=`, `?cwd=`, `?limit=`)
+//! - `GET /agents/<provider>/<thread_path>`: rendered thread
+//! - `GET /head/<provider>/<thread_path>`: frontmatter only
+//! - `GET /subagents/<provider>/<session_id>`: subagent listing
+//! - `GET /events/<provider>/<session_id>`: server-sent events as the thread grows
+//!
+//! Thread routes take the same query as `agents://` URIs (`?tools=1`,
+//! `?roles=user`, ...); `?format=` overrides the `Accept` header.
+//!
+//! There is no authentication, so the server only binds to loopback unless
+//! `--allow-remote` is given, and on loopback it rejects requests whose
+//! `Host` or `Origin` names another site, which keeps pages served by other
+//! origins (including DNS-rebound ones) from reading threads.
+
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream, ToSocketAddrs};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use xurl_core::html::escape_html;
+use xurl_core::{
+    FollowEvent, ProviderRoots, SessionListQuery, ThreadFollower, ThreadUri, TimelineOptions,
+    UriFormat, XurlError, list_sessions, render_session_list_markdown,
+    render_subagent_view_markdown, render_thread_head_markdown, render_thread_html,
+    resolve_subagent_view, resolve_thread_head, resolve_thread_view,
+};
+
+use crate::{
+    FOLLOW_POLL_INTERVAL, json_document, parse_provider, render_thread_document, resolve_target,
+};
+
+/// Polls between keep-alive comments on an idle event stream, so
+/// disconnected clients are noticed.
+const EVENTS_KEEPALIVE_POLLS: u32 = 30;
+/// Upper bound on request line plus headers.
+const MAX_REQUEST_BYTES: usize = 16 * 1024;
+/// How long a client may take to send its request.
+const READ_TIMEOUT: Duration = Duration::from_secs(10);
+
+pub fn serve(bind: &str, allow_remote: bool, roots: ProviderRoots) -> xurl_core::Result<()> {
+    let io_error = |source| XurlError::Io {
+        path: PathBuf::from(bind),
+        source,
+    };
+    let remote = bind
+        .to_socket_addrs()
+        .map_err(io_error)?
+        .any(|address| !address.ip().is_loopback());
+    if remote && !allow_remote {
+        return Err(XurlError::InvalidMode(format!(
+            "refusing to serve threads on non-loopback address {bind}; pass --allow-remote to expose them to the network"
+        )));
+    }
+
+    let listener = TcpListener::bind(bind).map_err(io_error)?;
+    let local = listener.local_addr().map_err(io_error)?;
+    eprintln!("serving threads on http://{local}/");
+
+    // Remote binds are reached under names this side cannot know.
+    let allowed_hosts = (!remote).then(|| {
+        let port = local.port();
+        vec![
+            format!("localhost:{port}"),
+            format!("127.0.0.1:{port}"),
+            format!("[::1]:{port}"),
+        ]
+    });
+
+    let roots = Arc::new(roots);
+    let allowed_hosts = Arc::new(allowed_hosts);
+    for stream in listener.incoming() {
+        let Ok(stream) = stream else {
+            continue;
+        };
+        let roots = Arc::clone(&roots);
+        let allowed_hosts = Arc::clone(&allowed_hosts);
+        thread::spawn(move || {
+            // A client hanging up mid-response is not worth reporting.
+            let _ = handle_connection(stream, &roots, allowed_hosts.as_deref());
+        });
+    }
+    Ok(())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum View {
+    Html,
+    Json,
+    Markdown,
+}
+
+impl View {
+    fn negotiate(accept: &str) -> Self {
+        for range in accept.split(',') {
+            let media = range.split(';').next().unwrap_or_default().trim();
+            match media {
+                "text/html" | "application/xhtml+xml" => return Self::Html,
+                "application/json" => return Self::Json,
+                "text/markdown" | "text/plain" => return Self::Markdown,
+                _ => {}
+            }
+        }
+        Self::Markdown
+    }
+}
+
+struct Request {
+    method: String,
+    /// Decoded path, used for routing.
+    path: String,
+    /// Decoded query pairs, used by the listing route.
+    query: Vec<(String, String)>,
+    /// Path and query as sent. Thread routes hand these to the URI parser,
+    /// which splits before decoding so an escaped `&` or `#` stays literal.
+    raw_path: String,
+    raw_query: String,
+    host: Option<String>,
+    origin: Option<String>,
+    view: View,
+}
+
+impl Request {
+    /// Whether `Host` and any `Origin` name one of `allowed_hosts`.
+    fn is_same_site(&self, allowed_hosts: &[String]) -> bool {
+        let allowed = |host: &str| {
+            allowed_hosts
+                .iter()
+                .any(|allowed| allowed.eq_ignore_ascii_case(host))
+        };
+        self.host.as_deref().is_some_and(allowed)
+            && self
+                .origin
+                .as_deref()
+                .is_none_or(|origin| origin.strip_prefix("http://").is_some_and(allowed))
+    }
+}
+
+struct Response {
+    status: u16,
+    content_type: &'static str,
+    body: String,
+}
+
+impl Response {
+    fn ok(view: View, body: String) -> Self {
+        let content_type = match view {
+            View::Html => "text/html; charset=utf-8",
+            View::Json => "application/json",
+            View::Markdown => "text/markdown; charset=utf-8",
+        };
+        Self {
+            status: 200,
+            content_type,
+            body,
+        }
+    }
+
+    fn error(status: u16, message: &str) -> Self {
+        Self {
+            status,
+            content_type: "text/plain; charset=utf-8",
+            body: format!("error: {message}\n"),
+        }
+    }
+}
+
+fn handle_connection(
+    stream: TcpStream,
+    roots: &ProviderRoots,
+    allowed_hosts: Option<&[String]>,
+) -> io::Result<()> {
+    stream.set_read_timeout(Some(READ_TIMEOUT))?;
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let mut stream = stream;
+    let request = match read_request(&mut reader) {
+        Ok(request) => request,
+        Err(message) => return write_response(&mut stream, &Response::error(400, &message)),
+    };
+
+    if let Some(allowed_hosts) = allowed_hosts
+        && !request.is_same_site(allowed_hosts)
+    {
+        return write_response(
+            &mut stream,
+            &Response::error(403, "Host or Origin is not this server"),
+        );
+    }
+
+    if request.method != "GET" {
+        return write_response(&mut stream, &Response::error(405, "only GET is supported"));
+    }
+
+    if let Some(rest) = request.raw_path.strip_prefix("/events/") {
+        return match parse_thread_uri(rest, &request.raw_query, roots) {
+            Ok(uri) => stream_events(&mut stream, &uri, roots),
+            Err(err) => write_response(&mut stream, &error_response(&err)),
+        };
+    }
+
+    let response = route(&request, roots).unwrap_or_else(|err| error_response(&err));
+    write_response(&mut stream, &response)
+}
+
+fn read_request(reader: &mut impl BufRead) -> Result<Request, String> {
+    let mut line = String::new();
+    reader
+        .read_line(&mut line)
+        .map_err(|err| format!("unreadable request: {err}"))?;
+    let mut parts = line.split_whitespace();
+    let (Some(method), Some(target)) = (parts.next(), parts.next()) else {
+        return Err("malformed request line".to_string());
+    };
+    let method = method.to_string();
+    let target = target.to_string();
+
+    let mut accept = String::new();
+    let mut host = None;
+    let mut origin = None;
+    let mut total = line.len();
+    loop {
+        line.clear();
+        let read = reader
+            .read_line(&mut line)
+            .map_err(|err| format!("unreadable request: {err}"))?;
+        total += read;
+        if total > MAX_REQUEST_BYTES {
+            return Err("request headers too large".to_string());
+        }
+        let header = line.trim_end();
+        if read == 0 || header.is_empty() {
+            break;
+        }
+        let Some((name, value)) = header.split_once(':') else {
+            continue;
+        };
+        let value = value.trim().to_string();
+        match name.trim().to_ascii_lowercase().as_str() {
+            "accept" => accept = value,
+            "host" => host = Some(value),
+            "origin" => origin = Some(value),
+            _ => {}
+        }
+    }
+
+    let (path, raw_query) = target.split_once('?').unwrap_or((&target, ""));
+    let path = path.trim_end_matches('/');
+    let query = raw_query
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            (percent_decode(key), percent_decode(value))
+        })
+        .collect();
+
+    Ok(Request {
+        method,
+        path: percent_decode(path),
+        query,
+        raw_path: path.to_string(),
+        raw_query: raw_query.to_string(),
+        host,
+        origin,
+        view: View::negotiate(&accept),
+    })
+}
+
+fn route(request: &Request, roots: &ProviderRoots) -> xurl_core::Result<Response> {
+    if request.path.is_empty() || request.path == "/agents" {
+        return list_threads(request, roots);
+    }
+
+    if let Some(rest) = request.raw_path.strip_prefix("/agents/") {
+        let uri = parse_thread_uri(rest, &request.raw_query, roots)?;
+        let view = thread_view(request, &uri)?;
+        let options = uri.query.timeline_options(TimelineOptions::default());
+        let body = match view {
+            View::Html => render_thread_html(&resolve_thread_view(&uri, roots, &options)?),
+            View::Json => json_document(&resolve_thread_view(&uri, roots, &options)?)?,
+            View::Markdown => render_thread_document(&uri, roots, &options)?,
+        };
+        return Ok(Response::ok(view, body));
+    }
+
+    if let Some(rest) = request.raw_path.strip_prefix("/head/") {
+        let uri = parse_thread_uri(rest, &request.raw_query, roots)?;
+        let view = thread_view(request, &uri)?;
+        return Ok(match view {
+            View::Json => Response::ok(view, json_document(&resolve_thread_head(&uri, roots)?)?),
+            View::Markdown => Response::ok(view, render_thread_head_markdown(&uri, roots)?),
+            View::Html => Response::ok(
+                view,
+                thread_page(&uri, &render_thread_head_markdown(&uri, roots)?),
+            ),
+        });
+    }
+
+    if let Some(rest) = request.raw_path.strip_prefix("/subagents/") {
+        let uri = parse_thread_uri(rest, &request.raw_query, roots)?;
+        let view = thread_view(request, &uri)?;
+        let subagents = resolve_subagent_view(&uri, roots, true)?;
+        return Ok(match view {
+            View::Json => Response::ok(view, json_document(&subagents)?),
+            View::Markdown => Response::ok(view, render_subagent_view_markdown(&subagents)),
+            View::Html => Response::ok(
+                view,
+                thread_page(&uri, &render_subagent_view_markdown(&subagents)),
+            ),
+        });
+    }
+
+    Ok(Response::error(
+        404,
+        &format!("no route for {}", request.path),
+    ))
+}
+
+fn list_threads(request: &Request, roots: &ProviderRoots) -> xurl_core::Result<Response> {
+    let mut query = SessionListQuery::default();
+    for (key, value) in &request.query {
+        match key.as_str() {
+            "provider" => query
+                .providers
+                .push(parse_provider(value).map_err(XurlError::InvalidMode)?),
+            "cwd" => query.cwd = Some(PathBuf::from(value)),
+            "limit" => {
+                query.limit = Some(
+                    value
+                        .parse()
+                        .map_err(|_| XurlError::InvalidMode(format!("invalid limit: {value}")))?,
+                )
+            }
+            _ => {
+                return Err(XurlError::InvalidMode(format!(
+                    "unknown query parameter `{key}`; expected provider, cwd or limit"
+                )));
+            }
+        }
+    }
+
+    let sessions = list_sessions(roots, &query);
+    let view = request.view;
+    Ok(match view {
+        View::Json => Response::ok(view, json_document(&sessions)?),
+        View::Markdown => Response::ok(view, render_session_list_markdown(&sessions)),
+        View::Html => {
+            let mut rows = String::new();
+            for session in &sessions.sessions {
+                let link = format!("/agents/{}/{}", session.provider, session.session_id);
+                rows.push_str(&format!(
+                    "<li><a href=\"{}\">{}</a> <code>{}</code></li>\n",
+                    escape_html(&link),
+                    escape_html(session.title.as_deref().unwrap_or(&session.session_id)),
+                    escape_html(&session.uri()),
+                ));
+            }
+            if rows.is_empty() {
+                rows.push_str("<li><em>No threads found.</em></li>\n");
+            }
+            Response::ok(
+                view,
+                html_page("Threads", &format!("<h1>Threads</h1>\n<ul>\n{rows}</ul>\n")),
+            )
+        }
+    })
+}
+
+/// Send the current timeline, then every new entry, as server-sent events
+/// until the client goes away.
+fn stream_events(stream: &mut TcpStream, uri: &ThreadUri, roots: &ProviderRoots) -> io::Result<()> {
+    let (mut follower, entries) = match ThreadFollower::start(uri, roots) {
+        Ok(started) => started,
+        Err(err) => return write_response(stream, &error_response(&err)),
+    };
+
+    stream.write_all(
+        b"HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\nCache-Control: no-cache\r\nConnection: close\r\n\r\n",
+    )?;
+    let initial = entries
+        .into_iter()
+        .map(FollowEvent::Entry)
+        .collect::<Vec<_>>();
+    write_events(stream, &initial)?;
+
+    let mut idle_polls = 0;
+    loop {
+        thread::sleep(FOLLOW_POLL_INTERVAL);
+        let events = match follower.poll() {
+            Ok(events) => events,
+            Err(err) => {
+                write!(stream, "event: error\ndata: {err}\n\n")?;
+                return stream.flush();
+            }
+        };
+
+        if events.is_empty() {
+            idle_polls += 1;
+            if idle_polls >= EVENTS_KEEPALIVE_POLLS {
+                idle_polls = 0;
+                stream.write_all(b": keep-alive\n\n")?;
+                stream.flush()?;
+            }
+            continue;
+        }
+        idle_polls = 0;
+        write_events(stream, &events)?;
+    }
+}
+
+fn write_events(stream: &mut TcpStream, events: &[FollowEvent]) -> io::Result<()> {
+    for event in events {
+        let name = match event {
+            FollowEvent::Entry(_) => "entry",
+            FollowEvent::ToolCall(_) => "tool_call",
+        };
+        let data = serde_json::to_string(event).map_err(io::Error::other)?;
+        write!(stream, "event: {name}\ndata: {data}\n\n")?;
+    }
+    stream.flush()
+}
+
+fn write_response(stream: &mut TcpStream, response: &Response) -> io::Result<()> {
+    let reason = match response.status {
+        200 => "OK",
+        400 => "Bad Request",
+        403 => "Forbidden",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        _ => "Internal Server Error",
+    };
+    write!(
+        stream,
+        "HTTP/1.1 {} {reason}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        response.status,
+        response.content_type,
+        response.body.len(),
+    )?;
+    stream.write_all(response.body.as_bytes())?;
+    stream.flush()
+}
+
+fn error_response(err: &XurlError) -> Response {
+    let status = match err {
+        XurlError::ThreadNotFound { .. }
+        | XurlError::EntryNotFound { .. }
+        | XurlError::NoMatchingThread(_) => 404,
+        XurlError::InvalidUri(_)
+        | XurlError::UnsupportedScheme(_)
+        | XurlError::InvalidSessionId(_)
+        | XurlError::InvalidMode(_)
+        | XurlError::AmbiguousThread { .. }
+        | XurlError::UnsupportedSubagentProvider(_) => 400,
+        _ => 500,
+    };
+    Response::error(status, &err.to_string())
+}
+
+/// `<provider>/<thread_path>` from a raw request path, plus the raw request
+/// query, into a thread URI.
+fn parse_thread_uri(
+    rest: &str,
+    query: &str,
+    roots: &ProviderRoots,
+) -> xurl_core::Result<ThreadUri> {
+    let uri = match query {
+        "" => format!("agents://{rest}"),
+        query => format!("agents://{rest}?{query}"),
+    };
+    resolve_target(&uri, roots)
+}
+
+/// The negotiated view unless the URI asks for a format.
+fn thread_view(request: &Request, uri: &ThreadUri) -> xurl_core::Result<View> {
+    Ok(match uri.query.format {
+        None => request.view,
+        Some(UriFormat::Html) => View::Html,
+        Some(UriFormat::Json) => View::Json,
+        Some(UriFormat::Markdown) => View::Markdown,
+        Some(UriFormat::Jsonl) => {
+            return Err(XurlError::InvalidMode(
+                "format=jsonl is not served over HTTP; use format=json".to_string(),
+            ));
+        }
+    })
+}
+
+fn thread_page(uri: &ThreadUri, markdown: &str) -> String {
+    let title = uri.as_agents_string();
+    html_page(
+        &title,
+        &format!(
+            "<h1><code>{}</code></h1>\n<pre>{}</pre>\n",
+            escape_html(&title),
+            escape_html(markdown)
+        ),
+    )
+}
+
+fn html_page(title: &str, body: &str) -> String {
+    format!(
+        "<!doctype html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n</head>\n<body>\n{body}</body>\n</html>\n",
+        escape_html(title)
+    )
+}
+
+fn percent_decode(raw: &str) -> String {
+    let bytes = raw.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+    while index < bytes.len() {
+        let escaped = bytes
+            .get(index + 1..index + 3)
+            .filter(|_| bytes[index] == b'%')
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match (escaped, bytes[index]) {
+            (Some(byte), _) => {
+                decoded.push(byte);
+                index += 3;
+                continue;
+            }
+            (None, b'+') => decoded.push(b' '),
+            (None, byte) => decoded.push(byte),
+        }
+        index += 1;
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
@@ -3,6 +3,7 @@ use std::path::PathBuf;
 
 use assert_cmd::Command;
 use predicates::prelude::*;
+use serde_json::json;
 use tempfile::tempdir;
 
 const SESSION_ID: &str = "019c871c-b1f9-7f60-9c4f-87ed09f13592";
@@ -20,6 +21,10 @@ const CLAUDE_AGENT_ID: &str = "acompact-69d537";
 const CLAUDE_REAL_MAIN_ID: &str = "b90fc33d-33cb-4027-8558-119e2b56c74e";
 const CLAUDE_REAL_AGENT_ID: &str = "a4f21c7";
 const OPENCODE_REAL_SESSION_ID: &str = "ses_7v2md9kx3c1p";
+const GOOSE_REAL_SESSION_ID: &str = "20251016_1";
+const GOOSE_REAL_LEGACY_SESSION_ID: &str = "20250325_143022";
+const CURSOR_REAL_SESSION_ID: &str = "3f6b2d1e-9a4c-4e8b-b7d2-5c1a0e9f8d3b";
+const CLINE_REAL_SESSION_ID: &str = "1760605923000";
 
 fn setup_codex_tree() -> tempfile::TempDir {
     let temp = tempdir().expect("tempdir");
@@ -177,6 +182,18 @@ fn opencode_real_fixture_root() -> PathBuf {
     PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/opencode_real_sanitized")
 }
 
+fn goose_real_fixture_root() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/goose_real_sanitized")
+}
+
+fn cursor_real_fixture_root() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/cursor_real_sanitized")
+}
+
+fn cline_real_fixture_root() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/cline_real_sanitized")
+}
+
 fn pi_real_fixture_root() -> PathBuf {
     PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/pi_real_sanitized")
 }
@@ -558,6 +575,131 @@ fn amp_outputs_markdown() {
         .stdout(predicate::str::contains("world"));
 }
 
+#[test]
+fn cline_task_renders_messages_tools_and_checkpoints() {
+    let temp = tempdir().expect("tempdir");
+    let task = temp
+        .path()
+        .join("Code/User/globalStorage/saoudrizwan.claude-dev/tasks/1731234567890");
+    fs::create_dir_all(&task).expect("mkdir");
+    fs::write(
+        task.join("api_conversation_history.json"),
+        r#"[{"role":"user","content":[{"type":"text","text":"<task>\nList the sources\n</task>"},{"type":"text","text":"<environment_details>\n# Current Working Directory (/tmp/project) Files\n</environment_details>"}]},{"role":"assistant","content":[{"type":"text","text":"Listing."},{"type":"tool_use","id":"toolu_1","name":"list_files","input":{"path":"src"}}]},{"role":"user","content":[{"type":"tool_result","tool_use_id":"toolu_1","content":"main.rs"}]}]"#,
+    )
+    .expect("write");
+    fs::write(
+        task.join("ui_messages.json"),
+        r#"[{"ts":1731234567891,"type":"say","say":"checkpoint_created","lastCheckpointHash":"4f2a9c1","conversationHistoryIndex":2}]"#,
+    )
+    .expect("write");
+
+    Command::new(assert_cmd::cargo::cargo_bin!("xurl"))
+        .env("VSCODE_APPDATA", temp.path())
+        .args(["--tools", "agents://cline/1731234567890"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "## 1. User\n\nList the sources\n\n",
+        ))
+        .stdout(predicate::str::contains("## 3. Tool `list_files`"))
+        .stdout(predicate::str::contains("main.rs"))
+        .stdout(predicate::str::contains("## 4. Checkpoint\n\n`4f2a9c1`"))
+        .stdout(predicate::str::contains("environment_details").not());
+
+    ls_command(&temp)
+        .env("VSCODE_APPDATA", temp.path())
+        .args(["--provider", "cline", "--cwd", "/tmp/project"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("agents://cline/1731234567890"))
+        .stdout(predicate::str::contains("List the sources"));
+}
+
+#[test]
+fn cline_real_fixture_pairs_xml_tool_calls_with_results() {
+    let fixture_root = cline_real_fixture_root();
+    assert!(fixture_root.exists(), "fixture root must exist");
+
+    let temp = tempdir().expect("tempdir");
+    isolated_command(&temp, &agents_uri("cline", CLINE_REAL_SESSION_ID))
+        .env("VSCODE_APPDATA", &fixture_root)
+        .arg("--tools")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Let me read the readme.\n\n## 3. Tool `read_file`",
+        ))
+        .stdout(predicate::str::contains("A smal demo project.\n```"))
+        .stdout(predicate::str::contains("## 5. Tool `replace_in_file`"))
+        .stdout(predicate::str::contains(
+            "The content was successfully saved to README.md.",
+        ))
+        .stdout(predicate::str::contains("## 6. Checkpoint\n\n`9c1e5a7`"))
+        .stdout(predicate::str::contains(
+            "## 7. Assistant\n\nFixed the typo in README.md.",
+        ))
+        .stdout(predicate::str::contains("<read_file>").not())
+        .stdout(predicate::str::contains("Result:").not());
+
+    isolated_command(&temp, "files")
+        .env("VSCODE_APPDATA", &fixture_root)
+        .arg(agents_uri("cline", CLINE_REAL_SESSION_ID))
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("- Cwd: `/home/user/demo`"))
+        .stdout(predicate::str::contains(
+            "read by `read_file` (turn 1, entry 3); modified by `replace_in_file` (turn 1, entry 5)",
+        ));
+}
+
+#[test]
+fn aider_history_lists_runs_and_renders_edit_blocks() {
+    let temp = tempdir().expect("tempdir");
+    let project = temp.path().join("projects/demo");
+    fs::create_dir_all(&project).expect("mkdir");
+    fs::write(
+        project.join(".aider.chat.history.md"),
+        "\n# aider chat started at 2024-05-01 10:00:00\n\n> Aider v0.50.0\n\n#### say hello\n\nHello!\n\n# aider chat started at 2024-05-02 09:30:15\n\n#### rename the helper\n\nRenaming it.\n\nsrc/lib.rs\n```rust\n<<<<<<< SEARCH\nfn old() {}\n=======\nfn new() {}\n>>>>>>> REPLACE\n```\n\n> Applied edit to src/lib.rs\n",
+    )
+    .expect("write");
+
+    ls_command(&temp)
+        .env("XURL_AIDER_ROOTS", temp.path().join("projects"))
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("agents://aider/").not());
+
+    let output = ls_command(&temp)
+        .env("XURL_AIDER_ROOTS", temp.path().join("projects"))
+        .args(["--provider", "aider"])
+        .output()
+        .expect("run ls");
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("- Count: `2`"));
+    let uri = stdout
+        .split('`')
+        .find(|part| part.starts_with("agents://aider/20240502-093015-"))
+        .expect("second run listed")
+        .to_string();
+
+    isolated_command(&temp, &uri)
+        .env("XURL_AIDER_ROOTS", temp.path().join("projects"))
+        .env("TMPDIR", temp.path())
+        .arg("--tools")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "## 1. User\n\nrename the helper\n\n",
+        ))
+        .stdout(predicate::str::contains("Renaming it."))
+        .stdout(predicate::str::contains("## 3. Tool `edit`"))
+        .stdout(predicate::str::contains("Applied edit to src/lib.rs"))
+        .stdout(predicate::str::contains("say hello").not());
+    // The run is cut out of the history in memory, not copied to a file.
+    assert!(!temp.path().join("xurl-aider").exists());
+}
+
 #[test]
 fn gemini_outputs_markdown() {
     let temp = setup_gemini_tree();
@@ -573,6 +715,50 @@ fn gemini_outputs_markdown() {
         .stdout(predicate::str::contains("world"));
 }
 
+#[test]
+fn qwen_outputs_markdown_and_lists_sessions() {
+    let temp = tempdir().expect("tempdir");
+    let thread_path = temp.path().join(
+        ".qwen/tmp/0c0d7b04c22749f3687ea60b66949fd3/chats/session-2026-01-08T11-55-29d207db.json",
+    );
+    fs::create_dir_all(thread_path.parent().expect("parent")).expect("mkdir");
+    fs::write(
+        &thread_path,
+        format!(
+            r#"{{
+  "sessionId": "{GEMINI_SESSION_ID}",
+  "projectHash": "0c0d7b04c22749f3687ea60b66949fd3",
+  "startTime": "2026-01-08T11:55:12.379Z",
+  "lastUpdated": "2026-01-08T12:31:14.881Z",
+  "messages": [
+    {{ "type": "user", "content": "hello" }},
+    {{ "type": "qwen", "content": "world" }}
+  ]
+}}"#
+        ),
+    )
+    .expect("write");
+
+    isolated_command(&temp, &agents_uri("qwen", GEMINI_SESSION_ID))
+        .env("QWEN_CODE_HOME", temp.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("provider: 'qwen'"))
+        .stdout(predicate::str::contains("## 2. Assistant"))
+        .stdout(predicate::str::contains("world"));
+
+    ls_command(&temp)
+        .env("QWEN_CODE_HOME", temp.path())
+        .args(["--provider", "qwen"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(agents_uri(
+            "qwen",
+            GEMINI_SESSION_ID,
+        )))
+        .stdout(predicate::str::contains("- Title: hello"));
+}
+
 #[test]
 fn pi_outputs_markdown_from_latest_leaf() {
     let temp = setup_pi_tree();
@@ -652,6 +838,37 @@ fn pi_real_fixture_outputs_markdown() {
         .stdout(predicate::str::contains("## 2. Assistant"));
 }
 
+#[test]
+fn pi_real_fixture_reports_message_metadata() {
+    Command::new(assert_cmd::cargo::cargo_bin!("xurl"))
+        .env("PI_CODING_AGENT_DIR", pi_real_fixture_root())
+        .arg(pi_real_uri())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "## 2. Assistant · 2026-02-23T13:20:07.862Z · gpt-5.3-codex · 3111 in / 11 out tokens · $0.0056",
+        ));
+
+    let output = Command::new(assert_cmd::cargo::cargo_bin!("xurl"))
+        .env("PI_CODING_AGENT_DIR", pi_real_fixture_root())
+        .args(["--format", "jsonl"])
+        .arg(pi_real_uri())
+        .output()
+        .expect("run xurl");
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8(output.stdout).expect("utf8");
+    let assistant = stdout
+        .lines()
+        .map(|line| serde_json::from_str::<serde_json::Value>(line).expect("valid json line"))
+        .find(|record| record["role"] == "assistant")
+        .expect("assistant record");
+    assert_eq!(assistant["model"], "gpt-5.3-codex");
+    assert_eq!(assistant["usage"]["input_tokens"], 3111);
+    assert_eq!(assistant["usage"]["total_tokens"], 3122);
+    assert_eq!(assistant["usage"]["cost"], 0.00559825);
+}
+
 #[test]
 fn claude_subagent_outputs_markdown_view() {
     let temp = setup_claude_subagent_tree();
@@ -735,3 +952,1246 @@ fn opencode_real_fixture_outputs_markdown() {
         .stdout(predicate::str::contains("# Thread"))
         .stdout(predicate::str::contains("## 1. User"));
 }
+
+#[test]
+fn goose_real_fixture_outputs_messages_and_tools() {
+    let fixture_root = goose_real_fixture_root();
+    assert!(fixture_root.exists(), "fixture root must exist");
+
+    let temp = tempdir().expect("tempdir");
+    isolated_command(&temp, &agents_uri("goose", GOOSE_REAL_SESSION_ID))
+        .env("XDG_DATA_HOME", &fixture_root)
+        .arg("--tools")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "## 1. User · 2025-10-16T09:12:03Z\n\nlist the files in this directory",
+        ))
+        .stdout(predicate::str::contains("## 3. Tool `developer__shell`"))
+        .stdout(predicate::str::contains("README.md\nsrc"))
+        .stdout(predicate::str::contains(
+            "The directory contains `README.md` and a `src` folder.",
+        ));
+
+    isolated_command(&temp, &agents_uri("goose", GOOSE_REAL_SESSION_ID))
+        .env("XDG_DATA_HOME", &fixture_root)
+        .arg("-I")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("provider: 'goose'"))
+        .stdout(predicate::str::contains("mode: 'thread'"))
+        .stdout(predicate::str::contains("xurl-goose"));
+
+    isolated_command(&temp, &agents_uri("goose", GOOSE_REAL_LEGACY_SESSION_ID))
+        .env("XDG_DATA_HOME", &fixture_root)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("does the project build?"))
+        .stdout(predicate::str::contains("Yes, `cargo build` succeeds."));
+
+    ls_command(&temp)
+        .env("XDG_DATA_HOME", &fixture_root)
+        .args(["--provider", "goose"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("- Count: `2`"))
+        .stdout(predicate::str::contains("- Title: List project files"))
+        .stdout(predicate::str::contains("- Title: Check the build"))
+        .stdout(predicate::str::contains("/Users/redacted/project"));
+}
+
+#[test]
+fn cursor_real_fixture_outputs_messages_and_tools() {
+    let fixture_root = cursor_real_fixture_root();
+    assert!(fixture_root.exists(), "fixture root must exist");
+
+    let temp = tempdir().expect("tempdir");
+    isolated_command(&temp, &agents_uri("cursor", CURSOR_REAL_SESSION_ID))
+        .env("VSCODE_APPDATA", &fixture_root)
+        .arg("--tools")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "## 1. User · 2025-10-16T09:12:03.000Z\n\nlist the files in this directory",
+        ))
+        .stdout(predicate::str::contains(
+            "I should list the directory first.",
+        ))
+        .stdout(predicate::str::contains("## 3. Tool `run_terminal_cmd`"))
+        .stdout(predicate::str::contains("README.md\nsrc"))
+        .stdout(predicate::str::contains(
+            "The directory contains `README.md` and a `src` folder.",
+        ));
+
+    isolated_command(&temp, &agents_uri("cursor", CURSOR_REAL_SESSION_ID))
+        .env("VSCODE_APPDATA", &fixture_root)
+        .arg("-I")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("provider: 'cursor'"))
+        .stdout(predicate::str::contains("xurl-cursor"));
+
+    ls_command(&temp)
+        .env("VSCODE_APPDATA", &fixture_root)
+        .args(["--provider", "cursor"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("- Count: `1`"))
+        .stdout(predicate::str::contains(agents_uri(
+            "cursor",
+            CURSOR_REAL_SESSION_ID,
+        )))
+        .stdout(predicate::str::contains("- Title: List project files"))
+        .stdout(predicate::str::contains("/Users/redacted/project"));
+}
+
+/// A command whose provider roots all point at missing directories.
+fn isolated_command(temp: &tempfile::TempDir, subcommand: &str) -> Command {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("xurl"));
+    cmd.env("CODEX_HOME", temp.path().join("missing-codex"))
+        .env("CLAUDE_CONFIG_DIR", temp.path().join("missing-claude"))
+        .env("GEMINI_CLI_HOME", temp.path().join("missing-gemini"))
+        .env("QWEN_CODE_HOME", temp.path().join("missing-qwen"))
+        .env("PI_CODING_AGENT_DIR", temp.path().join("missing-pi"))
+        .env("XDG_DATA_HOME", temp.path().join("missing-data"))
+        .env("XDG_CACHE_HOME", temp.path().join("missing-cache"))
+        .env("VSCODE_APPDATA", temp.path().join("missing-vscode"))
+        .env("XURL_AIDER_ROOTS", temp.path().join("missing-aider"))
+        .arg(subcommand);
+    cmd
+}
+
+fn ls_command(temp: &tempfile::TempDir) -> Command {
+    isolated_command(temp, "ls")
+}
+
+#[test]
+fn ls_lists_threads_with_first_prompt_and_message_count() {
+    let temp = setup_codex_tree();
+
+    ls_command(&temp)
+        .env("CODEX_HOME", temp.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("# Threads"))
+        .stdout(predicate::str::contains("- Count: `1`"))
+        .stdout(predicate::str::contains(format!(
+            "## 1. `{}`",
+            agents_codex_uri()
+        )))
+        .stdout(predicate::str::contains("- Title: hello"))
+        .stdout(predicate::str::contains("- Messages: `2`"));
+}
+
+#[test]
+fn ls_filters_by_provider_and_window() {
+    let temp = setup_codex_tree();
+    let amp = setup_amp_tree();
+
+    ls_command(&temp)
+        .env("CODEX_HOME", temp.path())
+        .env("XDG_DATA_HOME", amp.path())
+        .args(["--provider", "amp", "--since", "1h"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(format!(
+            "agents://amp/{AMP_SESSION_ID}"
+        )))
+        .stdout(predicate::str::contains(SESSION_ID).not());
+
+    ls_command(&temp)
+        .args(["--provider", "windsurf"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("unknown provider `windsurf`"));
+}
+
+#[test]
+fn ls_filters_by_cwd_across_real_fixtures() {
+    let temp = tempdir().expect("tempdir");
+
+    ls_command(&temp)
+        .env("CODEX_HOME", codex_real_fixture_root())
+        .env("CLAUDE_CONFIG_DIR", claude_real_fixture_root())
+        .env("PI_CODING_AGENT_DIR", pi_real_fixture_root())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(agents_uri(
+            "codex",
+            REAL_FIXTURE_MAIN_ID,
+        )))
+        .stdout(predicate::str::contains(agents_uri(
+            "claude",
+            CLAUDE_REAL_MAIN_ID,
+        )))
+        .stdout(predicate::str::contains(agents_uri(
+            "pi",
+            PI_REAL_SESSION_ID,
+        )))
+        .stdout(predicate::str::contains(format!(
+            "- Parent: `{}`",
+            agents_uri("codex", REAL_FIXTURE_MAIN_ID)
+        )))
+        .stdout(predicate::str::contains("- Archived: `yes`"))
+        .stdout(predicate::str::contains(CLAUDE_REAL_AGENT_ID).not());
+
+    ls_command(&temp)
+        .env("CODEX_HOME", codex_real_fixture_root())
+        .env("PI_CODING_AGENT_DIR", pi_real_fixture_root())
+        .args(["--cwd", "/redacted/workspace"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("- Count: `1`"))
+        .stdout(predicate::str::contains(agents_uri(
+            "pi",
+            PI_REAL_SESSION_ID,
+        )));
+
+    ls_command(&temp)
+        .env("PI_CODING_AGENT_DIR", pi_real_fixture_root())
+        .args(["--cwd", "/nonexistent/project"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("_No threads found._"));
+}
+
+#[test]
+fn json_format_outputs_head_and_timeline() {
+    let temp = setup_codex_tree();
+
+    let output = Command::new(assert_cmd::cargo::cargo_bin!("xurl"))
+        .env("CODEX_HOME", temp.path())
+        .env("CLAUDE_CONFIG_DIR", temp.path().join("missing-claude"))
+        .args(["--format", "json"])
+        .arg(agents_codex_uri())
+        .output()
+        .expect("run xurl");
+    assert!(output.status.success());
+
+    let value: serde_json::Value = serde_json::from_slice(&output.stdout).expect("valid json");
+    assert_eq!(value["uri"], agents_codex_uri());
+    assert_eq!(value["provider"], "codex");
+    assert_eq!(value["mode"], "subagent_index");
+    assert_eq!(value["subagents"], serde_json::json!([]));
+    assert_eq!(
+        value["timeline"],
+        serde_json::json!([
+            {"index": 1, "type": "message", "role": "user", "text": "hello"},
+            {"index": 2, "type": "message", "role": "assistant", "text": "world"},
+        ])
+    );
+}
+
+#[test]
+fn jsonl_format_outputs_one_record_per_entry() {
+    let temp = setup_amp_tree();
+
+    let output = Command::new(assert_cmd::cargo::cargo_bin!("xurl"))
+        .env("XDG_DATA_HOME", temp.path())
+        .args(["--format", "jsonl"])
+        .arg(amp_uri())
+        .output()
+        .expect("run xurl");
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8(output.stdout).expect("utf8");
+    let records = stdout
+        .lines()
+        .map(|line| serde_json::from_str::<serde_json::Value>(line).expect("valid json line"))
+        .collect::<Vec<_>>();
+    assert_eq!(records.len(), 2);
+    assert_eq!(records[0]["role"], "user");
+    assert_eq!(records[1]["index"], 2);
+    assert_eq!(records[1]["text"], "analyze\n\nworld");
+}
+
+#[test]
+fn json_head_outputs_pi_entries_and_subagent_detail() {
+    let temp = setup_pi_tree();
+
+    let output = Command::new(assert_cmd::cargo::cargo_bin!("xurl"))
+        .env("PI_CODING_AGENT_DIR", temp.path().join("agent"))
+        .args(["-I", "--format", "json"])
+        .arg(pi_uri())
+        .output()
+        .expect("run xurl");
+    assert!(output.status.success());
+    let value: serde_json::Value = serde_json::from_slice(&output.stdout).expect("valid json");
+    assert_eq!(value["mode"], "pi_entry_index");
+    assert!(value.get("timeline").is_none());
+    assert!(
+        value["entries"]
+            .as_array()
+            .expect("entries")
+            .iter()
+            .any(|entry| entry["entry_id"] == PI_ENTRY_ID)
+    );
+
+    let temp = setup_claude_subagent_tree();
+    let output = Command::new(assert_cmd::cargo::cargo_bin!("xurl"))
+        .env("CLAUDE_CONFIG_DIR", temp.path())
+        .args(["--format", "json"])
+        .arg(claude_subagent_uri())
+        .output()
+        .expect("run xurl");
+    assert!(output.status.success());
+    let value: serde_json::Value = serde_json::from_slice(&output.stdout).expect("valid json");
+    assert_eq!(value["mode"], "subagent_detail");
+    assert_eq!(value["agent_id"], CLAUDE_AGENT_ID);
+    assert_eq!(value["subagent"]["query"]["agent_id"], CLAUDE_AGENT_ID);
+    assert!(value.get("timeline").is_none());
+}
+
+#[test]
+fn ls_supports_json_formats() {
+    let temp = setup_codex_tree();
+
+    let output = ls_command(&temp)
+        .env("CODEX_HOME", temp.path())
+        .args(["--format", "jsonl"])
+        .output()
+        .expect("run xurl");
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).expect("utf8");
+    let record: serde_json::Value = serde_json::from_str(stdout.trim()).expect("single json line");
+    assert_eq!(record["provider"], "codex");
+    assert_eq!(record["session_id"], SESSION_ID);
+    assert_eq!(record["message_count"], 2);
+
+    let output = ls_command(&temp)
+        .env("CODEX_HOME", temp.path())
+        .args(["--format", "json"])
+        .output()
+        .expect("run xurl");
+    let value: serde_json::Value = serde_json::from_slice(&output.stdout).expect("valid json");
+    assert_eq!(value["sessions"][0]["session_id"], SESSION_ID);
+}
+
+#[test]
+fn follow_streams_appended_entries() {
+    use std::io::{BufRead, BufReader, Write};
+    use std::process::Stdio;
+    use std::sync::mpsc;
+    use std::time::Duration;
+
+    let temp = tempdir().expect("tempdir");
+    let thread_path = temp
+        .path()
+        .join(format!("projects/-tmp-project/{CLAUDE_SESSION_ID}.jsonl"));
+    fs::create_dir_all(thread_path.parent().expect("parent")).expect("mkdir");
+    fs::write(
+        &thread_path,
+        "{\"type\":\"user\",\"message\":{\"role\":\"user\",\"content\":\"hello\"}}\n",
+    )
+    .expect("write");
+
+    let mut child = std::process::Command::new(assert_cmd::cargo::cargo_bin!("xurl"))
+        .env("CLAUDE_CONFIG_DIR", temp.path())
+        .args(["--follow", "--format", "jsonl"])
+        .arg(format!("agents://claude/{CLAUDE_SESSION_ID}"))
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("spawn xurl");
+
+    let stdout = child.stdout.take().expect("stdout");
+    let (tx, rx) = mpsc::channel();
+    std::thread::spawn(move || {
+        for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+            if tx.send(line).is_err() {
+                break;
+            }
+        }
+    });
+    let next_record = || {
+        let line = rx
+            .recv_timeout(Duration::from_secs(10))
+            .expect("follow output");
+        serde_json::from_str::<serde_json::Value>(&line).expect("valid json line")
+    };
+
+    let first = next_record();
+    assert_eq!(first["event"], "entry");
+    assert_eq!(first["index"], 1);
+    assert_eq!(first["text"], "hello");
+
+    let mut file = fs::OpenOptions::new()
+        .append(true)
+        .open(&thread_path)
+        .expect("open");
+    writeln!(
+        file,
+        r#"{{"type":"assistant","message":{{"role":"assistant","content":[{{"type":"text","text":"world"}},{{"type":"tool_use","name":"Bash","input":{{"command":"ls"}}}}]}}}}"#
+    )
+    .expect("append");
+    drop(file);
+
+    let second = next_record();
+    assert_eq!(second["index"], 2);
+    assert_eq!(second["role"], "assistant");
+    assert_eq!(second["text"], "world");
+
+    let third = next_record();
+    assert_eq!(third["event"], "tool_call");
+    assert_eq!(third["name"], "Bash");
+
+    child.kill().expect("kill");
+    child.wait().expect("wait");
+}
+
+#[test]
+fn follow_rejects_json_format() {
+    let temp = setup_amp_tree();
+
+    Command::new(assert_cmd::cargo::cargo_bin!("xurl"))
+        .env("XDG_DATA_HOME", temp.path())
+        .args(["-f", "--format", "json"])
+        .arg(amp_uri())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("use --format markdown or jsonl"));
+}
+
+#[test]
+fn follow_rejects_view_flags_and_query_keys() {
+    let temp = setup_amp_tree();
+
+    Command::new(assert_cmd::cargo::cargo_bin!("xurl"))
+        .env("XDG_DATA_HOME", temp.path())
+        .args(["-f", "--role", "user"])
+        .arg(amp_uri())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("cannot be used with"));
+
+    for query in [
+        "tools=1",
+        "roles=assistant",
+        "thinking=0",
+        "max_tool_output=10",
+    ] {
+        Command::new(assert_cmd::cargo::cargo_bin!("xurl"))
+            .env("XDG_DATA_HOME", temp.path())
+            .arg("-f")
+            .arg(format!("agents://amp/{AMP_SESSION_ID}?{query}"))
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains(format!(
+                "the URI query key `{}` cannot be used with --follow",
+                query.split('=').next().unwrap_or_default()
+            )));
+    }
+}
+
+#[test]
+fn max_tool_output_requires_tools() {
+    let temp = setup_amp_tree();
+
+    Command::new(assert_cmd::cargo::cargo_bin!("xurl"))
+        .env("XDG_DATA_HOME", temp.path())
+        .args(["--max-tool-output", "12"])
+        .arg(amp_uri())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("--tools"));
+
+    Command::new(assert_cmd::cargo::cargo_bin!("xurl"))
+        .env("XDG_DATA_HOME", temp.path())
+        .arg(format!("{}?tools=1", agents_uri("amp", AMP_SESSION_ID)))
+        .args(["--max-tool-output", "500"])
+        .assert()
+        .success();
+}
+
+#[test]
+fn tools_flag_renders_paired_tool_calls_inline() {
+    let temp = setup_codex_subagent_tree();
+
+    Command::new(assert_cmd::cargo::cargo_bin!("xurl"))
+        .env("CODEX_HOME", temp.path())
+        .arg(agents_codex_uri())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Tool `spawn_agent`").not());
+
+    Command::new(assert_cmd::cargo::cargo_bin!("xurl"))
+        .env("CODEX_HOME", temp.path())
+        .args(["--tools", "--max-tool-output", "12"])
+        .arg(agents_codex_uri())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("## 1. Tool `spawn_agent`"))
+        .stdout(predicate::str::contains(
+            "## 2. Tool `wait`\n\n```json\n{\n  \"ids\": [",
+        ))
+        .stdout(predicate::str::contains(
+            "Output:\n\n```text\n{\"status\":{\"\n```",
+        ))
+        .stdout(predicate::str::contains("_Output truncated._"));
+
+    let output = Command::new(assert_cmd::cargo::cargo_bin!("xurl"))
+        .env("CODEX_HOME", temp.path())
+        .args(["--tools", "--format", "jsonl"])
+        .arg(agents_codex_uri())
+        .output()
+        .expect("run xurl");
+    assert!(output.status.success());
+    let records = String::from_utf8(output.stdout)
+        .expect("utf8")
+        .lines()
+        .map(|line| serde_json::from_str::<serde_json::Value>(line).expect("valid json line"))
+        .collect::<Vec<_>>();
+    assert_eq!(records.len(), 3);
+    assert_eq!(records[2]["type"], "tool");
+    assert_eq!(records[2]["name"], "close_agent");
+    assert_eq!(records[2]["call_id"], "call_close");
+    assert_eq!(records[2]["args"]["id"], SUBAGENT_ID);
+}
+
+/// Write a Claude thread from JSON records under `projects/-repo`.
+fn setup_claude_tool_thread(records: &[serde_json::Value]) -> tempfile::TempDir {
+    let temp = tempdir().expect("tempdir");
+    let thread_path = temp
+        .path()
+        .join(format!("projects/-repo/{CLAUDE_SESSION_ID}.jsonl"));
+    fs::create_dir_all(thread_path.parent().expect("parent")).expect("mkdir");
+    let raw = records
+        .iter()
+        .map(|record| format!("{record}\n"))
+        .collect::<String>();
+    fs::write(&thread_path, raw).expect("write");
+    temp
+}
+
+#[test]
+fn files_subcommand_lists_touched_paths_relative_to_cwd() {
+    let temp = setup_claude_tool_thread(&[
+        serde_json::json!({"type": "user", "cwd": "/repo", "sessionId": CLAUDE_SESSION_ID, "message": {"role": "user", "content": "fix the bug"}}),
+        serde_json::json!({"type": "assistant", "message": {"role": "assistant", "content": [
+            {"type": "tool_use", "id": "toolu_read", "name": "Read", "input": {"file_path": "/repo/src/lib.rs"}},
+            {"type": "tool_use", "id": "toolu_edit", "name": "Edit", "input": {"file_path": "/repo/src/lib.rs", "old_string": "a", "new_string": "b"}},
+            {"type": "tool_use", "id": "toolu_write", "name": "Write", "input": {"file_path": "/repo/notes.md", "content": "x"}}
+        ]}}),
+        serde_json::json!({"type": "user", "message": {"role": "user", "content": [
+            {"type": "tool_result", "tool_use_id": "toolu_read", "content": "fn a() {}"},
+            {"type": "tool_result", "tool_use_id": "toolu_edit", "content": "The file /repo/src/lib.rs has been updated."},
+            {"type": "tool_result", "tool_use_id": "toolu_write", "content": "File created successfully at: /repo/notes.md"}
+        ]}}),
+    ]);
+
+    Command::new(assert_cmd::cargo::cargo_bin!("xurl"))
+        .env("CLAUDE_CONFIG_DIR", temp.path())
+        .args(["files", &format!("agents://claude/{CLAUDE_SESSION_ID}")])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("# Files"))
+        .stdout(predicate::str::contains("- Cwd: `/repo`"))
+        .stdout(predicate::str::contains(
+            "## 1. `src/lib.rs`\n\n- Change: `modified`\n- Touches: read by `Read` (turn 1, entry 2); modified by `Edit` (turn 1, entry 3)",
+        ))
+        .stdout(predicate::str::contains(
+            "## 2. `notes.md`\n\n- Change: `created`",
+        ));
+
+    let output = Command::new(assert_cmd::cargo::cargo_bin!("xurl"))
+        .env("CLAUDE_CONFIG_DIR", temp.path())
+        .args(["files", "--format", "jsonl"])
+        .arg(format!("agents://claude/{CLAUDE_SESSION_ID}"))
+        .output()
+        .expect("run xurl");
+    assert!(output.status.success());
+    let files = String::from_utf8(output.stdout)
+        .expect("utf8")
+        .lines()
+        .map(|line| serde_json::from_str::<serde_json::Value>(line).expect("valid json line"))
+        .collect::<Vec<_>>();
+    assert_eq!(files.len(), 2);
+    assert_eq!(files[0]["path"], "src/lib.rs");
+    assert_eq!(files[0]["touches"][1]["change"], "modified");
+    assert_eq!(files[1]["change"], "created");
+}
+
+#[test]
+fn diff_subcommand_outputs_unified_diff_per_file() {
+    let temp = setup_claude_tool_thread(&[
+        serde_json::json!({"type": "user", "cwd": "/repo", "sessionId": CLAUDE_SESSION_ID, "message": {"role": "user", "content": "rename the helper"}}),
+        serde_json::json!({"type": "assistant", "message": {"role": "assistant", "content": [
+            {"type": "tool_use", "id": "toolu_edit", "name": "Edit", "input": {"file_path": "/repo/src/lib.rs", "old_string": "fn old() {\n    1\n}", "new_string": "fn new() {\n    1\n}"}},
+            {"type": "tool_use", "id": "toolu_write", "name": "Write", "input": {"file_path": "/repo/NOTES.md", "content": "done\n"}},
+            {"type": "tool_use", "id": "toolu_fail", "name": "Edit", "input": {"file_path": "/repo/src/main.rs", "old_string": "x", "new_string": "y"}}
+        ]}}),
+        serde_json::json!({"type": "user", "message": {"role": "user", "content": [
+            {"type": "tool_result", "tool_use_id": "toolu_edit", "content": "The file /repo/src/lib.rs has been updated. Here's the result of running `cat -n` on a snippet of the edited file:\n     1\tfn new() {\n     2\t    1\n     3\t}"},
+            {"type": "tool_result", "tool_use_id": "toolu_write", "content": "File created successfully at: /repo/NOTES.md"},
+            {"type": "tool_result", "tool_use_id": "toolu_fail", "content": "String to replace not found", "is_error": true}
+        ]}}),
+    ]);
+
+    Command::new(assert_cmd::cargo::cargo_bin!("xurl"))
+        .env("CLAUDE_CONFIG_DIR", temp.path())
+        .args(["diff", &format!("agents://claude/{CLAUDE_SESSION_ID}")])
+        .assert()
+        .success()
+        .stdout(
+            "diff --git a/src/lib.rs b/src/lib.rs\n--- a/src/lib.rs\n+++ b/src/lib.rs\n\
+             @@ -1,3 +1,3 @@ position unknown\n-fn old() {\n+fn new() {\n     1\n }\n\
+             diff --git a/NOTES.md b/NOTES.md\nnew file mode 100644\n--- /dev/null\n+++ b/NOTES.md\n\
+             @@ -0,0 +1,1 @@\n+done\n",
+        );
+
+    let output = Command::new(assert_cmd::cargo::cargo_bin!("xurl"))
+        .env("CLAUDE_CONFIG_DIR", temp.path())
+        .args(["diff", "--format", "json"])
+        .arg(format!("agents://claude/{CLAUDE_SESSION_ID}"))
+        .output()
+        .expect("run xurl");
+    assert!(output.status.success());
+    let view = serde_json::from_slice::<serde_json::Value>(&output.stdout).expect("valid json");
+    assert_eq!(view["cwd"], "/repo");
+    assert_eq!(view["files"].as_array().expect("files").len(), 2);
+    assert_eq!(view["files"][0]["change"], "modified");
+    assert_eq!(view["files"][0]["complete"], true);
+    assert_eq!(view["files"][1]["change"], "created");
+}
+
+#[test]
+fn search_matches_rendered_messages_across_providers() {
+    let temp = setup_codex_tree();
+    let amp = setup_amp_tree();
+
+    isolated_command(&temp, "search")
+        .env("CODEX_HOME", temp.path())
+        .env("XDG_DATA_HOME", amp.path())
+        .arg("WORLD")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("# Search"))
+        .stdout(predicate::str::contains("- Source: `scan`"))
+        .stdout(predicate::str::contains("- Threads Scanned: `2`"))
+        .stdout(predicate::str::contains("- Count: `2`"))
+        .stdout(predicate::str::contains(format!(
+            "`{}`",
+            agents_codex_uri()
+        )))
+        .stdout(predicate::str::contains(format!(
+            "`agents://amp/{AMP_SESSION_ID}`"
+        )))
+        .stdout(predicate::str::contains("- Message: `2` (assistant)"))
+        .stdout(predicate::str::contains("> **world**"))
+        .stdout(predicate::str::contains("\"role\"").not());
+
+    let output = isolated_command(&temp, "search")
+        .env("CODEX_HOME", temp.path())
+        .env("XDG_DATA_HOME", amp.path())
+        .args(["--provider", "amp", "--format", "jsonl", "world"])
+        .output()
+        .expect("run xurl");
+    assert!(output.status.success());
+    let hits = String::from_utf8(output.stdout)
+        .expect("utf8")
+        .lines()
+        .map(|line| serde_json::from_str::<serde_json::Value>(line).expect("valid json line"))
+        .collect::<Vec<_>>();
+    assert_eq!(hits.len(), 1);
+    assert_eq!(hits[0]["provider"], "amp");
+    assert_eq!(hits[0]["index"], 2);
+    let snippet = hits[0]["snippet"].as_str().expect("snippet");
+    let range = &hits[0]["highlights"][0];
+    let (start, end) = (
+        range[0].as_u64().expect("start") as usize,
+        range[1].as_u64().expect("end") as usize,
+    );
+    assert_eq!(&snippet[start..end], "world");
+
+    isolated_command(&temp, "search")
+        .env("CODEX_HOME", temp.path())
+        .args(["--role", "user", "world"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("_No matches found._"));
+
+    isolated_command(&temp, "search")
+        .env("CODEX_HOME", temp.path())
+        .args(["--until", "2000-01-01", "hello"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("- Threads Scanned: `0`"));
+}
+
+#[test]
+fn search_uses_index_once_rebuilt() {
+    let temp = setup_codex_tree();
+    let cache = tempdir().expect("tempdir");
+
+    isolated_command(&temp, "index")
+        .env("CODEX_HOME", temp.path())
+        .env("XDG_CACHE_HOME", cache.path())
+        .arg("status")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("_No index found."));
+
+    isolated_command(&temp, "index")
+        .env("CODEX_HOME", temp.path())
+        .env("XDG_CACHE_HOME", cache.path())
+        .arg("rebuild")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("# Search Index"))
+        .stdout(predicate::str::contains("- Threads: `1`"))
+        .stdout(predicate::str::contains("- Pending: `0`"))
+        .stdout(predicate::str::contains("## `codex`"));
+    assert!(cache.path().join("xurl/index.sqlite").exists());
+
+    isolated_command(&temp, "index")
+        .env("CODEX_HOME", temp.path())
+        .env("XDG_CACHE_HOME", cache.path())
+        .arg("refresh")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("- Threads: `1`"))
+        .stdout(predicate::str::contains("- Pending: `0`"));
+
+    isolated_command(&temp, "search")
+        .env("CODEX_HOME", temp.path())
+        .env("XDG_CACHE_HOME", cache.path())
+        .arg("world")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("- Source: `index`"))
+        .stdout(predicate::str::contains("- Message: `2` (assistant)"))
+        .stdout(predicate::str::contains("> **world**"));
+
+    isolated_command(&temp, "search")
+        .env("CODEX_HOME", temp.path())
+        .env("XDG_CACHE_HOME", cache.path())
+        .args(["--no-index", "world"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("- Source: `scan`"));
+}
+
+#[test]
+fn mcp_serves_threads_as_tools_and_resources() {
+    let temp = setup_codex_tree();
+    let uri = agents_codex_uri();
+    let requests = [
+        json!({"jsonrpc": "2.0", "id": 1, "method": "initialize", "params": {"protocolVersion": "2025-06-18"}}),
+        json!({"jsonrpc": "2.0", "method": "notifications/initialized"}),
+        json!({"jsonrpc": "2.0", "id": 2, "method": "tools/list"}),
+        json!({"jsonrpc": "2.0", "id": 3, "method": "tools/call", "params": {"name": "read_thread", "arguments": {"uri": uri}}}),
+        json!({"jsonrpc": "2.0", "id": 4, "method": "tools/call", "params": {"name": "thread_head", "arguments": {"uri": "agents://codex/not-a-session"}}}),
+        json!({"jsonrpc": "2.0", "id": 5, "method": "resources/list"}),
+        json!({"jsonrpc": "2.0", "id": 6, "method": "resources/read", "params": {"uri": uri}}),
+        json!({"jsonrpc": "2.0", "id": 7, "method": "bogus"}),
+        json!({"jsonrpc": "2.0", "id": 8, "method": "resources/list", "params": {"cursor": "1"}}),
+        json!({"jsonrpc": "2.0", "id": 9, "method": "resources/list", "params": {"cursor": "next"}}),
+        json!({"jsonrpc": "2.0", "id": 10, "method": "resources/read", "params": {"uri": "agents://codex/019c871c-b1f9-7f60-9c4f-000000000000"}}),
+        json!({"jsonrpc": "2.0", "id": 11, "method": "resources/read", "params": {"uri": "agents://codex/not-a-session"}}),
+    ];
+    let stdin = requests
+        .iter()
+        .map(|request| format!("{request}\n"))
+        .collect::<String>();
+
+    let output = isolated_command(&temp, "mcp")
+        .env("CODEX_HOME", temp.path())
+        .write_stdin(stdin)
+        .output()
+        .expect("run xurl");
+    assert!(output.status.success());
+    let responses = String::from_utf8(output.stdout)
+        .expect("utf8")
+        .lines()
+        .map(|line| serde_json::from_str::<serde_json::Value>(line).expect("valid json line"))
+        .collect::<Vec<_>>();
+    assert_eq!(responses.len(), 11, "notifications get no response");
+
+    assert_eq!(responses[0]["result"]["protocolVersion"], "2025-06-18");
+    assert_eq!(responses[0]["result"]["serverInfo"]["name"], "xurl");
+    let tools = responses[1]["result"]["tools"]
+        .as_array()
+        .expect("tools")
+        .iter()
+        .map(|tool| tool["name"].as_str().expect("name"))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        tools,
+        vec![
+            "read_thread",
+            "thread_head",
+            "list_subagents",
+            "list_active_sessions"
+        ]
+    );
+
+    let thread = &responses[2]["result"];
+    assert_eq!(thread["isError"], false);
+    let text = thread["content"][0]["text"].as_str().expect("text");
+    assert!(text.starts_with("---\n"));
+    assert!(text.contains("## 1. User"));
+
+    assert_eq!(responses[3]["result"]["isError"], true);
+
+    assert_eq!(responses[4]["result"]["resources"][0]["uri"], uri);
+    assert!(responses[4]["result"].get("nextCursor").is_none());
+    assert_eq!(responses[5]["result"]["contents"][0]["text"], text);
+    assert_eq!(responses[6]["error"]["code"], -32601);
+    assert_eq!(responses[7]["result"]["resources"], json!([]));
+    assert_eq!(responses[8]["error"]["code"], -32602);
+    assert_eq!(responses[9]["error"]["code"], -32002);
+    assert_eq!(responses[10]["error"]["code"], -32602);
+}
+
+#[test]
+fn serve_negotiates_views_and_streams_events() {
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::TcpStream;
+    use std::process::Stdio;
+    use std::time::Duration;
+
+    let temp = setup_codex_tree();
+    let mut child = std::process::Command::new(assert_cmd::cargo::cargo_bin!("xurl"))
+        .env("CODEX_HOME", temp.path())
+        .env("CLAUDE_CONFIG_DIR", temp.path().join("missing-claude"))
+        .env("GEMINI_CLI_HOME", temp.path().join("missing-gemini"))
+        .env("QWEN_CODE_HOME", temp.path().join("missing-qwen"))
+        .env("PI_CODING_AGENT_DIR", temp.path().join("missing-pi"))
+        .env("XDG_DATA_HOME", temp.path().join("missing-data"))
+        .env("VSCODE_APPDATA", temp.path().join("missing-vscode"))
+        .env("XURL_AIDER_ROOTS", temp.path().join("missing-aider"))
+        .args(["serve", "--bind", "127.0.0.1:0"])
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("spawn xurl");
+
+    let mut banner = String::new();
+    BufReader::new(child.stderr.take().expect("stderr"))
+        .read_line(&mut banner)
+        .expect("read banner");
+    let address = banner
+        .trim()
+        .strip_prefix("serving threads on http://")
+        .and_then(|rest| rest.strip_suffix('/'))
+        .expect("listening address")
+        .to_string();
+
+    let port = address.rsplit(':').next().expect("port").to_string();
+    let send = |request: String| {
+        let mut stream = TcpStream::connect(&address).expect("connect");
+        stream
+            .set_read_timeout(Some(Duration::from_secs(10)))
+            .expect("timeout");
+        stream.write_all(request.as_bytes()).expect("send request");
+        stream
+    };
+    let connect = |path: &str, accept: &str| {
+        send(format!(
+            "GET {path} HTTP/1.1\r\nHost: localhost:{port}\r\nAccept: {accept}\r\n\r\n"
+        ))
+    };
+    let read = |mut stream: TcpStream| {
+        let mut response = String::new();
+        stream.read_to_string(&mut response).expect("read response");
+        let (head, body) = response.split_once("\r\n\r\n").expect("headers");
+        (head.to_string(), body.to_string())
+    };
+    let get = |path: &str, accept: &str| read(connect(path, accept));
+    let thread_path = format!("/agents/codex/{SESSION_ID}");
+
+    let (head, body) = get(&thread_path, "*/*");
+    assert!(head.starts_with("HTTP/1.1 200 OK"));
+    assert!(head.contains("Content-Type: text/markdown"));
+    assert!(body.starts_with("---\n"));
+    assert!(body.contains("## 2. Assistant"));
+
+    let (head, body) = get(&thread_path, "text/html,application/xhtml+xml;q=0.9");
+    assert!(head.contains("Content-Type: text/html"));
+    assert!(body.starts_with("<!doctype html>"));
+    assert!(body.contains("world"));
+
+    let (head, body) = get(&thread_path, "application/json");
+    assert!(head.contains("Content-Type: application/json"));
+    let view = serde_json::from_str::<serde_json::Value>(&body).expect("json");
+    assert_eq!(view["timeline"][1]["text"], "world");
+
+    let (_, body) = get(&format!("/head/codex/{SESSION_ID}"), "text/markdown");
+    assert!(body.starts_with("---\n") && !body.contains("## 1. User"));
+
+    let (_, body) = get("/agents?provider=codex", "application/json");
+    let listing = serde_json::from_str::<serde_json::Value>(&body).expect("json");
+    assert_eq!(listing["sessions"][0]["session_id"], SESSION_ID);
+
+    let (head, _) = get("/agents/codex/not-a-session", "*/*");
+    assert!(head.starts_with("HTTP/1.1 400"));
+    let (head, _) = get(&format!("{thread_path}?roles=user%26tools%3D1"), "*/*");
+    assert!(head.starts_with("HTTP/1.1 400"));
+    let (head, _) = get(&format!("{thread_path}%3Ftools=1"), "*/*");
+    assert!(head.starts_with("HTTP/1.1 400"));
+    let (head, body) = get(&format!("{thread_path}?roles=user%2Cassistant"), "*/*");
+    assert!(head.starts_with("HTTP/1.1 200 OK"));
+    assert!(body.contains("## 2. Assistant"));
+    let (head, _) = get("/nowhere", "*/*");
+    assert!(head.starts_with("HTTP/1.1 404"));
+
+    for headers in [
+        format!("Host: attacker.example:{port}"),
+        "Host: localhost".to_string(),
+        format!("Host: 127.0.0.1:{port}\r\nOrigin: http://attacker.example"),
+    ] {
+        let (head, _) = read(send(format!(
+            "GET {thread_path} HTTP/1.1\r\n{headers}\r\n\r\n"
+        )));
+        assert!(head.starts_with("HTTP/1.1 403"), "{headers}: {head}");
+    }
+    let (head, _) = read(send(format!(
+        "GET {thread_path} HTTP/1.1\r\nHost: [::1]:{port}\r\nOrigin: http://localhost:{port}\r\n\r\n"
+    )));
+    assert!(head.starts_with("HTTP/1.1 200 OK"));
+
+    let mut events = BufReader::new(connect(&format!("/events/codex/{SESSION_ID}"), "*/*"));
+    let mut lines = Vec::new();
+    while lines.len() < 8 {
+        let mut line = String::new();
+        events.read_line(&mut line).expect("read event");
+        lines.push(line.trim_end().to_string());
+    }
+    assert!(lines.contains(&"Content-Type: text/event-stream".to_string()));
+    assert!(lines.contains(&"event: entry".to_string()));
+    let data = lines
+        .iter()
+        .find_map(|line| line.strip_prefix("data: "))
+        .expect("event data");
+    let entry = serde_json::from_str::<serde_json::Value>(data).expect("json");
+    assert_eq!(entry["text"], "hello");
+
+    child.kill().expect("kill");
+    child.wait().expect("wait");
+}
+
+#[test]
+fn serve_refuses_non_loopback_bind_without_opt_in() {
+    let temp = tempdir().expect("tempdir");
+    isolated_command(&temp, "serve")
+        .args(["--bind", "0.0.0.0:0"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("pass --allow-remote"));
+}
+
+#[test]
+fn html_format_renders_self_contained_page() {
+    let temp = setup_codex_tree();
+
+    let output = Command::new(assert_cmd::cargo::cargo_bin!("xurl"))
+        .env("CODEX_HOME", temp.path())
+        .args(["--format", "html"])
+        .arg(agents_codex_uri())
+        .output()
+        .expect("run xurl");
+    assert!(output.status.success());
+    let html = String::from_utf8(output.stdout).expect("utf8");
+    assert!(html.starts_with("<!doctype html>"));
+    assert!(html.contains("<style>"));
+    assert!(!html.contains("<script") && !html.contains("<link"));
+    assert!(html.contains(&format!(
+        "<tr><th>URI</th><td><code>{}</code></td></tr>",
+        agents_codex_uri()
+    )));
+    assert!(html.contains("<article class=\"entry message user\" id=\"msg-1\">"));
+    assert!(html.contains("<article class=\"entry message assistant\" id=\"msg-2\">"));
+    assert!(html.contains("<div class=\"text\">world</div>"));
+
+    ls_command(&temp)
+        .env("CODEX_HOME", temp.path())
+        .args(["--format", "html"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "--format html is only supported when reading a thread",
+        ));
+}
+
+#[test]
+fn uri_fragment_selects_timeline_entries() {
+    let temp = setup_codex_tree();
+
+    Command::new(assert_cmd::cargo::cargo_bin!("xurl"))
+        .env("CODEX_HOME", temp.path())
+        .arg(format!("{}#2", agents_codex_uri()))
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(format!(
+            "uri: '{}#2'",
+            agents_codex_uri()
+        )))
+        .stdout(predicate::str::contains("## 2. Assistant"))
+        .stdout(predicate::str::contains("## 1. User").not());
+
+    let output = Command::new(assert_cmd::cargo::cargo_bin!("xurl"))
+        .env("CODEX_HOME", temp.path())
+        .args(["--format", "jsonl"])
+        .arg(format!("{}#last", agents_codex_uri()))
+        .output()
+        .expect("run xurl");
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).expect("utf8");
+    let records = stdout.lines().collect::<Vec<_>>();
+    assert_eq!(records.len(), 1);
+    let record = serde_json::from_str::<serde_json::Value>(records[0]).expect("json");
+    assert_eq!(record["index"], 2);
+
+    Command::new(assert_cmd::cargo::cargo_bin!("xurl"))
+        .env("CODEX_HOME", temp.path())
+        .arg(format!("{}#3-5", agents_codex_uri()))
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "#3-5 selects no timeline entries; the thread has 2",
+        ));
+
+    Command::new(assert_cmd::cargo::cargo_bin!("xurl"))
+        .env("CODEX_HOME", temp.path())
+        .arg(format!("{}#latest", agents_codex_uri()))
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("invalid fragment `#latest`"));
+}
+
+#[test]
+fn uri_fragment_numbers_entries_the_same_with_and_without_tools() {
+    let temp = setup_claude_tool_thread(&[
+        serde_json::json!({"type": "user", "cwd": "/repo", "sessionId": CLAUDE_SESSION_ID, "message": {"role": "user", "content": "list the files"}}),
+        serde_json::json!({"type": "assistant", "message": {"role": "assistant", "content": [
+            {"type": "text", "text": "Listing them."},
+            {"type": "tool_use", "id": "toolu_ls", "name": "Bash", "input": {"command": "ls"}}
+        ]}}),
+        serde_json::json!({"type": "user", "message": {"role": "user", "content": [
+            {"type": "tool_result", "tool_use_id": "toolu_ls", "content": "src"}
+        ]}}),
+        serde_json::json!({"type": "assistant", "message": {"role": "assistant", "content": [{"type": "text", "text": "Only src."}]}}),
+    ]);
+    let uri = format!("agents://claude/{CLAUDE_SESSION_ID}");
+
+    for target in [format!("{uri}#4"), format!("{uri}?tools=1#4")] {
+        isolated_command(&temp, &target)
+            .env("CLAUDE_CONFIG_DIR", temp.path())
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("## 4. Assistant\n\nOnly src."))
+            .stdout(predicate::str::contains("Listing them.").not());
+    }
+
+    isolated_command(&temp, &format!("{uri}?tools=1#3"))
+        .env("CLAUDE_CONFIG_DIR", temp.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("## 3. Tool `Bash`"));
+    isolated_command(&temp, &uri)
+        .env("CLAUDE_CONFIG_DIR", temp.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("## 2. Assistant"))
+        .stdout(predicate::str::contains("## 4. Assistant"))
+        .stdout(predicate::str::contains("## 3.").not());
+}
+
+#[test]
+fn uri_query_sets_view_options_and_flags_override_it() {
+    let temp = setup_codex_tree();
+
+    Command::new(assert_cmd::cargo::cargo_bin!("xurl"))
+        .env("CODEX_HOME", temp.path())
+        .arg(format!("{}?thinking=0&roles=assistant", agents_codex_uri()))
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(format!(
+            "uri: '{}?roles=assistant&thinking=0'",
+            agents_codex_uri()
+        )))
+        .stdout(predicate::str::contains("## 2. Assistant"))
+        .stdout(predicate::str::contains("## 1. User").not());
+
+    let output = Command::new(assert_cmd::cargo::cargo_bin!("xurl"))
+        .env("CODEX_HOME", temp.path())
+        .arg(format!("{}?format=jsonl", agents_codex_uri()))
+        .output()
+        .expect("run xurl");
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).expect("utf8");
+    assert_eq!(stdout.lines().count(), 2);
+
+    Command::new(assert_cmd::cargo::cargo_bin!("xurl"))
+        .env("CODEX_HOME", temp.path())
+        .args(["--format", "markdown", "--role", "user"])
+        .arg(format!(
+            "{}?format=json&roles=assistant",
+            agents_codex_uri()
+        ))
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with("---\n"))
+        .stdout(predicate::str::contains("## 1. User"))
+        .stdout(predicate::str::contains("## 2. Assistant").not());
+
+    Command::new(assert_cmd::cargo::cargo_bin!("xurl"))
+        .env("CODEX_HOME", temp.path())
+        .arg(format!("{}?verbose=1", agents_codex_uri()))
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "unknown query parameter `verbose`; supported: format, head, max_tool_output, roles, thinking, tools",
+        ));
+}
+
+#[test]
+fn symbolic_uris_resolve_to_the_newest_matching_thread() {
+    let temp = setup_claude_tool_thread(&[
+        serde_json::json!({"type": "user", "cwd": "/repo", "sessionId": CLAUDE_SESSION_ID, "message": {"role": "user", "content": "fix the bug"}}),
+        serde_json::json!({"type": "assistant", "message": {"role": "assistant", "content": [{"type": "text", "text": "fixed"}]}}),
+    ]);
+
+    Command::new(assert_cmd::cargo::cargo_bin!("xurl"))
+        .env("CODEX_HOME", temp.path().join("missing-codex"))
+        .env("CLAUDE_CONFIG_DIR", temp.path())
+        .env("GEMINI_CLI_HOME", temp.path().join("missing-gemini"))
+        .env("QWEN_CODE_HOME", temp.path().join("missing-qwen"))
+        .env("PI_CODING_AGENT_DIR", temp.path().join("missing-pi"))
+        .env("XDG_DATA_HOME", temp.path().join("missing-data"))
+        .env("VSCODE_APPDATA", temp.path().join("missing-vscode"))
+        .env("XURL_AIDER_ROOTS", temp.path().join("missing-aider"))
+        .arg("agents://any/latest?cwd=/repo&roles=assistant")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(format!(
+            "uri: 'agents://claude/{CLAUDE_SESSION_ID}?roles=assistant'"
+        )))
+        .stdout(predicate::str::contains("fixed"));
+
+    isolated_command(&temp, "files")
+        .env("CLAUDE_CONFIG_DIR", temp.path())
+        .arg("agents://claude/latest?cwd=/elsewhere")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "no thread matches agents://claude/latest under /elsewhere",
+        ));
+
+    isolated_command(&temp, "files")
+        .env("CLAUDE_CONFIG_DIR", temp.path())
+        .arg("agents://claude/previous")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "no thread matches agents://claude/previous",
+        ));
+}
+
+#[test]
+fn short_session_id_prefixes_expand_to_the_unique_match() {
+    let temp = setup_codex_tree();
+    let twin = "019c871c-0000-7000-8000-000000000000";
+    fs::write(
+        temp.path().join(format!(
+            "sessions/2026/02/23/rollout-2026-02-23T05-00-00-{twin}.jsonl"
+        )),
+        "{\"type\":\"response_item\",\"payload\":{\"type\":\"message\",\"role\":\"user\",\"content\":[{\"type\":\"input_text\",\"text\":\"twin\"}]}}\n",
+    )
+    .expect("write");
+
+    Command::new(assert_cmd::cargo::cargo_bin!("xurl"))
+        .env("CODEX_HOME", temp.path())
+        .arg("agents://codex/019c871c-b1f9")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(format!(
+            "uri: 'agents://codex/{SESSION_ID}'"
+        )))
+        .stdout(predicate::str::contains("world"));
+
+    Command::new(assert_cmd::cargo::cargo_bin!("xurl"))
+        .env("CODEX_HOME", temp.path())
+        .arg("codex://019C871C")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(format!(
+            "agents://codex/019C871C matches more than one thread: agents://codex/{twin}, agents://codex/{SESSION_ID}"
+        )));
+}
+
+#[test]
+fn provider_less_ids_are_found_in_any_store() {
+    let temp = setup_codex_tree();
+
+    for target in [SESSION_ID.to_string(), format!("agents://{SESSION_ID}")] {
+        isolated_command(&temp, "files")
+            .env("CODEX_HOME", temp.path())
+            .args(["--format", "json"])
+            .arg(&target)
+            .assert()
+            .success()
+            .stdout(predicate::str::contains(format!(
+                "\"uri\": \"agents://codex/{SESSION_ID}\""
+            )));
+    }
+
+    isolated_command(&temp, "files")
+        .arg(format!("agents://{SESSION_ID}"))
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(format!(
+            "no thread matches agents://{SESSION_ID}"
+        )));
+}
+
+#[test]
+fn ps_flags_recent_threads_stopped_mid_turn() {
+    let temp = tempdir().expect("tempdir");
+    let sessions = temp.path().join("codex/sessions/2026/02/23");
+    fs::create_dir_all(&sessions).expect("mkdir");
+    fs::write(
+        sessions.join(format!("rollout-2026-02-23T04-48-50-{SESSION_ID}.jsonl")),
+        "{\"type\":\"response_item\",\"payload\":{\"type\":\"message\",\"role\":\"user\",\"content\":[{\"type\":\"input_text\",\"text\":\"hello\"}]}}\n",
+    )
+    .expect("write");
+    fs::write(
+        sessions.join(format!("rollout-2026-02-23T05-00-00-{SUBAGENT_ID}.jsonl")),
+        "{\"type\":\"response_item\",\"payload\":{\"type\":\"message\",\"role\":\"user\",\"content\":[{\"type\":\"input_text\",\"text\":\"hi\"}]}}\n{\"type\":\"response_item\",\"payload\":{\"type\":\"message\",\"role\":\"assistant\",\"content\":[{\"type\":\"output_text\",\"text\":\"done\"}]}}\n",
+    )
+    .expect("write");
+    // Cline writes from inside the editor, so no process ever claims its
+    // tasks; one waiting on the agent is still not stale.
+    let task_id = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .expect("clock")
+        .as_millis();
+    let task = temp.path().join(format!(
+        "vscode/Code/User/globalStorage/saoudrizwan.claude-dev/tasks/{task_id}"
+    ));
+    fs::create_dir_all(&task).expect("mkdir");
+    fs::write(
+        task.join("api_conversation_history.json"),
+        r#"[{"role":"user","content":[{"type":"text","text":"<task>\nList the sources\n</task>"}]}]"#,
+    )
+    .expect("write");
+
+    let output = isolated_command(&temp, "ps")
+        .env("CODEX_HOME", temp.path().join("codex"))
+        .env("VSCODE_APPDATA", temp.path().join("vscode"))
+        .args(["--format", "json"])
+        .output()
+        .expect("run xurl");
+    assert!(output.status.success());
+    let view = serde_json::from_slice::<serde_json::Value>(&output.stdout).expect("json");
+    let stale = view["stale"].as_array().expect("stale list");
+    assert_eq!(stale.len(), 1);
+    assert_eq!(stale[0]["uri"], format!("agents://codex/{SESSION_ID}"));
+    assert_eq!(stale[0]["reason"], "no reply to the last user message");
+
+    isolated_command(&temp, "ps")
+        .env("CODEX_HOME", temp.path().join("codex"))
+        .args(["--stale-within", "0s"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("# Running Agents"))
+        .stdout(predicate::str::contains("- Stale: `0`"));
+}
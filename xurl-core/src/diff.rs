@@ -0,0 +1,723 @@
+//! Reconstruct the edits made during a thread as unified diffs.
+//!
+//! Tool calls almost never record line numbers, so diffs are rebuilt from
+//! the text each call replaced:
+//!
+//! - Files created during the thread are replayed in memory and shown as a
+//!   single new-file diff of their final content.
+//! - Edits to files that existed before become one hunk per edit, using the
+//!   unchanged lines the edit itself carried as context. Their position is
+//!   unknown, so hunks start at line 1 and are labelled as such; apply them
+//!   with `git apply --unidiff-zero` so git locates them by context.
+//! - A string replacement may start or end mid-line. When the lines around
+//!   it are known, from an earlier read of the file or the numbered snippet
+//!   an edit tool printed, it is widened to whole lines; otherwise its hunk
+//!   is kept as is and noted, since git may not find it.
+//!
+//! Anything that cannot be expressed (whole-file rewrites of unseen content,
+//! deletions, notebook edits) is reported in [`FileDiff::notes`].
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use serde_json::Value;
+
+use crate::files::{
+    EDIT_TOOLS, READ_TOOLS, WRITE_TOOLS, normalize_path, patch_text, tool_paths, tool_workdir,
+    write_change,
+};
+use crate::model::{FileChange, FileDiff, ThreadEntry, TimelineEntry, ToolEntry};
+
+const OLD_TEXT_KEYS: &[&str] = &["old_string", "oldString", "oldText", "old_str"];
+const NEW_TEXT_KEYS: &[&str] = &["new_string", "newString", "newText", "new_str"];
+const REPLACE_ALL_KEYS: &[&str] = &["replace_all", "replaceAll"];
+const UNKNOWN_POSITION: &str = "position unknown";
+
+/// One hunk body: `(' ' | '-' | '+', line)` pairs.
+type HunkLines = Vec<(char, String)>;
+
+enum Edit {
+    Replace {
+        old: String,
+        new: String,
+        all: bool,
+        /// `old` and `new` widened to whole lines, when the lines around
+        /// the edit are known.
+        lines: Option<(String, String)>,
+    },
+    Write {
+        content: String,
+        created: bool,
+    },
+    Hunk(HunkLines),
+    Add(String),
+    Delete,
+    Move(String),
+    Unsupported,
+}
+
+struct FileState {
+    path: String,
+    old_path: Option<String>,
+    created: bool,
+    deleted: bool,
+    /// Current content, known for files created or rewritten in the thread.
+    content: Option<String>,
+    hunks: Vec<HunkLines>,
+    notes: Vec<String>,
+    entries: Vec<usize>,
+}
+
+impl FileState {
+    fn new(path: String) -> Self {
+        Self {
+            path,
+            old_path: None,
+            created: false,
+            deleted: false,
+            content: None,
+            hunks: Vec::new(),
+            notes: Vec::new(),
+            entries: Vec::new(),
+        }
+    }
+
+    fn apply(&mut self, edit: Edit, entry: usize, tool: &str) {
+        match edit {
+            Edit::Replace {
+                old,
+                new,
+                all,
+                lines,
+            } => {
+                if let Some(content) = &mut self.content {
+                    if content.contains(&old) {
+                        *content = if all {
+                            content.replace(&old, &new)
+                        } else {
+                            content.replacen(&old, &new, 1)
+                        };
+                    } else {
+                        self.notes.push(format!(
+                            "entry {entry}: `{tool}` edit did not match the replayed content"
+                        ));
+                    }
+                    return;
+                }
+                if all {
+                    self.notes.push(format!(
+                        "entry {entry}: `{tool}` replaced every occurrence; shown once"
+                    ));
+                }
+                let (old, new) = lines.unwrap_or_else(|| {
+                    self.notes.push(format!(
+                        "entry {entry}: `{tool}` edit may cover only part of a line; its hunk may not apply"
+                    ));
+                    (old, new)
+                });
+                self.push_hunk(replace_hunk(&old, &new));
+            }
+            Edit::Write { content, created } => {
+                if self.content.is_none() {
+                    if created && self.entries.is_empty() {
+                        self.created = true;
+                    } else {
+                        self.notes.push(format!(
+                            "entry {entry}: `{tool}` rewrote the file; its previous content is unknown"
+                        ));
+                    }
+                }
+                self.content = Some(content);
+            }
+            Edit::Hunk(lines) => {
+                if let Some(content) = &mut self.content {
+                    let old = hunk_side(&lines, '+');
+                    let new = hunk_side(&lines, '-');
+                    if content.contains(&old) {
+                        *content = content.replacen(&old, &new, 1);
+                    } else {
+                        self.notes.push(format!(
+                            "entry {entry}: `{tool}` hunk did not match the replayed content"
+                        ));
+                    }
+                    return;
+                }
+                self.push_hunk(lines);
+            }
+            Edit::Add(content) => {
+                self.created = true;
+                self.deleted = false;
+                self.content = Some(content);
+            }
+            Edit::Delete => {
+                self.deleted = true;
+                self.hunks.clear();
+                if !self.created {
+                    self.notes.push(format!(
+                        "entry {entry}: `{tool}` deleted the file; its previous content is unknown"
+                    ));
+                }
+            }
+            Edit::Move(path) => {
+                if !self.created && self.old_path.is_none() {
+                    self.old_path = Some(self.path.clone());
+                }
+                self.path = path;
+            }
+            Edit::Unsupported => {
+                self.notes.push(format!(
+                    "entry {entry}: `{tool}` edits cannot be shown as a diff"
+                ));
+            }
+        }
+    }
+
+    fn push_hunk(&mut self, lines: HunkLines) {
+        if lines.iter().any(|(op, _)| *op != ' ') {
+            self.hunks.push(lines);
+        }
+    }
+
+    fn into_diff(self) -> Option<FileDiff> {
+        if self.created && self.deleted {
+            return None;
+        }
+
+        let mut patch = String::new();
+        let change = if self.created {
+            let content = self.content.as_deref().unwrap_or_default();
+            patch.push_str(&format!(
+                "diff --git a/{0} b/{0}\nnew file mode 100644\n--- /dev/null\n+++ b/{0}\n",
+                self.path
+            ));
+            let lines = content.lines().collect::<Vec<_>>();
+            if !lines.is_empty() {
+                patch.push_str(&format!("@@ -0,0 +1,{} @@\n", lines.len()));
+                for line in lines {
+                    patch.push_str(&format!("+{line}\n"));
+                }
+                if !content.ends_with('\n') {
+                    patch.push_str("\\ No newline at end of file\n");
+                }
+            }
+            FileChange::Created
+        } else if self.deleted {
+            let old_path = self.old_path.as_deref().unwrap_or(&self.path);
+            patch.push_str(&format!(
+                "diff --git a/{0} b/{0}\ndeleted file mode 100644\n--- a/{0}\n+++ /dev/null\n",
+                old_path
+            ));
+            FileChange::Deleted
+        } else {
+            let old_path = self.old_path.as_deref().unwrap_or(&self.path);
+            if self.old_path.is_some() || !self.hunks.is_empty() {
+                patch.push_str(&format!("diff --git a/{old_path} b/{}\n", self.path));
+                if self.old_path.is_some() {
+                    patch.push_str(&format!(
+                        "rename from {old_path}\nrename to {}\n",
+                        self.path
+                    ));
+                }
+            }
+            if !self.hunks.is_empty() {
+                patch.push_str(&format!("--- a/{old_path}\n+++ b/{}\n", self.path));
+            }
+            for hunk in &self.hunks {
+                let old_count = hunk.iter().filter(|(op, _)| *op != '+').count();
+                let new_count = hunk.iter().filter(|(op, _)| *op != '-').count();
+                patch.push_str(&format!(
+                    "@@ -1,{old_count} +1,{new_count} @@ {UNKNOWN_POSITION}\n"
+                ));
+                for (op, line) in hunk {
+                    patch.push_str(&format!("{op}{line}\n"));
+                }
+            }
+            FileChange::Modified
+        };
+
+        Some(FileDiff {
+            complete: self.notes.is_empty(),
+            path: self.path,
+            old_path: self.old_path,
+            change,
+            patch,
+            notes: self.notes,
+            entries: self.entries,
+        })
+    }
+}
+
+/// Rebuild per-file diffs from a timeline built with tool entries enabled.
+///
+/// Files appear in the order they were first edited; reads only supply
+/// the lines around later edits.
+pub fn extract_file_diffs(entries: &[ThreadEntry], cwd: Option<&Path>) -> Vec<FileDiff> {
+    let mut files: Vec<FileState> = Vec::new();
+    let mut reads: HashMap<String, String> = HashMap::new();
+
+    for entry in entries {
+        let TimelineEntry::Tool(tool) = &entry.entry else {
+            continue;
+        };
+        if tool.is_error {
+            continue;
+        }
+
+        let workdir = tool_workdir(tool);
+        if READ_TOOLS.contains(&tool.name.as_str()) {
+            let Some(output) = tool.output.as_deref().filter(|_| !tool.truncated) else {
+                continue;
+            };
+            for raw_path in tool_paths(&tool.args) {
+                if let Some(path) = normalize_path(&raw_path, workdir.as_deref(), cwd) {
+                    let text = numbered_text(output).unwrap_or_else(|| output.to_string());
+                    reads.insert(path, text);
+                }
+            }
+            continue;
+        }
+
+        let snippet = tool.output.as_deref().and_then(numbered_text);
+        for (raw_path, edit) in tool_edits(tool) {
+            let Some(path) = normalize_path(&raw_path, workdir.as_deref(), cwd) else {
+                continue;
+            };
+            let edit = match edit {
+                Edit::Move(target) => match normalize_path(&target, workdir.as_deref(), cwd) {
+                    Some(target) => Edit::Move(target),
+                    None => continue,
+                },
+                Edit::Replace { old, new, all, .. } => {
+                    let lines = whole_lines(&old, &new, reads.get(&path), snippet.as_deref());
+                    Edit::Replace {
+                        old,
+                        new,
+                        all,
+                        lines,
+                    }
+                }
+                other => other,
+            };
+
+            let index = match files.iter().position(|file| file.path == path) {
+                Some(index) => index,
+                None => {
+                    files.push(FileState::new(path));
+                    files.len() - 1
+                }
+            };
+            let file = &mut files[index];
+            file.apply(edit, entry.index, &tool.name);
+            if file.entries.last() != Some(&entry.index) {
+                file.entries.push(entry.index);
+            }
+        }
+    }
+
+    files.into_iter().filter_map(FileState::into_diff).collect()
+}
+
+fn tool_edits(tool: &ToolEntry) -> Vec<(String, Edit)> {
+    let name = tool.name.as_str();
+
+    if let Some(patch) = patch_text(tool) {
+        return parse_patch_edits(&patch);
+    }
+
+    let Some(path) = tool_paths(&tool.args).into_iter().next() else {
+        return Vec::new();
+    };
+    if EDIT_TOOLS.contains(&name) {
+        let edits = match tool.args.get("edits").and_then(Value::as_array) {
+            Some(items) => items.iter().filter_map(replace_edit).collect::<Vec<_>>(),
+            None => replace_edit(&tool.args).into_iter().collect(),
+        };
+        if edits.is_empty() {
+            return vec![(path, Edit::Unsupported)];
+        }
+        return edits.into_iter().map(|edit| (path.clone(), edit)).collect();
+    }
+    if WRITE_TOOLS.contains(&name)
+        && let Some(content) = tool.args.get("content").and_then(Value::as_str)
+    {
+        let created = write_change(tool) == FileChange::Created;
+        return vec![(
+            path,
+            Edit::Write {
+                content: content.to_string(),
+                created,
+            },
+        )];
+    }
+
+    Vec::new()
+}
+
+fn replace_edit(args: &Value) -> Option<Edit> {
+    let text = |keys: &[&str]| {
+        keys.iter()
+            .find_map(|key| args.get(*key).and_then(Value::as_str))
+            .map(ToString::to_string)
+    };
+    Some(Edit::Replace {
+        old: text(OLD_TEXT_KEYS)?,
+        new: text(NEW_TEXT_KEYS)?,
+        all: REPLACE_ALL_KEYS
+            .iter()
+            .any(|key| args.get(*key).and_then(Value::as_bool) == Some(true)),
+        lines: None,
+    })
+}
+
+/// `old` and `new` widened to the whole lines they sit on, found through
+/// `old` in the file as last read or `new` in the edit's own snippet.
+fn whole_lines(
+    old: &str,
+    new: &str,
+    read: Option<&String>,
+    snippet: Option<&str>,
+) -> Option<(String, String)> {
+    let (prefix, suffix) = read
+        .and_then(|content| line_context(old, content))
+        .or_else(|| snippet.and_then(|content| line_context(new, content)))?;
+    Some((
+        format!("{prefix}{old}{suffix}"),
+        format!("{prefix}{new}{suffix}"),
+    ))
+}
+
+/// The rest of the lines around the first occurrence of `text` in
+/// `content`: what precedes it on its first line and follows it on its last.
+fn line_context<'a>(text: &str, content: &'a str) -> Option<(&'a str, &'a str)> {
+    if text.trim().is_empty() {
+        return None;
+    }
+    let start = content.find(text)?;
+    let end = start + text.len();
+    let line_start = content[..start]
+        .rfind('\n')
+        .map_or(0, |newline| newline + 1);
+    let line_end = if text.ends_with('\n') {
+        end
+    } else {
+        content[end..]
+            .find('\n')
+            .map_or(content.len(), |newline| end + newline)
+    };
+    Some((&content[line_start..start], &content[end..line_end]))
+}
+
+/// File text from `cat -n` style output (`    12\tline`, `    12→line` or
+/// `00012| line`), as read and edit tools print it. `None` when no line is
+/// numbered.
+fn numbered_text(output: &str) -> Option<String> {
+    let lines = output
+        .lines()
+        .filter_map(|line| {
+            let line = line.trim_start();
+            let rest = line.trim_start_matches(|ch: char| ch.is_ascii_digit());
+            if rest.len() == line.len() {
+                return None;
+            }
+            rest.strip_prefix('\t')
+                .or_else(|| rest.strip_prefix('→'))
+                .or_else(|| rest.strip_prefix("| "))
+        })
+        .collect::<Vec<_>>();
+    (!lines.is_empty()).then(|| lines.join("\n"))
+}
+
+/// Turn a string replacement into hunk lines, keeping the lines both sides
+/// share at the start and end as context.
+fn replace_hunk(old: &str, new: &str) -> HunkLines {
+    let old = old.lines().collect::<Vec<_>>();
+    let new = new.lines().collect::<Vec<_>>();
+
+    let prefix = old
+        .iter()
+        .zip(&new)
+        .take_while(|(left, right)| left == right)
+        .count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(left, right)| left == right)
+        .count();
+
+    let mut lines = Vec::new();
+    lines.extend(old[..prefix].iter().map(|line| (' ', line.to_string())));
+    lines.extend(
+        old[prefix..old.len() - suffix]
+            .iter()
+            .map(|line| ('-', line.to_string())),
+    );
+    lines.extend(
+        new[prefix..new.len() - suffix]
+            .iter()
+            .map(|line| ('+', line.to_string())),
+    );
+    lines.extend(
+        old[old.len() - suffix..]
+            .iter()
+            .map(|line| (' ', line.to_string())),
+    );
+    lines
+}
+
+/// The text on one side of a hunk: everything except lines marked `skip`.
+fn hunk_side(lines: &HunkLines, skip: char) -> String {
+    lines
+        .iter()
+        .filter(|(op, _)| *op != skip)
+        .map(|(_, line)| line.as_str())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Parse a Codex `apply_patch` envelope into per-file edits.
+fn parse_patch_edits(patch: &str) -> Vec<(String, Edit)> {
+    enum Section {
+        None,
+        Add(String, Vec<String>),
+        Update(String, HunkLines),
+    }
+
+    fn flush(section: &mut Section, edits: &mut Vec<(String, Edit)>) {
+        match section {
+            Section::None => {}
+            Section::Add(path, lines) => {
+                let mut content = lines.join("\n");
+                if !lines.is_empty() {
+                    content.push('\n');
+                }
+                edits.push((path.clone(), Edit::Add(content)));
+                *section = Section::None;
+            }
+            Section::Update(path, hunk) => {
+                if !hunk.is_empty() {
+                    edits.push((path.clone(), Edit::Hunk(std::mem::take(hunk))));
+                }
+            }
+        }
+    }
+
+    let mut edits = Vec::new();
+    let mut section = Section::None;
+    for line in patch.lines() {
+        if let Some(path) = line.strip_prefix("*** Add File: ") {
+            flush(&mut section, &mut edits);
+            section = Section::Add(path.trim().to_string(), Vec::new());
+        } else if let Some(path) = line.strip_prefix("*** Update File: ") {
+            flush(&mut section, &mut edits);
+            section = Section::Update(path.trim().to_string(), Vec::new());
+        } else if let Some(path) = line.strip_prefix("*** Delete File: ") {
+            flush(&mut section, &mut edits);
+            section = Section::None;
+            edits.push((path.trim().to_string(), Edit::Delete));
+        } else if let Some(target) = line.strip_prefix("*** Move to: ") {
+            if let Section::Update(path, _) = &mut section {
+                let target = target.trim().to_string();
+                edits.push((path.clone(), Edit::Move(target.clone())));
+                *path = target;
+            }
+        } else if line.starts_with("*** ") {
+            // `*** Begin Patch`, `*** End Patch`, `*** End of File`.
+            if line.trim_end() == "*** End Patch" {
+                flush(&mut section, &mut edits);
+                section = Section::None;
+            }
+        } else if line.starts_with("@@") {
+            flush(&mut section, &mut edits);
+        } else {
+            match &mut section {
+                Section::Add(_, lines) => {
+                    lines.push(line.strip_prefix('+').unwrap_or(line).to_string());
+                }
+                Section::Update(_, hunk) => {
+                    let mut chars = line.chars();
+                    match chars.next() {
+                        Some(op @ (' ' | '-' | '+')) => hunk.push((op, chars.as_str().to_string())),
+                        None => hunk.push((' ', String::new())),
+                        Some(_) => {}
+                    }
+                }
+                Section::None => {}
+            }
+        }
+    }
+    flush(&mut section, &mut edits);
+    edits
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn tool(index: usize, name: &str, args: Value, output: &str) -> ThreadEntry {
+        ThreadEntry {
+            index,
+            entry: TimelineEntry::Tool(ToolEntry {
+                name: name.to_string(),
+                call_id: None,
+                args,
+                output: Some(output.to_string()),
+                is_error: false,
+                truncated: false,
+            }),
+        }
+    }
+
+    #[test]
+    fn claude_edits_become_context_hunks_grouped_per_file() {
+        let entries = vec![
+            tool(
+                1,
+                "Edit",
+                json!({"file_path": "/repo/src/lib.rs", "old_string": "fn a() {\n    1\n}", "new_string": "fn a() {\n    2\n}"}),
+                "The file /repo/src/lib.rs has been updated. Here's the result of running `cat -n` on a snippet of the edited file:\n     3\t\n     4\tfn a() {\n     5\t    2\n     6\t}",
+            ),
+            tool(
+                2,
+                "MultiEdit",
+                json!({"file_path": "/repo/src/lib.rs", "edits": [
+                    {"old_string": "use a;", "new_string": "use a;\nuse b;"}
+                ]}),
+                "Applied 1 edit to /repo/src/lib.rs:\n1. Replaced \"use a;\" with \"use a;\nuse b;\"\n     1\tuse a;\n     2\tuse b;",
+            ),
+        ];
+
+        let diffs = extract_file_diffs(&entries, Some(Path::new("/repo")));
+        assert_eq!(diffs.len(), 1);
+        assert_eq!(diffs[0].path, "src/lib.rs");
+        assert_eq!(diffs[0].change, FileChange::Modified);
+        assert!(diffs[0].complete);
+        assert_eq!(diffs[0].entries, vec![1, 2]);
+        assert_eq!(
+            diffs[0].patch,
+            "diff --git a/src/lib.rs b/src/lib.rs\n--- a/src/lib.rs\n+++ b/src/lib.rs\n\
+             @@ -1,3 +1,3 @@ position unknown\n fn a() {\n-    1\n+    2\n }\n\
+             @@ -1,1 +1,2 @@ position unknown\n use a;\n+use b;\n"
+        );
+    }
+
+    #[test]
+    fn partial_line_edits_are_widened_or_noted() {
+        let entries = vec![
+            tool(
+                1,
+                "Read",
+                json!({"file_path": "/repo/src/lib.rs"}),
+                "     1\tpub fn old_name() -> u32 {\n     2\t    1\n     3\t}\n",
+            ),
+            tool(
+                2,
+                "Edit",
+                json!({"file_path": "/repo/src/lib.rs", "old_string": "old_name", "new_string": "new_name"}),
+                "The file /repo/src/lib.rs has been updated.",
+            ),
+            tool(
+                3,
+                "Edit",
+                json!({"file_path": "/repo/src/main.rs", "old_string": "1, 2", "new_string": "1, 2, 3"}),
+                "The file /repo/src/main.rs has been updated. Here's the result of running `cat -n` on a snippet of the edited file:\n     7\t    let xs = [1, 2, 3];",
+            ),
+            tool(
+                4,
+                "edit",
+                json!({"filePath": "/repo/src/util.rs", "oldString": "a + b", "newString": "a - b"}),
+                "",
+            ),
+        ];
+
+        let diffs = extract_file_diffs(&entries, Some(Path::new("/repo")));
+        assert!(diffs[0].complete);
+        assert!(diffs[0].patch.ends_with(
+            "@@ -1,1 +1,1 @@ position unknown\n-pub fn old_name() -> u32 {\n+pub fn new_name() -> u32 {\n"
+        ));
+        assert!(diffs[1].complete);
+        assert!(diffs[1].patch.ends_with(
+            "@@ -1,1 +1,1 @@ position unknown\n-    let xs = [1, 2];\n+    let xs = [1, 2, 3];\n"
+        ));
+        assert!(!diffs[2].complete);
+        assert!(diffs[2].notes[0].contains("may cover only part of a line"));
+        assert!(diffs[2].patch.ends_with("-a + b\n+a - b\n"));
+    }
+
+    #[test]
+    fn created_files_are_replayed_into_one_new_file_diff() {
+        let entries = vec![
+            tool(
+                1,
+                "Write",
+                json!({"file_path": "/repo/notes.md", "content": "one\ntwo\n"}),
+                "File created successfully at: /repo/notes.md",
+            ),
+            tool(
+                2,
+                "Edit",
+                json!({"file_path": "/repo/notes.md", "old_string": "two", "new_string": "three"}),
+                "",
+            ),
+        ];
+
+        let diffs = extract_file_diffs(&entries, Some(Path::new("/repo")));
+        assert_eq!(diffs[0].change, FileChange::Created);
+        assert_eq!(
+            diffs[0].patch,
+            "diff --git a/notes.md b/notes.md\nnew file mode 100644\n--- /dev/null\n+++ b/notes.md\n@@ -0,0 +1,2 @@\n+one\n+three\n"
+        );
+    }
+
+    #[test]
+    fn codex_patches_keep_hunks_moves_and_deletions() {
+        let patch = "*** Begin Patch\n*** Update File: src/main.rs\n*** Move to: src/app.rs\n@@ fn main\n fn main() {\n-    old();\n+    new();\n }\n*** Add File: docs/a.md\n+hello\n*** Delete File: old.txt\n*** End Patch\n";
+        let entries = vec![tool(
+            4,
+            "apply_patch",
+            Value::String(patch.to_string()),
+            "Success",
+        )];
+
+        let diffs = extract_file_diffs(&entries, None);
+        assert_eq!(diffs.len(), 3);
+        assert_eq!(diffs[0].path, "src/app.rs");
+        assert_eq!(diffs[0].old_path.as_deref(), Some("src/main.rs"));
+        assert_eq!(
+            diffs[0].patch,
+            "diff --git a/src/main.rs b/src/app.rs\nrename from src/main.rs\nrename to src/app.rs\n--- a/src/main.rs\n+++ b/src/app.rs\n@@ -1,3 +1,3 @@ position unknown\n fn main() {\n-    old();\n+    new();\n }\n"
+        );
+        assert_eq!(diffs[1].path, "docs/a.md");
+        assert!(diffs[1].patch.ends_with("@@ -0,0 +1,1 @@\n+hello\n"));
+        assert_eq!(diffs[2].change, FileChange::Deleted);
+        assert!(!diffs[2].complete);
+    }
+
+    #[test]
+    fn unrepresentable_edits_are_noted() {
+        let entries = vec![
+            tool(
+                1,
+                "write_file",
+                json!({"file_path": "/repo/README.md", "content": "new\n"}),
+                "Successfully overwrote file: /repo/README.md.",
+            ),
+            tool(
+                2,
+                "NotebookEdit",
+                json!({"notebook_path": "/repo/a.ipynb", "new_source": "x"}),
+                "",
+            ),
+            tool(3, "Read", json!({"file_path": "/repo/b.rs"}), ""),
+        ];
+
+        let diffs = extract_file_diffs(&entries, Some(Path::new("/repo")));
+        assert_eq!(diffs.len(), 2);
+        assert_eq!(diffs[0].patch, "");
+        assert!(!diffs[0].complete);
+        assert!(diffs[0].notes[0].contains("previous content is unknown"));
+        assert!(diffs[1].notes[0].contains("cannot be shown as a diff"));
+    }
+}
@@ -32,6 +32,15 @@ pub enum XurlError {
         searched_roots: Vec<PathBuf>,
     },
 
+    #[error("no thread matches {0}")]
+    NoMatchingThread(String),
+
+    #[error("{target} matches more than one thread: {}", candidates.join(", "))]
+    AmbiguousThread {
+        target: String,
+        candidates: Vec<String>,
+    },
+
     #[error("entry not found for provider={provider} session_id={session_id} entry_id={entry_id}")]
     EntryNotFound {
         provider: String,
@@ -39,6 +48,9 @@ pub enum XurlError {
         entry_id: String,
     },
 
+    #[error("#{selection} selects no timeline entries; the thread has {count}")]
+    EntryOutOfRange { selection: String, count: usize },
+
     #[error("thread file is empty: {path}")]
     EmptyThreadFile { path: PathBuf },
 
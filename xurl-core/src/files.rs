@@ -0,0 +1,598 @@
+//! Derive the set of files an agent read or changed from its tool calls.
+//!
+//! Providers name their file tools differently (`Read`/`read_file`/`read`,
+//! `Edit`/`replace`/`edit_file`, Codex `apply_patch` and shell commands), so
+//! classification is a name table plus a few argument shapes. Calls that
+//! failed are ignored, and anything that cannot be mapped to a path is
+//! skipped rather than guessed.
+
+use std::path::{Component, Path, PathBuf};
+
+use serde_json::Value;
+
+use crate::model::{
+    FileChange, FileTouch, MessageRole, ThreadEntry, TimelineEntry, ToolEntry, TouchedFile,
+};
+
+pub(crate) const READ_TOOLS: &[&str] = &["Read", "read", "read_file", "read_many_files", "view"];
+pub(crate) const EDIT_TOOLS: &[&str] = &[
+    "Edit",
+    "MultiEdit",
+    "NotebookEdit",
+    "edit",
+    "multiedit",
+    "replace",
+    "edit_file",
+    "replace_in_file",
+    "apply_diff",
+];
+pub(crate) const WRITE_TOOLS: &[&str] = &[
+    "Write",
+    "write",
+    "write_file",
+    "create_file",
+    "write_to_file",
+];
+const PATCH_TOOLS: &[&str] = &["apply_patch", "patch"];
+const SHELL_TOOLS: &[&str] = &[
+    "shell",
+    "exec_command",
+    "local_shell",
+    "Bash",
+    "bash",
+    "run_terminal_cmd",
+    "execute_command",
+];
+const PATH_KEYS: &[&str] = &[
+    "file_path",
+    "filePath",
+    "path",
+    "absolute_path",
+    "notebook_path",
+    "target_file",
+];
+const SHELL_READERS: &[&str] = &["cat", "head", "tail", "nl", "less", "more", "sed"];
+
+/// Collect touched files from a timeline built with tool entries enabled.
+///
+/// Paths inside `cwd` are reported relative to it; files are ordered by
+/// their first touch.
+pub fn extract_touched_files(entries: &[ThreadEntry], cwd: Option<&Path>) -> Vec<TouchedFile> {
+    let mut files: Vec<TouchedFile> = Vec::new();
+    let mut turn = 0;
+
+    for entry in entries {
+        let tool = match &entry.entry {
+            TimelineEntry::Message(message) => {
+                if message.role == MessageRole::User {
+                    turn += 1;
+                }
+                continue;
+            }
+            TimelineEntry::Tool(tool) if !tool.is_error => tool,
+            _ => continue,
+        };
+
+        for (raw_path, change) in classify_tool(tool) {
+            let Some(path) = normalize_path(&raw_path, tool_workdir(tool).as_deref(), cwd) else {
+                continue;
+            };
+            let touch = FileTouch {
+                change,
+                turn,
+                entry: entry.index,
+                tool: tool.name.clone(),
+            };
+            match files.iter_mut().find(|file| file.path == path) {
+                Some(file) => file.touches.push(touch),
+                None => files.push(TouchedFile {
+                    path,
+                    change,
+                    touches: vec![touch],
+                }),
+            }
+        }
+    }
+
+    for file in &mut files {
+        file.change = net_change(&file.touches);
+    }
+    files
+}
+
+/// Summarize a file's touches as the change between thread start and end.
+fn net_change(touches: &[FileTouch]) -> FileChange {
+    let mut writes = touches
+        .iter()
+        .map(|touch| touch.change)
+        .filter(|change| *change != FileChange::Read);
+    let Some(first) = writes.next() else {
+        return FileChange::Read;
+    };
+    let last = writes.next_back().unwrap_or(first);
+
+    let existed_before = first != FileChange::Created;
+    let exists_after = last != FileChange::Deleted;
+    match (existed_before, exists_after) {
+        (false, true) => FileChange::Created,
+        (true, true) => FileChange::Modified,
+        (_, false) => FileChange::Deleted,
+    }
+}
+
+fn classify_tool(tool: &ToolEntry) -> Vec<(String, FileChange)> {
+    let name = tool.name.as_str();
+
+    if READ_TOOLS.contains(&name) {
+        return tool_paths(&tool.args)
+            .into_iter()
+            .map(|path| (path, FileChange::Read))
+            .collect();
+    }
+    if EDIT_TOOLS.contains(&name) {
+        return tool_paths(&tool.args)
+            .into_iter()
+            .map(|path| (path, FileChange::Modified))
+            .collect();
+    }
+    if WRITE_TOOLS.contains(&name) {
+        let change = write_change(tool);
+        return tool_paths(&tool.args)
+            .into_iter()
+            .map(|path| (path, change))
+            .collect();
+    }
+    if let Some(patch) = patch_text(tool) {
+        return parse_patch(&patch);
+    }
+    if SHELL_TOOLS.contains(&name)
+        && let Some(script) = shell_script(&tool.args)
+    {
+        return parse_shell(&script);
+    }
+
+    Vec::new()
+}
+
+/// The `apply_patch` envelope a call carried, either as the tool input or
+/// embedded in a shell heredoc.
+pub(crate) fn patch_text(tool: &ToolEntry) -> Option<String> {
+    let name = tool.name.as_str();
+    if PATCH_TOOLS.contains(&name) {
+        return match &tool.args {
+            Value::String(patch) => Some(patch.clone()),
+            args => args
+                .get("input")
+                .or_else(|| args.get("patch"))
+                .and_then(Value::as_str)
+                .map(ToString::to_string),
+        };
+    }
+    if SHELL_TOOLS.contains(&name) {
+        return shell_script(&tool.args).filter(|script| script.contains("*** Begin Patch"));
+    }
+    None
+}
+
+/// Whole-file writes do not say whether the file existed; the tool output
+/// usually does. Without a hint the write is treated as a creation.
+pub(crate) fn write_change(tool: &ToolEntry) -> FileChange {
+    let output = tool.output.as_deref().unwrap_or_default().to_lowercase();
+    if ["overwrote", "updated", "overwritten", "modified"]
+        .iter()
+        .any(|hint| output.contains(hint))
+    {
+        FileChange::Modified
+    } else {
+        FileChange::Created
+    }
+}
+
+pub(crate) fn tool_paths(args: &Value) -> Vec<String> {
+    if let Some(path) = PATH_KEYS
+        .iter()
+        .find_map(|key| args.get(*key).and_then(Value::as_str))
+    {
+        return vec![path.to_string()];
+    }
+
+    args.get("paths")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(Value::as_str)
+        .map(ToString::to_string)
+        .collect()
+}
+
+/// Parse the file headers of an `apply_patch` envelope.
+fn parse_patch(patch: &str) -> Vec<(String, FileChange)> {
+    let mut changes = Vec::new();
+    for line in patch.lines() {
+        let line = line.trim_end();
+        if let Some(path) = line.strip_prefix("*** Add File: ") {
+            changes.push((path.trim().to_string(), FileChange::Created));
+        } else if let Some(path) = line.strip_prefix("*** Update File: ") {
+            changes.push((path.trim().to_string(), FileChange::Modified));
+        } else if let Some(path) = line.strip_prefix("*** Delete File: ") {
+            changes.push((path.trim().to_string(), FileChange::Deleted));
+        } else if let Some(path) = line.strip_prefix("*** Move to: ") {
+            if let Some((_, change)) = changes.last_mut() {
+                *change = FileChange::Deleted;
+            }
+            changes.push((path.trim().to_string(), FileChange::Created));
+        }
+    }
+    changes
+}
+
+/// The script a shell tool ran, unwrapping `["bash", "-lc", script]` argv.
+fn shell_script(args: &Value) -> Option<String> {
+    let command = args
+        .get("command")
+        .or_else(|| args.get("cmd"))
+        .unwrap_or(args);
+    match command {
+        Value::String(script) => Some(script.clone()),
+        Value::Array(argv) => {
+            let argv = argv.iter().filter_map(Value::as_str).collect::<Vec<_>>();
+            match argv.as_slice() {
+                [_, flag, script] if flag.starts_with('-') && flag.ends_with('c') => {
+                    Some((*script).to_string())
+                }
+                _ => Some(argv.join(" ")),
+            }
+        }
+        _ => None,
+    }
+}
+
+pub(crate) fn tool_workdir(tool: &ToolEntry) -> Option<PathBuf> {
+    tool.args
+        .get("workdir")
+        .or_else(|| tool.args.get("cwd"))
+        .and_then(Value::as_str)
+        .map(PathBuf::from)
+}
+
+/// Recognize plain reads (`cat`, `sed -n`, `head`, ...), in-place edits
+/// (`sed -i`) and `rm`.
+///
+/// Segments with redirections or substitutions are skipped: their effect
+/// cannot be read off the command line reliably.
+fn parse_shell(script: &str) -> Vec<(String, FileChange)> {
+    let mut changes = Vec::new();
+    for segment in script.split(['\n', ';', '|', '&']) {
+        if segment.contains(['>', '<', '$', '`']) {
+            continue;
+        }
+        let words = segment
+            .split_whitespace()
+            .map(|word| word.trim_matches(['\'', '"']))
+            .collect::<Vec<_>>();
+        let Some((program, rest)) = words.split_first() else {
+            continue;
+        };
+
+        let change = if *program == "sed" && rest.iter().any(|word| is_sed_in_place(word)) {
+            FileChange::Modified
+        } else if SHELL_READERS.contains(program) {
+            FileChange::Read
+        } else if *program == "rm" {
+            FileChange::Deleted
+        } else {
+            continue;
+        };
+
+        let mut operands = rest
+            .iter()
+            .filter(|word| !word.starts_with('-') && !word.is_empty())
+            .filter(|word| !word.chars().all(|ch| ch.is_ascii_digit()));
+        if *program == "sed" {
+            // The first operand is the sed script.
+            operands.next();
+        }
+        changes.extend(
+            operands
+                .filter(|word| !word.contains('*'))
+                .map(|word| (word.to_string(), change)),
+        );
+    }
+    changes
+}
+
+/// `-i`, `-i.bak`, `--in-place[=SUFFIX]`, or `-i` inside a cluster such as
+/// `-Ei`. Options that take an argument (`-e`, `-f`, `-l`) end a cluster.
+fn is_sed_in_place(word: &str) -> bool {
+    if let Some(long) = word.strip_prefix("--") {
+        return long == "in-place" || long.starts_with("in-place=");
+    }
+    word.strip_prefix('-').is_some_and(|cluster| {
+        cluster
+            .chars()
+            .take_while(|flag| !matches!(flag, 'e' | 'f' | 'l'))
+            .any(|flag| flag == 'i')
+    })
+}
+
+pub(crate) fn normalize_path(
+    raw: &str,
+    workdir: Option<&Path>,
+    cwd: Option<&Path>,
+) -> Option<String> {
+    let raw = raw.trim();
+    if raw.is_empty() {
+        return None;
+    }
+
+    let mut path = PathBuf::from(raw);
+    if path.is_relative()
+        && let Some(workdir) = workdir
+    {
+        path = workdir.join(path);
+    }
+    let path = clean_path(&path);
+
+    let relative = cwd
+        .and_then(|cwd| path.strip_prefix(clean_path(cwd)).ok())
+        .unwrap_or(&path);
+    if relative.as_os_str().is_empty() {
+        return None;
+    }
+    Some(relative.display().to_string())
+}
+
+/// Drop `.` components and resolve `..` lexically.
+fn clean_path(path: &Path) -> PathBuf {
+    let mut cleaned = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                if !cleaned.pop() {
+                    cleaned.push("..");
+                }
+            }
+            other => cleaned.push(other),
+        }
+    }
+    cleaned
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::model::ThreadMessage;
+
+    fn user(index: usize) -> ThreadEntry {
+        ThreadEntry {
+            index,
+            entry: TimelineEntry::Message(ThreadMessage::new(MessageRole::User, "go".to_string())),
+        }
+    }
+
+    fn tool(index: usize, name: &str, args: Value, output: &str) -> ThreadEntry {
+        ThreadEntry {
+            index,
+            entry: TimelineEntry::Tool(ToolEntry {
+                name: name.to_string(),
+                call_id: None,
+                args,
+                output: Some(output.to_string()),
+                is_error: false,
+                truncated: false,
+            }),
+        }
+    }
+
+    fn summary(files: &[TouchedFile]) -> Vec<(&str, FileChange)> {
+        files
+            .iter()
+            .map(|file| (file.path.as_str(), file.change))
+            .collect()
+    }
+
+    #[test]
+    fn classifies_claude_style_tools_relative_to_cwd() {
+        let entries = vec![
+            user(1),
+            tool(2, "Read", json!({"file_path": "/repo/src/lib.rs"}), ""),
+            tool(3, "Edit", json!({"file_path": "/repo/src/lib.rs"}), ""),
+            user(4),
+            tool(
+                5,
+                "Write",
+                json!({"file_path": "/repo/src/new.rs"}),
+                "File created successfully",
+            ),
+            tool(6, "Read", json!({"file_path": "/etc/hosts"}), ""),
+        ];
+
+        let files = extract_touched_files(&entries, Some(Path::new("/repo")));
+        assert_eq!(
+            summary(&files),
+            vec![
+                ("src/lib.rs", FileChange::Modified),
+                ("src/new.rs", FileChange::Created),
+                ("/etc/hosts", FileChange::Read),
+            ]
+        );
+        assert_eq!(files[0].touches.len(), 2);
+        assert_eq!(files[0].touches[1].turn, 1);
+        assert_eq!(files[0].touches[1].entry, 3);
+        assert_eq!(files[1].touches[0].turn, 2);
+        assert_eq!(files[1].touches[0].tool, "Write");
+    }
+
+    #[test]
+    fn overwrite_hint_marks_write_as_modified() {
+        let entries = vec![tool(
+            1,
+            "write_file",
+            json!({"file_path": "/repo/README.md"}),
+            "Successfully overwrote file: /repo/README.md.",
+        )];
+
+        let files = extract_touched_files(&entries, Some(Path::new("/repo")));
+        assert_eq!(summary(&files), vec![("README.md", FileChange::Modified)]);
+    }
+
+    #[test]
+    fn parses_codex_apply_patch_headers() {
+        let patch = "*** Begin Patch\n*** Add File: docs/a.md\n+hi\n*** Update File: src/main.rs\n*** Move to: src/app.rs\n@@\n-x\n+y\n*** Delete File: old.txt\n*** End Patch";
+        let entries = vec![
+            user(1),
+            tool(
+                2,
+                "apply_patch",
+                Value::String(patch.to_string()),
+                "Success",
+            ),
+        ];
+
+        let files = extract_touched_files(&entries, None);
+        assert_eq!(
+            summary(&files),
+            vec![
+                ("docs/a.md", FileChange::Created),
+                ("src/main.rs", FileChange::Deleted),
+                ("src/app.rs", FileChange::Created),
+                ("old.txt", FileChange::Deleted),
+            ]
+        );
+    }
+
+    #[test]
+    fn reads_shell_commands_against_workdir() {
+        let entries = vec![
+            tool(
+                1,
+                "shell",
+                json!({"command": ["bash", "-lc", "sed -n '1,80p' src/lib.rs && cat ./Cargo.toml"], "workdir": "/repo"}),
+                "",
+            ),
+            tool(
+                2,
+                "exec_command",
+                json!({"cmd": "cat src/lib.rs | head -n 5 && echo done > out.txt"}),
+                "",
+            ),
+            tool(
+                3,
+                "exec_command",
+                json!({"cmd": "rm -f tmp/scratch.txt"}),
+                "",
+            ),
+        ];
+
+        let files = extract_touched_files(&entries, Some(Path::new("/repo")));
+        assert_eq!(
+            summary(&files),
+            vec![
+                ("src/lib.rs", FileChange::Read),
+                ("Cargo.toml", FileChange::Read),
+                ("tmp/scratch.txt", FileChange::Deleted),
+            ]
+        );
+        assert_eq!(files[0].touches.len(), 2);
+    }
+
+    #[test]
+    fn sed_in_place_modifies_files() {
+        let entries = vec![
+            tool(
+                1,
+                "exec_command",
+                json!({"cmd": "sed -i 's/old/new/' src/a.rs"}),
+                "",
+            ),
+            tool(
+                2,
+                "exec_command",
+                json!({"cmd": "sed -i.bak -e 's/x/y/' src/b.rs"}),
+                "",
+            ),
+            tool(
+                3,
+                "Bash",
+                json!({"command": "sed -i '' 's/x/y/' src/c.rs && sed --in-place=.orig 's/x/y/' src/d.rs"}),
+                "",
+            ),
+            tool(
+                4,
+                "Bash",
+                json!({"command": "sed -Ei 's/a+/b/' src/e.rs; sed -n -e '/if/p' src/f.rs"}),
+                "",
+            ),
+        ];
+
+        let files = extract_touched_files(&entries, None);
+        assert_eq!(
+            summary(&files),
+            vec![
+                ("src/a.rs", FileChange::Modified),
+                ("src/b.rs", FileChange::Modified),
+                ("src/c.rs", FileChange::Modified),
+                ("src/d.rs", FileChange::Modified),
+                ("src/e.rs", FileChange::Modified),
+                ("src/f.rs", FileChange::Read),
+            ]
+        );
+    }
+
+    #[test]
+    fn reads_cursor_target_files_and_terminal_commands() {
+        let entries = vec![
+            tool(
+                1,
+                "read_file",
+                json!({"target_file": "src/lib.rs", "should_read_entire_file": true}),
+                "",
+            ),
+            tool(
+                2,
+                "edit_file",
+                json!({"target_file": "/repo/src/lib.rs", "code_edit": "// ... existing code ..."}),
+                "",
+            ),
+            tool(
+                3,
+                "run_terminal_cmd",
+                json!({"command": "cat Cargo.toml", "is_background": false}),
+                "",
+            ),
+        ];
+
+        let files = extract_touched_files(&entries, Some(Path::new("/repo")));
+        assert_eq!(
+            summary(&files),
+            vec![
+                ("src/lib.rs", FileChange::Modified),
+                ("Cargo.toml", FileChange::Read),
+            ]
+        );
+    }
+
+    #[test]
+    fn failed_calls_are_ignored_and_net_change_spans_touches() {
+        let mut failed = tool(1, "edit", json!({"filePath": "/repo/a.rs"}), "no match");
+        if let TimelineEntry::Tool(entry) = &mut failed.entry {
+            entry.is_error = true;
+        }
+        let entries = vec![
+            failed,
+            tool(2, "write", json!({"filePath": "/repo/b.rs"}), ""),
+            tool(3, "edit", json!({"filePath": "/repo/b.rs"}), ""),
+            tool(4, "read", json!({"path": "/repo/c.rs"}), ""),
+            tool(5, "exec_command", json!({"cmd": "rm c.rs"}), ""),
+        ];
+
+        let files = extract_touched_files(&entries, Some(Path::new("/repo/")));
+        assert_eq!(
+            summary(&files),
+            vec![("b.rs", FileChange::Created), ("c.rs", FileChange::Deleted)]
+        );
+    }
+}
@@ -0,0 +1,1037 @@
+//! Follow a thread as the agent keeps writing to it.
+//!
+//! [`ThreadFollower::start`] takes a snapshot of the current timeline and
+//! remembers where it ended; each [`ThreadFollower::poll`] then returns only
+//! what was appended since. How "appended" is detected depends on the store:
+//!
+//! - **Codex / Claude**: append-only JSONL, tailed with [`IncrementalReader`].
+//!   A Codex reply is held back until the `token_count` event with its usage.
+//! - **Pi**: append-only JSONL whose entries form a tree; the timeline of the
+//!   latest leaf is re-extracted, and when a new entry switches branches the
+//!   entries past the fork are returned again with their new numbers.
+//! - **Amp / Gemini**: a single JSON document rewritten in place; the
+//!   `messages` array is diffed against the length seen last time, holding
+//!   back the last message until a later one exists or it stops changing.
+//! - **OpenCode**: SQLite rows polled by `(time_created, id)`, holding back
+//!   assistant messages that are still streaming.
+//! - **Cline / Roo Code**: a JSON array rewritten in place, with checkpoints
+//!   in a sibling file; the whole timeline is re-extracted and only entries
+//!   past the last index are returned.
+//! - **Cursor**: each poll reads the composer's `composerData` row from
+//!   `state.vscdb`; when it changed, the composer is re-materialized and
+//!   handled like Cline, since bubbles are updated in place.
+//!
+//! Tool entries are counted but not returned, so entries keep the numbers
+//! they have in the rendered timeline; invocations arrive as tool calls.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use serde_json::{Value, json};
+
+use crate::error::{Result, XurlError};
+use crate::incremental::IncrementalReader;
+use crate::model::{
+    FollowEvent, MessageRole, ProviderKind, ThreadEntry, TimelineEntry, TimelineOptions, ToolCall,
+};
+use crate::provider::ProviderRoots;
+#[cfg(feature = "sqlite")]
+use crate::provider::cursor::CursorProvider;
+#[cfg(feature = "sqlite")]
+use crate::provider::goose::{self, GooseProvider};
+#[cfg(feature = "sqlite")]
+use crate::provider::opencode::{MessageCursor, OpencodeProvider};
+use crate::render::{self, ChunkState};
+use crate::service::resolve_thread;
+use crate::uri::ThreadUri;
+
+const TOOL_RESULT_TYPES: &[&str] = &["tool_result", "function_result", "function_response"];
+
+/// Polls a thread for entries and tool calls appended after a snapshot.
+pub struct ThreadFollower {
+    provider: ProviderKind,
+    session_id: String,
+    path: PathBuf,
+    source: FollowSource,
+    last_index: usize,
+    state: ChunkState,
+    /// Codex reply waiting for the `token_count` event that carries its usage.
+    awaiting_usage: Option<ThreadEntry>,
+}
+
+enum FollowSource {
+    Lines(IncrementalReader),
+    Tree {
+        reader: IncrementalReader,
+        raw: String,
+        timeline: Vec<TimelineEntry>,
+    },
+    Document {
+        seen_messages: usize,
+        /// The last message, which the agent rewrites while it streams.
+        pending: Option<Value>,
+        stamp: Option<(u64, SystemTime)>,
+    },
+    #[cfg(feature = "sqlite")]
+    Sqlite {
+        provider: OpencodeProvider,
+        cursor: Option<MessageCursor>,
+    },
+    Snapshot {
+        seen_calls: usize,
+        stamp: Option<(u64, SystemTime)>,
+    },
+    #[cfg(feature = "sqlite")]
+    GooseSqlite {
+        provider: GooseProvider,
+        cursor: Option<i64>,
+    },
+    #[cfg(feature = "sqlite")]
+    CursorSqlite {
+        provider: CursorProvider,
+        composer: Value,
+        /// Folder from the first resolve; finding it opens every workspace.
+        cwd: Option<String>,
+        seen_calls: usize,
+        stamp: Option<(u64, SystemTime)>,
+    },
+}
+
+impl ThreadFollower {
+    /// Resolve a main thread and return a follower together with the
+    /// timeline that already exists.
+    pub fn start(uri: &ThreadUri, roots: &ProviderRoots) -> Result<(Self, Vec<ThreadEntry>)> {
+        if uri.selection.is_some() {
+            return Err(XurlError::InvalidMode(
+                "follow mode streams the whole thread; drop the #fragment from the URI".to_string(),
+            ));
+        }
+        if uri.agent_id.is_some() {
+            return Err(XurlError::InvalidMode(
+                "follow mode requires a main thread URI; follow a child thread by its own session id"
+                    .to_string(),
+            ));
+        }
+
+        let resolved = resolve_thread(uri, roots)?;
+        let path = resolved.path;
+
+        let (mut source, raw) = match uri.provider {
+            // Threads from Goose's database are polled by message row id;
+            // legacy session files are appended to line by line.
+            #[cfg(feature = "sqlite")]
+            ProviderKind::Goose if resolved.metadata.source == goose::SQLITE_SOURCE => {
+                let provider = GooseProvider::new(&roots.goose_root);
+                let (raw, cursor) = provider.read_messages_after(&uri.session_id, None)?;
+                (FollowSource::GooseSqlite { provider, cursor }, raw)
+            }
+            ProviderKind::Pi => {
+                let mut reader = IncrementalReader::new(&path);
+                let raw = reader.read_new_text();
+                let source = FollowSource::Tree {
+                    reader,
+                    raw: raw.clone(),
+                    timeline: Vec::new(),
+                };
+                (source, raw)
+            }
+            ProviderKind::Codex | ProviderKind::Claude | ProviderKind::Goose => {
+                let mut reader = IncrementalReader::new(&path);
+                let raw = reader.read_new_text();
+                (FollowSource::Lines(reader), raw)
+            }
+            ProviderKind::Amp | ProviderKind::Gemini | ProviderKind::Qwen => {
+                let stamp = file_stamp(&path);
+                let raw = fs::read_to_string(&path).map_err(|source| XurlError::Io {
+                    path: path.clone(),
+                    source,
+                })?;
+                let mut document = parse_document(&path, &raw)?;
+                let pending = document
+                    .get_mut("messages")
+                    .and_then(Value::as_array_mut)
+                    .and_then(Vec::pop);
+                let seen_messages = document
+                    .get("messages")
+                    .and_then(Value::as_array)
+                    .map_or(0, Vec::len);
+                (
+                    FollowSource::Document {
+                        seen_messages,
+                        pending,
+                        stamp,
+                    },
+                    document.to_string(),
+                )
+            }
+            #[cfg(feature = "sqlite")]
+            ProviderKind::Opencode => {
+                let provider = OpencodeProvider::new(&roots.opencode_root);
+                let (raw, cursor) = provider.read_messages_after(&uri.session_id, None)?;
+                (FollowSource::Sqlite { provider, cursor }, raw)
+            }
+            #[cfg(not(feature = "sqlite"))]
+            ProviderKind::Opencode => {
+                return Err(XurlError::InvalidMode(format!(
+                    "provider {} requires the 'sqlite' feature",
+                    uri.provider
+                )));
+            }
+            ProviderKind::Aider => {
+                return Err(XurlError::InvalidMode(
+                    "follow mode is not supported for aider threads".to_string(),
+                ));
+            }
+            #[cfg(feature = "sqlite")]
+            ProviderKind::Cursor => {
+                let stamp = file_stamp(&path);
+                let raw = fs::read_to_string(&path).map_err(|source| XurlError::Io {
+                    path: path.clone(),
+                    source,
+                })?;
+                let seen_calls = render::extract_tool_calls(uri.provider, &path, &raw)?.len();
+                let provider = CursorProvider::new(&roots.cursor_root);
+                let composer = provider.load_composer(&uri.session_id)?;
+                let cwd = raw
+                    .lines()
+                    .next()
+                    .and_then(|header| serde_json::from_str::<Value>(header).ok())
+                    .and_then(|header| header.get("cwd")?.as_str().map(ToString::to_string));
+                (
+                    FollowSource::CursorSqlite {
+                        provider,
+                        composer,
+                        cwd,
+                        seen_calls,
+                        stamp,
+                    },
+                    raw,
+                )
+            }
+            #[cfg(not(feature = "sqlite"))]
+            ProviderKind::Cursor => {
+                return Err(XurlError::InvalidMode(format!(
+                    "provider {} requires the 'sqlite' feature",
+                    uri.provider
+                )));
+            }
+            ProviderKind::Cline | ProviderKind::Roo => {
+                let stamp = file_stamp(&path);
+                let raw = fs::read_to_string(&path).map_err(|source| XurlError::Io {
+                    path: path.clone(),
+                    source,
+                })?;
+                let seen_calls = render::extract_tool_calls(uri.provider, &path, &raw)?.len();
+                (FollowSource::Snapshot { seen_calls, stamp }, raw)
+            }
+        };
+
+        let mut state = ChunkState::default();
+        let entries = render::extract_timeline_chunk(
+            uri.provider,
+            &path,
+            &raw,
+            &uri.session_id,
+            None,
+            &numbering_options(),
+            &mut state,
+        )?;
+        if let FollowSource::Tree { timeline, .. } = &mut source {
+            timeline.clone_from(&entries);
+        }
+        let follower = Self {
+            provider: uri.provider,
+            session_id: uri.session_id.clone(),
+            path,
+            source,
+            last_index: entries.len(),
+            state,
+            awaiting_usage: None,
+        };
+
+        let entries = render::number_entries(entries, 1)
+            .into_iter()
+            .filter(|entry| !matches!(entry.entry, TimelineEntry::Tool(_)))
+            .collect();
+        Ok((follower, entries))
+    }
+
+    /// Path of the thread file (or the materialized copy for OpenCode).
+    pub fn source_path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Return entries and tool calls appended since the previous poll,
+    /// numbered to continue the initial timeline.
+    pub fn poll(&mut self) -> Result<Vec<FollowEvent>> {
+        let chunks: Vec<String> = match &mut self.source {
+            FollowSource::Lines(reader) => reader
+                .read_new_text()
+                .lines()
+                .map(ToString::to_string)
+                .collect(),
+            FollowSource::Tree {
+                reader,
+                raw,
+                timeline,
+            } => {
+                let appended = reader.read_new_text();
+                if appended.trim().is_empty() {
+                    return Ok(Vec::new());
+                }
+                raw.push_str(&appended);
+                return Ok(tree_events(
+                    self.provider,
+                    &self.path,
+                    &self.session_id,
+                    raw,
+                    &appended,
+                    timeline,
+                    &mut self.last_index,
+                ));
+            }
+            FollowSource::Document {
+                seen_messages,
+                pending,
+                stamp,
+            } => {
+                let current = file_stamp(&self.path);
+                if current.is_none() {
+                    return Ok(Vec::new());
+                }
+                if current == *stamp {
+                    // Unchanged since the last poll: the held-back message is final.
+                    let released = pending.take();
+                    *seen_messages += usize::from(released.is_some());
+                    released.iter().map(message_document).collect()
+                } else {
+                    // A document caught mid-rewrite will not parse; retry next poll.
+                    let Some(document) = fs::read_to_string(&self.path)
+                        .ok()
+                        .and_then(|raw| serde_json::from_str::<Value>(&raw).ok())
+                    else {
+                        return Ok(Vec::new());
+                    };
+                    *stamp = current;
+
+                    let mut messages = document
+                        .get("messages")
+                        .and_then(Value::as_array)
+                        .cloned()
+                        .unwrap_or_default();
+                    match messages.pop() {
+                        Some(last) if messages.len() >= *seen_messages => {
+                            let mut chunks: Vec<String> = messages[*seen_messages..]
+                                .iter()
+                                .map(message_document)
+                                .collect();
+                            *seen_messages = messages.len();
+                            if pending.as_ref() == Some(&last) {
+                                *pending = None;
+                                *seen_messages += 1;
+                                chunks.push(message_document(&last));
+                            } else {
+                                *pending = Some(last);
+                            }
+                            chunks
+                        }
+                        _ => Vec::new(),
+                    }
+                }
+            }
+            #[cfg(feature = "sqlite")]
+            FollowSource::Sqlite { provider, cursor } => {
+                let (raw, next) =
+                    provider.read_messages_after(&self.session_id, cursor.as_ref())?;
+                if next == *cursor {
+                    return Ok(Vec::new());
+                }
+                *cursor = next;
+                raw.lines().map(ToString::to_string).collect()
+            }
+            #[cfg(feature = "sqlite")]
+            FollowSource::GooseSqlite { provider, cursor } => {
+                let (raw, next) = provider.read_messages_after(&self.session_id, *cursor)?;
+                if next == *cursor {
+                    return Ok(Vec::new());
+                }
+                *cursor = next;
+                raw.lines().map(ToString::to_string).collect()
+            }
+            #[cfg(feature = "sqlite")]
+            FollowSource::CursorSqlite {
+                provider,
+                composer,
+                cwd,
+                seen_calls,
+                stamp,
+            } => {
+                let latest = provider.load_composer(&self.session_id)?;
+                if latest != *composer {
+                    provider.materialize(&self.session_id, &latest, cwd.as_deref())?;
+                    *composer = latest;
+                }
+                return Ok(snapshot_events(
+                    self.provider,
+                    &self.path,
+                    &self.session_id,
+                    &mut self.last_index,
+                    seen_calls,
+                    stamp,
+                ));
+            }
+            FollowSource::Snapshot { seen_calls, stamp } => {
+                return Ok(snapshot_events(
+                    self.provider,
+                    &self.path,
+                    &self.session_id,
+                    &mut self.last_index,
+                    seen_calls,
+                    stamp,
+                ));
+            }
+        };
+
+        let mut events = Vec::new();
+        for chunk in chunks.iter().filter(|chunk| !chunk.trim().is_empty()) {
+            self.extract_events(chunk, &mut events);
+        }
+        // Nothing new was written, so no usage is coming for a held reply.
+        if chunks.is_empty()
+            && let Some(entry) = self.awaiting_usage.take()
+        {
+            events.push(FollowEvent::Entry(entry));
+        }
+        Ok(events)
+    }
+
+    /// Extract events from one JSONL line, or one single-message document for
+    /// Amp and Gemini. Records that fail to parse are skipped.
+    fn extract_events(&mut self, chunk: &str, events: &mut Vec<FollowEvent>) {
+        if self.provider == ProviderKind::Codex
+            && let Some(usage) = render::codex_token_count(chunk)
+        {
+            if let Some(mut entry) = self.awaiting_usage.take() {
+                if let TimelineEntry::Message(message) = &mut entry.entry {
+                    message.usage = usage;
+                }
+                events.push(FollowEvent::Entry(entry));
+            }
+            return;
+        }
+
+        let entries = render::extract_timeline_chunk(
+            self.provider,
+            &self.path,
+            chunk,
+            &self.session_id,
+            None,
+            &numbering_options(),
+            &mut self.state,
+        )
+        .unwrap_or_default();
+        let calls: Vec<ToolCall> = render::extract_tool_calls(self.provider, &self.path, chunk)
+            .unwrap_or_default()
+            .into_iter()
+            .filter(is_invocation)
+            .collect();
+
+        // A later record means the held reply's usage was never written.
+        if (!entries.is_empty() || !calls.is_empty())
+            && let Some(entry) = self.awaiting_usage.take()
+        {
+            events.push(FollowEvent::Entry(entry));
+        }
+
+        for entry in entries {
+            self.last_index += 1;
+            if matches!(entry, TimelineEntry::Tool(_)) {
+                continue;
+            }
+            let entry = ThreadEntry {
+                index: self.last_index,
+                entry,
+            };
+            if self.provider == ProviderKind::Codex && is_assistant_message(&entry.entry) {
+                if let Some(held) = self.awaiting_usage.replace(entry) {
+                    events.push(FollowEvent::Entry(held));
+                }
+            } else {
+                events.push(FollowEvent::Entry(entry));
+            }
+        }
+        events.extend(calls.into_iter().map(FollowEvent::ToolCall));
+    }
+}
+
+/// Re-extract a Pi session with the lines just `appended` and return the
+/// entries of the latest leaf's timeline that differ from `timeline`, then
+/// the tool calls in `appended`.
+fn tree_events(
+    provider: ProviderKind,
+    path: &Path,
+    session_id: &str,
+    raw: &str,
+    appended: &str,
+    timeline: &mut Vec<TimelineEntry>,
+    last_index: &mut usize,
+) -> Vec<FollowEvent> {
+    let Ok(entries) = render::extract_timeline_entries(
+        provider,
+        path,
+        raw,
+        session_id,
+        None,
+        &numbering_options(),
+    ) else {
+        return Vec::new();
+    };
+
+    let fork = timeline
+        .iter()
+        .zip(&entries)
+        .take_while(|(seen, entry)| same_entry(seen, entry))
+        .count();
+    let mut events: Vec<FollowEvent> = render::number_entries(entries[fork..].to_vec(), fork + 1)
+        .into_iter()
+        .filter(|entry| !matches!(entry.entry, TimelineEntry::Tool(_)))
+        .map(FollowEvent::Entry)
+        .collect();
+    *last_index = entries.len();
+    *timeline = entries;
+
+    if let Ok(calls) = render::extract_tool_calls(provider, path, appended) {
+        events.extend(
+            calls
+                .into_iter()
+                .filter(is_invocation)
+                .map(FollowEvent::ToolCall),
+        );
+    }
+    events
+}
+
+/// Re-extract a rewritten document and return the entries past
+/// `last_index` and the tool calls past `seen_calls`. Nothing is returned
+/// while the file is unchanged since `stamp`.
+fn snapshot_events(
+    provider: ProviderKind,
+    path: &Path,
+    session_id: &str,
+    last_index: &mut usize,
+    seen_calls: &mut usize,
+    stamp: &mut Option<(u64, SystemTime)>,
+) -> Vec<FollowEvent> {
+    let current = file_stamp(path);
+    if current.is_none() || current == *stamp {
+        return Vec::new();
+    }
+    let Some(raw) = fs::read_to_string(path).ok() else {
+        return Vec::new();
+    };
+    // A document caught mid-rewrite will not parse; retry next poll.
+    let (Ok(entries), Ok(calls)) = (
+        render::extract_timeline_entries(
+            provider,
+            path,
+            &raw,
+            session_id,
+            None,
+            &numbering_options(),
+        ),
+        render::extract_tool_calls(provider, path, &raw),
+    ) else {
+        return Vec::new();
+    };
+    *stamp = current;
+
+    let mut events = Vec::new();
+    for entry in entries.into_iter().skip(*last_index) {
+        *last_index += 1;
+        if !matches!(entry, TimelineEntry::Tool(_)) {
+            events.push(FollowEvent::Entry(ThreadEntry {
+                index: *last_index,
+                entry,
+            }));
+        }
+    }
+    let start = (*seen_calls).min(calls.len());
+    *seen_calls = calls.len();
+    events.extend(
+        calls
+            .into_iter()
+            .skip(start)
+            .filter(is_invocation)
+            .map(FollowEvent::ToolCall),
+    );
+    events
+}
+
+/// Tool entries are extracted so that entries are numbered as in the
+/// rendered timeline.
+fn numbering_options() -> TimelineOptions {
+    TimelineOptions {
+        tools: true,
+        ..TimelineOptions::default()
+    }
+}
+
+/// Tool entries gain their output when the result is read, so they are
+/// matched by call instead.
+fn same_entry(seen: &TimelineEntry, entry: &TimelineEntry) -> bool {
+    match (seen, entry) {
+        (TimelineEntry::Tool(seen), TimelineEntry::Tool(entry)) => {
+            seen.name == entry.name && seen.call_id == entry.call_id
+        }
+        _ => seen == entry,
+    }
+}
+
+fn is_assistant_message(entry: &TimelineEntry) -> bool {
+    matches!(entry, TimelineEntry::Message(message) if message.role == MessageRole::Assistant)
+}
+
+fn is_invocation(call: &ToolCall) -> bool {
+    !TOOL_RESULT_TYPES.contains(&call.call_type.as_str())
+}
+
+/// A single-message document, extracted like a whole Amp or Gemini thread.
+fn message_document(message: &Value) -> String {
+    json!({ "messages": [message] }).to_string()
+}
+
+fn parse_document(path: &Path, raw: &str) -> Result<Value> {
+    serde_json::from_str::<Value>(raw).map_err(|source| XurlError::InvalidJsonLine {
+        path: path.to_path_buf(),
+        line: 1,
+        source,
+    })
+}
+
+fn file_stamp(path: &Path) -> Option<(u64, SystemTime)> {
+    let metadata = fs::metadata(path).ok()?;
+    Some((metadata.len(), metadata.modified().ok()?))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::io::Write;
+    use std::path::Path;
+
+    use tempfile::tempdir;
+
+    use crate::follow::ThreadFollower;
+    use crate::model::{FollowEvent, MessageRole, TimelineEntry};
+    use crate::provider::ProviderRoots;
+    use crate::uri::ThreadUri;
+
+    const CLAUDE_SESSION: &str = "2823d1df-720a-4c31-ac55-ae8ba726721f";
+
+    fn make_roots(base: &Path) -> ProviderRoots {
+        ProviderRoots {
+            amp_root: base.join("amp"),
+            codex_root: base.join("codex"),
+            claude_root: base.join("claude"),
+            gemini_root: base.join("gemini"),
+            qwen_root: base.join("qwen"),
+            pi_root: base.join("pi"),
+            opencode_root: base.join("opencode"),
+            cline_root: base.join("cline"),
+            roo_root: base.join("roo"),
+            aider_roots: vec![base.join("aider")],
+            goose_root: base.join("goose"),
+            cursor_root: base.join("cursor"),
+        }
+    }
+
+    fn append(path: &Path, line: &str) {
+        let mut file = fs::OpenOptions::new()
+            .append(true)
+            .open(path)
+            .expect("open");
+        writeln!(file, "{line}").expect("append");
+    }
+
+    fn message_text(event: &FollowEvent) -> Option<(usize, &str)> {
+        match event {
+            FollowEvent::Entry(entry) => match &entry.entry {
+                TimelineEntry::Message(message) => Some((entry.index, message.text.as_str())),
+                TimelineEntry::Compact { .. }
+                | TimelineEntry::Tool(_)
+                | TimelineEntry::Checkpoint { .. } => None,
+            },
+            FollowEvent::ToolCall(_) => None,
+        }
+    }
+
+    #[test]
+    fn follows_appended_jsonl_lines() {
+        let temp = tempdir().expect("tempdir");
+        let roots = make_roots(temp.path());
+        let project = roots.claude_root.join("projects/-tmp-project");
+        fs::create_dir_all(&project).expect("mkdir");
+        let path = project.join(format!("{CLAUDE_SESSION}.jsonl"));
+        fs::write(
+            &path,
+            "{\"type\":\"user\",\"message\":{\"role\":\"user\",\"content\":\"hello\"}}\n",
+        )
+        .expect("write");
+
+        let uri = ThreadUri::parse(&format!("agents://claude/{CLAUDE_SESSION}")).expect("uri");
+        let (mut follower, entries) = ThreadFollower::start(&uri, &roots).expect("start");
+        assert_eq!(entries.len(), 1);
+        assert!(follower.poll().expect("poll").is_empty());
+
+        append(
+            &path,
+            r#"{"type":"assistant","message":{"role":"assistant","content":[{"type":"text","text":"running"},{"type":"tool_use","id":"toolu_1","name":"Bash","input":{"command":"ls"}}]}}"#,
+        );
+        append(
+            &path,
+            r#"{"type":"user","message":{"role":"user","content":[{"type":"tool_result","tool_use_id":"toolu_1","content":"ok"}]}}"#,
+        );
+        append(&path, r#"{"type":"system","subtype":"compact_boundary"}"#);
+
+        // The tool call is entry 3, as in the rendered timeline, and its
+        // result read from the next line does not become an entry.
+        let events = follower.poll().expect("poll");
+        assert_eq!(events.len(), 3);
+        assert_eq!(message_text(&events[0]), Some((2, "running")));
+        assert!(matches!(&events[1], FollowEvent::ToolCall(call) if call.name == "Bash"));
+        assert!(matches!(
+            &events[2],
+            FollowEvent::Entry(entry)
+                if entry.index == 4 && matches!(entry.entry, TimelineEntry::Compact { .. })
+        ));
+    }
+
+    #[test]
+    fn follows_single_json_document_by_message_count() {
+        let temp = tempdir().expect("tempdir");
+        let roots = make_roots(temp.path());
+        let threads = roots.amp_root.join("threads");
+        fs::create_dir_all(&threads).expect("mkdir");
+        let path = threads.join("T-019c0797-c402-7389-bd80-d785c98df295.json");
+        let user = r#"{"role":"user","content":[{"type":"text","text":"hello"}]}"#;
+        let assistant = r#"{"role":"assistant","content":[{"type":"text","text":"world"}]}"#;
+        fs::write(&path, format!("{{\"messages\":[{user}]}}")).expect("write");
+
+        let uri =
+            ThreadUri::parse("agents://amp/T-019c0797-c402-7389-bd80-d785c98df295").expect("uri");
+        let (mut follower, entries) = ThreadFollower::start(&uri, &roots).expect("start");
+        assert!(entries.is_empty());
+
+        fs::write(
+            &path,
+            format!("{{\"messages\":[{user},{assistant}],\"title\":\"updated\"}}"),
+        )
+        .expect("rewrite");
+
+        // A later message exists, so the first one is complete.
+        let events = follower.poll().expect("poll");
+        assert_eq!(events.len(), 1);
+        assert_eq!(message_text(&events[0]), Some((1, "hello")));
+
+        let events = follower.poll().expect("poll");
+        assert_eq!(events.len(), 1);
+        assert_eq!(message_text(&events[0]), Some((2, "world")));
+        assert!(
+            matches!(&events[0], FollowEvent::Entry(entry) if matches!(&entry.entry, TimelineEntry::Message(m) if m.role == MessageRole::Assistant))
+        );
+        assert!(follower.poll().expect("poll").is_empty());
+    }
+
+    #[test]
+    fn holds_back_a_document_message_until_it_stops_changing() {
+        let temp = tempdir().expect("tempdir");
+        let roots = make_roots(temp.path());
+        let threads = roots.amp_root.join("threads");
+        fs::create_dir_all(&threads).expect("mkdir");
+        let path = threads.join("T-019c0797-c402-7389-bd80-d785c98df295.json");
+        let document = |texts: &[(&str, &str)]| {
+            let messages = texts
+                .iter()
+                .map(|(role, text)| {
+                    format!(r#"{{"role":"{role}","content":[{{"type":"text","text":"{text}"}}]}}"#)
+                })
+                .collect::<Vec<_>>()
+                .join(",");
+            format!("{{\"messages\":[{messages}]}}")
+        };
+        fs::write(&path, document(&[("user", "hello"), ("assistant", "wor")])).expect("write");
+
+        let uri =
+            ThreadUri::parse("agents://amp/T-019c0797-c402-7389-bd80-d785c98df295").expect("uri");
+        let (mut follower, entries) = ThreadFollower::start(&uri, &roots).expect("start");
+        assert_eq!(entries.len(), 1);
+
+        // The streaming reply is rewritten between polls; only its final
+        // text is returned.
+        fs::write(
+            &path,
+            document(&[("user", "hello"), ("assistant", "world, still streaming")]),
+        )
+        .expect("rewrite");
+        assert!(follower.poll().expect("poll").is_empty());
+
+        fs::write(
+            &path,
+            document(&[("user", "hello"), ("assistant", "world, done")]),
+        )
+        .expect("rewrite");
+        assert!(follower.poll().expect("poll").is_empty());
+
+        let events = follower.poll().expect("poll");
+        assert_eq!(events.len(), 1);
+        assert_eq!(message_text(&events[0]), Some((2, "world, done")));
+        assert!(follower.poll().expect("poll").is_empty());
+    }
+
+    #[test]
+    fn attaches_codex_usage_written_after_the_reply() {
+        let temp = tempdir().expect("tempdir");
+        let roots = make_roots(temp.path());
+        let session_id = "019c871c-b1f9-7f60-9c4f-87ed09f13592";
+        let day = roots.codex_root.join("sessions/2026/02/23");
+        fs::create_dir_all(&day).expect("mkdir");
+        let path = day.join(format!("rollout-2026-02-23T04-48-50-{session_id}.jsonl"));
+        fs::write(
+            &path,
+            concat!(
+                r#"{"type":"turn_context","payload":{"model":"gpt-5-codex"}}"#,
+                "\n",
+                r#"{"type":"response_item","payload":{"type":"message","role":"user","content":[{"type":"input_text","text":"hello"}]}}"#,
+                "\n",
+            ),
+        )
+        .expect("write");
+
+        let uri = ThreadUri::parse(&format!("agents://codex/{session_id}")).expect("uri");
+        let (mut follower, entries) = ThreadFollower::start(&uri, &roots).expect("start");
+        assert_eq!(entries.len(), 1);
+
+        append(
+            &path,
+            r#"{"type":"response_item","payload":{"type":"message","role":"assistant","content":[{"type":"output_text","text":"world"}]}}"#,
+        );
+        assert!(follower.poll().expect("poll").is_empty());
+
+        append(
+            &path,
+            r#"{"type":"event_msg","payload":{"type":"token_count","info":{"last_token_usage":{"input_tokens":900,"output_tokens":40,"total_tokens":940}}}}"#,
+        );
+        let events = follower.poll().expect("poll");
+        assert_eq!(events.len(), 1);
+        let FollowEvent::Entry(entry) = &events[0] else {
+            panic!("expected an entry");
+        };
+        let TimelineEntry::Message(message) = &entry.entry else {
+            panic!("expected a message");
+        };
+        assert_eq!(entry.index, 2);
+        assert_eq!(message.model.as_deref(), Some("gpt-5-codex"));
+        assert_eq!(
+            message.usage.as_ref().and_then(|usage| usage.total_tokens),
+            Some(940)
+        );
+    }
+
+    #[test]
+    fn follows_pi_branch_switches() {
+        let temp = tempdir().expect("tempdir");
+        let roots = make_roots(temp.path());
+        let session_id = "12cb4c19-2774-4de4-a0d0-9fa32fbae29f";
+        let project = roots.pi_root.join("sessions/--tmp-project--");
+        fs::create_dir_all(&project).expect("mkdir");
+        let path = project.join(format!("2026-02-23T13-00-12-780Z_{session_id}.jsonl"));
+        let message = |id: &str, parent: &str, role: &str, text: &str| {
+            format!(
+                r#"{{"type":"message","id":"{id}","parentId":{parent},"message":{{"role":"{role}","content":[{{"type":"text","text":"{text}"}}]}}}}"#
+            )
+        };
+        fs::write(
+            &path,
+            format!(
+                "{{\"type\":\"session\",\"version\":3,\"id\":\"{session_id}\",\"cwd\":\"/tmp/project\"}}\n{}\n{}\n",
+                message("a1", "null", "user", "root"),
+                message("b1", "\"a1\"", "assistant", "root done"),
+            ),
+        )
+        .expect("write");
+
+        let uri = ThreadUri::parse(&format!("agents://pi/{session_id}")).expect("uri");
+        let (mut follower, entries) = ThreadFollower::start(&uri, &roots).expect("start");
+        assert_eq!(entries.len(), 2);
+
+        append(&path, &message("c1", "\"b1\"", "user", "branch one"));
+        let events = follower.poll().expect("poll");
+        assert_eq!(events.len(), 1);
+        assert_eq!(message_text(&events[0]), Some((3, "branch one")));
+
+        // Branching from the first entry replaces everything after it.
+        append(&path, &message("d1", "\"a1\"", "assistant", "retried"));
+        let events = follower.poll().expect("poll");
+        assert_eq!(events.len(), 1);
+        assert_eq!(message_text(&events[0]), Some((2, "retried")));
+
+        append(&path, &message("e1", "\"d1\"", "user", "next"));
+        let events = follower.poll().expect("poll");
+        assert_eq!(events.len(), 1);
+        assert_eq!(message_text(&events[0]), Some((3, "next")));
+    }
+
+    #[test]
+    fn rejects_subagent_uris() {
+        let temp = tempdir().expect("tempdir");
+        let roots = make_roots(temp.path());
+        let uri = ThreadUri::parse(&format!("agents://claude/{CLAUDE_SESSION}/agent-a1b2c3"))
+            .expect("uri");
+
+        let err = ThreadFollower::start(&uri, &roots)
+            .err()
+            .expect("must fail");
+        assert!(format!("{err}").contains("main thread URI"));
+    }
+
+    #[cfg(feature = "sqlite")]
+    #[test]
+    fn follows_opencode_rows_once_completed() {
+        use rusqlite::{Connection, params};
+
+        let temp = tempdir().expect("tempdir");
+        let roots = make_roots(temp.path());
+        fs::create_dir_all(&roots.opencode_root).expect("mkdir");
+        let conn = Connection::open(roots.opencode_root.join("opencode.db")).expect("open");
+        conn.execute_batch(
+            "
+            CREATE TABLE session (id TEXT PRIMARY KEY);
+            CREATE TABLE message (
+                id TEXT PRIMARY KEY,
+                session_id TEXT NOT NULL,
+                time_created INTEGER NOT NULL,
+                data TEXT NOT NULL
+            );
+            CREATE TABLE part (
+                id TEXT PRIMARY KEY,
+                message_id TEXT NOT NULL,
+                session_id TEXT NOT NULL,
+                time_created INTEGER NOT NULL,
+                data TEXT NOT NULL
+            );
+            ",
+        )
+        .expect("schema");
+
+        let session_id = "ses_43a90e3adffejRgrTdlJa48CtE";
+        conn.execute("INSERT INTO session (id) VALUES (?1)", [session_id])
+            .expect("session");
+        let insert_message = |id: &str, time: i64, data: &str| {
+            conn.execute(
+                "INSERT OR REPLACE INTO message (id, session_id, time_created, data) VALUES (?1, ?2, ?3, ?4)",
+                params![id, session_id, time, data],
+            )
+            .expect("message");
+        };
+        let insert_part = |id: &str, message_id: &str, time: i64, text: &str| {
+            conn.execute(
+                "INSERT INTO part (id, message_id, session_id, time_created, data) VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![
+                    id,
+                    message_id,
+                    session_id,
+                    time,
+                    format!(r#"{{"type":"text","text":"{text}"}}"#)
+                ],
+            )
+            .expect("part");
+        };
+
+        insert_message("msg_1", 1, r#"{"role":"user","time":{"created":1}}"#);
+        insert_part("prt_1", "msg_1", 1, "hello");
+
+        let uri = ThreadUri::parse(&format!("agents://opencode/{session_id}")).expect("uri");
+        let (mut follower, entries) = ThreadFollower::start(&uri, &roots).expect("start");
+        assert_eq!(entries.len(), 1);
+
+        insert_message("msg_2", 2, r#"{"role":"assistant","time":{"created":2}}"#);
+        insert_part("prt_2", "msg_2", 2, "wor");
+        assert!(follower.poll().expect("poll").is_empty());
+
+        insert_message(
+            "msg_2",
+            2,
+            r#"{"role":"assistant","time":{"created":2,"completed":3}}"#,
+        );
+        conn.execute(
+            "UPDATE part SET data = ?1 WHERE id = 'prt_2'",
+            [r#"{"type":"text","text":"world"}"#],
+        )
+        .expect("update part");
+
+        let events = follower.poll().expect("poll");
+        assert_eq!(events.len(), 1);
+        assert_eq!(message_text(&events[0]), Some((2, "world")));
+        assert!(follower.poll().expect("poll").is_empty());
+    }
+    #[cfg(feature = "sqlite")]
+    #[test]
+    fn follows_cursor_composer_when_its_data_changes() {
+        use rusqlite::{Connection, params};
+        use serde_json::json;
+
+        let temp = tempdir().expect("tempdir");
+        let roots = make_roots(temp.path());
+        let global = roots.cursor_root.join("globalStorage");
+        fs::create_dir_all(&global).expect("mkdir");
+        let conn = Connection::open(global.join("state.vscdb")).expect("open");
+        conn.execute_batch(
+            "CREATE TABLE cursorDiskKV (key TEXT UNIQUE ON CONFLICT REPLACE, value BLOB);",
+        )
+        .expect("schema");
+        let put = |key: String, value: serde_json::Value| {
+            conn.execute(
+                "INSERT INTO cursorDiskKV (key, value) VALUES (?1, ?2)",
+                params![key, value.to_string()],
+            )
+            .expect("insert");
+        };
+
+        let session_id = "7c1e4b2a-3d5f-4a6e-9b8c-0d1e2f3a4b5c";
+        let composer = |bubbles: &[&str]| {
+            json!({
+                "composerId": session_id,
+                "fullConversationHeadersOnly": bubbles
+                    .iter()
+                    .map(|id| json!({"bubbleId": id}))
+                    .collect::<Vec<_>>(),
+            })
+        };
+        put(format!("composerData:{session_id}"), composer(&["b1"]));
+        put(
+            format!("bubbleId:{session_id}:b1"),
+            json!({"type": 1, "text": "hello"}),
+        );
+
+        let uri = ThreadUri::parse(&format!("agents://cursor/{session_id}")).expect("uri");
+        let (mut follower, entries) = ThreadFollower::start(&uri, &roots).expect("start");
+        assert_eq!(entries.len(), 1);
+
+        // Bubbles are only read again once the composer lists them.
+        put(
+            format!("bubbleId:{session_id}:b2"),
+            json!({"type": 2, "text": "world"}),
+        );
+        assert!(follower.poll().expect("poll").is_empty());
+
+        put(
+            format!("composerData:{session_id}"),
+            composer(&["b1", "b2"]),
+        );
+        let events = follower.poll().expect("poll");
+        assert_eq!(events.len(), 1);
+        assert_eq!(message_text(&events[0]), Some((2, "world")));
+        assert!(follower.poll().expect("poll").is_empty());
+    }
+}
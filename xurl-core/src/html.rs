@@ -0,0 +1,394 @@
+//! Self-contained HTML rendering of a thread view.
+//!
+//! The document carries its CSS inline and has no scripts or external
+//! assets, so it can be attached to a report as a single file. Every
+//! timeline entry is anchored as `#msg-<index>` using its thread numbering.
+
+use serde_json::Value;
+
+use crate::model::{
+    MessageRole, SubagentDetailView, ThreadEntry, ThreadHead, ThreadMessage, ThreadView,
+    TimelineEntry, ToolEntry,
+};
+use crate::render::message_details;
+
+const STYLE: &str = "
+:root { color-scheme: light dark; --fg: #1f2328; --muted: #656d76; --bg: #ffffff; --panel: #f6f8fa; --border: #d0d7de; --user: #ddf4ff; --assistant: #f6f8fa; --error: #cf222e; }
+@media (prefers-color-scheme: dark) { :root { --fg: #e6edf3; --muted: #8d96a0; --bg: #0d1117; --panel: #161b22; --border: #30363d; --user: #0c2d6b; --assistant: #161b22; --error: #f85149; } }
+body { margin: 0; background: var(--bg); color: var(--fg); font: 15px/1.5 -apple-system, BlinkMacSystemFont, \"Segoe UI\", Helvetica, Arial, sans-serif; }
+main { max-width: 960px; margin: 0 auto; padding: 24px 16px 64px; }
+h1 { font-size: 18px; word-break: break-all; }
+h2 { font-size: 16px; margin-top: 32px; }
+code, pre { font: 13px/1.45 ui-monospace, SFMono-Regular, Menlo, Consolas, monospace; }
+table.meta { border-collapse: collapse; width: 100%; margin-bottom: 24px; }
+table.meta th, table.meta td { border: 1px solid var(--border); padding: 4px 8px; text-align: left; vertical-align: top; word-break: break-all; }
+table.meta th { background: var(--panel); width: 180px; font-weight: 600; }
+table.meta ul { margin: 0; padding-left: 18px; }
+.entry { margin: 12px 0; scroll-margin-top: 16px; }
+.entry:target { outline: 2px solid #d4a72c; outline-offset: 2px; }
+.message { border: 1px solid var(--border); border-radius: 12px; padding: 8px 14px; max-width: 85%; }
+.message.user { background: var(--user); margin-left: auto; }
+.message.assistant { background: var(--assistant); margin-right: auto; }
+.message header { color: var(--muted); font-size: 12px; margin-bottom: 4px; }
+.role { font-weight: 600; color: var(--fg); }
+.anchor { color: var(--muted); text-decoration: none; margin-right: 6px; }
+.anchor:hover { text-decoration: underline; }
+.text { white-space: pre-wrap; overflow-wrap: anywhere; }
+details > summary { cursor: pointer; color: var(--muted); }
+details.reasoning { margin: 4px 0 8px; font-size: 13px; }
+details.reasoning .text { color: var(--muted); border-left: 3px solid var(--border); padding-left: 8px; }
+.tool { border: 1px dashed var(--border); border-radius: 8px; padding: 6px 12px; font-size: 13px; }
+.tool.error { border-color: var(--error); }
+.tool.error .label { color: var(--error); }
+.tool pre { background: var(--panel); padding: 8px; overflow-x: auto; white-space: pre-wrap; overflow-wrap: anywhere; }
+.label { font-weight: 600; margin-top: 6px; }
+.preview { color: var(--muted); }
+.compact { text-align: center; color: var(--muted); border-top: 1px dashed var(--border); padding-top: 6px; font-size: 13px; }
+.compact details { text-align: left; }
+.checkpoint { text-align: center; color: var(--muted); font-size: 13px; }
+.empty, .note { color: var(--muted); font-style: italic; }
+";
+
+/// Characters of tool arguments shown next to a collapsed tool call.
+const TOOL_PREVIEW_CHARS: usize = 120;
+
+/// Render a thread view as a standalone HTML document.
+pub fn render_thread_html(view: &ThreadView) -> String {
+    let title = escape_html(&view.head.uri);
+    let mut output = String::new();
+    output.push_str("<!doctype html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n");
+    output.push_str("<meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n");
+    output.push_str(&format!(
+        "<title>{title}</title>\n<style>{STYLE}</style>\n</head>\n<body>\n<main>\n"
+    ));
+    output.push_str(&format!("<h1>{title}</h1>\n"));
+    push_head_table(&view.head, &mut output);
+
+    if let Some(timeline) = &view.timeline {
+        output.push_str("<section class=\"timeline\">\n");
+        if timeline.is_empty() {
+            output.push_str(
+                "<p class=\"empty\">No user/assistant messages or compact events found.</p>\n",
+            );
+        }
+        for entry in timeline {
+            push_entry(entry, &mut output);
+        }
+        output.push_str("</section>\n");
+    }
+    if let Some(detail) = &view.subagent {
+        push_subagent_detail(detail, &mut output);
+    }
+
+    output.push_str("</main>\n</body>\n</html>\n");
+    output
+}
+
+/// Escape text for use in HTML element content and quoted attributes.
+pub fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for ch in text.chars() {
+        match ch {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(ch),
+        }
+    }
+    escaped
+}
+
+fn push_head_table(head: &ThreadHead, output: &mut String) {
+    let rows = [
+        ("URI", Some(head.uri.clone())),
+        ("Provider", Some(head.provider.to_string())),
+        ("Session", Some(head.session_id.clone())),
+        ("Source", Some(head.thread_source.clone())),
+        ("Mode", Some(head.mode.clone())),
+        ("Agent", head.agent_id.clone()),
+        ("Subagent URI", head.subagent_uri.clone()),
+        ("Status", head.status.clone()),
+        ("Status Source", head.status_source.clone()),
+        ("Child Thread", head.child_thread_id.clone()),
+        ("Child Thread Source", head.child_thread_source.clone()),
+        ("Child Last Updated", head.child_last_updated_at.clone()),
+        ("Entry", head.entry_id.clone()),
+    ];
+
+    output.push_str("<table class=\"meta\">\n");
+    for (label, value) in rows {
+        if let Some(value) = value {
+            output.push_str(&format!(
+                "<tr><th>{label}</th><td><code>{}</code></td></tr>\n",
+                escape_html(&value)
+            ));
+        }
+    }
+
+    if let Some(subagents) = head.subagents.as_ref().filter(|list| !list.is_empty()) {
+        let items = subagents
+            .iter()
+            .map(|subagent| {
+                format!(
+                    "<li><code>{}</code> ({})</li>",
+                    escape_html(&subagent.uri),
+                    escape_html(&subagent.status)
+                )
+            })
+            .collect::<String>();
+        output.push_str(&format!(
+            "<tr><th>Subagents</th><td><ul>{items}</ul></td></tr>\n"
+        ));
+    }
+    if let Some(entries) = head.entries.as_ref().filter(|list| !list.is_empty()) {
+        let items = entries
+            .iter()
+            .map(|entry| {
+                let preview = entry
+                    .preview
+                    .as_deref()
+                    .map(|preview| format!(" {}", escape_html(preview)))
+                    .unwrap_or_default();
+                format!("<li><code>{}</code>{preview}</li>", escape_html(&entry.uri))
+            })
+            .collect::<String>();
+        output.push_str(&format!(
+            "<tr><th>Entries</th><td><ul>{items}</ul></td></tr>\n"
+        ));
+    }
+    if !head.warnings.is_empty() {
+        let items = head
+            .warnings
+            .iter()
+            .map(|warning| format!("<li>{}</li>", escape_html(warning)))
+            .collect::<String>();
+        output.push_str(&format!(
+            "<tr><th>Warnings</th><td><ul>{items}</ul></td></tr>\n"
+        ));
+    }
+    output.push_str("</table>\n");
+}
+
+fn push_entry(entry: &ThreadEntry, output: &mut String) {
+    let index = entry.index;
+    let anchor = format!("<a class=\"anchor\" href=\"#msg-{index}\">#{index}</a>");
+    match &entry.entry {
+        TimelineEntry::Message(message) => {
+            push_message(message, &format!(" id=\"msg-{index}\""), &anchor, output);
+        }
+        TimelineEntry::Compact { summary } => {
+            output.push_str(&format!(
+                "<div class=\"entry compact\" id=\"msg-{index}\">{anchor}Context compacted"
+            ));
+            if let Some(summary) = summary.as_deref().filter(|text| !text.trim().is_empty()) {
+                output.push_str(&format!(
+                    "\n<details><summary>Summary</summary><div class=\"text\">{}</div></details>",
+                    escape_html(summary.trim())
+                ));
+            }
+            output.push_str("</div>\n");
+        }
+        TimelineEntry::Tool(tool) => push_tool(index, &anchor, tool, output),
+        TimelineEntry::Checkpoint { hash } => output.push_str(&format!(
+            "<div class=\"entry checkpoint\" id=\"msg-{index}\">{anchor}Checkpoint <code>{}</code></div>\n",
+            escape_html(hash)
+        )),
+    }
+}
+
+fn push_message(message: &ThreadMessage, id: &str, anchor: &str, output: &mut String) {
+    let (class, label) = match message.role {
+        MessageRole::User => ("user", "User"),
+        MessageRole::Assistant => ("assistant", "Assistant"),
+    };
+    output.push_str(&format!(
+        "<article class=\"entry message {class}\"{id}>\n<header>{anchor}<span class=\"role\">{label}</span>"
+    ));
+    let details = message_details(message);
+    if !details.is_empty() {
+        output.push_str(&format!(" · {}", escape_html(&details.join(" · "))));
+    }
+    output.push_str("</header>\n");
+
+    if !message.reasoning.is_empty() {
+        output.push_str(&format!(
+            "<details class=\"reasoning\"><summary>Reasoning</summary><div class=\"text\">{}</div></details>\n",
+            escape_html(&message.reasoning.join("\n\n"))
+        ));
+    }
+    let text = message.text_without_reasoning();
+    if !text.is_empty() {
+        output.push_str(&format!(
+            "<div class=\"text\">{}</div>\n",
+            escape_html(&text)
+        ));
+    }
+    output.push_str("</article>\n");
+}
+
+fn push_tool(index: usize, anchor: &str, tool: &ToolEntry, output: &mut String) {
+    let class = if tool.is_error { "tool error" } else { "tool" };
+    let args = match &tool.args {
+        Value::Null => None,
+        Value::String(text) => Some(text.clone()),
+        args => Some(serde_json::to_string_pretty(args).unwrap_or_else(|_| args.to_string())),
+    };
+    let preview = match &tool.args {
+        Value::Null => String::new(),
+        Value::String(text) => text.clone(),
+        args => args.to_string(),
+    };
+    let preview = preview.split_whitespace().collect::<Vec<_>>().join(" ");
+    let preview = match preview.char_indices().nth(TOOL_PREVIEW_CHARS) {
+        Some((cut, _)) => format!("{}…", &preview[..cut]),
+        None => preview,
+    };
+
+    output.push_str(&format!(
+        "<details class=\"entry {class}\" id=\"msg-{index}\"><summary>{anchor}Tool <code>{}</code> <span class=\"preview\">{}</span></summary>\n",
+        escape_html(&tool.name),
+        escape_html(&preview)
+    ));
+    if let Some(args) = args {
+        output.push_str(&format!(
+            "<div class=\"label\">Arguments</div><pre>{}</pre>\n",
+            escape_html(&args)
+        ));
+    }
+    if let Some(result) = &tool.output {
+        let label = if tool.is_error { "Error" } else { "Output" };
+        output.push_str(&format!(
+            "<div class=\"label\">{label}</div><pre>{}</pre>\n",
+            escape_html(result.trim_end())
+        ));
+        if tool.truncated {
+            output.push_str("<p class=\"note\">Output truncated.</p>\n");
+        }
+    }
+    output.push_str("</details>\n");
+}
+
+fn push_subagent_detail(detail: &SubagentDetailView, output: &mut String) {
+    output.push_str("<section class=\"subagent\">\n<h2>Subagent</h2>\n<table class=\"meta\">\n");
+    output.push_str(&format!(
+        "<tr><th>Status</th><td><code>{}</code> ({})</td></tr>\n",
+        escape_html(&detail.status),
+        escape_html(&detail.status_source)
+    ));
+    if let Some(child) = &detail.child_thread {
+        output.push_str(&format!(
+            "<tr><th>Child Thread</th><td><code>{}</code></td></tr>\n",
+            escape_html(&child.thread_id)
+        ));
+    }
+    if !detail.lifecycle.is_empty() {
+        let items = detail
+            .lifecycle
+            .iter()
+            .map(|event| {
+                format!(
+                    "<li><code>{}</code> {}: {}</li>",
+                    escape_html(event.timestamp.as_deref().unwrap_or("unknown")),
+                    escape_html(&event.event),
+                    escape_html(&event.detail)
+                )
+            })
+            .collect::<String>();
+        output.push_str(&format!(
+            "<tr><th>Lifecycle</th><td><ul>{items}</ul></td></tr>\n"
+        ));
+    }
+    output.push_str("</table>\n");
+
+    if detail.excerpt.is_empty() {
+        output.push_str("<p class=\"empty\">No child thread messages found.</p>\n");
+    }
+    for excerpt in &detail.excerpt {
+        let message = ThreadMessage::new(excerpt.role, excerpt.text.clone());
+        push_message(&message, "", "", output);
+    }
+    output.push_str("</section>\n");
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::model::ProviderKind;
+
+    fn head() -> ThreadHead {
+        ThreadHead {
+            uri: "agents://amp/T-019c0797-c402-7389-bd80-d785c98df295".to_string(),
+            provider: ProviderKind::Amp,
+            session_id: "T-019c0797-c402-7389-bd80-d785c98df295".to_string(),
+            thread_source: "/tmp/thread.json".to_string(),
+            mode: "thread".to_string(),
+            agent_id: None,
+            subagent_uri: None,
+            status: None,
+            status_source: None,
+            child_thread_id: None,
+            child_thread_source: None,
+            child_last_updated_at: None,
+            entry_id: None,
+            subagents: None,
+            entries: None,
+            warnings: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn renders_anchored_entries_with_folded_reasoning_and_tools() {
+        let mut answer = ThreadMessage::new(MessageRole::Assistant, "step by step\n\n<b>done</b>");
+        answer.reasoning = vec!["step by step".to_string()];
+        let view = ThreadView {
+            head: head(),
+            timeline: Some(vec![
+                ThreadEntry {
+                    index: 1,
+                    entry: TimelineEntry::Message(ThreadMessage::new(MessageRole::User, "hi")),
+                },
+                ThreadEntry {
+                    index: 2,
+                    entry: TimelineEntry::Tool(ToolEntry {
+                        name: "Bash".to_string(),
+                        call_id: None,
+                        args: json!({"command": "ls"}),
+                        output: Some("a.txt".to_string()),
+                        is_error: true,
+                        truncated: false,
+                    }),
+                },
+                ThreadEntry {
+                    index: 3,
+                    entry: TimelineEntry::Compact { summary: None },
+                },
+                ThreadEntry {
+                    index: 4,
+                    entry: TimelineEntry::Message(answer),
+                },
+            ]),
+            subagent: None,
+        };
+
+        let html = render_thread_html(&view);
+        assert!(html.starts_with("<!doctype html>"));
+        assert!(!html.contains("<script") && !html.contains("<link"));
+        assert!(html.contains("<tr><th>Provider</th><td><code>amp</code></td></tr>"));
+        assert!(html.contains("<article class=\"entry message user\" id=\"msg-1\">"));
+        assert!(html.contains("<details class=\"entry tool error\" id=\"msg-2\">"));
+        assert!(html.contains("<div class=\"label\">Error</div><pre>a.txt</pre>"));
+        assert!(
+            html.contains(
+                "id=\"msg-3\"><a class=\"anchor\" href=\"#msg-3\">#3</a>Context compacted"
+            )
+        );
+        assert!(
+            html.contains("<summary>Reasoning</summary><div class=\"text\">step by step</div>")
+        );
+        assert!(html.contains("<div class=\"text\">&lt;b&gt;done&lt;/b&gt;</div>"));
+    }
+}
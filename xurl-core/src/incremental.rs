@@ -1,13 +1,13 @@
 use std::fs;
-use std::io::{BufRead, BufReader, Seek, SeekFrom};
+use std::io::{Read, Seek, SeekFrom};
 use std::path::{Path, PathBuf};
 
 use serde_json::Value;
 
 /// Tracks a file position for incremental JSONL reading.
 ///
-/// Each call to [`read_new_lines`] reads only the data appended since the
-/// previous call, making it suitable for polling-based monitoring.
+/// Each call to [`read_new_lines`] reads only the complete records appended
+/// since the previous call, making it suitable for polling-based monitoring.
 pub struct IncrementalReader {
     path: PathBuf,
     offset: u64,
@@ -30,6 +30,14 @@ impl IncrementalReader {
         Self { path, offset }
     }
 
+    /// Create a reader that resumes at a previously saved [`offset`](Self::offset).
+    pub fn with_offset(path: impl Into<PathBuf>, offset: u64) -> Self {
+        Self {
+            path: path.into(),
+            offset,
+        }
+    }
+
     /// Current byte offset into the file.
     pub fn offset(&self) -> u64 {
         self.offset
@@ -40,54 +48,61 @@ impl IncrementalReader {
         &self.path
     }
 
-    /// Read lines appended since the last call.
+    /// Read complete records appended since the last call.
     ///
     /// Each line is parsed as JSON. Lines that are empty or fail to parse
     /// are silently skipped. Returns successfully parsed values.
     pub fn read_new_lines(&mut self) -> Vec<Value> {
+        self.read_new_text()
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .filter_map(|line| serde_json::from_str::<Value>(line).ok())
+            .collect()
+    }
+
+    /// Read the raw text of complete records appended since the last call.
+    ///
+    /// A final line without a newline is kept when it parses as JSON, so a
+    /// finished file loses nothing. Otherwise it is left unread until a
+    /// later call, since the writer is still in the middle of appending it.
+    pub fn read_new_text(&mut self) -> String {
         let file_len = match fs::metadata(&self.path) {
             Ok(m) => m.len(),
-            Err(_) => return Vec::new(),
+            Err(_) => return String::new(),
         };
 
         if file_len <= self.offset {
-            return Vec::new();
+            return String::new();
         }
 
-        let file = match fs::File::open(&self.path) {
+        let mut file = match fs::File::open(&self.path) {
             Ok(f) => f,
-            Err(_) => return Vec::new(),
+            Err(_) => return String::new(),
         };
 
-        let mut reader = BufReader::new(file);
-        if self.offset > 0 {
-            if reader.seek(SeekFrom::Start(self.offset)).is_err() {
-                return Vec::new();
-            }
+        if self.offset > 0 && file.seek(SeekFrom::Start(self.offset)).is_err() {
+            return String::new();
+        }
+
+        let mut bytes = Vec::new();
+        if file.read_to_end(&mut bytes).is_err() {
+            return String::new();
         }
 
-        let mut values = Vec::new();
-        let mut line = String::new();
-
-        loop {
-            line.clear();
-            match reader.read_line(&mut line) {
-                Ok(0) => break,
-                Ok(n) => {
-                    self.offset += n as u64;
-                    let trimmed = line.trim();
-                    if trimmed.is_empty() {
-                        continue;
-                    }
-                    if let Ok(val) = serde_json::from_str::<Value>(trimmed) {
-                        values.push(val);
-                    }
-                }
-                Err(_) => break,
-            }
+        let end = bytes
+            .iter()
+            .rposition(|byte| *byte == b'\n')
+            .map_or(0, |newline| newline + 1);
+        let tail = &bytes[end..];
+        let complete =
+            tail.trim_ascii().is_empty() || serde_json::from_slice::<Value>(tail).is_ok();
+        if !complete {
+            bytes.truncate(end);
         }
+        self.offset += bytes.len() as u64;
 
-        values
+        String::from_utf8_lossy(&bytes).into_owned()
     }
 
     /// Check whether the file has grown since the last read.
@@ -201,4 +216,49 @@ mod tests {
         let batch = reader.read_new_lines();
         assert_eq!(batch.len(), 1);
     }
+
+    #[test]
+    fn partial_trailing_line_is_deferred() {
+        let temp = tempdir().expect("tempdir");
+        let path = temp.path().join("test.jsonl");
+        fs::write(&path, "{\"a\":1}\n{\"b\":").expect("write");
+
+        let mut reader = IncrementalReader::new(&path);
+        let batch = reader.read_new_lines();
+        assert_eq!(batch.len(), 1);
+        assert_eq!(reader.offset(), 8);
+
+        let mut file = fs::OpenOptions::new()
+            .append(true)
+            .open(&path)
+            .expect("open");
+        writeln!(file, "2}}").expect("append");
+        drop(file);
+
+        assert_eq!(reader.read_new_text(), "{\"b\":2}\n");
+    }
+
+    #[test]
+    fn final_record_without_newline_is_read() {
+        let temp = tempdir().expect("tempdir");
+        let path = temp.path().join("test.jsonl");
+        fs::write(&path, "{\"a\":1}\n{\"b\":2}").expect("write");
+
+        let mut reader = IncrementalReader::new(&path);
+        let batch = reader.read_new_lines();
+        assert_eq!(batch.len(), 2);
+        assert_eq!(batch[1]["b"], 2);
+        assert!(!reader.has_new_data());
+
+        let mut file = fs::OpenOptions::new()
+            .append(true)
+            .open(&path)
+            .expect("open");
+        writeln!(file, "\n{{\"c\":3}}").expect("append");
+        drop(file);
+
+        let batch = reader.read_new_lines();
+        assert_eq!(batch.len(), 1);
+        assert_eq!(batch[0]["c"], 3);
+    }
 }
@@ -0,0 +1,882 @@
+//! Optional on-disk search index backed by SQLite FTS5.
+//!
+//! The index stores the rendered messages of every thread in an FTS5 table
+//! with the trigram tokenizer, so `LIKE '%term%'` lookups are served from
+//! the index and match the same substrings as a scan. Each thread row keeps
+//! the file stamp (mtime and size) it was indexed at:
+//!
+//! - unchanged stamps are skipped;
+//! - Codex and Claude rollouts are append-only, so growth is indexed from
+//!   the saved byte offset with [`IncrementalReader`];
+//! - anything else that changed is re-indexed from scratch.
+
+use std::collections::{HashMap, HashSet};
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use dirs::home_dir;
+use rusqlite::{Connection, OptionalExtension, Transaction, params};
+
+use crate::error::{Result, XurlError};
+use crate::incremental::IncrementalReader;
+use crate::model::{
+    IndexProviderStatus, IndexRefresh, IndexStatus, MessageRole, ProviderKind, SearchHit,
+    SearchQuery, SearchResultsView, SessionSummary, ThreadEntry, TimelineEntry, TimelineOptions,
+};
+use crate::provider::ProviderRoots;
+use crate::render::{self, ChunkState};
+use crate::search::{parse_terms, snippet, term_ranges};
+use crate::service::{read_thread_text, session_thread_path};
+
+const SCHEMA_VERSION: &str = "2";
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS meta (
+    key TEXT PRIMARY KEY,
+    value TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS threads (
+    id INTEGER PRIMARY KEY,
+    provider TEXT NOT NULL,
+    session_id TEXT NOT NULL,
+    path TEXT NOT NULL,
+    mtime INTEGER NOT NULL,
+    size INTEGER NOT NULL,
+    read_offset INTEGER NOT NULL,
+    next_index INTEGER NOT NULL,
+    open_calls TEXT NOT NULL,
+    title TEXT,
+    cwd TEXT,
+    created_at INTEGER,
+    updated_at INTEGER NOT NULL,
+    UNIQUE (provider, session_id)
+);
+CREATE VIRTUAL TABLE IF NOT EXISTS messages USING fts5(
+    text,
+    thread_id UNINDEXED,
+    idx UNINDEXED,
+    role UNINDEXED,
+    timestamp UNINDEXED,
+    tokenize = 'trigram'
+);
+";
+
+/// Where the index lives unless a caller picks another path.
+///
+/// Precedence:
+/// 1) `XDG_CACHE_HOME/xurl/index.sqlite`
+/// 2) `~/.cache/xurl/index.sqlite`
+pub fn default_index_path() -> Result<PathBuf> {
+    let cache = env::var_os("XDG_CACHE_HOME")
+        .filter(|path| !path.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| home_dir().map(|home| home.join(".cache")))
+        .ok_or(XurlError::HomeDirectoryNotFound)?;
+    Ok(cache.join("xurl").join("index.sqlite"))
+}
+
+/// Status of the index at `path` without creating it when it is missing.
+pub fn index_status(path: &Path, roots: &ProviderRoots) -> Result<IndexStatus> {
+    if !path.exists() {
+        return Ok(IndexStatus {
+            path: path.to_path_buf(),
+            exists: false,
+            size_bytes: 0,
+            threads: 0,
+            messages: 0,
+            last_refresh: None,
+            pending: 0,
+            providers: Vec::new(),
+        });
+    }
+    SearchIndex::open(path)?.status(roots)
+}
+
+/// Identity of an indexed thread file: `(mtime, size)`.
+type Stamp = (i64, i64);
+
+struct IndexedThread {
+    id: i64,
+    path: String,
+    stamp: Stamp,
+    read_offset: u64,
+    next_index: usize,
+    /// Tool calls still waiting for their result at `read_offset`.
+    open_calls: Vec<String>,
+}
+
+pub struct SearchIndex {
+    path: PathBuf,
+    conn: Connection,
+}
+
+impl SearchIndex {
+    /// Open (creating if needed) the index at `path`.
+    ///
+    /// An index written with a different schema version is discarded.
+    pub fn open(path: impl Into<PathBuf>) -> Result<Self> {
+        let path = path.into();
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|source| XurlError::Io {
+                path: parent.to_path_buf(),
+                source,
+            })?;
+        }
+
+        let conn = Connection::open(&path).map_err(|source| sqlite_error(&path, source))?;
+        let index = Self { path, conn };
+        index.migrate().map_err(|source| index.error(source))?;
+        Ok(index)
+    }
+
+    /// Delete the index at `path` and build it again from every provider.
+    pub fn rebuild(
+        path: impl Into<PathBuf>,
+        roots: &ProviderRoots,
+    ) -> Result<(Self, IndexRefresh)> {
+        let path = path.into();
+        if path.exists() {
+            fs::remove_file(&path).map_err(|source| XurlError::Io {
+                path: path.clone(),
+                source,
+            })?;
+        }
+        let mut index = Self::open(path)?;
+        let refresh = index.refresh(roots)?;
+        Ok((index, refresh))
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Bring the index up to date with the threads currently on disk.
+    ///
+    /// Threads that fail to read are reported as warnings and retried on
+    /// the next refresh. Threads of a provider whose listing failed are kept.
+    pub fn refresh(&mut self, roots: &ProviderRoots) -> Result<IndexRefresh> {
+        let mut refresh = IndexRefresh::default();
+        let (sessions, listed_providers) = list_all_sessions(roots, &mut refresh.warnings);
+        let path = self.path.clone();
+
+        let tx = self
+            .conn
+            .transaction()
+            .map_err(|source| sqlite_error(&path, source))?;
+        let mut indexed = load_threads(&tx).map_err(|source| sqlite_error(&path, source))?;
+
+        for session in &sessions {
+            let key = (session.provider, session.session_id.clone());
+            let existing = indexed.remove(&key);
+            match index_session(&tx, session, existing.as_ref(), roots) {
+                Ok(Outcome::Unchanged) => refresh.unchanged += 1,
+                Ok(outcome) => {
+                    match (&outcome, &existing) {
+                        (Outcome::Appended(_), _) => refresh.appended += 1,
+                        (_, None) => refresh.added += 1,
+                        (_, Some(_)) => refresh.reindexed += 1,
+                    }
+                    refresh.messages_added += outcome.messages();
+                }
+                Err(err) => refresh
+                    .warnings
+                    .push(format!("failed to index {}: {err}", session.uri())),
+            }
+        }
+
+        for ((provider, _), stale) in indexed {
+            if !listed_providers.contains(&provider) {
+                continue;
+            }
+            delete_thread(&tx, stale.id).map_err(|source| sqlite_error(&path, source))?;
+            refresh.removed += 1;
+        }
+
+        tx.execute(
+            "INSERT OR REPLACE INTO meta (key, value) VALUES ('last_refresh', ?1)",
+            [now_epoch().to_string()],
+        )
+        .map_err(|source| sqlite_error(&path, source))?;
+        tx.commit().map_err(|source| sqlite_error(&path, source))?;
+        Ok(refresh)
+    }
+
+    /// [`Self::refresh`] unless the last refresh is younger than `max_age`.
+    ///
+    /// Listing every provider costs more than most searches, so callers
+    /// that refresh on every query use this to bound that cost.
+    pub fn refresh_if_stale(
+        &mut self,
+        roots: &ProviderRoots,
+        max_age: Duration,
+    ) -> Result<Option<IndexRefresh>> {
+        let fresh = self
+            .last_refresh()
+            .map_err(|source| self.error(source))?
+            .is_some_and(|at| now_epoch().saturating_sub(at) < max_age.as_secs());
+        if fresh {
+            return Ok(None);
+        }
+        self.refresh(roots).map(Some)
+    }
+
+    fn last_refresh(&self) -> rusqlite::Result<Option<u64>> {
+        Ok(self
+            .conn
+            .query_row(
+                "SELECT value FROM meta WHERE key = 'last_refresh'",
+                [],
+                |row| row.get::<_, String>(0),
+            )
+            .optional()?
+            .and_then(|value| value.parse().ok()))
+    }
+
+    /// Summarize the index and count threads a refresh would touch.
+    pub fn status(&self, roots: &ProviderRoots) -> Result<IndexStatus> {
+        self.status_inner(roots)
+            .map_err(|source| self.error(source))
+    }
+
+    fn status_inner(&self, roots: &ProviderRoots) -> rusqlite::Result<IndexStatus> {
+        let mut providers = Vec::new();
+        for provider in ProviderKind::ALL {
+            let (threads, messages) = self.conn.query_row(
+                "SELECT COUNT(*), (SELECT COUNT(*) FROM messages m JOIN threads t ON t.id = m.thread_id WHERE t.provider = ?1)
+                 FROM threads WHERE provider = ?1",
+                [provider.to_string()],
+                |row| Ok((row.get::<_, i64>(0)?, row.get::<_, i64>(1)?)),
+            )?;
+            if threads > 0 {
+                providers.push(IndexProviderStatus {
+                    provider,
+                    threads: threads as usize,
+                    messages: messages as usize,
+                });
+            }
+        }
+
+        let last_refresh = self.last_refresh()?;
+
+        let mut warnings = Vec::new();
+        let (sessions, listed_providers) = list_all_sessions(roots, &mut warnings);
+        let mut indexed = load_threads(&self.conn)?;
+        let mut pending = 0;
+        for session in &sessions {
+            match indexed.remove(&(session.provider, session.session_id.clone())) {
+                Some(thread) if thread.stamp == session_stamp(session) => {}
+                _ => pending += 1,
+            }
+        }
+        pending += indexed
+            .keys()
+            .filter(|(provider, _)| listed_providers.contains(provider))
+            .count();
+
+        Ok(IndexStatus {
+            path: self.path.clone(),
+            exists: true,
+            size_bytes: fs::metadata(&self.path).map(|meta| meta.len()).unwrap_or(0),
+            threads: providers.iter().map(|status| status.threads).sum(),
+            messages: providers.iter().map(|status| status.messages).sum(),
+            last_refresh,
+            pending,
+            providers,
+        })
+    }
+
+    /// Run a search against the index. Filters and ordering match
+    /// [`crate::search_threads`].
+    pub fn search(&self, query: &SearchQuery) -> Result<SearchResultsView> {
+        self.search_inner(query)
+            .map_err(|source| self.error(source))
+    }
+
+    fn search_inner(&self, query: &SearchQuery) -> rusqlite::Result<SearchResultsView> {
+        let terms = parse_terms(&query.text);
+
+        let mut stmt = self.conn.prepare(
+            "SELECT id, provider, session_id, title, cwd, created_at, updated_at FROM threads",
+        )?;
+        let threads = stmt
+            .query_map([], |row| {
+                Ok((
+                    row.get::<_, i64>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, String>(2)?,
+                    row.get::<_, Option<String>>(3)?,
+                    row.get::<_, Option<String>>(4)?,
+                    row.get::<_, Option<i64>>(5)?,
+                    row.get::<_, i64>(6)?,
+                ))
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        let mut candidates = HashMap::new();
+        for (id, provider, session_id, title, cwd, created_at, updated_at) in threads {
+            let Ok(provider) = provider.parse::<ProviderKind>() else {
+                continue;
+            };
+            let updated_at = updated_at.max(0) as u64;
+            let started_at = created_at.map_or(updated_at, |created| created.max(0) as u64);
+            let keep = (query.providers.is_empty() || query.providers.contains(&provider))
+                && query.updated_after.is_none_or(|after| updated_at >= after)
+                && query
+                    .started_before
+                    .is_none_or(|before| started_at <= before)
+                && query.cwd.as_deref().is_none_or(|filter| {
+                    cwd.as_deref()
+                        .is_some_and(|cwd| Path::new(cwd).starts_with(filter))
+                });
+            if keep {
+                candidates.insert(id, (provider, session_id, title, cwd, updated_at));
+            }
+        }
+
+        // One FTS query across threads, ordered like the listing; threads
+        // the filters dropped are skipped as their rows come by.
+        let mut hits = Vec::new();
+        if !terms.is_empty() {
+            let conditions = vec!["m.text LIKE ? ESCAPE '\\'"; terms.len()].join(" AND ");
+            let mut stmt = self.conn.prepare(&format!(
+                "SELECT m.thread_id, m.idx, m.role, m.timestamp, m.text
+                 FROM messages m JOIN threads t ON t.id = m.thread_id
+                 WHERE {conditions} ORDER BY t.updated_at DESC, t.session_id, m.idx"
+            ))?;
+            let patterns = terms.iter().map(|term| like_pattern(term));
+            let rows = stmt.query_map(rusqlite::params_from_iter(patterns), |row| {
+                Ok((
+                    row.get::<_, i64>(0)?,
+                    row.get::<_, i64>(1)?,
+                    row.get::<_, String>(2)?,
+                    row.get::<_, Option<String>>(3)?,
+                    row.get::<_, String>(4)?,
+                ))
+            })?;
+
+            for row in rows {
+                let (thread_id, index, role, timestamp, text) = row?;
+                let Some((provider, session_id, title, cwd, updated_at)) =
+                    candidates.get(&thread_id)
+                else {
+                    continue;
+                };
+                let role = match role.as_str() {
+                    "user" => MessageRole::User,
+                    _ => MessageRole::Assistant,
+                };
+                if !query.roles.is_empty() && !query.roles.contains(&role) {
+                    continue;
+                }
+                // LIKE folds more than ASCII case; keep scan semantics.
+                let Some(ranges) = term_ranges(&text, &terms) else {
+                    continue;
+                };
+                let (snippet, highlights) = snippet(&text, &ranges);
+                hits.push(SearchHit {
+                    uri: format!("agents://{provider}/{session_id}"),
+                    provider: *provider,
+                    session_id: session_id.clone(),
+                    title: title.clone(),
+                    cwd: cwd.clone(),
+                    updated_at: *updated_at,
+                    index: index as usize,
+                    role,
+                    timestamp,
+                    snippet,
+                    highlights,
+                });
+                if query.limit.is_some_and(|limit| hits.len() >= limit) {
+                    break;
+                }
+            }
+        }
+
+        Ok(SearchResultsView {
+            query: query.clone(),
+            indexed: true,
+            sessions_scanned: candidates.len(),
+            hits,
+            warnings: Vec::new(),
+        })
+    }
+
+    fn migrate(&self) -> rusqlite::Result<()> {
+        let version = self
+            .conn
+            .query_row(
+                "SELECT value FROM meta WHERE key = 'schema_version'",
+                [],
+                |row| row.get::<_, String>(0),
+            )
+            .optional()
+            .unwrap_or(None);
+        if version
+            .as_deref()
+            .is_some_and(|version| version != SCHEMA_VERSION)
+        {
+            self.conn.execute_batch(
+                "DROP TABLE IF EXISTS messages; DROP TABLE IF EXISTS threads; DROP TABLE IF EXISTS meta;",
+            )?;
+        }
+
+        self.conn.execute_batch(SCHEMA)?;
+        self.conn.execute(
+            "INSERT OR REPLACE INTO meta (key, value) VALUES ('schema_version', ?1)",
+            [SCHEMA_VERSION],
+        )?;
+        Ok(())
+    }
+
+    fn error(&self, source: rusqlite::Error) -> XurlError {
+        sqlite_error(&self.path, source)
+    }
+}
+
+enum Outcome {
+    Unchanged,
+    Indexed(usize),
+    Appended(usize),
+}
+
+impl Outcome {
+    fn messages(&self) -> usize {
+        match self {
+            Self::Unchanged => 0,
+            Self::Indexed(count) | Self::Appended(count) => *count,
+        }
+    }
+}
+
+fn index_session(
+    tx: &Transaction<'_>,
+    session: &SessionSummary,
+    existing: Option<&IndexedThread>,
+    roots: &ProviderRoots,
+) -> Result<Outcome> {
+    let stamp = session_stamp(session);
+    let path = session.path.display().to_string();
+    let sql = |source| sqlite_error(&session.path, source);
+
+    if let Some(thread) = existing {
+        if thread.stamp == stamp && thread.path == path {
+            return Ok(Outcome::Unchanged);
+        }
+
+        if is_append_only(session.provider)
+            && thread.path == path
+            && thread.read_offset > 0
+            && stamp.1 >= thread.stamp.1
+        {
+            let mut reader = IncrementalReader::with_offset(&session.path, thread.read_offset);
+            let raw = reader.read_new_text();
+            let mut state = ChunkState {
+                open_calls: thread.open_calls.clone(),
+                ..ChunkState::default()
+            };
+            let entries =
+                extract_entries(session, &session.path, &raw, thread.next_index, &mut state)?;
+            let added = insert_messages(tx, thread.id, &entries).map_err(sql)?;
+            tx.execute(
+                "UPDATE threads SET mtime = ?2, size = ?3, read_offset = ?4, next_index = ?5,
+                 open_calls = ?6, title = ?7, cwd = ?8, created_at = ?9, updated_at = ?10
+                 WHERE id = ?1",
+                params![
+                    thread.id,
+                    stamp.0,
+                    stamp.1,
+                    reader.offset() as i64,
+                    (thread.next_index + entries.len()) as i64,
+                    encode_calls(&state.open_calls),
+                    session.title,
+                    session.cwd,
+                    session.created_at.map(|created| created as i64),
+                    session.updated_at as i64,
+                ],
+            )
+            .map_err(sql)?;
+            return Ok(Outcome::Appended(added));
+        }
+
+        delete_thread(tx, thread.id).map_err(sql)?;
+    }
+
+    let mut state = ChunkState::default();
+    let (entries, read_offset) = if is_append_only(session.provider) {
+        let mut reader = IncrementalReader::new(&session.path);
+        let raw = reader.read_new_text();
+        (
+            extract_entries(session, &session.path, &raw, 1, &mut state)?,
+            reader.offset(),
+        )
+    } else {
+        let thread_path = session_thread_path(session, roots)?;
+        let raw = read_thread_text(session.provider, &session.session_id, &thread_path)?;
+        (
+            extract_entries(session, &thread_path, &raw, 1, &mut state)?,
+            0,
+        )
+    };
+
+    tx.execute(
+        "INSERT INTO threads (provider, session_id, path, mtime, size, read_offset, next_index,
+         open_calls, title, cwd, created_at, updated_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)",
+        params![
+            session.provider.to_string(),
+            session.session_id,
+            path,
+            stamp.0,
+            stamp.1,
+            read_offset as i64,
+            (entries.len() + 1) as i64,
+            encode_calls(&state.open_calls),
+            session.title,
+            session.cwd,
+            session.created_at.map(|created| created as i64),
+            session.updated_at as i64,
+        ],
+    )
+    .map_err(sql)?;
+    let added = insert_messages(tx, tx.last_insert_rowid(), &entries).map_err(sql)?;
+    Ok(Outcome::Indexed(added))
+}
+
+/// Codex and Claude only ever append to a rollout. Pi also appends, but a
+/// new entry can switch the active branch, so it is re-read in full.
+fn is_append_only(provider: ProviderKind) -> bool {
+    matches!(provider, ProviderKind::Codex | ProviderKind::Claude)
+}
+
+/// Entries numbered like the default timeline, which counts tool entries.
+/// `state` carries open tool calls between appended chunks.
+fn extract_entries(
+    session: &SessionSummary,
+    path: &Path,
+    raw: &str,
+    first_index: usize,
+    state: &mut ChunkState,
+) -> Result<Vec<ThreadEntry>> {
+    if raw.trim().is_empty() {
+        return Ok(Vec::new());
+    }
+    let entries = render::extract_timeline_chunk(
+        session.provider,
+        path,
+        raw,
+        &session.session_id,
+        None,
+        &TimelineOptions {
+            tools: true,
+            ..TimelineOptions::default()
+        },
+        state,
+    )?;
+    Ok(render::number_entries(entries, first_index))
+}
+
+fn encode_calls(calls: &[String]) -> String {
+    serde_json::to_string(calls).unwrap_or_default()
+}
+
+fn insert_messages(
+    tx: &Transaction<'_>,
+    thread_id: i64,
+    entries: &[ThreadEntry],
+) -> rusqlite::Result<usize> {
+    let mut stmt = tx.prepare_cached(
+        "INSERT INTO messages (text, thread_id, idx, role, timestamp) VALUES (?1, ?2, ?3, ?4, ?5)",
+    )?;
+    let mut added = 0;
+    for entry in entries {
+        if let TimelineEntry::Message(message) = &entry.entry
+            && !message.text.trim().is_empty()
+        {
+            stmt.execute(params![
+                message.text,
+                thread_id,
+                entry.index as i64,
+                message.role.to_string(),
+                message.timestamp,
+            ])?;
+            added += 1;
+        }
+    }
+    Ok(added)
+}
+
+fn delete_thread(conn: &Connection, thread_id: i64) -> rusqlite::Result<()> {
+    conn.execute("DELETE FROM messages WHERE thread_id = ?1", [thread_id])?;
+    conn.execute("DELETE FROM threads WHERE id = ?1", [thread_id])?;
+    Ok(())
+}
+
+fn load_threads(
+    conn: &Connection,
+) -> rusqlite::Result<HashMap<(ProviderKind, String), IndexedThread>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, provider, session_id, path, mtime, size, read_offset, next_index, open_calls
+         FROM threads",
+    )?;
+    let rows = stmt.query_map([], |row| {
+        Ok((
+            row.get::<_, String>(1)?,
+            row.get::<_, String>(2)?,
+            IndexedThread {
+                id: row.get(0)?,
+                path: row.get(3)?,
+                stamp: (row.get(4)?, row.get(5)?),
+                read_offset: row.get::<_, i64>(6)?.max(0) as u64,
+                next_index: row.get::<_, i64>(7)?.max(1) as usize,
+                open_calls: serde_json::from_str(&row.get::<_, String>(8)?).unwrap_or_default(),
+            },
+        ))
+    })?;
+
+    let mut threads = HashMap::new();
+    for row in rows {
+        let (provider, session_id, thread) = row?;
+        if let Ok(provider) = provider.parse::<ProviderKind>() {
+            threads.insert((provider, session_id), thread);
+        }
+    }
+    Ok(threads)
+}
+
+/// Every listed session, plus the providers whose listing succeeded.
+fn list_all_sessions(
+    roots: &ProviderRoots,
+    warnings: &mut Vec<String>,
+) -> (Vec<SessionSummary>, HashSet<ProviderKind>) {
+    let mut sessions = Vec::new();
+    let mut listed = HashSet::new();
+    let mut seen = HashSet::new();
+    for provider in ProviderKind::ALL
+        .into_iter()
+        .filter(|provider| provider.listed_by_default())
+    {
+        match roots.list_sessions(provider) {
+            Ok(found) => {
+                listed.insert(provider);
+                sessions.extend(
+                    found.into_iter().filter(|session| {
+                        seen.insert((session.provider, session.session_id.clone()))
+                    }),
+                );
+            }
+            Err(err) => warnings.push(format!("failed to list {provider} sessions: {err}")),
+        }
+    }
+    (sessions, listed)
+}
+
+/// OpenCode, Goose and Cursor threads share one database file, so their
+/// stamp is the session's own update time instead of the file's.
+fn session_stamp(session: &SessionSummary) -> Stamp {
+    if matches!(
+        session.provider,
+        ProviderKind::Opencode | ProviderKind::Goose | ProviderKind::Cursor
+    ) {
+        return (session.updated_at as i64, 0);
+    }
+    fs::metadata(&session.path)
+        .map(|meta| {
+            let mtime = meta
+                .modified()
+                .ok()
+                .and_then(|modified| modified.duration_since(UNIX_EPOCH).ok())
+                .map_or(0, |elapsed| elapsed.as_millis() as i64);
+            (mtime, meta.len() as i64)
+        })
+        .unwrap_or((0, 0))
+}
+
+fn like_pattern(term: &str) -> String {
+    let mut pattern = String::with_capacity(term.len() + 2);
+    pattern.push('%');
+    for ch in term.chars() {
+        if matches!(ch, '%' | '_' | '\\') {
+            pattern.push('\\');
+        }
+        pattern.push(ch);
+    }
+    pattern.push('%');
+    pattern
+}
+
+fn now_epoch() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|now| now.as_secs())
+        .unwrap_or_default()
+}
+
+fn sqlite_error(path: &Path, source: rusqlite::Error) -> XurlError {
+    XurlError::Sqlite {
+        path: path.to_path_buf(),
+        source,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use tempfile::tempdir;
+
+    use super::*;
+    use crate::search::search_threads;
+
+    const SESSION_ID: &str = "019c871c-b1f9-7f60-9c4f-87ed09f13592";
+
+    fn make_roots(base: &Path) -> ProviderRoots {
+        ProviderRoots {
+            amp_root: base.join("amp"),
+            codex_root: base.join("codex"),
+            claude_root: base.join("claude"),
+            gemini_root: base.join("gemini"),
+            qwen_root: base.join("qwen"),
+            pi_root: base.join("pi"),
+            opencode_root: base.join("opencode"),
+            cline_root: base.join("cline"),
+            roo_root: base.join("roo"),
+            aider_roots: vec![base.join("aider")],
+            goose_root: base.join("goose"),
+            cursor_root: base.join("cursor"),
+        }
+    }
+
+    fn codex_message(role: &str, text: &str) -> String {
+        let kind = if role == "user" {
+            "input_text"
+        } else {
+            "output_text"
+        };
+        format!(
+            "{{\"type\":\"response_item\",\"payload\":{{\"type\":\"message\",\"role\":\"{role}\",\"content\":[{{\"type\":\"{kind}\",\"text\":\"{text}\"}}]}}}}\n"
+        )
+    }
+
+    fn write_codex_thread(base: &Path, lines: &str) -> PathBuf {
+        let path = base.join(format!(
+            "codex/sessions/2026/02/23/rollout-2026-02-23T04-48-50-{SESSION_ID}.jsonl"
+        ));
+        fs::create_dir_all(path.parent().expect("parent")).expect("mkdir");
+        fs::write(&path, lines).expect("write");
+        path
+    }
+
+    fn query(text: &str) -> SearchQuery {
+        SearchQuery {
+            text: text.to_string(),
+            ..SearchQuery::default()
+        }
+    }
+
+    #[test]
+    fn indexed_search_matches_scan() {
+        let temp = tempdir().expect("tempdir");
+        let roots = make_roots(temp.path());
+        write_codex_thread(
+            temp.path(),
+            &format!(
+                "{}{}",
+                codex_message("user", "fix the S3 retry bug"),
+                codex_message("assistant", "Retry handling for s3 is fixed")
+            ),
+        );
+
+        let (index, refresh) =
+            SearchIndex::rebuild(temp.path().join("cache/index.sqlite"), &roots).expect("rebuild");
+        assert_eq!(refresh.added, 1);
+        assert_eq!(refresh.messages_added, 2);
+
+        for text in ["s3 retry", "\"retry bug\"", "handling", "missing"] {
+            let indexed = index.search(&query(text)).expect("search");
+            let scanned = search_threads(&roots, &query(text));
+            assert!(indexed.indexed);
+            assert_eq!(indexed.hits, scanned.hits, "query {text}");
+        }
+    }
+
+    #[test]
+    fn refresh_appends_new_lines_and_drops_missing_threads() {
+        let temp = tempdir().expect("tempdir");
+        let roots = make_roots(temp.path());
+        let call = r#"{"type":"response_item","payload":{"type":"function_call","name":"shell","call_id":"call_1","arguments":"{}"}}"#;
+        let thread = write_codex_thread(
+            temp.path(),
+            &format!("{}{call}\n", codex_message("user", "first prompt")),
+        );
+        let mut index = SearchIndex::open(temp.path().join("index.sqlite")).expect("open");
+        index.refresh(&roots).expect("refresh");
+
+        let unchanged = index.refresh(&roots).expect("refresh");
+        assert_eq!(unchanged.unchanged, 1);
+
+        let mut file = fs::OpenOptions::new()
+            .append(true)
+            .open(&thread)
+            .expect("open thread");
+        // The call's output arrives in the appended part and, like in a full
+        // read, does not take a number of its own.
+        let output = r#"{"type":"response_item","payload":{"type":"function_call_output","call_id":"call_1","output":"ok"}}"#;
+        write!(
+            file,
+            "{output}\n{}",
+            codex_message("assistant", "appended answer")
+        )
+        .expect("append");
+        drop(file);
+        assert_eq!(index.status(&roots).expect("status").pending, 1);
+
+        let appended = index.refresh(&roots).expect("refresh");
+        assert_eq!(appended.appended, 1);
+        assert_eq!(appended.messages_added, 1);
+        let hits = index.search(&query("appended")).expect("search").hits;
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].index, 3);
+        assert_eq!(hits, search_threads(&roots, &query("appended")).hits);
+
+        fs::remove_file(&thread).expect("remove");
+        let removed = index.refresh(&roots).expect("refresh");
+        assert_eq!(removed.removed, 1);
+        let status = index.status(&roots).expect("status");
+        assert_eq!((status.threads, status.messages, status.pending), (0, 0, 0));
+    }
+
+    #[test]
+    fn refresh_if_stale_skips_recent_refreshes() {
+        let temp = tempdir().expect("tempdir");
+        let roots = make_roots(temp.path());
+        let mut index = SearchIndex::open(temp.path().join("index.sqlite")).expect("open");
+        let hour = Duration::from_secs(3600);
+
+        let first = index.refresh_if_stale(&roots, hour).expect("refresh");
+        assert!(first.is_some(), "a new index has never been refreshed");
+
+        write_codex_thread(temp.path(), &codex_message("user", "late prompt"));
+        assert!(
+            index
+                .refresh_if_stale(&roots, hour)
+                .expect("refresh")
+                .is_none()
+        );
+        assert!(
+            index
+                .search(&query("late"))
+                .expect("search")
+                .hits
+                .is_empty()
+        );
+
+        let forced = index
+            .refresh_if_stale(&roots, Duration::ZERO)
+            .expect("refresh")
+            .expect("zero max age always refreshes");
+        assert_eq!(forced.added, 1);
+        assert_eq!(index.search(&query("late")).expect("search").hits.len(), 1);
+    }
+}
@@ -1,23 +1,57 @@
+pub mod diff;
 pub mod error;
+pub mod files;
+pub mod follow;
+pub mod html;
 pub mod incremental;
+#[cfg(feature = "sqlite")]
+pub mod index;
+pub mod lookup;
 pub mod model;
 pub mod process;
 pub mod provider;
 pub mod render;
+pub mod running;
+pub mod search;
 pub mod service;
+pub mod time;
 pub mod uri;
 
+pub use diff::extract_file_diffs;
 pub use error::{Result, XurlError};
+pub use files::extract_touched_files;
+pub use follow::ThreadFollower;
+pub use html::render_thread_html;
 pub use incremental::IncrementalReader;
+#[cfg(feature = "sqlite")]
+pub use index::{SearchIndex, default_index_path, index_status};
+pub use lookup::resolve_uri;
 pub use model::{
-    ActiveSession, MessageRole, PiEntryListView, ProviderKind, ResolutionMeta, ResolvedThread,
-    SubagentDetailView, SubagentInfo, SubagentListView, SubagentView, ThreadMessage, ToolCall,
+    ActiveSession, DEFAULT_MAX_TOOL_OUTPUT, FileChange, FileDiff, FileTouch, FollowEvent,
+    IndexProviderStatus, IndexRefresh, IndexStatus, MessageRole, PiEntryListView, ProviderKind,
+    ResolutionMeta, ResolvedThread, RunningAgent, RunningAgentsView, SearchHit, SearchQuery,
+    SearchResultsView, SessionListQuery, SessionListView, SessionSummary, StaleSession,
+    SubagentCounts, SubagentDetailView, SubagentInfo, SubagentListView, SubagentView,
+    ThreadDiffView, ThreadEntry, ThreadHead, ThreadHeadEntry, ThreadHeadSubagent, ThreadMessage,
+    ThreadView, TimelineEntry, TimelineOptions, TokenUsage, ToolCall, ToolEntry, TouchedFile,
+    TouchedFilesView,
+};
+pub use process::{
+    AgentProcess, discover_agent_pid, discover_agent_pids, discover_pid_for_session,
 };
-pub use process::{discover_agent_pid, discover_agent_pids, discover_pid_for_session, AgentProcess};
 pub use provider::ProviderRoots;
-pub use render::{extract_tool_calls, TOOL_TYPES};
+pub use render::{
+    TOOL_TYPES, extract_thread_entries, extract_tool_calls, render_entry_markdown,
+    render_tool_call_markdown,
+};
+pub use running::list_running_agents;
+pub use search::search_threads;
 pub use service::{
-    list_subagents, render_subagent_view_markdown, render_thread_head_markdown,
-    render_thread_markdown, resolve_subagent_view, resolve_thread, resolve_thread_json,
+    list_session_summaries, list_sessions, list_subagents, render_index_status_markdown,
+    render_running_agents_markdown, render_search_markdown, render_session_list_markdown,
+    render_subagent_view_markdown, render_thread_diff, render_thread_head_markdown,
+    render_thread_markdown, render_touched_files_markdown, resolve_subagent_view, resolve_thread,
+    resolve_thread_diff, resolve_thread_head, resolve_thread_json, resolve_thread_view,
+    resolve_touched_files,
 };
-pub use uri::ThreadUri;
+pub use uri::{EntrySelection, ThreadUri, UriFormat, UriQuery};
@@ -0,0 +1,457 @@
+//! URIs that can only be resolved by looking at the provider stores.
+//!
+//! `agents://<provider>/latest` and `agents://<provider>/previous` name the
+//! newest and second-newest thread by modification time; `any` in place of
+//! the provider searches every provider, and `?cwd=<dir>` keeps only
+//! threads whose recorded working directory is inside `<dir>`. A short
+//! session id prefix, git style, expands to the one session it matches,
+//! and `agents://<session_id>` (or a bare id) finds the provider that has
+//! the session.
+//! The result is an ordinary [`ThreadUri`] for the concrete session, so the
+//! rest of the URI (child path, view query, fragment) carries over unchanged.
+
+use std::borrow::Cow;
+use std::fs;
+use std::path::PathBuf;
+
+use crate::error::{Result, XurlError};
+use crate::model::{ProviderKind, SessionListQuery};
+use crate::provider::ProviderRoots;
+use crate::service::filtered_sessions;
+use crate::uri::{ThreadUri, is_session_id, is_session_prefix, percent_decode};
+
+/// Provider segment that matches every provider.
+const ANY_PROVIDER: &str = "any";
+
+/// Parse `input` as a thread URI, resolving symbolic targets, short
+/// session id prefixes and provider-less ids against the provider stores
+/// first. Input without a scheme is read as `agents://<input>`. Stores that
+/// fail to read during the lookup are reported in `warnings`.
+pub fn resolve_uri(
+    input: &str,
+    roots: &ProviderRoots,
+    warnings: &mut Vec<String>,
+) -> Result<ThreadUri> {
+    let input = if input.contains("://") {
+        Cow::Borrowed(input)
+    } else {
+        Cow::Owned(format!("agents://{input}"))
+    };
+    let input = input.as_ref();
+    let Some(parts) = UriParts::split(input) else {
+        return ThreadUri::parse(input);
+    };
+
+    if is_symbolic(parts.session) {
+        return resolve_symbolic(&parts, roots, warnings);
+    }
+    if parts.cwd.is_some() {
+        return Err(XurlError::InvalidUri(format!(
+            "`cwd` only applies to symbolic targets such as agents://claude/latest?cwd=.: {input}"
+        )));
+    }
+
+    let Some(provider) = parts.provider.filter(|provider| *provider != ANY_PROVIDER) else {
+        return resolve_unqualified(&parts, roots, warnings);
+    };
+    if let Ok(provider) = provider.parse::<ProviderKind>()
+        && !is_session_id(provider, parts.session)
+        && is_session_prefix(provider, parts.session)
+    {
+        let session_id = expand_prefix(provider, parts.session, roots)?;
+        return parts.resolved(provider, &session_id);
+    }
+    ThreadUri::parse(input)
+}
+
+/// The pieces of a thread URI, before the session segment is validated.
+struct UriParts<'a> {
+    /// `None` for `agents://<session_id>`.
+    provider: Option<&'a str>,
+    session: &'a str,
+    child: Option<&'a str>,
+    /// The query without `cwd`, which only steers lookup.
+    query: Option<String>,
+    cwd: Option<&'a str>,
+    fragment: Option<&'a str>,
+}
+
+impl<'a> UriParts<'a> {
+    fn split(input: &'a str) -> Option<Self> {
+        let (rest, fragment) = match input.split_once('#') {
+            Some((rest, fragment)) => (rest, Some(fragment)),
+            None => (input, None),
+        };
+        let (location, query) = match rest.split_once('?') {
+            Some((location, query)) => (location, Some(query)),
+            None => (rest, None),
+        };
+        let (cwd, query) = split_cwd(query);
+
+        let (scheme, target) = location.split_once("://")?;
+        let (provider, path) = match target.split_once('/') {
+            _ if scheme != "agents" => (Some(scheme), target),
+            Some((head, _)) if looks_like_session(head) => (None, target),
+            Some((provider, path)) => (Some(provider), path),
+            None if looks_like_session(target) || is_symbolic(target) => (None, target),
+            None => return None,
+        };
+        let path = match provider {
+            Some("codex") => path.strip_prefix("threads/").unwrap_or(path),
+            _ => path,
+        };
+        let (session, child) = match path.split_once('/') {
+            Some((session, child)) => (session, Some(child)),
+            None => (path, None),
+        };
+
+        Some(Self {
+            provider,
+            session,
+            child,
+            query,
+            cwd,
+            fragment,
+        })
+    }
+
+    /// The same URI pointing at a concrete session.
+    fn resolved(&self, provider: ProviderKind, session_id: &str) -> Result<ThreadUri> {
+        let mut uri = format!("agents://{provider}/{session_id}");
+        if let Some(child) = self.child {
+            uri = format!("{uri}/{child}");
+        }
+        if let Some(query) = &self.query {
+            uri = format!("{uri}?{query}");
+        }
+        if let Some(fragment) = self.fragment {
+            uri = format!("{uri}#{fragment}");
+        }
+        ThreadUri::parse(&uri)
+    }
+}
+
+fn resolve_symbolic(
+    parts: &UriParts<'_>,
+    roots: &ProviderRoots,
+    warnings: &mut Vec<String>,
+) -> Result<ThreadUri> {
+    let provider = match parts.provider {
+        None | Some(ANY_PROVIDER) => None,
+        Some(provider) => Some(provider.parse::<ProviderKind>()?),
+    };
+    let position = match parts.session {
+        "latest" => 0,
+        _ => 1,
+    };
+    let cwd = parts.cwd.map(|cwd| {
+        let cwd = PathBuf::from(percent_decode(cwd));
+        fs::canonicalize(&cwd).unwrap_or(cwd)
+    });
+
+    let sessions = filtered_sessions(
+        roots,
+        &SessionListQuery {
+            providers: provider.into_iter().collect(),
+            cwd: cwd.clone(),
+            ..SessionListQuery::default()
+        },
+        warnings,
+    );
+    let Some(session) = sessions.get(position) else {
+        let scope = cwd
+            .map(|cwd| format!(" under {}", cwd.display()))
+            .unwrap_or_default();
+        return Err(XurlError::NoMatchingThread(format!(
+            "agents://{}/{}{scope}",
+            provider.map_or(ANY_PROVIDER.to_string(), |provider| provider.to_string()),
+            parts.session
+        )));
+    };
+    parts.resolved(session.provider, &session.session_id)
+}
+
+/// Probe every provider whose id format fits the session segment and
+/// return the single thread found. Providers whose store fails to read are
+/// reported in `warnings` and skipped.
+fn resolve_unqualified(
+    parts: &UriParts<'_>,
+    roots: &ProviderRoots,
+    warnings: &mut Vec<String>,
+) -> Result<ThreadUri> {
+    let id = parts.session;
+    let mut found = Vec::new();
+    for provider in ProviderKind::ALL
+        .into_iter()
+        .filter(|provider| provider.listed_by_default())
+    {
+        if is_session_id(provider, id) {
+            let session_id = ThreadUri::parse(&format!("agents://{provider}/{id}"))?.session_id;
+            match roots.has_session(provider, &session_id) {
+                Ok(true) => found.push((provider, session_id)),
+                Ok(false) => {}
+                Err(err) => warnings.push(format!("failed to look up {provider} sessions: {err}")),
+            }
+        } else if is_session_prefix(provider, id) {
+            match prefix_matches(provider, id, roots) {
+                Ok(matches) => {
+                    found.extend(matches.into_iter().map(|session_id| (provider, session_id)))
+                }
+                Err(err) => warnings.push(format!("failed to list {provider} sessions: {err}")),
+            }
+        }
+    }
+
+    match found.as_slice() {
+        [] => Err(XurlError::NoMatchingThread(format!("agents://{id}"))),
+        [(provider, session_id)] => parts.resolved(*provider, session_id),
+        _ => Err(XurlError::AmbiguousThread {
+            target: format!("agents://{id}"),
+            candidates: found
+                .iter()
+                .map(|(provider, session_id)| format!("agents://{provider}/{session_id}"))
+                .collect(),
+        }),
+    }
+}
+
+fn is_symbolic(segment: &str) -> bool {
+    matches!(segment, "latest" | "previous")
+}
+
+/// Whether a URI segment is a session id or id prefix for some provider,
+/// rather than a provider name.
+fn looks_like_session(segment: &str) -> bool {
+    ProviderKind::ALL
+        .into_iter()
+        .any(|provider| is_session_id(provider, segment) || is_session_prefix(provider, segment))
+}
+
+/// The one session id in the provider's store that starts with `prefix`.
+fn expand_prefix(provider: ProviderKind, prefix: &str, roots: &ProviderRoots) -> Result<String> {
+    let mut matches = prefix_matches(provider, prefix, roots)?;
+    match matches.len() {
+        0 => Err(XurlError::NoMatchingThread(format!(
+            "agents://{provider}/{prefix}…"
+        ))),
+        1 => Ok(matches.remove(0)),
+        _ => Err(XurlError::AmbiguousThread {
+            target: format!("agents://{provider}/{prefix}"),
+            candidates: matches
+                .iter()
+                .map(|session_id| format!("agents://{provider}/{session_id}"))
+                .collect(),
+        }),
+    }
+}
+
+/// Session ids in the provider's store that start with `prefix`, sorted.
+fn prefix_matches(
+    provider: ProviderKind,
+    prefix: &str,
+    roots: &ProviderRoots,
+) -> Result<Vec<String>> {
+    let mut matches = roots
+        .list_sessions(provider)?
+        .into_iter()
+        .map(|session| session.session_id)
+        .filter(|session_id| match provider {
+            // OpenCode ids are case-sensitive; the rest are hex.
+            ProviderKind::Opencode => session_id.starts_with(prefix),
+            _ => session_id
+                .get(..prefix.len())
+                .is_some_and(|head| head.eq_ignore_ascii_case(prefix)),
+        })
+        .collect::<Vec<_>>();
+    matches.sort();
+    matches.dedup();
+    Ok(matches)
+}
+
+/// Take `cwd=<dir>` out of a query, returning it and the remaining pairs.
+fn split_cwd(query: Option<&str>) -> (Option<&str>, Option<String>) {
+    let Some(query) = query else {
+        return (None, None);
+    };
+    let mut cwd = None;
+    let mut rest = Vec::new();
+    for pair in query.split('&').filter(|pair| !pair.is_empty()) {
+        match pair.strip_prefix("cwd=") {
+            Some(value) => cwd = Some(value),
+            None => rest.push(pair),
+        }
+    }
+    let rest = (!rest.is_empty()).then(|| rest.join("&"));
+    (cwd, rest)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::path::Path;
+
+    use tempfile::tempdir;
+
+    use super::resolve_uri;
+    use crate::error::Result;
+    use crate::model::ProviderKind;
+    use crate::provider::ProviderRoots;
+    use crate::uri::ThreadUri;
+
+    const OLDER: &str = "2823d1df-720a-4c31-ac55-ae8ba726721f";
+    const NEWER: &str = "9b2e4c1a-0d3f-4e5b-8a7c-6f1e2d3c4b5a";
+
+    fn resolve(input: &str, roots: &ProviderRoots) -> Result<ThreadUri> {
+        resolve_uri(input, roots, &mut Vec::new())
+    }
+
+    fn write_claude_thread(root: &Path, project: &str, session_id: &str, cwd: &str, age: u64) {
+        let dir = root.join("projects").join(project);
+        fs::create_dir_all(&dir).expect("mkdir");
+        let path = dir.join(format!("{session_id}.jsonl"));
+        fs::write(
+            &path,
+            format!(
+                "{{\"type\":\"user\",\"sessionId\":\"{session_id}\",\"cwd\":\"{cwd}\",\"message\":{{\"role\":\"user\",\"content\":\"hi\"}}}}\n"
+            ),
+        )
+        .expect("write");
+        let modified = std::time::SystemTime::now() - std::time::Duration::from_secs(age);
+        fs::File::options()
+            .write(true)
+            .open(&path)
+            .and_then(|file| file.set_modified(modified))
+            .expect("set mtime");
+    }
+
+    fn make_roots(base: &Path) -> ProviderRoots {
+        ProviderRoots {
+            amp_root: base.join("amp"),
+            codex_root: base.join("codex"),
+            claude_root: base.join("claude"),
+            gemini_root: base.join("gemini"),
+            qwen_root: base.join("qwen"),
+            pi_root: base.join("pi"),
+            opencode_root: base.join("opencode"),
+            cline_root: base.join("cline"),
+            roo_root: base.join("roo"),
+            aider_roots: vec![base.join("aider")],
+            goose_root: base.join("goose"),
+            cursor_root: base.join("cursor"),
+        }
+    }
+
+    #[test]
+    fn resolves_latest_and_previous_by_mtime_and_cwd() {
+        let temp = tempdir().expect("tempdir");
+        let roots = make_roots(temp.path());
+        write_claude_thread(&roots.claude_root, "-repo-a", OLDER, "/repo/a", 600);
+        write_claude_thread(&roots.claude_root, "-repo-b", NEWER, "/repo/b/sub", 60);
+
+        let latest = resolve("agents://claude/latest", &roots).expect("latest");
+        assert_eq!(latest.provider, ProviderKind::Claude);
+        assert_eq!(latest.session_id, NEWER);
+
+        let previous = resolve("agents://any/previous?tools=1#last", &roots).expect("previous");
+        assert_eq!(
+            previous.as_agents_string(),
+            format!("agents://claude/{OLDER}?tools=1#last")
+        );
+
+        let scoped = resolve("claude://latest?cwd=/repo/a", &roots).expect("scoped");
+        assert_eq!(scoped.session_id, OLDER);
+
+        let err = resolve("agents://codex/latest", &roots).expect_err("no codex threads");
+        assert!(format!("{err}").contains("no thread matches agents://codex/latest"));
+        let err = resolve("agents://claude/previous?cwd=/repo/b", &roots)
+            .expect_err("only one thread under /repo/b");
+        assert!(format!("{err}").contains("under /repo/b"));
+        let err = resolve(&format!("agents://claude/{OLDER}?cwd=."), &roots)
+            .expect_err("cwd needs a symbolic target");
+        assert!(format!("{err}").contains("only applies to symbolic targets"));
+    }
+
+    #[test]
+    fn expands_unique_session_id_prefixes() {
+        let temp = tempdir().expect("tempdir");
+        let roots = make_roots(temp.path());
+        write_claude_thread(&roots.claude_root, "-repo", OLDER, "/repo", 600);
+        write_claude_thread(&roots.claude_root, "-repo", NEWER, "/repo", 60);
+        let twin = "2823d1df-0000-4000-8000-000000000000";
+        write_claude_thread(&roots.claude_root, "-repo", twin, "/repo", 30);
+
+        let uri = resolve("agents://claude/9B2E4C#2", &roots).expect("unique prefix");
+        assert_eq!(uri.as_agents_string(), format!("agents://claude/{NEWER}#2"));
+        let uri = resolve("claude://2823d1df-720a", &roots).expect("longer prefix");
+        assert_eq!(uri.session_id, OLDER);
+
+        let err = resolve("agents://claude/2823d1", &roots).expect_err("ambiguous");
+        assert_eq!(
+            format!("{err}"),
+            format!(
+                "agents://claude/2823d1 matches more than one thread: agents://claude/{twin}, agents://claude/{OLDER}"
+            )
+        );
+        let err = resolve("agents://claude/abcdef", &roots).expect_err("no match");
+        assert!(format!("{err}").contains("no thread matches agents://claude/abcdef…"));
+        let err = resolve("agents://claude/9b2e4", &roots).expect_err("too short");
+        assert!(format!("{err}").contains("invalid session id"));
+    }
+
+    #[test]
+    fn finds_the_provider_for_provider_less_ids() {
+        let temp = tempdir().expect("tempdir");
+        let roots = make_roots(temp.path());
+        write_claude_thread(&roots.claude_root, "-repo", OLDER, "/repo", 600);
+        write_claude_thread(&roots.claude_root, "-repo", NEWER, "/repo", 60);
+        let pi_dir = roots.pi_root.join("sessions/--repo--");
+        fs::create_dir_all(&pi_dir).expect("mkdir");
+        fs::write(
+            pi_dir.join(format!("2026-02-23T13-00-12-780Z_{OLDER}.jsonl")),
+            format!(
+                "{{\"type\":\"session\",\"version\":3,\"id\":\"{OLDER}\",\"cwd\":\"/repo\"}}\n"
+            ),
+        )
+        .expect("write");
+
+        let uri = resolve(&format!("agents://{NEWER}?tools=1"), &roots).expect("unique");
+        assert_eq!(
+            uri.as_agents_string(),
+            format!("agents://claude/{NEWER}?tools=1")
+        );
+        let uri = resolve("9b2e4c1a", &roots).expect("bare prefix");
+        assert_eq!(uri.provider, ProviderKind::Claude);
+        let uri = resolve("latest", &roots).expect("bare symbolic");
+        assert_eq!(uri.provider, ProviderKind::Pi);
+
+        let err = resolve(OLDER, &roots).expect_err("in two stores");
+        assert_eq!(
+            format!("{err}"),
+            format!(
+                "agents://{OLDER} matches more than one thread: agents://claude/{OLDER}, agents://pi/{OLDER}"
+            )
+        );
+        let err = resolve("agents://0123abcd-0000-4000-8000-000000000000", &roots)
+            .expect_err("unknown id");
+        assert!(format!("{err}").contains("no thread matches agents://0123abcd"));
+        let err = resolve("agents://windsurf/2823d1df", &roots).expect_err("unknown provider");
+        assert!(format!("{err}").contains("unsupported scheme: windsurf"));
+    }
+
+    #[cfg(feature = "sqlite")]
+    #[test]
+    fn reports_stores_that_fail_to_read_while_probing_ids() {
+        let temp = tempdir().expect("tempdir");
+        let roots = make_roots(temp.path());
+        write_claude_thread(&roots.claude_root, "-repo", NEWER, "/repo", 60);
+        let global = roots.cursor_root.join("globalStorage");
+        fs::create_dir_all(&global).expect("mkdir");
+        fs::write(global.join("state.vscdb"), "not a database").expect("write");
+
+        let mut warnings = Vec::new();
+        let uri = resolve_uri(NEWER, &roots, &mut warnings).expect("found in claude");
+        assert_eq!(uri.provider, ProviderKind::Claude);
+        assert_eq!(warnings.len(), 1, "{warnings:?}");
+        assert!(warnings[0].starts_with("failed to look up cursor sessions"));
+    }
+}
@@ -1,17 +1,53 @@
 use std::fmt;
 use std::path::PathBuf;
+use std::str::FromStr;
 
 use serde::Serialize;
 use serde_json::Value;
 
+use crate::error::XurlError;
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "lowercase")]
 pub enum ProviderKind {
     Amp,
     Codex,
     Claude,
     Gemini,
+    Qwen,
     Pi,
     Opencode,
+    Cline,
+    Roo,
+    Aider,
+    Goose,
+    Cursor,
+}
+
+impl ProviderKind {
+    pub const ALL: [ProviderKind; 12] = [
+        Self::Amp,
+        Self::Codex,
+        Self::Claude,
+        Self::Gemini,
+        Self::Qwen,
+        Self::Pi,
+        Self::Opencode,
+        Self::Cline,
+        Self::Roo,
+        Self::Aider,
+        Self::Goose,
+        Self::Cursor,
+    ];
+
+    /// Whether the provider is enumerated when no provider is named, as by
+    /// `xurl ls`, `xurl ps`, the search index and provider-less ids.
+    ///
+    /// Aider has no central store; its threads are found by walking project
+    /// trees, so it is only listed when asked for by name.
+    pub fn listed_by_default(self) -> bool {
+        self != Self::Aider
+    }
 }
 
 impl fmt::Display for ProviderKind {
@@ -21,8 +57,36 @@ impl fmt::Display for ProviderKind {
             Self::Codex => write!(f, "codex"),
             Self::Claude => write!(f, "claude"),
             Self::Gemini => write!(f, "gemini"),
+            Self::Qwen => write!(f, "qwen"),
             Self::Pi => write!(f, "pi"),
             Self::Opencode => write!(f, "opencode"),
+            Self::Cline => write!(f, "cline"),
+            Self::Roo => write!(f, "roo"),
+            Self::Aider => write!(f, "aider"),
+            Self::Goose => write!(f, "goose"),
+            Self::Cursor => write!(f, "cursor"),
+        }
+    }
+}
+
+impl FromStr for ProviderKind {
+    type Err = XurlError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        match input {
+            "amp" => Ok(Self::Amp),
+            "codex" => Ok(Self::Codex),
+            "claude" => Ok(Self::Claude),
+            "gemini" => Ok(Self::Gemini),
+            "qwen" => Ok(Self::Qwen),
+            "pi" => Ok(Self::Pi),
+            "opencode" => Ok(Self::Opencode),
+            "cline" => Ok(Self::Cline),
+            "roo" => Ok(Self::Roo),
+            "aider" => Ok(Self::Aider),
+            "goose" => Ok(Self::Goose),
+            "cursor" => Ok(Self::Cursor),
+            _ => Err(XurlError::UnsupportedScheme(input.to_string())),
         }
     }
 }
@@ -43,6 +107,7 @@ pub struct ResolvedThread {
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
 pub enum MessageRole {
     User,
     Assistant,
@@ -57,10 +122,205 @@ impl fmt::Display for MessageRole {
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub struct ThreadMessage {
     pub role: MessageRole,
     pub text: String,
+    /// When the record was written, as RFC 3339.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timestamp: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub usage: Option<TokenUsage>,
+    /// Reasoning chunks that are also part of `text`, kept apart so
+    /// renderers can fold them away.
+    #[serde(skip)]
+    pub reasoning: Vec<String>,
+}
+
+impl ThreadMessage {
+    pub fn new(role: MessageRole, text: impl Into<String>) -> Self {
+        Self {
+            role,
+            text: text.into(),
+            timestamp: None,
+            model: None,
+            usage: None,
+            reasoning: Vec::new(),
+        }
+    }
+
+    /// Message text with the reasoning chunks taken out.
+    pub fn text_without_reasoning(&self) -> String {
+        let mut text = self.text.clone();
+        for chunk in &self.reasoning {
+            text = text.replacen(chunk.as_str(), "", 1);
+        }
+        while text.contains("\n\n\n") {
+            text = text.replace("\n\n\n", "\n\n");
+        }
+        text.trim().to_string()
+    }
+}
+
+/// Token counts for the model response that produced a message, plus its
+/// cost when the provider records one.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct TokenUsage {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub input_tokens: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub output_tokens: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cache_read_tokens: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cache_write_tokens: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reasoning_tokens: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total_tokens: Option<u64>,
+    /// Cost in US dollars.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cost: Option<f64>,
+}
+
+impl TokenUsage {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum TimelineEntry {
+    Message(ThreadMessage),
+    Compact {
+        summary: Option<String>,
+    },
+    Tool(ToolEntry),
+    /// A workspace snapshot the agent can roll back to, named by its
+    /// shadow-git commit.
+    Checkpoint {
+        hash: String,
+    },
+}
+
+/// A tool invocation paired with its result by call id.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ToolEntry {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub call_id: Option<String>,
+    pub args: Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub output: Option<String>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub is_error: bool,
+    /// Whether `output` was cut to [`TimelineOptions::max_tool_output`].
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub truncated: bool,
+}
+
+/// Default cap on tool output characters kept per timeline entry.
+pub const DEFAULT_MAX_TOOL_OUTPUT: usize = 2000;
+
+/// Controls which records become timeline entries.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimelineOptions {
+    /// Include tool invocations and their results.
+    pub tools: bool,
+    /// Maximum characters of tool output kept per entry.
+    pub max_tool_output: usize,
+    /// Keep only messages from this role; tool and compaction entries are
+    /// dropped too. Entries keep their unfiltered numbering.
+    pub role: Option<MessageRole>,
+    /// Keep reasoning chunks in message text.
+    pub thinking: bool,
+}
+
+impl Default for TimelineOptions {
+    fn default() -> Self {
+        Self {
+            tools: false,
+            max_tool_output: DEFAULT_MAX_TOOL_OUTPUT,
+            role: None,
+            thinking: true,
+        }
+    }
+}
+
+/// A timeline entry together with its 1-based position in the thread.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ThreadEntry {
+    pub index: usize,
+    #[serde(flatten)]
+    pub entry: TimelineEntry,
+}
+
+/// Header fields shared by the YAML frontmatter and JSON output.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ThreadHead {
+    pub uri: String,
+    pub provider: ProviderKind,
+    pub session_id: String,
+    pub thread_source: String,
+    pub mode: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub agent_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subagent_uri: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status_source: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub child_thread_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub child_thread_source: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub child_last_updated_at: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub entry_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subagents: Option<Vec<ThreadHeadSubagent>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub entries: Option<Vec<ThreadHeadEntry>>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ThreadHeadSubagent {
+    pub agent_id: String,
+    pub uri: String,
+    pub status: String,
+    pub status_source: String,
+    pub last_update: Option<String>,
+    pub thread_source: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ThreadHeadEntry {
+    pub entry_id: String,
+    pub uri: String,
+    pub entry_type: String,
+    pub parent_id: Option<String>,
+    pub timestamp: Option<String>,
+    pub preview: Option<String>,
+    pub is_leaf: bool,
+}
+
+/// Full machine-readable view of a thread URI: the header plus either the
+/// rendered timeline or, for subagent URIs, the drill-down view.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ThreadView {
+    #[serde(flatten)]
+    pub head: ThreadHead,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timeline: Option<Vec<ThreadEntry>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subagent: Option<SubagentDetailView>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize)]
@@ -178,6 +438,271 @@ pub struct ToolCall {
     pub call_type: String,
 }
 
+/// Something newly appended to a thread while it is being followed.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum FollowEvent {
+    Entry(ThreadEntry),
+    ToolCall(ToolCall),
+}
+
+/// One thread discovered by enumerating a provider's local store.
+///
+/// Timestamps are epoch seconds. `path` is the file `resolve` would return,
+/// except for database-backed providers where it points at the database.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SessionSummary {
+    pub provider: ProviderKind,
+    pub session_id: String,
+    pub path: PathBuf,
+    pub created_at: Option<u64>,
+    pub updated_at: u64,
+    pub cwd: Option<String>,
+    pub title: Option<String>,
+    pub archived: bool,
+    pub parent_session_id: Option<String>,
+    pub message_count: Option<usize>,
+}
+
+impl SessionSummary {
+    pub fn uri(&self) -> String {
+        format!("agents://{}/{}", self.provider, self.session_id)
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct SessionListQuery {
+    pub providers: Vec<ProviderKind>,
+    pub updated_after: Option<u64>,
+    pub cwd: Option<PathBuf>,
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SessionListView {
+    pub query: SessionListQuery,
+    pub sessions: Vec<SessionSummary>,
+    #[serde(skip_serializing)]
+    pub warnings: Vec<String>,
+}
+
+/// Filters for [`crate::search_threads`]. Timestamps are epoch seconds.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct SearchQuery {
+    /// Whitespace-separated terms (or `"quoted phrases"`) that must all
+    /// appear in the same message, ignoring ASCII case.
+    pub text: String,
+    pub providers: Vec<ProviderKind>,
+    pub roles: Vec<MessageRole>,
+    pub updated_after: Option<u64>,
+    /// Keep threads created at or before this point; threads without a
+    /// creation time use their last update.
+    pub started_before: Option<u64>,
+    pub cwd: Option<PathBuf>,
+    /// Maximum number of hits.
+    pub limit: Option<usize>,
+}
+
+/// A message that matched a search.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SearchHit {
+    pub uri: String,
+    pub provider: ProviderKind,
+    pub session_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cwd: Option<String>,
+    pub updated_at: u64,
+    /// Index of the message in the rendered timeline.
+    pub index: usize,
+    pub role: MessageRole,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timestamp: Option<String>,
+    /// Single-line excerpt around the first match.
+    pub snippet: String,
+    /// Byte ranges of matched terms within `snippet`.
+    pub highlights: Vec<(usize, usize)>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SearchResultsView {
+    pub query: SearchQuery,
+    /// Whether hits came from the on-disk index rather than a scan.
+    pub indexed: bool,
+    pub sessions_scanned: usize,
+    pub hits: Vec<SearchHit>,
+    #[serde(skip_serializing)]
+    pub warnings: Vec<String>,
+}
+
+/// What one incremental index refresh did.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct IndexRefresh {
+    /// Threads indexed for the first time.
+    pub added: usize,
+    /// Append-only threads that only had new lines indexed.
+    pub appended: usize,
+    /// Threads re-indexed from scratch because they changed in place.
+    pub reindexed: usize,
+    /// Threads dropped because they no longer exist.
+    pub removed: usize,
+    pub unchanged: usize,
+    pub messages_added: usize,
+    #[serde(skip_serializing)]
+    pub warnings: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct IndexProviderStatus {
+    pub provider: ProviderKind,
+    pub threads: usize,
+    pub messages: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct IndexStatus {
+    pub path: PathBuf,
+    pub exists: bool,
+    pub size_bytes: u64,
+    pub threads: usize,
+    pub messages: usize,
+    /// Epoch seconds of the last completed refresh.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_refresh: Option<u64>,
+    /// Threads that are new, changed or gone since the last refresh.
+    pub pending: usize,
+    pub providers: Vec<IndexProviderStatus>,
+}
+
+/// How a tool call touched a file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FileChange {
+    Read,
+    Modified,
+    Created,
+    Deleted,
+}
+
+impl fmt::Display for FileChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Read => write!(f, "read"),
+            Self::Modified => write!(f, "modified"),
+            Self::Created => write!(f, "created"),
+            Self::Deleted => write!(f, "deleted"),
+        }
+    }
+}
+
+/// One tool call that read or changed a file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FileTouch {
+    pub change: FileChange,
+    /// User turn the call belongs to (0 before the first user message).
+    pub turn: usize,
+    /// Index of the tool entry in the `--tools` timeline.
+    pub entry: usize,
+    pub tool: String,
+}
+
+/// A file touched during a thread, with every touch in order.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TouchedFile {
+    /// Relative to the session cwd when the file lives inside it.
+    pub path: String,
+    /// Net effect over the thread: the last write, or `read` if never written.
+    pub change: FileChange,
+    pub touches: Vec<FileTouch>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TouchedFilesView {
+    pub uri: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cwd: Option<String>,
+    pub files: Vec<TouchedFile>,
+}
+
+/// Edits made to one file during a thread, as a unified diff.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FileDiff {
+    pub path: String,
+    /// Source path when the file was moved.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub old_path: Option<String>,
+    pub change: FileChange,
+    /// `diff --git` section for this file, empty when nothing could be shown.
+    pub patch: String,
+    /// Whether `patch` captures every edit; see `notes` when it does not.
+    pub complete: bool,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub notes: Vec<String>,
+    /// Timeline entry indexes of the tool calls that edited the file.
+    pub entries: Vec<usize>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ThreadDiffView {
+    pub uri: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cwd: Option<String>,
+    pub files: Vec<FileDiff>,
+}
+
+/// A running agent process and the thread it is writing.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RunningAgent {
+    pub pid: u32,
+    pub provider: ProviderKind,
+    pub command: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cwd: Option<String>,
+    /// Seconds since the process started.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub uptime_secs: Option<u64>,
+    /// The thread the process is writing, when it could be matched.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub uri: Option<String>,
+    /// How the thread was matched: `open_file`, `command` or `cwd`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub matched_by: Option<String>,
+    /// When the thread was last written, as Unix epoch seconds.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_activity: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subagents: Option<SubagentCounts>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct SubagentCounts {
+    /// Subagents still running or starting up.
+    pub running: usize,
+    pub total: usize,
+}
+
+/// A recently written thread that stopped mid-turn with no process left
+/// writing it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct StaleSession {
+    pub provider: ProviderKind,
+    pub uri: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cwd: Option<String>,
+    pub last_activity: u64,
+    /// What the thread was waiting on when it stopped.
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RunningAgentsView {
+    pub agents: Vec<RunningAgent>,
+    pub stale: Vec<StaleSession>,
+    #[serde(skip_serializing)]
+    pub warnings: Vec<String>,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct ActiveSession {
     pub provider: ProviderKind,
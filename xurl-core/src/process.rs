@@ -1,4 +1,5 @@
-use std::path::Path;
+use std::path::{Path, PathBuf};
+#[cfg(any(test, not(target_os = "linux")))]
 use std::process::Command;
 
 use crate::model::ProviderKind;
@@ -9,63 +10,117 @@ pub struct AgentProcess {
     pub pid: u32,
     pub provider: ProviderKind,
     pub command: String,
+    /// Working directory of the process, where the platform exposes it.
+    pub cwd: Option<PathBuf>,
+    /// Process start time as Unix epoch seconds, where known.
+    pub started_at: Option<u64>,
+    /// Regular files the process holds open (Linux only).
+    pub open_files: Vec<PathBuf>,
 }
 
-/// Discover the PID(s) of running agent processes for a given provider.
+impl AgentProcess {
+    /// The thread file under one of `provider_roots` this process holds
+    /// open, if any.
+    pub fn session_file(&self, provider_roots: &[PathBuf]) -> Option<&Path> {
+        self.open_files
+            .iter()
+            .map(PathBuf::as_path)
+            .filter(|path| provider_roots.iter().any(|root| path.starts_with(root)))
+            .find(|path| {
+                path.extension()
+                    .and_then(|extension| extension.to_str())
+                    .is_some_and(|extension| matches!(extension, "jsonl" | "json"))
+            })
+    }
+}
+
+/// Discover the running agent processes for a given provider.
 ///
-/// Uses a combination of provider-specific heuristics and `pgrep`:
-/// - **Claude**: checks `pgrep -f "claude"` (the Claude Code CLI)
-/// - **Codex**: checks `pgrep -f "codex"`
-/// - **Amp**: checks `pgrep -f "amp"`
-/// - **Gemini**: checks `pgrep -f "gemini"`
-/// - **Pi**: checks `pgrep -f "pi"`
-/// - **Opencode**: checks `pgrep -f "opencode"`
+/// On Linux every `/proc/<pid>` is inspected directly: a process counts as
+/// the agent when its executable, `argv[0]`, or the script run by a
+/// `node`/`bun`/`deno` interpreter is the provider's CLI, so `pip` or
+/// `pipewire` never pass for Pi. Working directory, start time and open
+/// files are read along the way.
 ///
-/// Returns all matching PIDs (not just the first), allowing callers
-/// to correlate with session files.
+/// Elsewhere `pgrep -f <name>` finds candidates and the same command-line
+/// check filters them.
 pub fn discover_agent_pids(provider: ProviderKind) -> Vec<AgentProcess> {
-    let binary_hint = provider_binary_hint(provider);
-
-    let pids = pgrep_by_name(binary_hint);
-    let mut results = Vec::new();
-
-    for pid in pids {
-        let command = read_process_command(pid).unwrap_or_else(|| binary_hint.to_string());
-        results.push(AgentProcess {
-            pid,
-            provider,
-            command,
-        });
+    #[cfg(target_os = "linux")]
+    {
+        procfs::agent_processes(Path::new(procfs::PROC_ROOT), provider)
     }
 
-    results
+    #[cfg(not(target_os = "linux"))]
+    {
+        let binary_hint = provider_binary_hint(provider);
+        pgrep_by_name(binary_hint)
+            .into_iter()
+            .filter_map(|pid| {
+                let command = read_process_command(pid)?;
+                let argv = command
+                    .split_whitespace()
+                    .map(str::to_string)
+                    .collect::<Vec<_>>();
+                is_agent_command(provider, &argv, None).then(|| AgentProcess {
+                    pid,
+                    provider,
+                    command,
+                    cwd: None,
+                    started_at: None,
+                    open_files: Vec::new(),
+                })
+            })
+            .collect()
+    }
 }
 
 /// Discover a single PID for a provider (convenience wrapper).
 ///
 /// Returns the first matching PID, or `None` if no process found.
 pub fn discover_agent_pid(provider: ProviderKind) -> Option<u32> {
-    let binary_hint = provider_binary_hint(provider);
-    pgrep_by_name(binary_hint).into_iter().next()
+    discover_agent_pids(provider)
+        .into_iter()
+        .map(|process| process.pid)
+        .next()
 }
 
-/// Try to find a PID for a specific session by checking provider-specific
-/// lock/marker files.
+/// Find the process writing a specific session.
 ///
-/// Currently supports:
-/// - **Claude**: reads `<claude_root>/projects/<project_hash>/.active_session`
-///   which may contain a PID or session reference.
+/// A process matches when it holds the session's thread file open under
+/// `provider_root`, or names the session id on its command line (as in
+/// `claude --resume <id>`). On Linux that is the whole answer, so `None`
+/// means no running process owns the session. Other platforms cannot see
+/// open files and fall back to Claude's `.lock` markers and then to any
+/// running process of the provider.
 pub fn discover_pid_for_session(
     provider: ProviderKind,
-    _session_id: &str,
+    session_id: &str,
     provider_root: &Path,
 ) -> Option<u32> {
+    let processes = discover_agent_pids(provider);
+    let holds_session = |process: &&AgentProcess| {
+        process
+            .session_file(&[provider_root.to_path_buf()])
+            .and_then(|path| path.file_name())
+            .and_then(|name| name.to_str())
+            .is_some_and(|name| name.contains(session_id))
+    };
+    let found = processes
+        .iter()
+        .find(holds_session)
+        .or_else(|| {
+            processes
+                .iter()
+                .find(|process| process.command.contains(session_id))
+        })
+        .map(|process| process.pid);
+
+    if cfg!(target_os = "linux") || found.is_some() {
+        return found;
+    }
     match provider {
         ProviderKind::Claude => discover_claude_session_pid(provider_root),
-        _ => {
-            // Fall back to generic pgrep
-            discover_agent_pid(provider)
-        }
+        _ => processes.first().map(|process| process.pid),
     }
 }
 
@@ -83,16 +138,13 @@ fn discover_claude_session_pid(claude_root: &Path) -> Option<u32> {
     let entries = std::fs::read_dir(&projects_dir).ok()?;
     for entry in entries.filter_map(|e| e.ok()) {
         let lock_path = entry.path().join(".lock");
-        if lock_path.exists() {
-            if let Ok(content) = std::fs::read_to_string(&lock_path) {
-                let trimmed = content.trim();
-                if let Ok(pid) = trimmed.parse::<u32>() {
-                    // Verify this PID is still alive
-                    if process_alive(pid) {
-                        return Some(pid);
-                    }
-                }
-            }
+        if lock_path.exists()
+            && let Ok(content) = std::fs::read_to_string(&lock_path)
+            && let Ok(pid) = content.trim().parse::<u32>()
+            // Verify this PID is still alive
+            && process_alive(pid)
+        {
+            return Some(pid);
         }
     }
 
@@ -106,17 +158,81 @@ fn provider_binary_hint(provider: ProviderKind) -> &'static str {
         ProviderKind::Codex => "codex",
         ProviderKind::Amp => "amp",
         ProviderKind::Gemini => "gemini",
+        ProviderKind::Qwen => "qwen",
         ProviderKind::Pi => "pi",
         ProviderKind::Opencode => "opencode",
+        ProviderKind::Cline => "cline",
+        ProviderKind::Roo => "roo",
+        ProviderKind::Aider => "aider",
+        ProviderKind::Goose => "goose",
+        // The editor's own processes are Electron helpers; its agent CLI is
+        // the one process worth reporting.
+        ProviderKind::Cursor => "cursor-agent",
+    }
+}
+
+/// The npm package that ships the provider's CLI, for interpreter launches
+/// such as `node /usr/lib/node_modules/@openai/codex/bin/codex.js`.
+fn provider_package_hint(provider: ProviderKind) -> &'static str {
+    match provider {
+        ProviderKind::Claude => "@anthropic-ai/claude-code",
+        ProviderKind::Codex => "@openai/codex",
+        ProviderKind::Amp => "@sourcegraph/amp",
+        ProviderKind::Gemini => "@google/gemini-cli",
+        ProviderKind::Qwen => "@qwen-code/qwen-code",
+        ProviderKind::Pi => "@mariozechner/pi-coding-agent",
+        ProviderKind::Opencode => "opencode-ai",
+        ProviderKind::Cline => "cline",
+        ProviderKind::Roo => "@roo-code/cli",
+        ProviderKind::Aider => "aider-chat",
+        ProviderKind::Goose => "goose",
+        ProviderKind::Cursor => "cursor-agent",
+    }
+}
+
+/// Script interpreters whose first non-flag argument names the real program.
+const INTERPRETERS: [&str; 6] = ["node", "bun", "deno", "tsx", "python", "python3"];
+
+/// Whether a command line (and, when known, the executable) is the
+/// provider's CLI rather than something that merely mentions its name.
+fn is_agent_command(provider: ProviderKind, argv: &[String], exe: Option<&Path>) -> bool {
+    let name = provider_binary_hint(provider);
+    let basename = |path: &str| {
+        Path::new(path)
+            .file_name()
+            .and_then(|name| name.to_str())
+            .map(str::to_string)
+    };
+
+    if exe
+        .and_then(|exe| exe.file_name())
+        .and_then(|name| name.to_str())
+        == Some(name)
+    {
+        return true;
+    }
+    let Some(program) = argv.first().and_then(|program| basename(program)) else {
+        return false;
+    };
+    if program == name {
+        return true;
+    }
+    if !INTERPRETERS.contains(&program.as_str()) {
+        return false;
     }
+    argv.iter()
+        .skip(1)
+        .find(|arg| !arg.starts_with('-'))
+        .is_some_and(|script| {
+            basename(script).as_deref() == Some(name)
+                || script.contains(provider_package_hint(provider))
+        })
 }
 
 /// Run `pgrep -f <pattern>` and return all matching PIDs.
+#[cfg(not(target_os = "linux"))]
 fn pgrep_by_name(pattern: &str) -> Vec<u32> {
-    let output = Command::new("pgrep")
-        .args(["-f", pattern])
-        .output()
-        .ok();
+    let output = Command::new("pgrep").args(["-f", pattern]).output().ok();
 
     let Some(output) = output else {
         return Vec::new();
@@ -130,6 +246,7 @@ fn pgrep_by_name(pattern: &str) -> Vec<u32> {
 }
 
 /// Read the command line of a process (macOS/Linux).
+#[cfg(any(test, not(target_os = "linux")))]
 fn read_process_command(pid: u32) -> Option<String> {
     let output = Command::new("ps")
         .args(["-p", &pid.to_string(), "-o", "command="])
@@ -151,6 +268,192 @@ fn process_alive(pid: u32) -> bool {
     unsafe { libc::kill(pid as libc::pid_t, 0) == 0 }
 }
 
+/// Native process inspection through `/proc`.
+#[cfg(target_os = "linux")]
+mod procfs {
+    use std::fs;
+    use std::path::{Path, PathBuf};
+
+    use super::{AgentProcess, is_agent_command};
+    use crate::model::ProviderKind;
+
+    pub(super) const PROC_ROOT: &str = "/proc";
+
+    /// Every process under `proc_root` running the provider's CLI.
+    pub(super) fn agent_processes(proc_root: &Path, provider: ProviderKind) -> Vec<AgentProcess> {
+        let Ok(entries) = fs::read_dir(proc_root) else {
+            return Vec::new();
+        };
+        let own_pid = std::process::id();
+        let boot_time = boot_time(proc_root);
+
+        let mut processes = entries
+            .filter_map(Result::ok)
+            .filter_map(|entry| {
+                let pid = entry.file_name().to_str()?.parse::<u32>().ok()?;
+                (pid != own_pid).then_some((pid, entry.path()))
+            })
+            .filter_map(|(pid, dir)| {
+                // Processes can exit between listing and reading; skip them.
+                let argv = read_cmdline(&dir)?;
+                let exe = fs::read_link(dir.join("exe")).ok();
+                if !is_agent_command(provider, &argv, exe.as_deref()) {
+                    return None;
+                }
+                Some(AgentProcess {
+                    pid,
+                    provider,
+                    command: argv.join(" "),
+                    cwd: fs::read_link(dir.join("cwd")).ok(),
+                    started_at: boot_time.and_then(|boot| start_time(&dir, boot)),
+                    open_files: open_files(&dir),
+                })
+            })
+            .collect::<Vec<_>>();
+        processes.sort_by_key(|process| process.pid);
+        processes
+    }
+
+    /// NUL-separated arguments; kernel threads have none.
+    pub(super) fn read_cmdline(dir: &Path) -> Option<Vec<String>> {
+        let raw = fs::read(dir.join("cmdline")).ok()?;
+        let argv = raw
+            .split(|byte| *byte == 0)
+            .filter(|arg| !arg.is_empty())
+            .map(|arg| String::from_utf8_lossy(arg).into_owned())
+            .collect::<Vec<_>>();
+        (!argv.is_empty()).then_some(argv)
+    }
+
+    /// Absolute paths behind `fd/*`, skipping sockets, pipes and devices.
+    fn open_files(dir: &Path) -> Vec<PathBuf> {
+        let Ok(entries) = fs::read_dir(dir.join("fd")) else {
+            return Vec::new();
+        };
+        let mut files = entries
+            .filter_map(Result::ok)
+            .filter_map(|entry| fs::read_link(entry.path()).ok())
+            .filter(|target| target.is_absolute() && !target.starts_with("/dev"))
+            .collect::<Vec<_>>();
+        files.sort();
+        files.dedup();
+        files
+    }
+
+    /// Boot time in Unix epoch seconds, from the `btime` line of `stat`.
+    fn boot_time(proc_root: &Path) -> Option<u64> {
+        fs::read_to_string(proc_root.join("stat"))
+            .ok()?
+            .lines()
+            .find_map(|line| line.strip_prefix("btime "))?
+            .trim()
+            .parse()
+            .ok()
+    }
+
+    /// Start time from field 22 of `<pid>/stat`, in clock ticks after boot.
+    fn start_time(dir: &Path, boot_time: u64) -> Option<u64> {
+        let stat = fs::read_to_string(dir.join("stat")).ok()?;
+        // The command name in field 2 may contain spaces; count from its `)`.
+        let ticks = stat
+            .rsplit_once(')')?
+            .1
+            .split_whitespace()
+            .nth(19)?
+            .parse::<u64>()
+            .ok()?;
+        let ticks_per_second = u64::try_from(unsafe { libc::sysconf(libc::_SC_CLK_TCK) })
+            .ok()
+            .filter(|ticks| *ticks > 0)?;
+        Some(boot_time + ticks / ticks_per_second)
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use std::fs;
+        use std::os::unix::fs::symlink;
+        use std::path::Path;
+
+        use tempfile::tempdir;
+
+        use super::agent_processes;
+        use crate::model::ProviderKind;
+
+        fn fake_process(root: &Path, pid: u32, argv: &[&str], cwd: &Path, open: &[&Path]) {
+            let dir = root.join(pid.to_string());
+            fs::create_dir_all(dir.join("fd")).expect("mkdir");
+            fs::write(dir.join("cmdline"), format!("{}\0", argv.join("\0"))).expect("cmdline");
+            symlink(cwd, dir.join("cwd")).expect("cwd link");
+            for (fd, target) in open.iter().enumerate() {
+                symlink(target, dir.join("fd").join(fd.to_string())).expect("fd link");
+            }
+            fs::write(
+                dir.join("stat"),
+                format!("{pid} (MainThread x) S 1 1 1 0 -1 0 0 0 0 0 0 0 0 0 20 0 1 0 500 0 0"),
+            )
+            .expect("stat");
+        }
+
+        #[test]
+        fn finds_agents_precisely_with_cwd_and_open_files() {
+            let temp = tempdir().expect("tempdir");
+            let proc_root = temp.path().join("proc");
+            let thread = temp
+                .path()
+                .join("pi/sessions/--repo--/2026-02-23_12cb4c19-2774-4de4-a0d0-9fa32fbae29f.jsonl");
+            fs::create_dir_all(&proc_root).expect("mkdir");
+            fs::write(proc_root.join("stat"), "cpu 1 2 3\nbtime 1700000000\n").expect("stat");
+
+            fake_process(
+                &proc_root,
+                101,
+                &[
+                    "node",
+                    "/usr/lib/node_modules/@mariozechner/pi-coding-agent/dist/cli.js",
+                ],
+                Path::new("/repo"),
+                &[Path::new("/dev/null"), &thread],
+            );
+            fake_process(
+                &proc_root,
+                102,
+                &["pi", "--continue"],
+                Path::new("/other"),
+                &[],
+            );
+            fake_process(
+                &proc_root,
+                103,
+                &["/usr/bin/pip", "install", "pi"],
+                Path::new("/"),
+                &[],
+            );
+            fake_process(&proc_root, 104, &["/usr/bin/pipewire"], Path::new("/"), &[]);
+            fake_process(&proc_root, 105, &["vim", "pi.md"], Path::new("/"), &[]);
+
+            let found = agent_processes(&proc_root, ProviderKind::Pi);
+            assert_eq!(
+                found.iter().map(|process| process.pid).collect::<Vec<_>>(),
+                vec![101, 102]
+            );
+            assert_eq!(found[0].cwd.as_deref(), Some(Path::new("/repo")));
+            assert_eq!(found[0].open_files, vec![thread.clone()]);
+            assert_eq!(
+                found[0].session_file(&[temp.path().join("pi")]),
+                Some(thread.as_path())
+            );
+            assert!(
+                found[0]
+                    .started_at
+                    .is_some_and(|started| started >= 1_700_000_000)
+            );
+            assert!(found[1].open_files.is_empty());
+
+            assert!(agent_processes(&proc_root, ProviderKind::Claude).is_empty());
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
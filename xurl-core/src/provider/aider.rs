@@ -0,0 +1,283 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use walkdir::WalkDir;
+
+use crate::error::{Result, XurlError};
+use crate::model::{ProviderKind, ResolutionMeta, ResolvedThread, SessionSummary};
+use crate::provider::{Provider, modified_epoch};
+use crate::time::parse_rfc3339_epoch;
+
+pub const CHAT_HISTORY_FILE: &str = ".aider.chat.history.md";
+pub(crate) const SESSION_HEADER: &str = "# aider chat started at ";
+/// How deep below a project root a chat history file is looked for.
+const MAX_SCAN_DEPTH: usize = 6;
+/// Directories that never hold a project's own history file.
+const SKIPPED_DIRS: &[&str] = &["node_modules", "target", "vendor", "__pycache__", "Library"];
+
+/// Aider writes a chat history into every repository it runs in and appends
+/// one `# aider chat started at <time>` section per run. Each section is a
+/// thread; its id is the start time plus a hash of the history file's path,
+/// e.g. `20240501-100000-3f9a2c1d`.
+#[derive(Debug, Clone)]
+pub struct AiderProvider {
+    roots: Vec<PathBuf>,
+}
+
+/// One run of Aider inside a chat history file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct AiderSession<'a> {
+    pub id: String,
+    /// The naive header time: Aider writes local time without an offset, and
+    /// it is read as if it were UTC, so it can be off by the writer's offset.
+    pub started_at: Option<u64>,
+    /// The section, header included.
+    pub text: &'a str,
+}
+
+impl AiderProvider {
+    pub fn new(roots: Vec<PathBuf>) -> Self {
+        Self { roots }
+    }
+
+    fn history_files(&self) -> Vec<PathBuf> {
+        let mut files = Vec::new();
+        for root in &self.roots {
+            let walker = WalkDir::new(root)
+                .max_depth(MAX_SCAN_DEPTH)
+                .into_iter()
+                .filter_entry(|entry| {
+                    entry.depth() == 0
+                        || !entry.file_type().is_dir()
+                        || entry.file_name().to_str().is_some_and(|name| {
+                            !name.starts_with('.') && !SKIPPED_DIRS.contains(&name)
+                        })
+                });
+            files.extend(
+                walker
+                    .filter_map(std::result::Result::ok)
+                    .filter(|entry| {
+                        entry.file_type().is_file() && entry.file_name() == CHAT_HISTORY_FILE
+                    })
+                    .map(|entry| entry.into_path()),
+            );
+        }
+        files.sort();
+        files.dedup();
+        files
+    }
+
+    /// The section `session_id` of a chat history file, read like any other
+    /// single-thread transcript.
+    pub(crate) fn session_text(history: &Path, raw: &str, session_id: &str) -> Result<String> {
+        split_sessions(history, raw)
+            .into_iter()
+            .find(|session| session.id == session_id)
+            .map(|session| session.text.to_string())
+            .ok_or_else(|| XurlError::ThreadNotFound {
+                provider: ProviderKind::Aider.to_string(),
+                session_id: session_id.to_string(),
+                searched_roots: vec![history.to_path_buf()],
+            })
+    }
+}
+
+/// Split a chat history into its runs. Text before the first header is
+/// ignored.
+pub(crate) fn split_sessions<'a>(path: &Path, raw: &'a str) -> Vec<AiderSession<'a>> {
+    let mut starts = Vec::new();
+    let mut offset = 0;
+    for line in raw.split_inclusive('\n') {
+        if line.starts_with(SESSION_HEADER) {
+            starts.push(offset);
+        }
+        offset += line.len();
+    }
+
+    let path_hash = fnv1a(
+        &fs::canonicalize(path)
+            .unwrap_or_else(|_| path.to_path_buf())
+            .to_string_lossy(),
+    );
+    let mut sessions: Vec<AiderSession<'a>> = Vec::new();
+    for (position, start) in starts.iter().enumerate() {
+        let end = starts.get(position + 1).copied().unwrap_or(raw.len());
+        let text = &raw[*start..end];
+        let started = text[SESSION_HEADER.len()..]
+            .lines()
+            .next()
+            .unwrap_or_default()
+            .trim();
+        let stamp = started
+            .chars()
+            .filter(char::is_ascii_digit)
+            .take(14)
+            .collect::<String>();
+        if stamp.len() != 14 {
+            continue;
+        }
+
+        // The id comes from the header text itself, so it does not depend on
+        // the reader's time zone. Two runs started in the same second get
+        // distinct hashes.
+        let base = format!("{}-{}", &stamp[..8], &stamp[8..]);
+        let repeats = sessions
+            .iter()
+            .filter(|session| session.id.starts_with(&base))
+            .count();
+        let hash = if repeats == 0 {
+            path_hash
+        } else {
+            fnv1a(&format!("{path_hash:08x}#{repeats}"))
+        };
+        sessions.push(AiderSession {
+            id: format!("{base}-{hash:08x}"),
+            started_at: parse_rfc3339_epoch(started),
+            text,
+        });
+    }
+    sessions
+}
+
+/// 32-bit FNV-1a, which unlike `DefaultHasher` is stable across releases.
+fn fnv1a(text: &str) -> u32 {
+    text.bytes().fold(0x811c_9dc5, |hash, byte| {
+        (hash ^ u32::from(byte)).wrapping_mul(0x0100_0193)
+    })
+}
+
+impl Provider for AiderProvider {
+    fn resolve(&self, session_id: &str) -> Result<ResolvedThread> {
+        let history = self.history_files().into_iter().find(|history| {
+            fs::read_to_string(history).is_ok_and(|raw| {
+                split_sessions(history, &raw)
+                    .iter()
+                    .any(|session| session.id == session_id)
+            })
+        });
+        let Some(history) = history else {
+            return Err(XurlError::ThreadNotFound {
+                provider: ProviderKind::Aider.to_string(),
+                session_id: session_id.to_string(),
+                searched_roots: self.roots.clone(),
+            });
+        };
+
+        Ok(ResolvedThread {
+            provider: ProviderKind::Aider,
+            session_id: session_id.to_string(),
+            path: history.clone(),
+            metadata: ResolutionMeta {
+                source: format!("aider:{}", history.display()),
+                candidate_count: 1,
+                warnings: Vec::new(),
+            },
+        })
+    }
+
+    fn list_sessions(&self) -> Result<Vec<SessionSummary>> {
+        let mut summaries = Vec::new();
+        for history in self.history_files() {
+            let Ok(raw) = fs::read_to_string(&history) else {
+                continue;
+            };
+            let sessions = split_sessions(&history, &raw);
+            let modified = modified_epoch(&history);
+            let cwd = history
+                .parent()
+                .map(|parent| parent.to_string_lossy().to_string());
+
+            for (position, session) in sessions.iter().enumerate() {
+                // A run ends when the next one starts; the last one is still
+                // being written to.
+                let updated_at = match sessions.get(position + 1) {
+                    Some(next) => next.started_at.unwrap_or(modified),
+                    None => modified.max(session.started_at.unwrap_or_default()),
+                };
+                summaries.push(SessionSummary {
+                    provider: ProviderKind::Aider,
+                    session_id: session.id.clone(),
+                    path: history.clone(),
+                    created_at: session.started_at,
+                    updated_at,
+                    cwd: cwd.clone(),
+                    title: None,
+                    archived: false,
+                    parent_session_id: None,
+                    message_count: None,
+                });
+            }
+        }
+        Ok(summaries)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use tempfile::tempdir;
+
+    use crate::provider::Provider;
+    use crate::provider::aider::{AiderProvider, split_sessions};
+
+    const HISTORY: &str = "\n# aider chat started at 2024-05-01 10:00:00\n\n> Aider v0.50.0\n\n#### fix the bug\n\nDone.\n\n# aider chat started at 2024-05-01 10:00:00\n\n#### again\n\n# aider chat started at 2024-05-02 09:30:15\n\n#### add tests\n";
+
+    #[test]
+    fn splits_runs_into_sessions_with_stable_ids() {
+        let temp = tempdir().expect("tempdir");
+        let path = temp.path().join(".aider.chat.history.md");
+        fs::write(&path, HISTORY).expect("write");
+
+        let sessions = split_sessions(&path, HISTORY);
+        assert_eq!(sessions.len(), 3);
+        assert!(sessions[0].id.starts_with("20240501-100000-"));
+        assert!(sessions[1].id.starts_with("20240501-100000-"));
+        assert_ne!(sessions[0].id, sessions[1].id);
+        assert!(sessions[2].id.starts_with("20240502-093015-"));
+        assert_eq!(sessions[2].started_at, Some(1_714_642_215));
+        assert!(sessions[0].text.contains("#### fix the bug"));
+        assert!(!sessions[0].text.contains("again"));
+        assert_eq!(split_sessions(&path, HISTORY), sessions);
+    }
+
+    #[test]
+    fn lists_and_resolves_history_files_under_roots() {
+        let temp = tempdir().expect("tempdir");
+        let project = temp.path().join("src/app");
+        fs::create_dir_all(&project).expect("mkdir");
+        fs::create_dir_all(temp.path().join("node_modules/dep")).expect("mkdir");
+        fs::write(project.join(".aider.chat.history.md"), HISTORY).expect("write");
+        fs::write(
+            temp.path().join("node_modules/dep/.aider.chat.history.md"),
+            HISTORY,
+        )
+        .expect("write");
+
+        let provider = AiderProvider::new(vec![temp.path().to_path_buf()]);
+        let sessions = provider.list_sessions().expect("list should succeed");
+        assert_eq!(sessions.len(), 3);
+        assert_eq!(
+            sessions[0].cwd.as_deref(),
+            Some(project.to_string_lossy().as_ref())
+        );
+        assert_eq!(
+            sessions[0].updated_at,
+            sessions[1].created_at.expect("start")
+        );
+
+        let resolved = provider
+            .resolve(&sessions[2].session_id)
+            .expect("resolve should succeed");
+        let raw = fs::read_to_string(&resolved.path).expect("read history");
+        let raw = AiderProvider::session_text(&resolved.path, &raw, &sessions[2].session_id)
+            .expect("section");
+        assert!(raw.starts_with("# aider chat started at 2024-05-02 09:30:15"));
+        assert!(raw.contains("#### add tests"));
+
+        let err = provider
+            .resolve("20240503-000000-00000000")
+            .expect_err("must fail");
+        assert!(format!("{err}").contains("thread not found"));
+    }
+}
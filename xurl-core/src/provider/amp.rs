@@ -1,8 +1,13 @@
-use std::path::PathBuf;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde_json::Value;
 
 use crate::error::{Result, XurlError};
-use crate::model::{ProviderKind, ResolutionMeta, ResolvedThread};
-use crate::provider::Provider;
+use crate::model::{ProviderKind, ResolutionMeta, ResolvedThread, SessionSummary};
+use crate::provider::{Provider, modified_epoch};
+use crate::time::normalize_epoch;
+use crate::uri::is_session_id;
 
 #[derive(Debug, Clone)]
 pub struct AmpProvider {
@@ -17,6 +22,52 @@ impl AmpProvider {
     fn threads_root(&self) -> PathBuf {
         self.root.join("threads")
     }
+
+    fn summarize(path: &Path, session_id: &str) -> SessionSummary {
+        let value = fs::read_to_string(path)
+            .ok()
+            .and_then(|raw| serde_json::from_str::<Value>(&raw).ok())
+            .unwrap_or(Value::Null);
+
+        let title = value
+            .get("title")
+            .and_then(Value::as_str)
+            .filter(|title| !title.trim().is_empty())
+            .map(ToString::to_string);
+        let cwd = value
+            .pointer("/env/initial/trees/0/uri")
+            .and_then(Value::as_str)
+            .map(|uri| uri.strip_prefix("file://").unwrap_or(uri).to_string());
+        // Forked and handed-off threads record the originating thread as a
+        // relationship whose role is `parent`.
+        let parent_session_id = value
+            .get("relationships")
+            .and_then(Value::as_array)
+            .and_then(|relationships| {
+                relationships.iter().find(|relationship| {
+                    relationship.get("role").and_then(Value::as_str) == Some("parent")
+                })
+            })
+            .and_then(|relationship| relationship.get("threadID"))
+            .and_then(Value::as_str)
+            .map(ToString::to_string);
+
+        SessionSummary {
+            provider: ProviderKind::Amp,
+            session_id: session_id.to_string(),
+            path: path.to_path_buf(),
+            created_at: value
+                .get("created")
+                .and_then(Value::as_i64)
+                .and_then(normalize_epoch),
+            updated_at: modified_epoch(path),
+            cwd,
+            title,
+            archived: false,
+            parent_session_id,
+            message_count: None,
+        }
+    }
 }
 
 impl Provider for AmpProvider {
@@ -43,6 +94,26 @@ impl Provider for AmpProvider {
             },
         })
     }
+
+    fn list_sessions(&self) -> Result<Vec<SessionSummary>> {
+        let Ok(entries) = fs::read_dir(self.threads_root()) else {
+            return Ok(Vec::new());
+        };
+
+        Ok(entries
+            .filter_map(std::result::Result::ok)
+            .map(|entry| entry.path())
+            .filter_map(|path| {
+                let session_id = path
+                    .file_name()?
+                    .to_str()?
+                    .strip_suffix(".json")?
+                    .to_string();
+                is_session_id(ProviderKind::Amp, &session_id)
+                    .then(|| Self::summarize(&path, &session_id))
+            })
+            .collect())
+    }
 }
 
 #[cfg(test)]
@@ -70,6 +141,35 @@ mod tests {
         assert_eq!(resolved.metadata.source, "amp:threads");
     }
 
+    #[test]
+    fn lists_threads_with_title() {
+        let temp = tempdir().expect("tempdir");
+        let threads = temp.path().join("threads");
+        fs::create_dir_all(&threads).expect("mkdir");
+        fs::write(
+            threads.join("T-019c0797-c402-7389-bd80-d785c98df295.json"),
+            r#"{"created":1768307088294,"title":"Fix retries","env":{"initial":{"trees":[{"uri":"file:///tmp/project"}]}},"relationships":[{"threadID":"T-019c0700-0000-7000-8000-000000000001","type":"handoff","role":"parent"}],"messages":[]}"#,
+        )
+        .expect("write");
+        fs::write(threads.join("notes.json"), "{}").expect("write");
+
+        let sessions = AmpProvider::new(temp.path())
+            .list_sessions()
+            .expect("list should succeed");
+        assert_eq!(sessions.len(), 1);
+        assert_eq!(
+            sessions[0].session_id,
+            "T-019c0797-c402-7389-bd80-d785c98df295"
+        );
+        assert_eq!(sessions[0].title.as_deref(), Some("Fix retries"));
+        assert_eq!(sessions[0].cwd.as_deref(), Some("/tmp/project"));
+        assert_eq!(sessions[0].created_at, Some(1_768_307_088));
+        assert_eq!(
+            sessions[0].parent_session_id.as_deref(),
+            Some("T-019c0700-0000-7000-8000-000000000001")
+        );
+    }
+
     #[test]
     fn missing_thread_returns_not_found() {
         let temp = tempdir().expect("tempdir");
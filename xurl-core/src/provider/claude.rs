@@ -9,8 +9,10 @@ use serde_json::Value;
 use walkdir::WalkDir;
 
 use crate::error::{Result, XurlError};
-use crate::model::{ProviderKind, ResolutionMeta, ResolvedThread};
-use crate::provider::Provider;
+use crate::model::{ProviderKind, ResolutionMeta, ResolvedThread, SessionSummary};
+use crate::provider::{Provider, modified_epoch};
+use crate::time::parse_rfc3339_epoch;
+use crate::uri::is_session_id;
 
 #[derive(Debug, Deserialize)]
 struct SessionsIndex {
@@ -24,6 +26,18 @@ struct SessionIndexEntry {
     session_id: String,
     #[serde(rename = "fullPath")]
     full_path: Option<PathBuf>,
+    #[serde(default)]
+    summary: Option<String>,
+    #[serde(rename = "firstPrompt", default)]
+    first_prompt: Option<String>,
+    #[serde(rename = "projectPath", default)]
+    project_path: Option<String>,
+    #[serde(default)]
+    created: Option<String>,
+    #[serde(default)]
+    modified: Option<String>,
+    #[serde(rename = "isSidechain", default)]
+    is_sidechain: bool,
 }
 
 #[derive(Debug, Clone)]
@@ -59,7 +73,7 @@ impl ClaudeProvider {
         scored.into_iter().next().map(|(path, _)| (path, count))
     }
 
-    fn find_from_sessions_index(projects_root: &Path, session_id: &str) -> Vec<PathBuf> {
+    fn read_sessions_indexes(projects_root: &Path) -> Vec<SessionIndexEntry> {
         if !projects_root.exists() {
             return Vec::new();
         }
@@ -71,15 +85,15 @@ impl ClaudeProvider {
             .filter(|entry| entry.file_name() == "sessions-index.json")
             .filter_map(|entry| fs::read_to_string(entry.path()).ok())
             .filter_map(|content| serde_json::from_str::<SessionsIndex>(&content).ok())
-            .flat_map(|index| {
-                index.entries.into_iter().filter_map(|entry| {
-                    if entry.session_id == session_id {
-                        entry.full_path
-                    } else {
-                        None
-                    }
-                })
-            })
+            .flat_map(|index| index.entries)
+            .collect()
+    }
+
+    fn find_from_sessions_index(projects_root: &Path, session_id: &str) -> Vec<PathBuf> {
+        Self::read_sessions_indexes(projects_root)
+            .into_iter()
+            .filter(|entry| entry.session_id == session_id)
+            .filter_map(|entry| entry.full_path)
             .filter(|path| path.exists())
             .collect()
     }
@@ -146,6 +160,92 @@ impl ClaudeProvider {
             .collect()
     }
 
+    fn summarize(path: &Path) -> Option<SessionSummary> {
+        let file = fs::File::open(path).ok()?;
+        let reader = BufReader::new(file);
+
+        let mut session_id = path
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .filter(|stem| is_session_id(ProviderKind::Claude, stem))
+            .map(str::to_ascii_lowercase);
+        let mut cwd = None::<String>;
+        let mut title = None::<String>;
+        let mut created_at = None::<u64>;
+
+        for line in reader.lines().take(50).map_while(std::result::Result::ok) {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let Ok(value) = serde_json::from_str::<Value>(&line) else {
+                continue;
+            };
+
+            if session_id.is_none() {
+                session_id = value
+                    .get("sessionId")
+                    .and_then(Value::as_str)
+                    .map(ToString::to_string);
+            }
+            if cwd.is_none() {
+                cwd = value
+                    .get("cwd")
+                    .and_then(Value::as_str)
+                    .map(ToString::to_string);
+            }
+            if title.is_none() && value.get("type").and_then(Value::as_str) == Some("summary") {
+                title = value
+                    .get("summary")
+                    .and_then(Value::as_str)
+                    .map(ToString::to_string);
+            }
+            if created_at.is_none() {
+                created_at = value
+                    .get("timestamp")
+                    .and_then(Value::as_str)
+                    .and_then(parse_rfc3339_epoch);
+            }
+            if session_id.is_some() && cwd.is_some() && title.is_some() && created_at.is_some() {
+                break;
+            }
+        }
+
+        Some(SessionSummary {
+            provider: ProviderKind::Claude,
+            session_id: session_id?,
+            path: path.to_path_buf(),
+            created_at,
+            updated_at: modified_epoch(path),
+            cwd,
+            title,
+            archived: false,
+            parent_session_id: None,
+            message_count: None,
+        })
+    }
+
+    /// Prefer the metadata Claude keeps in `sessions-index.json` over what
+    /// can be recovered from the transcript head.
+    fn apply_index_entry(summary: &mut SessionSummary, entry: &SessionIndexEntry) {
+        if let Some(title) = entry
+            .summary
+            .as_ref()
+            .or(entry.first_prompt.as_ref())
+            .filter(|title| !title.trim().is_empty())
+        {
+            summary.title = Some(title.clone());
+        }
+        if let Some(project_path) = &entry.project_path {
+            summary.cwd = Some(project_path.clone());
+        }
+        if let Some(created) = entry.created.as_deref().and_then(parse_rfc3339_epoch) {
+            summary.created_at = Some(created);
+        }
+        if let Some(modified) = entry.modified.as_deref().and_then(parse_rfc3339_epoch) {
+            summary.updated_at = summary.updated_at.max(modified);
+        }
+    }
+
     fn make_resolved(
         session_id: &str,
         selected: PathBuf,
@@ -214,6 +314,50 @@ impl Provider for ClaudeProvider {
             searched_roots: vec![projects],
         })
     }
+
+    fn list_sessions(&self) -> Result<Vec<SessionSummary>> {
+        let projects = self.projects_root();
+        if !projects.exists() {
+            return Ok(Vec::new());
+        }
+
+        // Main transcripts live directly under `projects/<project>/`; sidechain
+        // `agent-*.jsonl` files and nested `subagents/` directories are skipped.
+        let mut sessions = WalkDir::new(&projects)
+            .min_depth(2)
+            .max_depth(2)
+            .into_iter()
+            .filter_map(std::result::Result::ok)
+            .filter(|entry| entry.file_type().is_file())
+            .map(|entry| entry.into_path())
+            .filter(|path| {
+                path.file_name()
+                    .and_then(|name| name.to_str())
+                    .is_some_and(|name| name.ends_with(".jsonl") && !name.starts_with("agent-"))
+            })
+            .filter_map(|path| Self::summarize(&path))
+            .collect::<Vec<_>>();
+
+        for entry in Self::read_sessions_indexes(&projects) {
+            if entry.is_sidechain {
+                continue;
+            }
+
+            if let Some(summary) = sessions
+                .iter_mut()
+                .find(|summary| summary.session_id.eq_ignore_ascii_case(&entry.session_id))
+            {
+                Self::apply_index_entry(summary, &entry);
+            } else if let Some(path) = entry.full_path.as_ref().filter(|path| path.exists())
+                && let Some(mut summary) = Self::summarize(path)
+            {
+                Self::apply_index_entry(&mut summary, &entry);
+                sessions.push(summary);
+            }
+        }
+
+        Ok(sessions)
+    }
 }
 
 #[cfg(test)]
@@ -268,6 +412,65 @@ mod tests {
         assert_eq!(resolved.metadata.source, "claude:filename");
     }
 
+    #[test]
+    fn lists_main_transcripts_and_skips_agents() {
+        let temp = tempdir().expect("tempdir");
+        let projects = temp.path().join("projects/project-d");
+        fs::create_dir_all(projects.join("subagents")).expect("mkdir");
+
+        fs::write(
+            projects.join("2823d1df-720a-4c31-ac55-ae8ba726721f.jsonl"),
+            "{\"type\":\"summary\",\"summary\":\"Fix retries\"}\n{\"type\":\"user\",\"cwd\":\"/tmp/project\",\"sessionId\":\"2823d1df-720a-4c31-ac55-ae8ba726721f\"}\n",
+        )
+        .expect("write main");
+        fs::write(
+            projects.join("agent-a1b2c3.jsonl"),
+            "{\"type\":\"user\",\"isSidechain\":true}\n",
+        )
+        .expect("write agent");
+
+        let sessions = ClaudeProvider::new(temp.path())
+            .list_sessions()
+            .expect("list should succeed");
+        assert_eq!(sessions.len(), 1);
+        assert_eq!(
+            sessions[0].session_id,
+            "2823d1df-720a-4c31-ac55-ae8ba726721f"
+        );
+        assert_eq!(sessions[0].cwd.as_deref(), Some("/tmp/project"));
+        assert_eq!(sessions[0].title.as_deref(), Some("Fix retries"));
+    }
+
+    #[test]
+    fn lists_sessions_with_index_metadata() {
+        let temp = tempdir().expect("tempdir");
+        let projects = temp.path().join("projects/project-e");
+        fs::create_dir_all(&projects).expect("mkdir");
+        let thread_file = projects.join("2823d1df-720a-4c31-ac55-ae8ba726721f.jsonl");
+        fs::write(
+            &thread_file,
+            "{\"type\":\"user\",\"timestamp\":\"2026-02-23T13:00:12.780Z\",\"cwd\":\"/tmp/old\"}\n",
+        )
+        .expect("write thread");
+        fs::write(
+            projects.join("sessions-index.json"),
+            format!(
+                "{{\"entries\":[{{\"sessionId\":\"2823d1df-720a-4c31-ac55-ae8ba726721f\",\"fullPath\":\"{}\",\"firstPrompt\":\"fix the flaky test\",\"projectPath\":\"/tmp/project\",\"created\":\"2026-02-23T12:00:00.000Z\"}}]}}",
+                thread_file.display()
+            ),
+        )
+        .expect("write index");
+
+        let sessions = ClaudeProvider::new(temp.path())
+            .list_sessions()
+            .expect("list should succeed");
+        assert_eq!(sessions.len(), 1);
+        assert_eq!(sessions[0].title.as_deref(), Some("fix the flaky test"));
+        assert_eq!(sessions[0].cwd.as_deref(), Some("/tmp/project"));
+        assert_eq!(sessions[0].created_at, Some(1_771_848_000));
+        assert!(!sessions[0].archived);
+    }
+
     #[test]
     fn resolves_from_header_scan() {
         let temp = tempdir().expect("tempdir");
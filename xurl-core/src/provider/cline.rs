@@ -0,0 +1,242 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde_json::Value;
+
+use crate::error::{Result, XurlError};
+use crate::model::{ProviderKind, ResolutionMeta, ResolvedThread, SessionSummary};
+use crate::provider::{Provider, modified_epoch};
+use crate::time::normalize_epoch;
+use crate::uri::is_session_id;
+
+/// The API transcript inside a task directory; it is the thread file.
+pub const API_HISTORY_FILE: &str = "api_conversation_history.json";
+/// The UI message log next to it, which records checkpoints.
+pub const UI_MESSAGES_FILE: &str = "ui_messages.json";
+
+/// Both extensions append an `<environment_details>` block to user turns
+/// that names the workspace the task started in.
+static CWD_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"Current (?:Working|Workspace) Directory \(([^)\n]+)\)").expect("valid regex")
+});
+
+/// Task histories of the Cline VS Code extension and its Roo Code fork,
+/// which share one layout under the extension's globalStorage:
+/// `tasks/<task_id>/{api_conversation_history,ui_messages}.json`.
+#[derive(Debug, Clone)]
+pub struct ClineProvider {
+    kind: ProviderKind,
+    root: PathBuf,
+}
+
+impl ClineProvider {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self {
+            kind: ProviderKind::Cline,
+            root: root.into(),
+        }
+    }
+
+    pub fn roo(root: impl Into<PathBuf>) -> Self {
+        Self {
+            kind: ProviderKind::Roo,
+            root: root.into(),
+        }
+    }
+
+    fn tasks_root(&self) -> PathBuf {
+        self.root.join("tasks")
+    }
+
+    /// Cline keeps `HistoryItem`s in `state/taskHistory.json`; Roo Code keeps
+    /// them in VS Code's state database, so it falls back to the transcript.
+    fn task_history(&self) -> HashMap<String, Value> {
+        fs::read_to_string(self.root.join("state/taskHistory.json"))
+            .ok()
+            .and_then(|raw| serde_json::from_str::<Value>(&raw).ok())
+            .and_then(|value| value.as_array().cloned())
+            .into_iter()
+            .flatten()
+            .filter_map(|item| {
+                let id = item.get("id").and_then(Value::as_str)?.to_ascii_lowercase();
+                Some((id, item))
+            })
+            .collect()
+    }
+
+    fn summarize(&self, path: &Path, session_id: &str, history: Option<&Value>) -> SessionSummary {
+        let messages = fs::read_to_string(path)
+            .ok()
+            .and_then(|raw| serde_json::from_str::<Value>(&raw).ok())
+            .unwrap_or(Value::Null);
+
+        let title = history
+            .and_then(|item| item.get("task"))
+            .and_then(Value::as_str)
+            .map(str::trim)
+            .filter(|task| !task.is_empty())
+            .map(ToString::to_string);
+        let cwd = history
+            .and_then(|item| item.get("cwdOnTaskInitialization"))
+            .and_then(Value::as_str)
+            .map(ToString::to_string)
+            .or_else(|| first_user_cwd(&messages));
+        // Timestamp ids are the creation time; UUID ids fall back to the
+        // first message.
+        let created_at = session_id
+            .parse::<i64>()
+            .ok()
+            .or_else(|| messages.pointer("/0/ts").and_then(Value::as_i64))
+            .and_then(normalize_epoch);
+        let updated_at =
+            modified_epoch(path).max(modified_epoch(&path.with_file_name(UI_MESSAGES_FILE)));
+
+        SessionSummary {
+            provider: self.kind,
+            session_id: session_id.to_string(),
+            path: path.to_path_buf(),
+            created_at,
+            updated_at,
+            cwd,
+            title,
+            archived: false,
+            parent_session_id: None,
+            message_count: None,
+        }
+    }
+}
+
+fn first_user_cwd(messages: &Value) -> Option<String> {
+    let message = messages
+        .as_array()?
+        .iter()
+        .find(|message| message.get("role").and_then(Value::as_str) == Some("user"))?;
+    let texts = match message.get("content")? {
+        Value::String(text) => vec![text.as_str()],
+        Value::Array(items) => items
+            .iter()
+            .filter_map(|item| item.get("text").and_then(Value::as_str))
+            .collect(),
+        _ => Vec::new(),
+    };
+    texts.into_iter().find_map(|text| {
+        CWD_RE
+            .captures(text)
+            .map(|captures| captures[1].trim().to_string())
+    })
+}
+
+impl Provider for ClineProvider {
+    fn resolve(&self, session_id: &str) -> Result<ResolvedThread> {
+        let tasks_root = self.tasks_root();
+        let path = tasks_root.join(session_id).join(API_HISTORY_FILE);
+
+        if !path.exists() {
+            return Err(XurlError::ThreadNotFound {
+                provider: self.kind.to_string(),
+                session_id: session_id.to_string(),
+                searched_roots: vec![tasks_root],
+            });
+        }
+
+        Ok(ResolvedThread {
+            provider: self.kind,
+            session_id: session_id.to_string(),
+            path,
+            metadata: ResolutionMeta {
+                source: format!("{}:tasks", self.kind),
+                candidate_count: 1,
+                warnings: Vec::new(),
+            },
+        })
+    }
+
+    fn list_sessions(&self) -> Result<Vec<SessionSummary>> {
+        let Ok(entries) = fs::read_dir(self.tasks_root()) else {
+            return Ok(Vec::new());
+        };
+        let history = self.task_history();
+
+        Ok(entries
+            .filter_map(std::result::Result::ok)
+            .filter_map(|entry| {
+                let session_id = entry.file_name().to_str()?.to_ascii_lowercase();
+                let path = entry.path().join(API_HISTORY_FILE);
+                (is_session_id(self.kind, &session_id) && path.is_file())
+                    .then(|| self.summarize(&path, &session_id, history.get(&session_id)))
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use tempfile::tempdir;
+
+    use crate::model::ProviderKind;
+    use crate::provider::Provider;
+    use crate::provider::cline::ClineProvider;
+
+    #[test]
+    fn resolves_api_history_in_task_directory() {
+        let temp = tempdir().expect("tempdir");
+        let task = temp.path().join("tasks/1731234567890");
+        fs::create_dir_all(&task).expect("mkdir");
+        let path = task.join("api_conversation_history.json");
+        fs::write(&path, "[]").expect("write");
+
+        let resolved = ClineProvider::new(temp.path())
+            .resolve("1731234567890")
+            .expect("resolve should succeed");
+        assert_eq!(resolved.path, path);
+        assert_eq!(resolved.metadata.source, "cline:tasks");
+
+        let err = ClineProvider::roo(temp.path())
+            .resolve("1731234567899")
+            .expect_err("must fail");
+        assert!(format!("{err}").contains("thread not found"));
+    }
+
+    #[test]
+    fn lists_tasks_with_history_title_or_environment_cwd() {
+        let temp = tempdir().expect("tempdir");
+        let tasks = temp.path().join("tasks");
+        for id in ["1731234567890", "1731234599999", "not-a-task"] {
+            fs::create_dir_all(tasks.join(id)).expect("mkdir");
+        }
+        fs::write(
+            tasks.join("1731234567890/api_conversation_history.json"),
+            r#"[{"role":"user","content":[{"type":"text","text":"<task>\nFix retries\n</task>"},{"type":"text","text":"<environment_details>\n# Current Working Directory (/tmp/project) Files\nsrc/\n</environment_details>"}]}]"#,
+        )
+        .expect("write");
+        fs::write(
+            tasks.join("1731234599999/api_conversation_history.json"),
+            "[]",
+        )
+        .expect("write");
+        fs::write(tasks.join("not-a-task/api_conversation_history.json"), "[]").expect("write");
+        fs::create_dir_all(temp.path().join("state")).expect("mkdir");
+        fs::write(
+            temp.path().join("state/taskHistory.json"),
+            r#"[{"id":"1731234599999","ts":1731234600000,"task":"Add a CLI","cwdOnTaskInitialization":"/work/cli"}]"#,
+        )
+        .expect("write");
+
+        let mut sessions = ClineProvider::new(temp.path())
+            .list_sessions()
+            .expect("list should succeed");
+        sessions.sort_by(|a, b| a.session_id.cmp(&b.session_id));
+        assert_eq!(sessions.len(), 2);
+        assert_eq!(sessions[0].provider, ProviderKind::Cline);
+        assert_eq!(sessions[0].title, None);
+        assert_eq!(sessions[0].cwd.as_deref(), Some("/tmp/project"));
+        assert_eq!(sessions[0].created_at, Some(1_731_234_567));
+        assert_eq!(sessions[1].title.as_deref(), Some("Add a CLI"));
+        assert_eq!(sessions[1].cwd.as_deref(), Some("/work/cli"));
+    }
+}
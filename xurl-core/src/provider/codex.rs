@@ -1,14 +1,19 @@
 use std::cmp::Reverse;
+use std::collections::HashSet;
 use std::fs;
+use std::io::{BufRead, BufReader};
 use std::path::{Path, PathBuf};
 use std::time::SystemTime;
 
 use rusqlite::{Connection, OpenFlags, OptionalExtension};
+use serde_json::Value;
 use walkdir::WalkDir;
 
 use crate::error::{Result, XurlError};
-use crate::model::{ProviderKind, ResolutionMeta, ResolvedThread};
-use crate::provider::Provider;
+use crate::model::{ProviderKind, ResolutionMeta, ResolvedThread, SessionSummary};
+use crate::provider::{Provider, modified_epoch};
+use crate::time::{normalize_epoch, parse_rfc3339_epoch};
+use crate::uri::is_session_id;
 
 #[derive(Debug, Clone)]
 pub struct CodexProvider {
@@ -21,6 +26,16 @@ struct SqliteThreadRecord {
     archived: bool,
 }
 
+#[derive(Debug, Clone)]
+struct SqliteThreadSummary {
+    id: String,
+    record: SqliteThreadRecord,
+    created_at: Option<u64>,
+    updated_at: Option<u64>,
+    cwd: Option<String>,
+    title: Option<String>,
+}
+
 impl CodexProvider {
     pub fn new(root: impl Into<PathBuf>) -> Self {
         Self { root: root.into() }
@@ -91,6 +106,47 @@ impl CodexProvider {
         Ok(row)
     }
 
+    fn query_thread_summaries(
+        db_path: &Path,
+    ) -> std::result::Result<Vec<SqliteThreadSummary>, rusqlite::Error> {
+        let conn = Connection::open_with_flags(db_path, OpenFlags::SQLITE_OPEN_READ_ONLY)?;
+        let columns = {
+            let mut stmt = conn.prepare("PRAGMA table_info(threads)")?;
+            let rows = stmt.query_map([], |row| row.get::<_, String>(1))?;
+            rows.collect::<std::result::Result<HashSet<_>, _>>()?
+        };
+        let optional = |name: &str| {
+            if columns.contains(name) {
+                name.to_string()
+            } else {
+                "NULL".to_string()
+            }
+        };
+
+        let sql = format!(
+            "SELECT id, rollout_path, archived, {}, {}, {}, {} FROM threads",
+            optional("created_at"),
+            optional("updated_at"),
+            optional("cwd"),
+            optional("title"),
+        );
+        let mut stmt = conn.prepare(&sql)?;
+        let rows = stmt.query_map([], |row| {
+            Ok(SqliteThreadSummary {
+                id: row.get::<_, String>(0)?,
+                record: SqliteThreadRecord {
+                    rollout_path: PathBuf::from(row.get::<_, String>(1)?),
+                    archived: row.get::<_, i64>(2)? != 0,
+                },
+                created_at: row.get::<_, Option<i64>>(3)?.and_then(normalize_epoch),
+                updated_at: row.get::<_, Option<i64>>(4)?.and_then(normalize_epoch),
+                cwd: row.get::<_, Option<String>>(5)?,
+                title: row.get::<_, Option<String>>(6)?,
+            })
+        })?;
+        rows.collect()
+    }
+
     fn lookup_thread_from_state_db(
         state_dbs: &[PathBuf],
         session_id: &str,
@@ -129,6 +185,87 @@ impl CodexProvider {
             .collect()
     }
 
+    /// Extract the session id from a `rollout-<timestamp>-<session_id>.jsonl` file name.
+    fn rollout_session_id(path: &Path) -> Option<String> {
+        let stem = path
+            .file_name()?
+            .to_str()?
+            .strip_prefix("rollout-")?
+            .strip_suffix(".jsonl")?;
+        let session_id = stem.get(stem.len().checked_sub(36)?..)?;
+        is_session_id(ProviderKind::Codex, session_id).then(|| session_id.to_ascii_lowercase())
+    }
+
+    fn read_session_meta(path: &Path) -> Option<Value> {
+        let file = fs::File::open(path).ok()?;
+        let first = BufReader::new(file)
+            .lines()
+            .take(5)
+            .map_while(std::result::Result::ok)
+            .find(|line| !line.trim().is_empty())?;
+        let value = serde_json::from_str::<Value>(&first).ok()?;
+        (value.get("type").and_then(Value::as_str) == Some("session_meta"))
+            .then(|| value.get("payload").cloned())
+            .flatten()
+    }
+
+    fn summarize_rollout(path: &Path, archived: bool) -> Option<SessionSummary> {
+        let session_id = Self::rollout_session_id(path)?;
+        let meta = Self::read_session_meta(path);
+        let meta_str = |pointer: &str| {
+            meta.as_ref()
+                .and_then(|meta| meta.pointer(pointer))
+                .and_then(Value::as_str)
+        };
+
+        Some(SessionSummary {
+            provider: ProviderKind::Codex,
+            session_id,
+            path: path.to_path_buf(),
+            created_at: meta_str("/timestamp").and_then(parse_rfc3339_epoch),
+            updated_at: modified_epoch(path),
+            cwd: meta_str("/cwd").map(ToString::to_string),
+            title: None,
+            archived,
+            parent_session_id: meta_str("/source/subagent/thread_spawn/parent_thread_id")
+                .map(str::to_ascii_lowercase),
+            message_count: None,
+        })
+    }
+
+    fn summarize_rollouts(root: &Path, archived: bool) -> Vec<SessionSummary> {
+        if !root.exists() {
+            return Vec::new();
+        }
+
+        WalkDir::new(root)
+            .into_iter()
+            .filter_map(std::result::Result::ok)
+            .filter(|entry| entry.file_type().is_file())
+            .filter_map(|entry| Self::summarize_rollout(entry.path(), archived))
+            .collect()
+    }
+
+    fn apply_thread_summary(summary: &mut SessionSummary, thread: &SqliteThreadSummary) {
+        summary.archived = thread.record.archived;
+        if thread.created_at.is_some() {
+            summary.created_at = thread.created_at;
+        }
+        if let Some(updated_at) = thread.updated_at {
+            summary.updated_at = summary.updated_at.max(updated_at);
+        }
+        if let Some(cwd) = thread.cwd.as_ref().filter(|cwd| !cwd.is_empty()) {
+            summary.cwd = Some(cwd.clone());
+        }
+        if let Some(title) = thread
+            .title
+            .as_ref()
+            .filter(|title| !title.trim().is_empty())
+        {
+            summary.title = Some(title.clone());
+        }
+    }
+
     fn choose_latest(paths: Vec<PathBuf>) -> Option<(PathBuf, usize)> {
         if paths.is_empty() {
             return None;
@@ -254,6 +391,48 @@ impl Provider for CodexProvider {
                 .collect(),
         })
     }
+
+    fn list_sessions(&self) -> Result<Vec<SessionSummary>> {
+        let mut sessions = Self::summarize_rollouts(&self.sessions_root(), false);
+        sessions.extend(Self::summarize_rollouts(&self.archived_root(), true));
+
+        // The state database is authoritative for titles and archive state, but
+        // rollouts it does not know about yet are still listed. Newer databases
+        // win when several define the same thread.
+        let mut indexed = HashSet::new();
+        for db_path in self.state_db_paths() {
+            let Ok(threads) = Self::query_thread_summaries(&db_path) else {
+                continue;
+            };
+
+            for thread in threads {
+                let session_id = thread.id.to_ascii_lowercase();
+                if !indexed.insert(session_id.clone()) {
+                    continue;
+                }
+
+                let mut matched = false;
+                for summary in sessions
+                    .iter_mut()
+                    .filter(|summary| summary.session_id == session_id)
+                {
+                    Self::apply_thread_summary(summary, &thread);
+                    matched = true;
+                }
+
+                if !matched
+                    && thread.record.rollout_path.exists()
+                    && let Some(mut summary) =
+                        Self::summarize_rollout(&thread.record.rollout_path, thread.record.archived)
+                {
+                    Self::apply_thread_summary(&mut summary, &thread);
+                    sessions.push(summary);
+                }
+            }
+        }
+
+        Ok(sessions)
+    }
 }
 
 #[cfg(test)]
@@ -315,6 +494,94 @@ mod tests {
         assert_eq!(resolved.metadata.source, "codex:archived_sessions");
     }
 
+    #[test]
+    fn lists_rollouts_with_ids_from_file_names() {
+        let temp = tempdir().expect("tempdir");
+        let active = temp
+            .path()
+            .join("sessions/2026/02/23/rollout-2026-02-23T04-48-50-019c871c-b1f9-7f60-9c4f-87ed09f13592.jsonl");
+        fs::create_dir_all(active.parent().expect("parent")).expect("mkdir");
+        fs::write(
+            &active,
+            "{\"type\":\"session_meta\",\"payload\":{\"id\":\"019c871c-b1f9-7f60-9c4f-87ed09f13592\",\"timestamp\":\"2026-02-23T13:00:12.780Z\",\"cwd\":\"/tmp/project\",\"source\":{\"subagent\":{\"thread_spawn\":{\"parent_thread_id\":\"019c8129-f668-7951-8d56-cc5513541c26\",\"depth\":1}}}}}\n",
+        )
+        .expect("write");
+        let archived = temp
+            .path()
+            .join("archived_sessions/rollout-2026-02-22T01-05-36-019c8129-f668-7951-8d56-cc5513541c26.jsonl");
+        fs::create_dir_all(archived.parent().expect("parent")).expect("mkdir");
+        fs::write(&archived, "{}\n").expect("write");
+
+        let mut sessions = CodexProvider::new(temp.path())
+            .list_sessions()
+            .expect("list should succeed");
+        sessions.sort_by(|a, b| a.session_id.cmp(&b.session_id));
+
+        assert_eq!(sessions.len(), 2);
+        assert_eq!(
+            sessions[0].session_id,
+            "019c8129-f668-7951-8d56-cc5513541c26"
+        );
+        assert_eq!(sessions[0].cwd, None);
+        assert!(sessions[0].archived);
+        assert_eq!(
+            sessions[1].session_id,
+            "019c871c-b1f9-7f60-9c4f-87ed09f13592"
+        );
+        assert_eq!(sessions[1].cwd.as_deref(), Some("/tmp/project"));
+        assert_eq!(sessions[1].created_at, Some(1_771_851_612));
+        assert_eq!(
+            sessions[1].parent_session_id.as_deref(),
+            Some("019c8129-f668-7951-8d56-cc5513541c26")
+        );
+        assert!(!sessions[1].archived);
+    }
+
+    #[test]
+    fn lists_sessions_with_threads_table_metadata() {
+        let temp = tempdir().expect("tempdir");
+        let indexed = temp.path().join(
+            "elsewhere/rollout-2026-02-23T04-48-50-019c871c-b1f9-7f60-9c4f-87ed09f13592.jsonl",
+        );
+        fs::create_dir_all(indexed.parent().expect("parent")).expect("mkdir");
+        fs::write(&indexed, "{}\n").expect("write");
+
+        let conn = Connection::open(temp.path().join("state_5.sqlite")).expect("open sqlite");
+        conn.execute_batch(
+            "
+            CREATE TABLE threads (
+                id TEXT PRIMARY KEY,
+                rollout_path TEXT NOT NULL,
+                created_at INTEGER NOT NULL,
+                updated_at INTEGER NOT NULL,
+                cwd TEXT NOT NULL,
+                title TEXT NOT NULL,
+                archived INTEGER NOT NULL DEFAULT 0
+            );
+            ",
+        )
+        .expect("create schema");
+        conn.execute(
+            "INSERT INTO threads VALUES (?1, ?2, 1771851612, 4102444800, '/tmp/project', 'Fix retries', 1)",
+            (
+                "019c871c-b1f9-7f60-9c4f-87ed09f13592",
+                indexed.display().to_string(),
+            ),
+        )
+        .expect("insert");
+
+        let sessions = CodexProvider::new(temp.path())
+            .list_sessions()
+            .expect("list should succeed");
+        assert_eq!(sessions.len(), 1);
+        assert_eq!(sessions[0].path, indexed);
+        assert_eq!(sessions[0].title.as_deref(), Some("Fix retries"));
+        assert_eq!(sessions[0].cwd.as_deref(), Some("/tmp/project"));
+        assert_eq!(sessions[0].created_at, Some(1_771_851_612));
+        assert_eq!(sessions[0].updated_at, 4_102_444_800);
+        assert!(sessions[0].archived);
+    }
+
     #[test]
     fn returns_not_found_when_missing() {
         let temp = tempdir().expect("tempdir");
@@ -0,0 +1,560 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use rusqlite::types::ValueRef;
+use rusqlite::{Connection, OpenFlags, OptionalExtension, Row};
+use serde_json::{Value, json};
+
+use crate::error::{Result, XurlError};
+use crate::model::{ProviderKind, ResolutionMeta, ResolvedThread, SessionSummary};
+use crate::provider::Provider;
+use crate::time::normalize_epoch;
+use crate::uri::is_session_id;
+
+/// Resolution source of composers materialized from the global database.
+pub(crate) const SOURCE: &str = "cursor:state.vscdb";
+
+/// Cursor composers (its agent and chat panes) under Cursor's `User`
+/// directory. Conversations live in the `cursorDiskKV` table of
+/// `globalStorage/state.vscdb`: `composerData:<id>` holds the composer and
+/// the order of its messages, and `bubbleId:<id>:<bubble>` holds each
+/// message. Releases before bubbles were split out inline them in the
+/// composer as `conversation`. The folder a composer belongs to is only
+/// recorded per workspace, in the `ItemTable` of
+/// `workspaceStorage/<hash>/state.vscdb`.
+///
+/// Threads are materialized as JSONL: a header line with the composer's
+/// metadata, then one bubble per line.
+#[derive(Debug, Clone)]
+pub struct CursorProvider {
+    root: PathBuf,
+}
+
+impl CursorProvider {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    fn db_path(&self) -> PathBuf {
+        self.root.join("globalStorage/state.vscdb")
+    }
+
+    fn workspaces_root(&self) -> PathBuf {
+        self.root.join("workspaceStorage")
+    }
+
+    fn materialized_path(session_id: &str) -> PathBuf {
+        std::env::temp_dir()
+            .join("xurl-cursor")
+            .join(format!("{session_id}.jsonl"))
+    }
+
+    /// Open a `state.vscdb` read-only. Cursor keeps the database open while
+    /// it runs, so nothing here may take a write lock.
+    fn open_db(path: &Path) -> Result<Option<Connection>> {
+        if !path.exists() {
+            return Ok(None);
+        }
+        Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY)
+            .map(Some)
+            .map_err(|source| XurlError::Sqlite {
+                path: path.to_path_buf(),
+                source,
+            })
+    }
+
+    fn has_table(conn: &Connection, table: &str) -> std::result::Result<bool, rusqlite::Error> {
+        conn.query_row(
+            "SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = ?1",
+            [table],
+            |_| Ok(()),
+        )
+        .optional()
+        .map(|found| found.is_some())
+    }
+
+    fn composer(
+        conn: &Connection,
+        session_id: &str,
+    ) -> std::result::Result<Option<Value>, rusqlite::Error> {
+        if !Self::has_table(conn, "cursorDiskKV")? {
+            return Ok(None);
+        }
+        conn.query_row(
+            "SELECT value FROM cursorDiskKV WHERE key = ?1",
+            [format!("composerData:{session_id}")],
+            |row| Ok(json_column(row, 0)),
+        )
+        .optional()
+        .map(Option::flatten)
+    }
+
+    /// The composer's bubbles in conversation order. Bubbles that are
+    /// listed but missing or unreadable are reported as warnings.
+    fn bubbles(
+        conn: &Connection,
+        session_id: &str,
+        composer: &Value,
+        warnings: &mut Vec<String>,
+    ) -> std::result::Result<Vec<Value>, rusqlite::Error> {
+        let Some(headers) = composer
+            .get("fullConversationHeadersOnly")
+            .and_then(Value::as_array)
+        else {
+            return Ok(composer
+                .get("conversation")
+                .and_then(Value::as_array)
+                .cloned()
+                .unwrap_or_default());
+        };
+
+        // A key range rather than LIKE, so the lookup uses the key index
+        // instead of scanning a table that often runs to gigabytes.
+        let prefix = format!("bubbleId:{session_id}:");
+        let mut stmt =
+            conn.prepare("SELECT key, value FROM cursorDiskKV WHERE key >= ?1 AND key < ?2")?;
+        let rows = stmt.query_map([prefix.clone(), format!("bubbleId:{session_id};")], |row| {
+            Ok((row.get::<_, String>(0)?, json_column(row, 1)))
+        })?;
+        let mut stored = HashMap::new();
+        for row in rows {
+            let (key, value) = row?;
+            if let Some(bubble_id) = key.strip_prefix(&prefix) {
+                stored.insert(bubble_id.to_string(), value);
+            }
+        }
+
+        let mut bubbles = Vec::new();
+        for bubble_id in headers
+            .iter()
+            .filter_map(|header| header.get("bubbleId").and_then(Value::as_str))
+        {
+            match stored.remove(bubble_id) {
+                Some(Some(bubble)) => bubbles.push(bubble),
+                Some(None) => warnings.push(format!(
+                    "skipped bubble id={bubble_id}: invalid json payload"
+                )),
+                None => warnings.push(format!("skipped bubble id={bubble_id}: not stored")),
+            }
+        }
+        Ok(bubbles)
+    }
+
+    /// Folder of every composer opened in a single-folder workspace, keyed
+    /// by composer id. Unreadable workspaces are skipped.
+    fn composer_folders(&self) -> HashMap<String, String> {
+        let mut folders = HashMap::new();
+        let Ok(entries) = fs::read_dir(self.workspaces_root()) else {
+            return folders;
+        };
+        for entry in entries.filter_map(std::result::Result::ok) {
+            let dir = entry.path();
+            let Some(folder) = fs::read_to_string(dir.join("workspace.json"))
+                .ok()
+                .and_then(|raw| serde_json::from_str::<Value>(&raw).ok())
+                .and_then(|workspace| {
+                    workspace
+                        .get("folder")
+                        .and_then(Value::as_str)
+                        .and_then(file_uri_path)
+                })
+            else {
+                continue;
+            };
+            let Ok(Some(conn)) = Self::open_db(&dir.join("state.vscdb")) else {
+                continue;
+            };
+            let composers = conn
+                .query_row(
+                    "SELECT value FROM ItemTable WHERE key = 'composer.composerData'",
+                    [],
+                    |row| Ok(json_column(row, 0)),
+                )
+                .ok()
+                .flatten();
+            for composer_id in composers
+                .iter()
+                .filter_map(|data| data.get("allComposers").and_then(Value::as_array))
+                .flatten()
+                .filter_map(|composer| composer.get("composerId").and_then(Value::as_str))
+            {
+                folders.insert(composer_id.to_string(), folder.clone());
+            }
+        }
+        folders
+    }
+
+    /// The `composerData` of one composer.
+    pub(crate) fn load_composer(&self, session_id: &str) -> Result<Value> {
+        let db_path = self.db_path();
+        let not_found = || XurlError::ThreadNotFound {
+            provider: ProviderKind::Cursor.to_string(),
+            session_id: session_id.to_string(),
+            searched_roots: vec![db_path.clone()],
+        };
+        let conn = Self::open_db(&db_path)?.ok_or_else(not_found)?;
+        Self::composer(&conn, session_id)
+            .map_err(|source| XurlError::Sqlite {
+                path: db_path.clone(),
+                source,
+            })?
+            .ok_or_else(not_found)
+    }
+
+    /// Write `composer` and its bubbles to the materialized copy, leaving
+    /// the file untouched when its content would not change.
+    ///
+    /// `cwd` is looked up separately because finding it opens every
+    /// workspace database.
+    pub(crate) fn materialize(
+        &self,
+        session_id: &str,
+        composer: &Value,
+        cwd: Option<&str>,
+    ) -> Result<ResolvedThread> {
+        let db_path = self.db_path();
+        let sql = |source| XurlError::Sqlite {
+            path: db_path.clone(),
+            source,
+        };
+        let conn = Self::open_db(&db_path)?.ok_or_else(|| XurlError::ThreadNotFound {
+            provider: ProviderKind::Cursor.to_string(),
+            session_id: session_id.to_string(),
+            searched_roots: vec![db_path.clone()],
+        })?;
+
+        let mut warnings = Vec::new();
+        let bubbles = Self::bubbles(&conn, session_id, composer, &mut warnings).map_err(sql)?;
+        let header = json!({
+            "composerId": session_id,
+            "name": composer.get("name"),
+            "createdAt": composer.get("createdAt"),
+            "cwd": cwd,
+        });
+        let mut output = String::new();
+        for line in std::iter::once(header).chain(bubbles) {
+            output.push_str(&line.to_string());
+            output.push('\n');
+        }
+
+        // Follow mode watches the copy for changes.
+        let path = Self::materialized_path(session_id);
+        if fs::read_to_string(&path).ok().as_deref() != Some(output.as_str()) {
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent).map_err(|source| XurlError::Io {
+                    path: parent.to_path_buf(),
+                    source,
+                })?;
+            }
+            fs::write(&path, output).map_err(|source| XurlError::Io {
+                path: path.clone(),
+                source,
+            })?;
+        }
+
+        Ok(ResolvedThread {
+            provider: ProviderKind::Cursor,
+            session_id: session_id.to_string(),
+            path,
+            metadata: ResolutionMeta {
+                source: SOURCE.to_string(),
+                candidate_count: 1,
+                warnings,
+            },
+        })
+    }
+
+    fn fetch_summaries(
+        conn: &Connection,
+        db_path: &Path,
+        folders: &HashMap<String, String>,
+    ) -> std::result::Result<Vec<SessionSummary>, rusqlite::Error> {
+        if !Self::has_table(conn, "cursorDiskKV")? {
+            return Ok(Vec::new());
+        }
+        let mut stmt = conn.prepare(
+            "SELECT key, value FROM cursorDiskKV WHERE key >= 'composerData:' AND key < 'composerData;'",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            Ok((row.get::<_, String>(0)?, json_column(row, 1)))
+        })?;
+
+        let mut summaries = Vec::new();
+        for row in rows {
+            let (key, composer) = row?;
+            let (Some(session_id), Some(composer)) = (key.strip_prefix("composerData:"), composer)
+            else {
+                continue;
+            };
+            // Cursor stores a composer for every pane it opens, including
+            // ones that were never sent a message.
+            if !is_session_id(ProviderKind::Cursor, session_id) || bubble_count(&composer) == 0 {
+                continue;
+            }
+            let epoch = |key: &str| {
+                composer
+                    .get(key)
+                    .and_then(Value::as_i64)
+                    .and_then(normalize_epoch)
+            };
+            let created_at = epoch("createdAt");
+            summaries.push(SessionSummary {
+                provider: ProviderKind::Cursor,
+                session_id: session_id.to_string(),
+                path: db_path.to_path_buf(),
+                created_at,
+                updated_at: epoch("lastUpdatedAt").or(created_at).unwrap_or_default(),
+                cwd: folders.get(session_id).cloned(),
+                title: composer
+                    .get("name")
+                    .and_then(Value::as_str)
+                    .map(str::trim)
+                    .filter(|name| !name.is_empty())
+                    .map(ToString::to_string),
+                archived: false,
+                parent_session_id: None,
+                message_count: None,
+            });
+        }
+        Ok(summaries)
+    }
+}
+
+/// A JSON value stored as TEXT or BLOB; anything else reads as `None`.
+fn json_column(row: &Row<'_>, index: usize) -> Option<Value> {
+    match row.get_ref(index).ok()? {
+        ValueRef::Text(bytes) | ValueRef::Blob(bytes) => serde_json::from_slice(bytes).ok(),
+        _ => None,
+    }
+}
+
+fn bubble_count(composer: &Value) -> usize {
+    composer
+        .get("fullConversationHeadersOnly")
+        .or_else(|| composer.get("conversation"))
+        .and_then(Value::as_array)
+        .map_or(0, Vec::len)
+}
+
+/// Path of a `file://` URI, with percent escapes decoded.
+fn file_uri_path(uri: &str) -> Option<String> {
+    let encoded = uri.strip_prefix("file://")?.as_bytes();
+    let mut decoded = Vec::with_capacity(encoded.len());
+    let mut idx = 0;
+    while idx < encoded.len() {
+        let escaped = (encoded[idx] == b'%')
+            .then(|| encoded.get(idx + 1..idx + 3))
+            .flatten()
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match escaped {
+            Some(byte) => {
+                decoded.push(byte);
+                idx += 3;
+            }
+            None => {
+                decoded.push(encoded[idx]);
+                idx += 1;
+            }
+        }
+    }
+    String::from_utf8(decoded).ok()
+}
+
+impl Provider for CursorProvider {
+    fn resolve(&self, session_id: &str) -> Result<ResolvedThread> {
+        let composer = self.load_composer(session_id)?;
+        let cwd = self.composer_folders().remove(session_id);
+        self.materialize(session_id, &composer, cwd.as_deref())
+    }
+
+    fn list_sessions(&self) -> Result<Vec<SessionSummary>> {
+        let db_path = self.db_path();
+        let Some(conn) = Self::open_db(&db_path)? else {
+            return Ok(Vec::new());
+        };
+        Self::fetch_summaries(&conn, &db_path, &self.composer_folders()).map_err(|source| {
+            XurlError::Sqlite {
+                path: db_path.clone(),
+                source,
+            }
+        })
+    }
+
+    fn has_session(&self, session_id: &str) -> Result<bool> {
+        let db_path = self.db_path();
+        let Some(conn) = Self::open_db(&db_path)? else {
+            return Ok(false);
+        };
+        Self::composer(&conn, session_id)
+            .map(|composer| composer.is_some())
+            .map_err(|source| XurlError::Sqlite {
+                path: db_path,
+                source,
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::path::Path;
+
+    use rusqlite::{Connection, params};
+    use serde_json::json;
+    use tempfile::tempdir;
+
+    use crate::provider::Provider;
+    use crate::provider::cursor::{CursorProvider, file_uri_path};
+
+    const COMPOSER: &str = "5f0c2a4e-8b1d-4c3a-9e7f-1a2b3c4d5e6f";
+    const LEGACY: &str = "0a1b2c3d-4e5f-4a6b-8c7d-9e0f1a2b3c4d";
+    const EMPTY: &str = "9e8d7c6b-5a4f-4e3d-8c2b-1a0f9e8d7c6b";
+
+    fn prepare_db(path: &Path, table: &str) -> Connection {
+        fs::create_dir_all(path.parent().expect("parent")).expect("mkdir");
+        let conn = Connection::open(path).expect("open sqlite");
+        conn.execute_batch(&format!(
+            "CREATE TABLE {table} (key TEXT UNIQUE ON CONFLICT REPLACE, value BLOB);"
+        ))
+        .expect("create schema");
+        conn
+    }
+
+    fn put(conn: &Connection, table: &str, key: &str, value: serde_json::Value) {
+        conn.execute(
+            &format!("INSERT INTO {table} (key, value) VALUES (?1, ?2)"),
+            params![key, value.to_string()],
+        )
+        .expect("insert");
+    }
+
+    fn prepare_root(root: &Path) {
+        let conn = prepare_db(&root.join("globalStorage/state.vscdb"), "cursorDiskKV");
+        put(
+            &conn,
+            "cursorDiskKV",
+            &format!("composerData:{COMPOSER}"),
+            json!({
+                "composerId": COMPOSER,
+                "name": "Fix retries",
+                "createdAt": 1_760_601_600_000_i64,
+                "lastUpdatedAt": 1_760_601_900_000_i64,
+                "fullConversationHeadersOnly": [
+                    {"bubbleId": "b1", "type": 1},
+                    {"bubbleId": "b2", "type": 2},
+                    {"bubbleId": "b3", "type": 2},
+                ],
+            }),
+        );
+        put(
+            &conn,
+            "cursorDiskKV",
+            &format!("bubbleId:{COMPOSER}:b2"),
+            json!({"type": 2, "text": "Reading."}),
+        );
+        put(
+            &conn,
+            "cursorDiskKV",
+            &format!("bubbleId:{COMPOSER}:b1"),
+            json!({"type": 1, "text": "fix retries"}),
+        );
+        put(
+            &conn,
+            "cursorDiskKV",
+            &format!("composerData:{LEGACY}"),
+            json!({
+                "composerId": LEGACY,
+                "createdAt": 1_750_000_000_000_i64,
+                "conversation": [{"type": 1, "text": "hello"}],
+            }),
+        );
+        put(
+            &conn,
+            "cursorDiskKV",
+            &format!("composerData:{EMPTY}"),
+            json!({"composerId": EMPTY, "fullConversationHeadersOnly": []}),
+        );
+
+        let workspace = root.join("workspaceStorage/4b1e");
+        let conn = prepare_db(&workspace.join("state.vscdb"), "ItemTable");
+        put(
+            &conn,
+            "ItemTable",
+            "composer.composerData",
+            json!({"allComposers": [{"composerId": COMPOSER}]}),
+        );
+        fs::write(
+            workspace.join("workspace.json"),
+            r#"{"folder":"file:///tmp/my%20project"}"#,
+        )
+        .expect("write");
+    }
+
+    #[test]
+    fn resolves_composer_bubbles_in_conversation_order() {
+        let temp = tempdir().expect("tempdir");
+        prepare_root(temp.path());
+
+        let provider = CursorProvider::new(temp.path());
+        let resolved = provider.resolve(COMPOSER).expect("resolve");
+        assert_eq!(resolved.metadata.source, "cursor:state.vscdb");
+        assert_eq!(
+            resolved.metadata.warnings,
+            vec!["skipped bubble id=b3: not stored".to_string()]
+        );
+        let raw = fs::read_to_string(&resolved.path).expect("read materialized");
+        let lines = raw.lines().collect::<Vec<_>>();
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].contains(r#""cwd":"/tmp/my project""#));
+        assert!(lines[1].contains(r#""text":"fix retries""#));
+        assert!(lines[2].contains(r#""text":"Reading.""#));
+
+        let legacy = provider.resolve(LEGACY).expect("resolve");
+        let raw = fs::read_to_string(&legacy.path).expect("read materialized");
+        assert!(raw.lines().nth(1).expect("bubble").contains("hello"));
+
+        let err = provider
+            .resolve("00000000-0000-4000-8000-000000000000")
+            .expect_err("must fail");
+        assert!(format!("{err}").contains("thread not found"));
+    }
+
+    #[test]
+    fn lists_composers_with_messages_and_their_workspace_folder() {
+        let temp = tempdir().expect("tempdir");
+        prepare_root(temp.path());
+
+        let mut sessions = CursorProvider::new(temp.path())
+            .list_sessions()
+            .expect("list should succeed");
+        sessions.sort_by(|a, b| a.session_id.cmp(&b.session_id));
+        assert_eq!(sessions.len(), 2);
+        assert_eq!(sessions[0].session_id, LEGACY);
+        assert_eq!(sessions[0].cwd, None);
+        assert_eq!(sessions[0].updated_at, 1_750_000_000);
+        assert_eq!(sessions[1].session_id, COMPOSER);
+        assert_eq!(sessions[1].title.as_deref(), Some("Fix retries"));
+        assert_eq!(sessions[1].cwd.as_deref(), Some("/tmp/my project"));
+        assert_eq!(sessions[1].created_at, Some(1_760_601_600));
+        assert_eq!(sessions[1].updated_at, 1_760_601_900);
+
+        let missing = tempdir().expect("tempdir");
+        assert!(
+            CursorProvider::new(missing.path())
+                .list_sessions()
+                .expect("list should succeed")
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn decodes_file_uris() {
+        assert_eq!(
+            file_uri_path("file:///Users/me/My%20App").as_deref(),
+            Some("/Users/me/My App")
+        );
+        assert_eq!(file_uri_path("vscode-remote://ssh/repo"), None);
+    }
+}
@@ -7,17 +7,77 @@ use serde_json::Value;
 use walkdir::WalkDir;
 
 use crate::error::{Result, XurlError};
-use crate::model::{ProviderKind, ResolutionMeta, ResolvedThread};
-use crate::provider::Provider;
+use crate::model::{ProviderKind, ResolutionMeta, ResolvedThread, SessionSummary};
+use crate::provider::{Provider, modified_epoch};
+use crate::time::parse_rfc3339_epoch;
+
+/// A member of the Gemini CLI family. Forks keep Gemini CLI's
+/// `<root>/tmp/<project_hash>/chats/session-*.json` layout under their own
+/// home directory and name the model's messages after themselves.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GeminiFlavor {
+    pub kind: ProviderKind,
+    /// The data directory under the user's home, e.g. `.gemini`.
+    pub dir_name: &'static str,
+    /// Overrides the home directory that holds `dir_name`.
+    pub home_env: &'static str,
+    /// `messages[].type` values of the model's messages.
+    pub assistant_types: &'static [&'static str],
+}
+
+pub const GEMINI: GeminiFlavor = GeminiFlavor {
+    kind: ProviderKind::Gemini,
+    dir_name: ".gemini",
+    home_env: "GEMINI_CLI_HOME",
+    assistant_types: &["gemini"],
+};
+
+/// Qwen Code renamed the model's messages to `qwen`; chats recorded before
+/// the rename still use `gemini`.
+pub const QWEN: GeminiFlavor = GeminiFlavor {
+    kind: ProviderKind::Qwen,
+    dir_name: ".qwen",
+    home_env: "QWEN_CODE_HOME",
+    assistant_types: &["qwen", "gemini"],
+};
+
+pub const FAMILY: [GeminiFlavor; 2] = [GEMINI, QWEN];
+
+impl GeminiFlavor {
+    /// The flavor behind `provider`, if it is part of the family.
+    pub fn of(provider: ProviderKind) -> Option<&'static Self> {
+        FAMILY.iter().find(|flavor| flavor.kind == provider)
+    }
+
+    /// `<home_env>/<dir_name>`, falling back to `~/<dir_name>`.
+    pub fn root(&self, home: &Path) -> PathBuf {
+        std::env::var_os(self.home_env)
+            .filter(|path| !path.is_empty())
+            .map_or_else(|| home.to_path_buf(), PathBuf::from)
+            .join(self.dir_name)
+    }
+}
 
 #[derive(Debug, Clone)]
 pub struct GeminiProvider {
+    flavor: &'static GeminiFlavor,
     root: PathBuf,
 }
 
 impl GeminiProvider {
     pub fn new(root: impl Into<PathBuf>) -> Self {
-        Self { root: root.into() }
+        Self::with_flavor(&GEMINI, root)
+    }
+
+    pub fn qwen(root: impl Into<PathBuf>) -> Self {
+        Self::with_flavor(&QWEN, root)
+    }
+
+    pub fn with_flavor(flavor: &'static GeminiFlavor, root: impl Into<PathBuf>) -> Self {
+        Self {
+            flavor,
+            root: root.into(),
+        }
     }
 
     fn tmp_root(&self) -> PathBuf {
@@ -69,6 +129,36 @@ impl GeminiProvider {
             .collect()
     }
 
+    fn summarize(&self, path: &Path) -> Option<SessionSummary> {
+        let raw = fs::read_to_string(path).ok()?;
+        let value = serde_json::from_str::<Value>(&raw).ok()?;
+        let session_id = value.get("sessionId").and_then(Value::as_str)?;
+
+        // `lastUpdated` tracks the conversation itself, which is more accurate
+        // than the file mtime after copies or restores.
+        let updated_at = value
+            .get("lastUpdated")
+            .and_then(Value::as_str)
+            .and_then(parse_rfc3339_epoch)
+            .unwrap_or_else(|| modified_epoch(path));
+
+        Some(SessionSummary {
+            provider: self.flavor.kind,
+            session_id: session_id.to_ascii_lowercase(),
+            path: path.to_path_buf(),
+            created_at: value
+                .get("startTime")
+                .and_then(Value::as_str)
+                .and_then(parse_rfc3339_epoch),
+            updated_at,
+            cwd: None,
+            title: None,
+            archived: false,
+            parent_session_id: None,
+            message_count: None,
+        })
+    }
+
     fn choose_latest(paths: Vec<PathBuf>) -> Option<(PathBuf, usize)> {
         if paths.is_empty() {
             return None;
@@ -97,7 +187,7 @@ impl Provider for GeminiProvider {
 
         if let Some((selected, count)) = Self::choose_latest(candidates) {
             let mut metadata = ResolutionMeta {
-                source: "gemini:chats".to_string(),
+                source: format!("{}:chats", self.flavor.kind),
                 candidate_count: count,
                 warnings: Vec::new(),
             };
@@ -110,7 +200,7 @@ impl Provider for GeminiProvider {
             }
 
             return Ok(ResolvedThread {
-                provider: ProviderKind::Gemini,
+                provider: self.flavor.kind,
                 session_id: session_id.to_string(),
                 path: selected,
                 metadata,
@@ -118,11 +208,27 @@ impl Provider for GeminiProvider {
         }
 
         Err(XurlError::ThreadNotFound {
-            provider: ProviderKind::Gemini.to_string(),
+            provider: self.flavor.kind.to_string(),
             session_id: session_id.to_string(),
             searched_roots: vec![tmp_root],
         })
     }
+
+    fn list_sessions(&self) -> Result<Vec<SessionSummary>> {
+        let tmp_root = self.tmp_root();
+        if !tmp_root.exists() {
+            return Ok(Vec::new());
+        }
+
+        Ok(WalkDir::new(&tmp_root)
+            .into_iter()
+            .filter_map(std::result::Result::ok)
+            .filter(|entry| entry.file_type().is_file())
+            .map(|entry| entry.into_path())
+            .filter(|path| Self::is_session_file(path))
+            .filter_map(|path| self.summarize(&path))
+            .collect())
+    }
 }
 
 #[cfg(test)]
@@ -133,8 +239,9 @@ mod tests {
 
     use tempfile::tempdir;
 
+    use crate::model::ProviderKind;
     use crate::provider::Provider;
-    use crate::provider::gemini::GeminiProvider;
+    use crate::provider::gemini::{GeminiFlavor, GeminiProvider, QWEN};
 
     fn write_session(
         root: &Path,
@@ -222,6 +329,52 @@ mod tests {
         assert!(first.exists());
     }
 
+    #[test]
+    fn lists_sessions_with_last_updated_time() {
+        let temp = tempdir().expect("tempdir");
+        let session_id = "29d207db-ca7e-40ba-87f7-e14c9de60613";
+        write_session(
+            temp.path(),
+            "hash-a",
+            "session-2026-01-08T11-55-29d207db.json",
+            session_id,
+            "hello",
+        );
+
+        let sessions = GeminiProvider::new(temp.path())
+            .list_sessions()
+            .expect("list should succeed");
+        assert_eq!(sessions.len(), 1);
+        assert_eq!(sessions[0].session_id, session_id);
+        assert_eq!(sessions[0].created_at, Some(1_767_873_312));
+        assert_eq!(sessions[0].updated_at, 1_767_875_474);
+    }
+
+    #[test]
+    fn qwen_flavor_reports_its_own_provider() {
+        let temp = tempdir().expect("tempdir");
+        let session_id = "29d207db-ca7e-40ba-87f7-e14c9de60613";
+        write_session(
+            temp.path(),
+            "hash-a",
+            "session-2026-01-08T11-55-29d207db.json",
+            session_id,
+            "hello",
+        );
+
+        let provider = GeminiProvider::qwen(temp.path());
+        let resolved = provider
+            .resolve(session_id)
+            .expect("resolve should succeed");
+        assert_eq!(resolved.provider, ProviderKind::Qwen);
+        assert_eq!(resolved.metadata.source, "qwen:chats");
+
+        let sessions = provider.list_sessions().expect("list should succeed");
+        assert_eq!(sessions[0].provider, ProviderKind::Qwen);
+        assert_eq!(GeminiFlavor::of(ProviderKind::Qwen), Some(&QWEN));
+        assert_eq!(GeminiFlavor::of(ProviderKind::Claude), None);
+    }
+
     #[test]
     fn missing_thread_returns_not_found() {
         let temp = tempdir().expect("tempdir");
@@ -0,0 +1,486 @@
+use std::collections::HashSet;
+use std::fs;
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+
+use rusqlite::types::ValueRef;
+use rusqlite::{Connection, OpenFlags, Row, params};
+use serde_json::{Value, json};
+
+use crate::error::{Result, XurlError};
+use crate::model::{ProviderKind, ResolutionMeta, ResolvedThread, SessionSummary};
+use crate::provider::{Provider, modified_epoch};
+use crate::time::{normalize_epoch, parse_rfc3339_epoch};
+use crate::uri::is_session_id;
+
+/// Resolution source of threads materialized from the session database.
+pub(crate) const SQLITE_SOURCE: &str = "goose:sqlite";
+
+/// Goose sessions under its data directory. Current releases keep them in
+/// `sessions/sessions.db`; older ones wrote `sessions/<session_id>.jsonl`, a
+/// metadata line followed by one message per line. Threads from the
+/// database are materialized in that same JSONL shape.
+#[derive(Debug, Clone)]
+pub struct GooseProvider {
+    root: PathBuf,
+}
+
+impl GooseProvider {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    fn sessions_root(&self) -> PathBuf {
+        self.root.join("sessions")
+    }
+
+    fn db_path(&self) -> PathBuf {
+        self.sessions_root().join("sessions.db")
+    }
+
+    fn materialized_path(session_id: &str) -> PathBuf {
+        std::env::temp_dir()
+            .join("xurl-goose")
+            .join(format!("{session_id}.jsonl"))
+    }
+
+    fn open_db(&self) -> Result<Option<Connection>> {
+        let db_path = self.db_path();
+        if !db_path.exists() {
+            return Ok(None);
+        }
+        Connection::open_with_flags(&db_path, OpenFlags::SQLITE_OPEN_READ_ONLY)
+            .map(Some)
+            .map_err(|source| XurlError::Sqlite {
+                path: db_path,
+                source,
+            })
+    }
+
+    fn table_columns(
+        conn: &Connection,
+        table: &str,
+    ) -> std::result::Result<HashSet<String>, rusqlite::Error> {
+        let mut stmt = conn.prepare(&format!("PRAGMA table_info({table})"))?;
+        let rows = stmt.query_map([], |row| row.get::<_, String>(1))?;
+        rows.collect()
+    }
+
+    fn session_header(
+        conn: &Connection,
+        session_id: &str,
+    ) -> std::result::Result<Option<Value>, rusqlite::Error> {
+        let mut stmt =
+            conn.prepare("SELECT working_dir, description FROM sessions WHERE id = ?1")?;
+        let mut rows = stmt.query([session_id])?;
+        let Some(row) = rows.next()? else {
+            return Ok(None);
+        };
+        Ok(Some(json!({
+            "working_dir": row.get::<_, Option<String>>(0)?,
+            "description": row.get::<_, Option<String>>(1)?,
+        })))
+    }
+
+    fn fetch_summaries(
+        conn: &Connection,
+        db_path: &Path,
+    ) -> std::result::Result<Vec<SessionSummary>, rusqlite::Error> {
+        // `name` replaced `description` as the session title in later schema
+        // versions; either may be missing or empty.
+        let columns = Self::table_columns(conn, "sessions")?;
+        let name = if columns.contains("name") {
+            "name"
+        } else {
+            "NULL"
+        };
+        let sql = format!(
+            "SELECT id, {name}, description, working_dir, created_at, updated_at FROM sessions"
+        );
+        let mut stmt = conn.prepare(&sql)?;
+        let rows = stmt.query_map([], |row| {
+            let title = [row.get::<_, Option<String>>(1)?, row.get(2)?]
+                .into_iter()
+                .flatten()
+                .map(|title| title.trim().to_string())
+                .find(|title| !title.is_empty());
+            Ok(SessionSummary {
+                provider: ProviderKind::Goose,
+                session_id: row.get::<_, String>(0)?,
+                path: db_path.to_path_buf(),
+                created_at: timestamp_column(row, 4),
+                updated_at: timestamp_column(row, 5).unwrap_or_default(),
+                cwd: row.get::<_, Option<String>>(3)?,
+                title,
+                archived: false,
+                parent_session_id: None,
+                message_count: None,
+            })
+        })?;
+        rows.collect()
+    }
+
+    /// Messages with a row id above `after`, as JSONL message lines, plus
+    /// the id of the last one.
+    fn fetch_messages(
+        conn: &Connection,
+        session_id: &str,
+        after: i64,
+        warnings: &mut Vec<String>,
+    ) -> std::result::Result<(Vec<Value>, Option<i64>), rusqlite::Error> {
+        let metadata = if Self::table_columns(conn, "messages")?.contains("metadata_json") {
+            "metadata_json"
+        } else {
+            "NULL"
+        };
+        let mut stmt = conn.prepare(&format!(
+            "SELECT id, role, content_json, created_timestamp, {metadata}
+             FROM messages
+             WHERE session_id = ?1 AND id > ?2
+             ORDER BY id ASC"
+        ))?;
+        let rows = stmt.query_map(params![session_id, after], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, Option<i64>>(3)?,
+                row.get::<_, Option<String>>(4)?,
+            ))
+        })?;
+
+        let mut messages = Vec::new();
+        let mut last_id = None;
+        for row in rows {
+            let (id, role, content, created, metadata) = row?;
+            last_id = Some(id);
+            let content = match serde_json::from_str::<Value>(&content) {
+                Ok(content) => content,
+                Err(err) => {
+                    warnings.push(format!(
+                        "skipped message id={id}: invalid json payload ({err})"
+                    ));
+                    continue;
+                }
+            };
+            let mut message = json!({
+                "id": id,
+                "role": role,
+                "created": created,
+                "content": content,
+            });
+            if let Some(metadata) =
+                metadata.and_then(|metadata| serde_json::from_str::<Value>(&metadata).ok())
+            {
+                message["metadata"] = metadata;
+            }
+            messages.push(message);
+        }
+        Ok((messages, last_id))
+    }
+
+    /// Materialize messages after the `after` row id as JSONL for follow
+    /// mode, returning the cursor to poll from next.
+    pub(crate) fn read_messages_after(
+        &self,
+        session_id: &str,
+        after: Option<i64>,
+    ) -> Result<(String, Option<i64>)> {
+        let db_path = self.db_path();
+        let sql = |source| XurlError::Sqlite {
+            path: db_path.clone(),
+            source,
+        };
+        let Some(conn) = self.open_db()? else {
+            return Ok((String::new(), after));
+        };
+
+        let mut warnings = Vec::new();
+        let (messages, last_id) =
+            Self::fetch_messages(&conn, session_id, after.unwrap_or(i64::MIN), &mut warnings)
+                .map_err(sql)?;
+        Ok((render_jsonl(None, messages), last_id.or(after)))
+    }
+
+    fn resolve_from_db(
+        &self,
+        conn: &Connection,
+        session_id: &str,
+    ) -> Result<Option<ResolvedThread>> {
+        let db_path = self.db_path();
+        let sql = |source| XurlError::Sqlite {
+            path: db_path.clone(),
+            source,
+        };
+        let Some(header) = Self::session_header(conn, session_id).map_err(sql)? else {
+            return Ok(None);
+        };
+
+        let mut warnings = Vec::new();
+        let (messages, _) =
+            Self::fetch_messages(conn, session_id, i64::MIN, &mut warnings).map_err(sql)?;
+        let path = Self::materialized_path(session_id);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|source| XurlError::Io {
+                path: parent.to_path_buf(),
+                source,
+            })?;
+        }
+        fs::write(&path, render_jsonl(Some(header), messages)).map_err(|source| XurlError::Io {
+            path: path.clone(),
+            source,
+        })?;
+
+        Ok(Some(ResolvedThread {
+            provider: ProviderKind::Goose,
+            session_id: session_id.to_string(),
+            path,
+            metadata: ResolutionMeta {
+                source: SQLITE_SOURCE.to_string(),
+                candidate_count: 1,
+                warnings,
+            },
+        }))
+    }
+
+    fn summarize_jsonl(path: &Path, session_id: &str) -> SessionSummary {
+        let mut lines = fs::File::open(path)
+            .map(BufReader::new)
+            .into_iter()
+            .flat_map(BufRead::lines)
+            .map_while(std::result::Result::ok)
+            .filter_map(|line| serde_json::from_str::<Value>(&line).ok());
+        let header = lines.next().unwrap_or(Value::Null);
+        let first = lines.next().unwrap_or(Value::Null);
+
+        SessionSummary {
+            provider: ProviderKind::Goose,
+            session_id: session_id.to_string(),
+            path: path.to_path_buf(),
+            created_at: first
+                .get("created")
+                .and_then(Value::as_i64)
+                .and_then(normalize_epoch),
+            updated_at: modified_epoch(path),
+            cwd: header
+                .get("working_dir")
+                .and_then(Value::as_str)
+                .map(ToString::to_string),
+            title: header
+                .get("description")
+                .and_then(Value::as_str)
+                .map(str::trim)
+                .filter(|description| !description.is_empty())
+                .map(ToString::to_string),
+            archived: false,
+            parent_session_id: None,
+            message_count: None,
+        }
+    }
+}
+
+/// Session timestamps are SQLite `CURRENT_TIMESTAMP` text in most rows, but
+/// epoch numbers are accepted too.
+fn timestamp_column(row: &Row<'_>, index: usize) -> Option<u64> {
+    match row.get_ref(index).ok()? {
+        ValueRef::Integer(epoch) => normalize_epoch(epoch),
+        ValueRef::Text(text) => parse_rfc3339_epoch(std::str::from_utf8(text).ok()?),
+        _ => None,
+    }
+}
+
+fn render_jsonl(header: Option<Value>, messages: Vec<Value>) -> String {
+    let mut output = String::new();
+    for line in header.into_iter().chain(messages) {
+        output.push_str(&line.to_string());
+        output.push('\n');
+    }
+    output
+}
+
+impl Provider for GooseProvider {
+    fn resolve(&self, session_id: &str) -> Result<ResolvedThread> {
+        if let Some(conn) = self.open_db()?
+            && let Some(resolved) = self.resolve_from_db(&conn, session_id)?
+        {
+            return Ok(resolved);
+        }
+
+        let path = self.sessions_root().join(format!("{session_id}.jsonl"));
+        if !path.is_file() {
+            return Err(XurlError::ThreadNotFound {
+                provider: ProviderKind::Goose.to_string(),
+                session_id: session_id.to_string(),
+                searched_roots: vec![self.sessions_root()],
+            });
+        }
+
+        Ok(ResolvedThread {
+            provider: ProviderKind::Goose,
+            session_id: session_id.to_string(),
+            path,
+            metadata: ResolutionMeta {
+                source: "goose:jsonl".to_string(),
+                candidate_count: 1,
+                warnings: Vec::new(),
+            },
+        })
+    }
+
+    fn list_sessions(&self) -> Result<Vec<SessionSummary>> {
+        let mut sessions = match self.open_db()? {
+            Some(conn) => {
+                let db_path = self.db_path();
+                Self::fetch_summaries(&conn, &db_path).map_err(|source| XurlError::Sqlite {
+                    path: db_path.clone(),
+                    source,
+                })?
+            }
+            None => Vec::new(),
+        };
+
+        // Goose imports legacy files into the database on upgrade; the
+        // database copy wins.
+        let imported = sessions
+            .iter()
+            .map(|session| session.session_id.clone())
+            .collect::<HashSet<_>>();
+        let Ok(entries) = fs::read_dir(self.sessions_root()) else {
+            return Ok(sessions);
+        };
+        for entry in entries.filter_map(std::result::Result::ok) {
+            let path = entry.path();
+            if path.extension().is_none_or(|ext| ext != "jsonl") {
+                continue;
+            }
+            let Some(session_id) = path.file_stem().and_then(|stem| stem.to_str()) else {
+                continue;
+            };
+            if is_session_id(ProviderKind::Goose, session_id) && !imported.contains(session_id) {
+                sessions.push(Self::summarize_jsonl(&path, session_id));
+            }
+        }
+        Ok(sessions)
+    }
+
+    fn has_session(&self, session_id: &str) -> Result<bool> {
+        if let Some(conn) = self.open_db()? {
+            let header =
+                Self::session_header(&conn, session_id).map_err(|source| XurlError::Sqlite {
+                    path: self.db_path(),
+                    source,
+                })?;
+            if header.is_some() {
+                return Ok(true);
+            }
+        }
+        Ok(self
+            .sessions_root()
+            .join(format!("{session_id}.jsonl"))
+            .is_file())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::path::Path;
+
+    use rusqlite::Connection;
+    use tempfile::tempdir;
+
+    use crate::provider::Provider;
+    use crate::provider::goose::GooseProvider;
+
+    fn prepare_db(path: &Path) -> Connection {
+        fs::create_dir_all(path.parent().expect("parent")).expect("mkdir");
+        let conn = Connection::open(path).expect("open sqlite");
+        conn.execute_batch(
+            "
+            CREATE TABLE sessions (
+                id TEXT PRIMARY KEY,
+                description TEXT NOT NULL DEFAULT '',
+                working_dir TEXT NOT NULL,
+                created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+                updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+            );
+            CREATE TABLE messages (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                session_id TEXT NOT NULL REFERENCES sessions(id),
+                role TEXT NOT NULL,
+                content_json TEXT NOT NULL,
+                created_timestamp INTEGER NOT NULL
+            );
+            INSERT INTO sessions VALUES
+                ('20251016_1', 'List files', '/tmp/project', '2025-10-16 08:00:00', '2025-10-16 08:05:00');
+            INSERT INTO messages (session_id, role, content_json, created_timestamp) VALUES
+                ('20251016_1', 'user', '[{\"type\":\"text\",\"text\":\"list files\"}]', 1760601600),
+                ('20251016_1', 'assistant', '[{\"type\":\"text\",\"text\":\"Listing.\"}]', 1760601601);
+            ",
+        )
+        .expect("create schema");
+        conn
+    }
+
+    #[test]
+    fn resolves_database_sessions_before_legacy_files() {
+        let temp = tempdir().expect("tempdir");
+        prepare_db(&temp.path().join("sessions/sessions.db"));
+        fs::write(
+            temp.path().join("sessions/20251016_1.jsonl"),
+            "{\"working_dir\":\"/old\"}\n",
+        )
+        .expect("write");
+        fs::write(
+            temp.path().join("sessions/20250325_143022.jsonl"),
+            "{\"working_dir\":\"/tmp/legacy\",\"description\":\"Old run\"}\n{\"role\":\"user\",\"created\":1742913022,\"content\":[]}\n",
+        )
+        .expect("write");
+
+        let provider = GooseProvider::new(temp.path());
+        let resolved = provider.resolve("20251016_1").expect("resolve");
+        assert_eq!(resolved.metadata.source, "goose:sqlite");
+        let raw = fs::read_to_string(&resolved.path).expect("read materialized");
+        let lines = raw.lines().collect::<Vec<_>>();
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].contains(r#""working_dir":"/tmp/project""#));
+        assert!(lines[1].contains(r#""text":"list files""#));
+
+        let legacy = provider.resolve("20250325_143022").expect("resolve");
+        assert_eq!(legacy.metadata.source, "goose:jsonl");
+
+        let (raw, cursor) = provider
+            .read_messages_after("20251016_1", Some(1))
+            .expect("read after");
+        assert_eq!(raw.lines().count(), 1);
+        assert_eq!(cursor, Some(2));
+
+        let err = provider.resolve("20251016_9").expect_err("must fail");
+        assert!(format!("{err}").contains("thread not found"));
+    }
+
+    #[test]
+    fn lists_database_and_legacy_sessions_once() {
+        let temp = tempdir().expect("tempdir");
+        prepare_db(&temp.path().join("sessions/sessions.db"));
+        fs::write(temp.path().join("sessions/20251016_1.jsonl"), "{}\n").expect("write");
+        fs::write(
+            temp.path().join("sessions/20250325_143022.jsonl"),
+            "{\"working_dir\":\"/tmp/legacy\",\"description\":\"Old run\"}\n{\"role\":\"user\",\"created\":1742913022,\"content\":[]}\n",
+        )
+        .expect("write");
+
+        let mut sessions = GooseProvider::new(temp.path())
+            .list_sessions()
+            .expect("list should succeed");
+        sessions.sort_by(|a, b| a.session_id.cmp(&b.session_id));
+        assert_eq!(sessions.len(), 2);
+        assert_eq!(sessions[0].session_id, "20250325_143022");
+        assert_eq!(sessions[0].cwd.as_deref(), Some("/tmp/legacy"));
+        assert_eq!(sessions[0].title.as_deref(), Some("Old run"));
+        assert_eq!(sessions[0].created_at, Some(1_742_913_022));
+        assert_eq!(sessions[1].title.as_deref(), Some("List files"));
+        assert_eq!(sessions[1].created_at, Some(1_760_601_600));
+        assert_eq!(sessions[1].updated_at, 1_760_601_900);
+    }
+}
@@ -4,22 +4,51 @@ use std::path::{Path, PathBuf};
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 use dirs::home_dir;
-use walkdir::WalkDir;
 
 use crate::error::{Result, XurlError};
-use crate::model::{ActiveSession, ProviderKind, ResolvedThread};
+use crate::model::{ActiveSession, ProviderKind, ResolvedThread, SessionSummary};
 
+pub mod aider;
 pub mod amp;
 pub mod claude;
+pub mod cline;
 #[cfg(feature = "sqlite")]
 pub mod codex;
+#[cfg(feature = "sqlite")]
+pub mod cursor;
 pub mod gemini;
 #[cfg(feature = "sqlite")]
+pub mod goose;
+#[cfg(feature = "sqlite")]
 pub mod opencode;
 pub mod pi;
 
 pub trait Provider {
     fn resolve(&self, session_id: &str) -> Result<ResolvedThread>;
+
+    /// Enumerate every thread in the provider's local store.
+    ///
+    /// Missing roots yield an empty list rather than an error.
+    fn list_sessions(&self) -> Result<Vec<SessionSummary>>;
+
+    /// Whether the store has the thread. Stores that materialize a thread
+    /// to resolve it look it up instead.
+    fn has_session(&self, session_id: &str) -> Result<bool> {
+        match self.resolve(session_id) {
+            Ok(_) => Ok(true),
+            Err(XurlError::ThreadNotFound { .. }) => Ok(false),
+            Err(err) => Err(err),
+        }
+    }
+}
+
+pub(crate) fn modified_epoch(path: &Path) -> u64 {
+    std::fs::metadata(path)
+        .and_then(|meta| meta.modified())
+        .ok()
+        .and_then(|modified| modified.duration_since(UNIX_EPOCH).ok())
+        .map(|duration| duration.as_secs())
+        .unwrap_or(0)
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -28,8 +57,16 @@ pub struct ProviderRoots {
     pub codex_root: PathBuf,
     pub claude_root: PathBuf,
     pub gemini_root: PathBuf,
+    pub qwen_root: PathBuf,
     pub pi_root: PathBuf,
     pub opencode_root: PathBuf,
+    pub cline_root: PathBuf,
+    pub roo_root: PathBuf,
+    /// Aider keeps a history file in each repository instead of a central
+    /// store, so it is searched for under these project roots.
+    pub aider_roots: Vec<PathBuf>,
+    pub goose_root: PathBuf,
+    pub cursor_root: PathBuf,
 }
 
 impl ProviderRoots {
@@ -62,10 +99,12 @@ impl ProviderRoots {
         // Precedence:
         // 1) GEMINI_CLI_HOME/.gemini (official Gemini CLI home env)
         // 2) ~/.gemini (Gemini default)
-        let gemini_root = env::var_os("GEMINI_CLI_HOME")
-            .map(PathBuf::from)
-            .map(|path| path.join(".gemini"))
-            .unwrap_or_else(|| home.join(".gemini"));
+        let gemini_root = gemini::GEMINI.root(&home);
+
+        // Precedence:
+        // 1) QWEN_CODE_HOME/.qwen
+        // 2) ~/.qwen (Qwen Code default)
+        let qwen_root = gemini::QWEN.root(&home);
 
         // Precedence:
         // 1) PI_CODING_AGENT_DIR (official pi coding agent root env)
@@ -84,97 +123,234 @@ impl ProviderRoots {
             .map(|path| path.join("opencode"))
             .unwrap_or_else(|| home.join(".local/share/opencode"));
 
+        // Precedence:
+        // 1) VSCODE_APPDATA (VS Code app data override, honored by its forks)
+        // 2) <platform config dir> (VS Code default)
+        let app_data = env::var_os("VSCODE_APPDATA")
+            .filter(|path| !path.is_empty())
+            .map(PathBuf::from)
+            .or_else(dirs::config_dir)
+            .unwrap_or_else(|| home.join(".config"));
+        let global_storage = app_data.join("Code/User/globalStorage");
+        let cline_root = global_storage.join("saoudrizwan.claude-dev");
+        let roo_root = global_storage.join("rooveterinaryinc.roo-cline");
+
+        // Precedence:
+        // 1) XURL_AIDER_ROOTS (path list, like PATH)
+        // 2) the git repository containing the current directory
+        let aider_roots = env::var_os("XURL_AIDER_ROOTS")
+            .filter(|paths| !paths.is_empty())
+            .map(|paths| env::split_paths(&paths).collect())
+            .unwrap_or_else(|| {
+                env::current_dir()
+                    .ok()
+                    .and_then(|cwd| {
+                        cwd.ancestors()
+                            .find(|dir| dir.join(".git").exists())
+                            .map(Path::to_path_buf)
+                    })
+                    .into_iter()
+                    .collect()
+            });
+
+        // Precedence:
+        // 1) GOOSE_PATH_ROOT/data (Goose's override for all of its paths)
+        // 2) XDG_DATA_HOME/goose
+        // 3) ~/.local/share/goose
+        let goose_root = env::var_os("GOOSE_PATH_ROOT")
+            .filter(|path| !path.is_empty())
+            .map(|path| PathBuf::from(path).join("data"))
+            .or_else(|| {
+                env::var_os("XDG_DATA_HOME")
+                    .filter(|path| !path.is_empty())
+                    .map(|path| PathBuf::from(path).join("goose"))
+            })
+            .unwrap_or_else(|| home.join(".local/share/goose"));
+
+        // Cursor is a VS Code fork and keeps its state in the same app data
+        // directory, under `Cursor/User`.
+        let cursor_root = app_data.join("Cursor/User");
+
         Ok(Self {
             amp_root,
             codex_root,
             claude_root,
             gemini_root,
+            qwen_root,
             pi_root,
             opencode_root,
+            cline_root,
+            roo_root,
+            aider_roots,
+            goose_root,
+            cursor_root,
         })
     }
 
-    /// Scan all provider root directories, returning sessions modified within `max_age`.
-    pub fn list_active_sessions(&self, max_age: Duration) -> Vec<ActiveSession> {
-        let providers: &[(ProviderKind, &Path, &str)] = &[
-            (ProviderKind::Claude, &self.claude_root, "projects"),
-            (ProviderKind::Codex, &self.codex_root, "sessions"),
-            (ProviderKind::Amp, &self.amp_root, "threads"),
-            (ProviderKind::Gemini, &self.gemini_root, "tmp"),
-            (ProviderKind::Pi, &self.pi_root, "sessions"),
-            (ProviderKind::Opencode, &self.opencode_root, "sessions"),
-        ];
-
-        let now = SystemTime::now();
-        let mut sessions = Vec::new();
-
-        for &(provider, root, subdir) in providers {
-            let scan_root = root.join(subdir);
-            if !scan_root.exists() {
-                continue;
+    /// The configured roots of a provider's local store; only Aider has
+    /// more than one.
+    pub fn roots(&self, provider: ProviderKind) -> &[PathBuf] {
+        let root = match provider {
+            ProviderKind::Amp => &self.amp_root,
+            ProviderKind::Codex => &self.codex_root,
+            ProviderKind::Claude => &self.claude_root,
+            ProviderKind::Gemini => &self.gemini_root,
+            ProviderKind::Qwen => &self.qwen_root,
+            ProviderKind::Pi => &self.pi_root,
+            ProviderKind::Opencode => &self.opencode_root,
+            ProviderKind::Cline => &self.cline_root,
+            ProviderKind::Roo => &self.roo_root,
+            ProviderKind::Aider => return &self.aider_roots,
+            ProviderKind::Goose => &self.goose_root,
+            ProviderKind::Cursor => &self.cursor_root,
+        };
+        std::slice::from_ref(root)
+    }
+
+    /// Enumerate the threads of a single provider from its configured root.
+    pub fn list_sessions(&self, provider: ProviderKind) -> Result<Vec<SessionSummary>> {
+        match provider {
+            ProviderKind::Amp => amp::AmpProvider::new(&self.amp_root).list_sessions(),
+            #[cfg(feature = "sqlite")]
+            ProviderKind::Codex => codex::CodexProvider::new(&self.codex_root).list_sessions(),
+            #[cfg(not(feature = "sqlite"))]
+            ProviderKind::Codex => Err(XurlError::InvalidMode(format!(
+                "provider {provider} requires the 'sqlite' feature"
+            ))),
+            ProviderKind::Claude => claude::ClaudeProvider::new(&self.claude_root).list_sessions(),
+            ProviderKind::Gemini => gemini::GeminiProvider::new(&self.gemini_root).list_sessions(),
+            ProviderKind::Qwen => gemini::GeminiProvider::qwen(&self.qwen_root).list_sessions(),
+            ProviderKind::Pi => pi::PiProvider::new(&self.pi_root).list_sessions(),
+            #[cfg(feature = "sqlite")]
+            ProviderKind::Opencode => {
+                opencode::OpencodeProvider::new(&self.opencode_root).list_sessions()
+            }
+            #[cfg(not(feature = "sqlite"))]
+            ProviderKind::Opencode => Err(XurlError::InvalidMode(format!(
+                "provider {provider} requires the 'sqlite' feature"
+            ))),
+            ProviderKind::Cline => cline::ClineProvider::new(&self.cline_root).list_sessions(),
+            ProviderKind::Roo => cline::ClineProvider::roo(&self.roo_root).list_sessions(),
+            ProviderKind::Aider => {
+                aider::AiderProvider::new(self.aider_roots.clone()).list_sessions()
             }
+            #[cfg(feature = "sqlite")]
+            ProviderKind::Goose => goose::GooseProvider::new(&self.goose_root).list_sessions(),
+            #[cfg(not(feature = "sqlite"))]
+            ProviderKind::Goose => Err(XurlError::InvalidMode(format!(
+                "provider {provider} requires the 'sqlite' feature"
+            ))),
+            #[cfg(feature = "sqlite")]
+            ProviderKind::Cursor => cursor::CursorProvider::new(&self.cursor_root).list_sessions(),
+            #[cfg(not(feature = "sqlite"))]
+            ProviderKind::Cursor => Err(XurlError::InvalidMode(format!(
+                "provider {provider} requires the 'sqlite' feature"
+            ))),
+        }
+    }
 
-            let walker = WalkDir::new(&scan_root)
-                .max_depth(4)
-                .into_iter()
-                .filter_map(|e| e.ok())
-                .filter(|e| e.file_type().is_file());
+    /// Whether a single provider's store has the thread.
+    pub fn has_session(&self, provider: ProviderKind, session_id: &str) -> Result<bool> {
+        match provider {
+            ProviderKind::Amp => amp::AmpProvider::new(&self.amp_root).has_session(session_id),
+            #[cfg(feature = "sqlite")]
+            ProviderKind::Codex => {
+                codex::CodexProvider::new(&self.codex_root).has_session(session_id)
+            }
+            #[cfg(not(feature = "sqlite"))]
+            ProviderKind::Codex => Err(XurlError::InvalidMode(format!(
+                "provider {provider} requires the 'sqlite' feature"
+            ))),
+            ProviderKind::Claude => {
+                claude::ClaudeProvider::new(&self.claude_root).has_session(session_id)
+            }
+            ProviderKind::Gemini => {
+                gemini::GeminiProvider::new(&self.gemini_root).has_session(session_id)
+            }
+            ProviderKind::Qwen => {
+                gemini::GeminiProvider::qwen(&self.qwen_root).has_session(session_id)
+            }
+            ProviderKind::Pi => pi::PiProvider::new(&self.pi_root).has_session(session_id),
+            #[cfg(feature = "sqlite")]
+            ProviderKind::Opencode => {
+                opencode::OpencodeProvider::new(&self.opencode_root).has_session(session_id)
+            }
+            #[cfg(not(feature = "sqlite"))]
+            ProviderKind::Opencode => Err(XurlError::InvalidMode(format!(
+                "provider {provider} requires the 'sqlite' feature"
+            ))),
+            ProviderKind::Cline => {
+                cline::ClineProvider::new(&self.cline_root).has_session(session_id)
+            }
+            ProviderKind::Roo => cline::ClineProvider::roo(&self.roo_root).has_session(session_id),
+            ProviderKind::Aider => {
+                aider::AiderProvider::new(self.aider_roots.clone()).has_session(session_id)
+            }
+            #[cfg(feature = "sqlite")]
+            ProviderKind::Goose => {
+                goose::GooseProvider::new(&self.goose_root).has_session(session_id)
+            }
+            #[cfg(not(feature = "sqlite"))]
+            ProviderKind::Goose => Err(XurlError::InvalidMode(format!(
+                "provider {provider} requires the 'sqlite' feature"
+            ))),
+            #[cfg(feature = "sqlite")]
+            ProviderKind::Cursor => {
+                cursor::CursorProvider::new(&self.cursor_root).has_session(session_id)
+            }
+            #[cfg(not(feature = "sqlite"))]
+            ProviderKind::Cursor => Err(XurlError::InvalidMode(format!(
+                "provider {provider} requires the 'sqlite' feature"
+            ))),
+        }
+    }
 
-            for entry in walker {
-                let path = entry.path();
-                let ext = path.extension().and_then(|e| e.to_str()).unwrap_or("");
-                if ext != "jsonl" && ext != "json" {
-                    continue;
-                }
+    /// List unarchived sessions across all providers that were updated within `max_age`.
+    pub fn list_active_sessions(&self, max_age: Duration) -> Vec<ActiveSession> {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|now| now.as_secs())
+            .unwrap_or(0);
 
-                let mtime = entry.metadata().ok().and_then(|m| m.modified().ok());
-                let age = mtime.and_then(|mt| now.duration_since(mt).ok());
-                if age.is_some_and(|d| d > max_age) {
+        let mut deduped: Vec<ActiveSession> = Vec::new();
+        let mut best: HashMap<(ProviderKind, String), usize> = HashMap::new();
+
+        for provider in ProviderKind::ALL
+            .into_iter()
+            .filter(|provider| provider.listed_by_default())
+        {
+            let Ok(summaries) = self.list_sessions(provider) else {
+                continue;
+            };
+
+            for summary in summaries {
+                if summary.archived {
                     continue;
                 }
 
-                let file_len = entry.metadata().map(|m| m.len()).unwrap_or(0);
-                if file_len < 10 {
+                let age_secs = now.saturating_sub(summary.updated_at);
+                if age_secs > max_age.as_secs() {
                     continue;
                 }
 
-                let session_id = path
-                    .file_stem()
-                    .and_then(|s| s.to_str())
-                    .unwrap_or("unknown")
-                    .to_string();
-
-                let mtime_epoch = mtime
-                    .and_then(|mt| mt.duration_since(UNIX_EPOCH).ok())
-                    .map(|d| d.as_secs())
-                    .unwrap_or(0);
-
-                let age_secs = age.map(|d| d.as_secs()).unwrap_or(u64::MAX);
-                let is_active = age_secs < 60;
-
-                sessions.push(ActiveSession {
+                let session = ActiveSession {
                     provider,
-                    session_id,
-                    path: path.to_path_buf(),
-                    mtime_epoch,
-                    is_active,
-                });
-            }
-        }
-
-        // Deduplicate by (provider, session_id), keeping the most recent
-        let mut best: HashMap<(ProviderKind, String), usize> = HashMap::new();
-        let mut deduped: Vec<ActiveSession> = Vec::new();
-
-        for session in sessions {
-            let key = (session.provider, session.session_id.clone());
-            if let Some(&idx) = best.get(&key) {
-                if session.mtime_epoch > deduped[idx].mtime_epoch {
-                    deduped[idx] = session;
+                    session_id: summary.session_id,
+                    path: summary.path,
+                    mtime_epoch: summary.updated_at,
+                    is_active: age_secs < 60,
+                };
+
+                // Deduplicate by (provider, session_id), keeping the most recent
+                let key = (session.provider, session.session_id.clone());
+                if let Some(&idx) = best.get(&key) {
+                    if session.mtime_epoch > deduped[idx].mtime_epoch {
+                        deduped[idx] = session;
+                    }
+                } else {
+                    best.insert(key, deduped.len());
+                    deduped.push(session);
                 }
-            } else {
-                best.insert(key, deduped.len());
-                deduped.push(session);
             }
         }
 
@@ -204,8 +380,14 @@ mod tests {
             codex_root: base.join("codex"),
             claude_root: base.join("claude"),
             gemini_root: base.join("gemini"),
+            qwen_root: base.join("qwen"),
             pi_root: base.join("pi"),
             opencode_root: base.join("opencode"),
+            cline_root: base.join("cline"),
+            roo_root: base.join("roo"),
+            aider_roots: vec![base.join("aider")],
+            goose_root: base.join("goose"),
+            cursor_root: base.join("cursor"),
         }
     }
 
@@ -217,14 +399,20 @@ mod tests {
         // Create a Claude session file
         let projects = roots.claude_root.join("projects").join("proj1");
         fs::create_dir_all(&projects).expect("mkdir");
-        let session_file = projects.join("abc123.jsonl");
-        fs::write(&session_file, "{\"type\":\"user\"}\n{\"type\":\"assistant\"}\n")
-            .expect("write");
+        let session_file = projects.join("2823d1df-720a-4c31-ac55-ae8ba726721f.jsonl");
+        fs::write(
+            &session_file,
+            "{\"type\":\"user\"}\n{\"type\":\"assistant\"}\n",
+        )
+        .expect("write");
 
         let sessions = roots.list_active_sessions(Duration::from_secs(300));
         assert_eq!(sessions.len(), 1);
         assert_eq!(sessions[0].provider, ProviderKind::Claude);
-        assert_eq!(sessions[0].session_id, "abc123");
+        assert_eq!(
+            sessions[0].session_id,
+            "2823d1df-720a-4c31-ac55-ae8ba726721f"
+        );
         assert!(sessions[0].is_active);
     }
 
@@ -246,20 +434,20 @@ mod tests {
         let temp = tempdir().expect("tempdir");
         let roots = make_roots(temp.path());
 
-        // Create two files with same stem in different subdirs
+        // Create two transcripts for the same session in different subdirs
         let dir1 = roots.claude_root.join("projects").join("proj1");
         let dir2 = roots.claude_root.join("projects").join("proj2");
         fs::create_dir_all(&dir1).expect("mkdir");
         fs::create_dir_all(&dir2).expect("mkdir");
         fs::write(
-            dir1.join("same-session.jsonl"),
-            "{\"type\":\"user\",\"old\":true}\n",
+            dir1.join("old-copy.jsonl"),
+            "{\"type\":\"user\",\"sessionId\":\"2823d1df-720a-4c31-ac55-ae8ba726721f\"}\n",
         )
         .expect("write");
         // Small delay to ensure different mtime
         std::thread::sleep(Duration::from_millis(50));
         fs::write(
-            dir2.join("same-session.jsonl"),
+            dir2.join("2823d1df-720a-4c31-ac55-ae8ba726721f.jsonl"),
             "{\"type\":\"user\",\"new\":true}\n",
         )
         .expect("write");
@@ -267,11 +455,43 @@ mod tests {
         let sessions = roots.list_active_sessions(Duration::from_secs(300));
         let matching: Vec<_> = sessions
             .iter()
-            .filter(|s| s.session_id == "same-session")
+            .filter(|s| s.session_id == "2823d1df-720a-4c31-ac55-ae8ba726721f")
             .collect();
         assert_eq!(matching.len(), 1);
     }
 
+    #[cfg(feature = "sqlite")]
+    #[test]
+    fn uses_provider_session_ids_and_skips_archived() {
+        let temp = tempdir().expect("tempdir");
+        let roots = make_roots(temp.path());
+
+        let sessions_dir = roots.codex_root.join("sessions/2026/02/23");
+        fs::create_dir_all(&sessions_dir).expect("mkdir");
+        fs::write(
+            sessions_dir
+                .join("rollout-2026-02-23T04-48-50-019c871c-b1f9-7f60-9c4f-87ed09f13592.jsonl"),
+            "{\"type\":\"session_meta\"}\n",
+        )
+        .expect("write");
+        let archived_dir = roots.codex_root.join("archived_sessions");
+        fs::create_dir_all(&archived_dir).expect("mkdir");
+        fs::write(
+            archived_dir
+                .join("rollout-2026-02-22T01-05-36-019c8129-f668-7951-8d56-cc5513541c26.jsonl"),
+            "{\"type\":\"session_meta\"}\n",
+        )
+        .expect("write");
+
+        let sessions = roots.list_active_sessions(Duration::from_secs(300));
+        assert_eq!(sessions.len(), 1);
+        assert_eq!(sessions[0].provider, ProviderKind::Codex);
+        assert_eq!(
+            sessions[0].session_id,
+            "019c871c-b1f9-7f60-9c4f-87ed09f13592"
+        );
+    }
+
     #[test]
     fn empty_roots_returns_empty() {
         let temp = tempdir().expect("tempdir");
@@ -1,13 +1,23 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::path::PathBuf;
 
-use rusqlite::{Connection, OpenFlags};
+use rusqlite::{Connection, OpenFlags, params};
 use serde_json::{Value, json};
 
 use crate::error::{Result, XurlError};
-use crate::model::{ProviderKind, ResolutionMeta, ResolvedThread};
+use crate::model::{ProviderKind, ResolutionMeta, ResolvedThread, SessionSummary};
 use crate::provider::Provider;
+use crate::time::normalize_epoch;
+
+/// Position of the last message seen, ordered by `(time_created, id)`.
+pub(crate) type MessageCursor = (i64, String);
+
+struct MessageBatch {
+    messages: Vec<(String, Value)>,
+    parts: HashMap<String, Vec<Value>>,
+    cursor: Option<MessageCursor>,
+}
 
 #[derive(Debug, Clone)]
 pub struct OpencodeProvider {
@@ -38,6 +48,66 @@ impl OpencodeProvider {
         Ok(rows.next()?.is_some())
     }
 
+    fn session_columns(conn: &Connection) -> std::result::Result<HashSet<String>, rusqlite::Error> {
+        let mut stmt = conn.prepare("PRAGMA table_info(session)")?;
+        let rows = stmt.query_map([], |row| row.get::<_, String>(1))?;
+        rows.collect()
+    }
+
+    fn fetch_summaries(
+        conn: &Connection,
+        db_path: &std::path::Path,
+    ) -> std::result::Result<Vec<SessionSummary>, rusqlite::Error> {
+        // Older databases only carry `id`; optional columns degrade to NULL so
+        // listing keeps working across OpenCode schema versions.
+        let columns = Self::session_columns(conn)?;
+        let optional = |name: &str| {
+            if columns.contains(name) {
+                format!("s.{name}")
+            } else {
+                "NULL".to_string()
+            }
+        };
+        let updated = if columns.contains("time_updated") {
+            "s.time_updated".to_string()
+        } else {
+            "(SELECT MAX(m.time_created) FROM message m WHERE m.session_id = s.id)".to_string()
+        };
+
+        let created = if columns.contains("time_created") {
+            "s.time_created".to_string()
+        } else {
+            "(SELECT MIN(m.time_created) FROM message m WHERE m.session_id = s.id)".to_string()
+        };
+
+        let sql = format!(
+            "SELECT s.id, {}, {}, {updated}, {created}, {}, {} FROM session s",
+            optional("title"),
+            optional("directory"),
+            optional("parent_id"),
+            optional("time_archived"),
+        );
+        let mut stmt = conn.prepare(&sql)?;
+        let rows = stmt.query_map([], |row| {
+            Ok(SessionSummary {
+                provider: ProviderKind::Opencode,
+                session_id: row.get::<_, String>(0)?,
+                path: db_path.to_path_buf(),
+                created_at: row.get::<_, Option<i64>>(4)?.and_then(normalize_epoch),
+                updated_at: row
+                    .get::<_, Option<i64>>(3)?
+                    .and_then(normalize_epoch)
+                    .unwrap_or_default(),
+                cwd: row.get::<_, Option<String>>(2)?,
+                title: row.get::<_, Option<String>>(1)?,
+                archived: row.get::<_, Option<i64>>(6)?.is_some(),
+                parent_session_id: row.get::<_, Option<String>>(5)?,
+                message_count: None,
+            })
+        })?;
+        rows.collect()
+    }
+
     fn fetch_messages(
         conn: &Connection,
         session_id: &str,
@@ -107,6 +177,112 @@ impl OpencodeProvider {
         Ok(result)
     }
 
+    /// Materialize messages ordered after `after` as JSONL for follow mode.
+    ///
+    /// Stops at the first assistant message that has neither completed nor
+    /// failed, since its parts are still being written. Returns the cursor of
+    /// the last message included so the caller can poll for newer rows.
+    pub(crate) fn read_messages_after(
+        &self,
+        session_id: &str,
+        after: Option<&MessageCursor>,
+    ) -> Result<(String, Option<MessageCursor>)> {
+        let db_path = self.db_path();
+        let conn = Connection::open_with_flags(&db_path, OpenFlags::SQLITE_OPEN_READ_ONLY)
+            .map_err(|source| XurlError::Sqlite {
+                path: db_path.clone(),
+                source,
+            })?;
+
+        let mut warnings = Vec::new();
+        let batch = Self::fetch_completed_after(&conn, session_id, after, &mut warnings).map_err(
+            |source| XurlError::Sqlite {
+                path: db_path.clone(),
+                source,
+            },
+        )?;
+
+        Ok((
+            Self::render_jsonl(session_id, batch.messages, batch.parts),
+            batch.cursor,
+        ))
+    }
+
+    fn fetch_completed_after(
+        conn: &Connection,
+        session_id: &str,
+        after: Option<&MessageCursor>,
+        warnings: &mut Vec<String>,
+    ) -> std::result::Result<MessageBatch, rusqlite::Error> {
+        let (after_time, after_id) = after
+            .map(|(time, id)| (*time, id.as_str()))
+            .unwrap_or((i64::MIN, ""));
+        let mut stmt = conn.prepare(
+            "SELECT id, time_created, data
+             FROM message
+             WHERE session_id = ?1
+               AND (time_created > ?2 OR (time_created = ?2 AND id > ?3))
+             ORDER BY time_created ASC, id ASC",
+        )?;
+        let rows = stmt.query_map(params![session_id, after_time, after_id], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, i64>(1)?,
+                row.get::<_, String>(2)?,
+            ))
+        })?;
+
+        let mut messages = Vec::new();
+        let mut cursor = after.cloned();
+        for row in rows {
+            let (id, time_created, data) = row?;
+            match serde_json::from_str::<Value>(&data) {
+                Ok(value) => {
+                    if !Self::is_message_settled(&value) {
+                        break;
+                    }
+                    messages.push((id.clone(), value));
+                }
+                Err(err) => warnings.push(format!(
+                    "skipped message id={id}: invalid json payload ({err})"
+                )),
+            }
+            cursor = Some((time_created, id));
+        }
+
+        let mut part_stmt = conn.prepare(
+            "SELECT data
+             FROM part
+             WHERE message_id = ?1
+             ORDER BY time_created ASC, id ASC",
+        )?;
+        let mut parts = HashMap::new();
+        for (id, _) in &messages {
+            let rows = part_stmt.query_map([id], |row| row.get::<_, String>(0))?;
+            for data in rows {
+                let data = data?;
+                match serde_json::from_str::<Value>(&data) {
+                    Ok(value) => parts.entry(id.clone()).or_insert_with(Vec::new).push(value),
+                    Err(err) => warnings.push(format!(
+                        "skipped part for message_id={id}: invalid json payload ({err})"
+                    )),
+                }
+            }
+        }
+
+        Ok(MessageBatch {
+            messages,
+            parts,
+            cursor,
+        })
+    }
+
+    fn is_message_settled(message: &Value) -> bool {
+        message.get("role").and_then(Value::as_str) != Some("assistant")
+            || message.pointer("/time/completed").is_some()
+            || message.get("error").is_some()
+    }
+
     fn render_jsonl(
         session_id: &str,
         messages: Vec<(String, Value)>,
@@ -207,6 +383,37 @@ impl Provider for OpencodeProvider {
             },
         })
     }
+
+    fn list_sessions(&self) -> Result<Vec<SessionSummary>> {
+        let db_path = self.db_path();
+        if !db_path.exists() {
+            return Ok(Vec::new());
+        }
+
+        let conn = Connection::open_with_flags(&db_path, OpenFlags::SQLITE_OPEN_READ_ONLY)
+            .map_err(|source| XurlError::Sqlite {
+                path: db_path.clone(),
+                source,
+            })?;
+
+        Self::fetch_summaries(&conn, &db_path).map_err(|source| XurlError::Sqlite {
+            path: db_path.clone(),
+            source,
+        })
+    }
+
+    fn has_session(&self, session_id: &str) -> Result<bool> {
+        let db_path = self.db_path();
+        if !db_path.exists() {
+            return Ok(false);
+        }
+        Connection::open_with_flags(&db_path, OpenFlags::SQLITE_OPEN_READ_ONLY)
+            .and_then(|conn| Self::session_exists(&conn, session_id))
+            .map_err(|source| XurlError::Sqlite {
+                path: db_path,
+                source,
+            })
+    }
 }
 
 #[cfg(test)]
@@ -315,6 +522,75 @@ mod tests {
         assert!(raw.contains(r#""text":"world""#));
     }
 
+    #[test]
+    fn lists_sessions_without_optional_columns() {
+        let temp = tempdir().expect("tempdir");
+        let db = temp.path().join("opencode.db");
+        let conn = prepare_db(&db);
+
+        let session_id = "ses_43a90e3adffejRgrTdlJa48CtE";
+        conn.execute("INSERT INTO session (id) VALUES (?1)", [session_id])
+            .expect("insert session");
+        conn.execute(
+            "INSERT INTO message (id, session_id, time_created, data) VALUES (?1, ?2, ?3, ?4)",
+            params![
+                "msg_1",
+                session_id,
+                1_768_307_088_294_i64,
+                r#"{"role":"user","time":{"created":1768307088294}}"#
+            ],
+        )
+        .expect("insert message");
+
+        let sessions = OpencodeProvider::new(temp.path())
+            .list_sessions()
+            .expect("list should succeed");
+        assert_eq!(sessions.len(), 1);
+        assert_eq!(sessions[0].session_id, session_id);
+        assert_eq!(sessions[0].path, db);
+        assert_eq!(sessions[0].updated_at, 1_768_307_088);
+        assert_eq!(sessions[0].title, None);
+        assert_eq!(sessions[0].created_at, Some(1_768_307_088));
+        assert!(!sessions[0].archived);
+    }
+
+    #[test]
+    fn lists_sessions_with_full_schema() {
+        let temp = tempdir().expect("tempdir");
+        let conn = Connection::open(temp.path().join("opencode.db")).expect("open sqlite");
+        conn.execute_batch(
+            "
+            CREATE TABLE session (
+                id TEXT PRIMARY KEY,
+                parent_id TEXT,
+                directory TEXT NOT NULL,
+                title TEXT NOT NULL,
+                time_created INTEGER NOT NULL,
+                time_updated INTEGER NOT NULL,
+                time_archived INTEGER
+            );
+            INSERT INTO session VALUES
+                ('ses_parent', NULL, '/tmp/project', 'Refactor parser', 1768307000000, 1768307088294, NULL),
+                ('ses_child', 'ses_parent', '/tmp/project', 'Subtask', 1768307010000, 1768307020000, 1768307030000);
+            ",
+        )
+        .expect("create schema");
+
+        let mut sessions = OpencodeProvider::new(temp.path())
+            .list_sessions()
+            .expect("list should succeed");
+        sessions.sort_by(|a, b| a.session_id.cmp(&b.session_id));
+
+        assert_eq!(sessions.len(), 2);
+        assert_eq!(sessions[0].session_id, "ses_child");
+        assert_eq!(sessions[0].parent_session_id.as_deref(), Some("ses_parent"));
+        assert!(sessions[0].archived);
+        assert_eq!(sessions[1].title.as_deref(), Some("Refactor parser"));
+        assert_eq!(sessions[1].cwd.as_deref(), Some("/tmp/project"));
+        assert_eq!(sessions[1].created_at, Some(1_768_307_000));
+        assert_eq!(sessions[1].updated_at, 1_768_307_088);
+    }
+
     #[test]
     fn returns_not_found_when_db_missing() {
         let temp = tempdir().expect("tempdir");
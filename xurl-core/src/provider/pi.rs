@@ -8,8 +8,9 @@ use serde_json::Value;
 use walkdir::WalkDir;
 
 use crate::error::{Result, XurlError};
-use crate::model::{ProviderKind, ResolutionMeta, ResolvedThread};
-use crate::provider::Provider;
+use crate::model::{ProviderKind, ResolutionMeta, ResolvedThread, SessionSummary};
+use crate::provider::{Provider, modified_epoch};
+use crate::time::parse_rfc3339_epoch;
 
 #[derive(Debug, Clone)]
 pub struct PiProvider {
@@ -25,38 +26,30 @@ impl PiProvider {
         self.root.join("sessions")
     }
 
-    fn has_session_id(path: &Path, session_id: &str) -> bool {
-        let file = match fs::File::open(path) {
-            Ok(file) => file,
-            Err(_) => return false,
-        };
+    fn read_header(path: &Path) -> Option<Value> {
+        let file = fs::File::open(path).ok()?;
         let reader = BufReader::new(file);
 
-        let Some(first_non_empty) = reader
+        let first_non_empty = reader
             .lines()
             .take(20)
             .filter_map(std::result::Result::ok)
-            .find(|line| !line.trim().is_empty())
-        else {
-            return false;
-        };
+            .find(|line| !line.trim().is_empty())?;
 
-        let Ok(header) = serde_json::from_str::<Value>(&first_non_empty) else {
-            return false;
-        };
+        let header = serde_json::from_str::<Value>(&first_non_empty).ok()?;
+        (header.get("type").and_then(Value::as_str) == Some("session")).then_some(header)
+    }
 
-        header.get("type").and_then(Value::as_str) == Some("session")
-            && header
+    fn has_session_id(path: &Path, session_id: &str) -> bool {
+        Self::read_header(path).is_some_and(|header| {
+            header
                 .get("id")
                 .and_then(Value::as_str)
                 .is_some_and(|id| id.eq_ignore_ascii_case(session_id))
+        })
     }
 
-    fn find_candidates(sessions_root: &Path, session_id: &str) -> Vec<PathBuf> {
-        if !sessions_root.exists() {
-            return Vec::new();
-        }
-
+    fn session_files(sessions_root: &Path) -> impl Iterator<Item = PathBuf> {
         WalkDir::new(sessions_root)
             .into_iter()
             .filter_map(std::result::Result::ok)
@@ -67,10 +60,53 @@ impl PiProvider {
                     .and_then(|ext| ext.to_str())
                     .is_some_and(|ext| ext == "jsonl")
             })
+    }
+
+    fn find_candidates(sessions_root: &Path, session_id: &str) -> Vec<PathBuf> {
+        if !sessions_root.exists() {
+            return Vec::new();
+        }
+
+        Self::session_files(sessions_root)
             .filter(|path| Self::has_session_id(path, session_id))
             .collect()
     }
 
+    fn summarize(path: &Path) -> Option<SessionSummary> {
+        let header = Self::read_header(path)?;
+        let session_id = header.get("id").and_then(Value::as_str)?;
+        // Forked sessions reference the parent session file rather than its id.
+        let parent_session_id = header
+            .get("parentSession")
+            .and_then(Value::as_str)
+            .and_then(|parent| Self::read_header(Path::new(parent)))
+            .and_then(|parent| {
+                parent
+                    .get("id")
+                    .and_then(Value::as_str)
+                    .map(str::to_ascii_lowercase)
+            });
+
+        Some(SessionSummary {
+            provider: ProviderKind::Pi,
+            session_id: session_id.to_ascii_lowercase(),
+            path: path.to_path_buf(),
+            created_at: header
+                .get("timestamp")
+                .and_then(Value::as_str)
+                .and_then(parse_rfc3339_epoch),
+            updated_at: modified_epoch(path),
+            cwd: header
+                .get("cwd")
+                .and_then(Value::as_str)
+                .map(ToString::to_string),
+            title: None,
+            archived: false,
+            parent_session_id,
+            message_count: None,
+        })
+    }
+
     fn choose_latest(paths: Vec<PathBuf>) -> Option<(PathBuf, usize)> {
         if paths.is_empty() {
             return None;
@@ -124,6 +160,17 @@ impl Provider for PiProvider {
             searched_roots: vec![sessions_root],
         })
     }
+
+    fn list_sessions(&self) -> Result<Vec<SessionSummary>> {
+        let sessions_root = self.sessions_root();
+        if !sessions_root.exists() {
+            return Ok(Vec::new());
+        }
+
+        Ok(Self::session_files(&sessions_root)
+            .filter_map(|path| Self::summarize(&path))
+            .collect())
+    }
 }
 
 #[cfg(test)]
@@ -202,6 +249,54 @@ mod tests {
         assert!(first.exists());
     }
 
+    #[test]
+    fn lists_sessions_with_header_cwd() {
+        let temp = tempdir().expect("tempdir");
+        let session_id = "12cb4c19-2774-4de4-a0d0-9fa32fbae29f";
+        let path = write_session(
+            temp.path(),
+            "--Users-xuanwo-Code-xurl--",
+            "2026-02-23T13-00-12-780Z_12cb4c19-2774-4de4-a0d0-9fa32fbae29f.jsonl",
+            session_id,
+        );
+        fs::write(temp.path().join("sessions/notes.jsonl"), "{}\n").expect("write");
+
+        let sessions = PiProvider::new(temp.path())
+            .list_sessions()
+            .expect("list should succeed");
+        assert_eq!(sessions.len(), 1);
+        assert_eq!(sessions[0].session_id, session_id);
+        assert_eq!(sessions[0].path, path);
+        assert_eq!(sessions[0].cwd.as_deref(), Some("/tmp/project"));
+        assert_eq!(sessions[0].created_at, Some(1_771_851_612));
+        assert_eq!(sessions[0].parent_session_id, None);
+    }
+
+    #[test]
+    fn lists_forked_session_with_parent_id() {
+        let temp = tempdir().expect("tempdir");
+        let parent_id = "12cb4c19-2774-4de4-a0d0-9fa32fbae29f";
+        let parent = write_session(temp.path(), "project", "parent.jsonl", parent_id);
+        let fork = temp.path().join("sessions/project/fork.jsonl");
+        fs::write(
+            &fork,
+            format!(
+                "{{\"type\":\"session\",\"version\":3,\"id\":\"7a0c1f52-1d2e-4f7a-9b3c-5d6e7f809102\",\"timestamp\":\"2026-02-23T14:00:00.000Z\",\"cwd\":\"/tmp/project\",\"parentSession\":\"{}\"}}\n",
+                parent.display()
+            ),
+        )
+        .expect("write fork");
+
+        let sessions = PiProvider::new(temp.path())
+            .list_sessions()
+            .expect("list should succeed");
+        let fork = sessions
+            .iter()
+            .find(|session| session.session_id == "7a0c1f52-1d2e-4f7a-9b3c-5d6e7f809102")
+            .expect("fork should be listed");
+        assert_eq!(fork.parent_session_id.as_deref(), Some(parent_id));
+    }
+
     #[test]
     fn missing_thread_returns_not_found() {
         let temp = tempdir().expect("tempdir");
@@ -1,10 +1,19 @@
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
+use std::ops::Range;
 use std::path::Path;
 
-use serde_json::Value;
+use serde_json::{Value, json};
 
 use crate::error::{Result, XurlError};
-use crate::model::{MessageRole, ProviderKind, ThreadMessage, ToolCall};
+use crate::model::{
+    MessageRole, ProviderKind, ThreadEntry, ThreadMessage, TimelineEntry, TimelineOptions,
+    TokenUsage, ToolCall, ToolEntry,
+};
+use crate::provider::aider::SESSION_HEADER as AIDER_SESSION_HEADER;
+use crate::provider::cline::UI_MESSAGES_FILE;
+use crate::provider::gemini::GeminiFlavor;
+use crate::time::{format_epoch_rfc3339, normalize_epoch, parse_rfc3339_epoch};
 use crate::uri::ThreadUri;
 
 pub const TOOL_TYPES: &[&str] = &[
@@ -16,21 +25,70 @@ pub const TOOL_TYPES: &[&str] = &[
     "function_response",
 ];
 const COMPACT_PLACEHOLDER: &str = "Context was compacted.";
+const TOOL_ARGS_PREVIEW_CHARS: usize = 160;
+/// Tools that Cline and older Roo Code call by writing XML into assistant
+/// text instead of through the API's `tool_use` blocks.
+const CLINE_XML_TOOLS: &[&str] = &[
+    "execute_command",
+    "read_file",
+    "write_to_file",
+    "replace_in_file",
+    "apply_diff",
+    "insert_content",
+    "search_and_replace",
+    "search_files",
+    "list_files",
+    "list_code_definition_names",
+    "browser_action",
+    "use_mcp_tool",
+    "access_mcp_resource",
+    "ask_followup_question",
+    "attempt_completion",
+    "plan_mode_respond",
+    "new_task",
+    "switch_mode",
+    "fetch_instructions",
+    "codebase_search",
+];
+/// XML tools whose parameter is a reply to the user, kept as message text.
+const CLINE_REPLY_PARAMS: &[(&str, &str)] = &[
+    ("attempt_completion", "result"),
+    ("ask_followup_question", "question"),
+    ("plan_mode_respond", "response"),
+];
+/// How Cline words a failed or refused XML tool call in its result block.
+const CLINE_TOOL_ERRORS: &[&str] = &[
+    "The tool execution failed",
+    "The user denied this operation",
+];
 
-enum TimelineEntry {
-    Message(ThreadMessage),
-    Compact { summary: Option<String> },
-}
+pub fn render_markdown(
+    uri: &ThreadUri,
+    source_path: &Path,
+    raw_jsonl: &str,
+    options: &TimelineOptions,
+) -> Result<String> {
+    let mut entries = extract_thread_entries(uri, source_path, raw_jsonl, options)?;
+    if let Some(selection) = uri.selection {
+        entries = selection.apply(entries)?;
+    }
 
-pub fn render_markdown(uri: &ThreadUri, source_path: &Path, raw_jsonl: &str) -> Result<String> {
-    let entries = extract_timeline_entries(
-        uri.provider,
-        source_path,
-        raw_jsonl,
-        &uri.session_id,
-        uri.agent_id.as_deref(),
-    )?;
+    let mut output = render_markdown_header(uri, source_path);
 
+    if entries.is_empty() {
+        output.push_str("_No user/assistant messages or compact events found._\n");
+        return Ok(output);
+    }
+
+    for entry in &entries {
+        output.push_str(&render_entry_markdown(entry));
+    }
+
+    Ok(output)
+}
+
+/// Render the frontmatter and timeline heading that precede the entries.
+fn render_markdown_header(uri: &ThreadUri, source_path: &Path) -> String {
     let mut output = String::new();
     let thread_uri = uri.as_agents_string();
     let source = source_path.to_string_lossy();
@@ -43,53 +101,227 @@ pub fn render_markdown(uri: &ThreadUri, source_path: &Path, raw_jsonl: &str) ->
     output.push_str("---\n\n");
     output.push_str("# Thread\n\n");
     output.push_str("## Timeline\n\n");
+    output
+}
 
-    if entries.is_empty() {
-        output.push_str("_No user/assistant messages or compact events found._\n");
-        return Ok(output);
-    }
-
-    for (idx, entry) in entries.iter().enumerate() {
-        let title = match entry {
-            TimelineEntry::Message(message) => match message.role {
+/// Render one numbered timeline entry as a markdown section.
+pub fn render_entry_markdown(entry: &ThreadEntry) -> String {
+    let ThreadEntry { index, entry } = entry;
+    let (heading, body) = match entry {
+        TimelineEntry::Message(message) => {
+            let title = match message.role {
                 MessageRole::User => "User",
                 MessageRole::Assistant => "Assistant",
-            },
-            TimelineEntry::Compact { .. } => "Context Compacted",
-        };
+            };
+            let details = message_details(message);
+            let heading = if details.is_empty() {
+                title.to_string()
+            } else {
+                format!("{title} · {}", details.join(" · "))
+            };
+            (heading, message.text.trim().to_string())
+        }
+        TimelineEntry::Compact { summary } => {
+            let summary = summary.as_deref().unwrap_or(COMPACT_PLACEHOLDER);
+            ("Context Compacted".to_string(), summary.trim().to_string())
+        }
+        TimelineEntry::Tool(tool) => (format!("Tool `{}`", tool.name), render_tool_body(tool)),
+        TimelineEntry::Checkpoint { hash } => ("Checkpoint".to_string(), format!("`{hash}`")),
+    };
 
-        output.push_str(&format!("## {}. {}\n\n", idx + 1, title));
-        match entry {
-            TimelineEntry::Message(message) => output.push_str(message.text.trim()),
-            TimelineEntry::Compact { summary } => {
-                let summary = summary.as_deref().unwrap_or(COMPACT_PLACEHOLDER);
-                output.push_str(summary.trim());
-            }
+    format!("## {index}. {heading}\n\n{body}\n\n")
+}
+
+/// Arguments as a JSON (or raw text) block, followed by the paired output.
+fn render_tool_body(tool: &ToolEntry) -> String {
+    let mut body = match &tool.args {
+        Value::Null => String::new(),
+        Value::String(text) => fenced(text, "text"),
+        args => fenced(
+            &serde_json::to_string_pretty(args).unwrap_or_else(|_| args.to_string()),
+            "json",
+        ),
+    };
+
+    if let Some(output) = &tool.output {
+        if !body.is_empty() {
+            body.push_str("\n\n");
+        }
+        body.push_str(if tool.is_error {
+            "Error:\n\n"
+        } else {
+            "Output:\n\n"
+        });
+        body.push_str(&fenced(output.trim_end(), "text"));
+        if tool.truncated {
+            body.push_str("\n\n_Output truncated._");
         }
-        output.push_str("\n\n");
     }
 
-    Ok(output)
+    if body.is_empty() {
+        body.push_str("_No arguments or output recorded._");
+    }
+    body
+}
+
+/// Wrap `content` in a code fence longer than any backtick run inside it.
+fn fenced(content: &str, lang: &str) -> String {
+    let longest_run = content
+        .split(|ch| ch != '`')
+        .map(str::len)
+        .max()
+        .unwrap_or(0);
+    let fence = "`".repeat(longest_run.max(2) + 1);
+    format!("{fence}{lang}\n{content}\n{fence}")
+}
+
+/// Timestamp, model, token counts and cost shown next to a message heading.
+pub(crate) fn message_details(message: &ThreadMessage) -> Vec<String> {
+    let mut details = Vec::new();
+    if let Some(timestamp) = &message.timestamp {
+        details.push(timestamp.clone());
+    }
+    if let Some(model) = &message.model {
+        details.push(model.clone());
+    }
+    let Some(usage) = &message.usage else {
+        return details;
+    };
+
+    let counts = [
+        (usage.input_tokens, "in"),
+        (usage.output_tokens, "out"),
+        (usage.cache_read_tokens, "cached"),
+        (usage.cache_write_tokens, "cache write"),
+        (usage.reasoning_tokens, "reasoning"),
+    ]
+    .into_iter()
+    .filter_map(|(count, label)| {
+        count
+            .filter(|count| *count > 0)
+            .map(|c| format!("{c} {label}"))
+    })
+    .collect::<Vec<_>>();
+    if !counts.is_empty() {
+        details.push(format!("{} tokens", counts.join(" / ")));
+    } else if let Some(total) = usage.total_tokens {
+        details.push(format!("{total} tokens"));
+    }
+    if let Some(cost) = usage.cost.filter(|cost| *cost > 0.0) {
+        details.push(format!("${cost:.4}"));
+    }
+
+    details
+}
+
+/// Render a tool invocation observed while following a thread.
+pub fn render_tool_call_markdown(call: &ToolCall) -> String {
+    let args = match &call.args {
+        Value::Null => String::new(),
+        args => {
+            let encoded = args.to_string();
+            let preview = match encoded.char_indices().nth(TOOL_ARGS_PREVIEW_CHARS) {
+                Some((cut, _)) => format!("{}...", &encoded[..cut]),
+                None => encoded,
+            };
+            format!(" `{}`", preview.replace('`', "'"))
+        }
+    };
+    format!("> Tool call `{}`{args}\n\n", call.name)
 }
 
 fn yaml_single_quoted(value: &str) -> String {
     value.replace('\'', "''")
 }
 
+/// Extract the numbered timeline (messages, compaction markers and, when
+/// requested, tool calls) for a thread URI.
+///
+/// Tool entries are always numbered, even when they are left out, so an
+/// entry has the same number in every view.
+pub fn extract_thread_entries(
+    uri: &ThreadUri,
+    source_path: &Path,
+    raw_jsonl: &str,
+    options: &TimelineOptions,
+) -> Result<Vec<ThreadEntry>> {
+    let entries = extract_timeline_entries(
+        uri.provider,
+        source_path,
+        raw_jsonl,
+        &uri.session_id,
+        uri.agent_id.as_deref(),
+        &TimelineOptions {
+            tools: true,
+            ..*options
+        },
+    )?;
+    Ok(number_entries(entries, 1)
+        .into_iter()
+        .filter_map(|entry| {
+            Some(ThreadEntry {
+                index: entry.index,
+                entry: view_entry(entry.entry, options)?,
+            })
+        })
+        .collect())
+}
+
+/// Number a timeline extracted with tool entries, starting at `first`.
+pub(crate) fn number_entries(entries: Vec<TimelineEntry>, first: usize) -> Vec<ThreadEntry> {
+    entries
+        .into_iter()
+        .enumerate()
+        .map(|(offset, entry)| ThreadEntry {
+            index: first + offset,
+            entry,
+        })
+        .collect()
+}
+
+/// Apply the tools, role and thinking view options to one numbered entry.
+pub(crate) fn view_entry(entry: TimelineEntry, options: &TimelineOptions) -> Option<TimelineEntry> {
+    match entry {
+        TimelineEntry::Message(mut message) => {
+            if options.role.is_some_and(|role| role != message.role) {
+                return None;
+            }
+            if !options.thinking && !message.reasoning.is_empty() {
+                message.text = message.text_without_reasoning();
+                message.reasoning.clear();
+                if message.text.is_empty() {
+                    return None;
+                }
+            }
+            Some(TimelineEntry::Message(message))
+        }
+        TimelineEntry::Tool(_) if !options.tools => None,
+        _ if options.role.is_some() => None,
+        other => Some(other),
+    }
+}
+
 pub fn extract_messages(
     provider: ProviderKind,
     path: &Path,
     raw_jsonl: &str,
 ) -> Result<Vec<ThreadMessage>> {
-    Ok(
-        extract_timeline_entries(provider, path, raw_jsonl, "", None)?
-            .into_iter()
-            .filter_map(|entry| match entry {
-                TimelineEntry::Message(message) => Some(message),
-                TimelineEntry::Compact { .. } => None,
-            })
-            .collect(),
-    )
+    Ok(extract_timeline_entries(
+        provider,
+        path,
+        raw_jsonl,
+        "",
+        None,
+        &TimelineOptions::default(),
+    )?
+    .into_iter()
+    .filter_map(|entry| match entry {
+        TimelineEntry::Message(message) => Some(message),
+        TimelineEntry::Compact { .. }
+        | TimelineEntry::Tool(_)
+        | TimelineEntry::Checkpoint { .. } => None,
+    })
+    .collect())
 }
 
 /// Extract tool call invocations from raw JSONL/JSON content.
@@ -97,17 +329,56 @@ pub fn extract_messages(
 /// Handles per-provider formats:
 /// - **Claude**: `message.content[].type ∈ TOOL_TYPES`
 /// - **Codex**: `payload.type ∈ TOOL_TYPES`
-/// - **Amp/Gemini**: single JSON → `messages[].content[].type ∈ TOOL_TYPES`
+/// - **Amp/Gemini/Qwen**: single JSON → `messages[].content[].type ∈ TOOL_TYPES`
 /// - **Pi**: JSONL → `message.content[].type ∈ TOOL_TYPES`
 /// - **Opencode**: JSONL → `parts[].type == "tool"`
+/// - **Cline/Roo**: JSON array → `[].content[].type ∈ TOOL_TYPES`, plus XML calls in assistant text
+/// - **Aider**: markdown → SEARCH/REPLACE blocks in replies
+/// - **Goose**: JSONL → `content[].type == "toolRequest"`
+/// - **Cursor**: JSONL → `toolFormerData` on each bubble
 pub fn extract_tool_calls(
     provider: ProviderKind,
     path: &Path,
     raw_jsonl: &str,
 ) -> Result<Vec<ToolCall>> {
-    if provider == ProviderKind::Amp || provider == ProviderKind::Gemini {
+    if matches!(
+        provider,
+        ProviderKind::Amp | ProviderKind::Gemini | ProviderKind::Qwen
+    ) {
         return extract_tool_calls_single_json(path, raw_jsonl);
     }
+    if provider == ProviderKind::Aider {
+        let mut tools = ToolTracker::new(usize::MAX);
+        return Ok(extract_aider_entries(raw_jsonl, Some(&mut tools))
+            .into_iter()
+            .filter_map(|entry| match entry {
+                TimelineEntry::Tool(tool) => Some(ToolCall {
+                    name: tool.name,
+                    args: tool.args,
+                    call_type: "tool_use".to_string(),
+                }),
+                _ => None,
+            })
+            .collect());
+    }
+    if matches!(provider, ProviderKind::Cline | ProviderKind::Roo) {
+        let mut calls = Vec::new();
+        for message in parse_json_array(path, raw_jsonl)? {
+            extract_tool_calls_from_content_array(&message, "message", &mut calls);
+            if message.get("role").and_then(Value::as_str) == Some("assistant") {
+                for text in cline_texts(message.get("content")) {
+                    calls.extend(cline_xml_calls(text).into_iter().map(|(name, args, _)| {
+                        ToolCall {
+                            name: name.to_string(),
+                            args,
+                            call_type: "tool_use".to_string(),
+                        }
+                    }));
+                }
+            }
+        }
+        return Ok(calls);
+    }
 
     let mut calls = Vec::new();
 
@@ -136,6 +407,12 @@ pub fn extract_tool_calls(
             ProviderKind::Opencode => {
                 extract_tool_calls_from_opencode(&value, &mut calls);
             }
+            ProviderKind::Goose => {
+                extract_tool_calls_from_goose(&value, &mut calls);
+            }
+            ProviderKind::Cursor => {
+                extract_tool_calls_from_cursor(&value, &mut calls);
+            }
             _ => {}
         }
     }
@@ -143,7 +420,7 @@ pub fn extract_tool_calls(
     Ok(calls)
 }
 
-/// Extract tool calls from a single-JSON provider (Amp, Gemini).
+/// Extract tool calls from a single-JSON provider (Amp, Gemini, Qwen).
 fn extract_tool_calls_single_json(path: &Path, raw_json: &str) -> Result<Vec<ToolCall>> {
     let value =
         serde_json::from_str::<Value>(raw_json).map_err(|source| XurlError::InvalidJsonLine {
@@ -161,24 +438,24 @@ fn extract_tool_calls_single_json(path: &Path, raw_json: &str) -> Result<Vec<Too
     {
         if let Some(content) = message.get("content").and_then(Value::as_array) {
             for item in content {
-                if let Some(call_type) = item.get("type").and_then(Value::as_str) {
-                    if TOOL_TYPES.contains(&call_type) {
-                        let name = item
-                            .get("name")
-                            .and_then(Value::as_str)
-                            .unwrap_or("unknown")
-                            .to_string();
-                        let args = item
-                            .get("input")
-                            .or_else(|| item.get("arguments"))
-                            .cloned()
-                            .unwrap_or(Value::Null);
-                        calls.push(ToolCall {
-                            name,
-                            args,
-                            call_type: call_type.to_string(),
-                        });
-                    }
+                if let Some(call_type) = item.get("type").and_then(Value::as_str)
+                    && TOOL_TYPES.contains(&call_type)
+                {
+                    let name = item
+                        .get("name")
+                        .and_then(Value::as_str)
+                        .unwrap_or("unknown")
+                        .to_string();
+                    let args = item
+                        .get("input")
+                        .or_else(|| item.get("arguments"))
+                        .cloned()
+                        .unwrap_or(Value::Null);
+                    calls.push(ToolCall {
+                        name,
+                        args,
+                        call_type: call_type.to_string(),
+                    });
                 }
             }
         }
@@ -204,24 +481,24 @@ fn extract_tool_calls_from_content_array(
     };
 
     for item in items {
-        if let Some(call_type) = item.get("type").and_then(Value::as_str) {
-            if TOOL_TYPES.contains(&call_type) {
-                let name = item
-                    .get("name")
-                    .and_then(Value::as_str)
-                    .unwrap_or("unknown")
-                    .to_string();
-                let args = item
-                    .get("input")
-                    .or_else(|| item.get("arguments"))
-                    .cloned()
-                    .unwrap_or(Value::Null);
-                out.push(ToolCall {
-                    name,
-                    args,
-                    call_type: call_type.to_string(),
-                });
-            }
+        if let Some(call_type) = item.get("type").and_then(Value::as_str)
+            && TOOL_TYPES.contains(&call_type)
+        {
+            let name = item
+                .get("name")
+                .and_then(Value::as_str)
+                .unwrap_or("unknown")
+                .to_string();
+            let args = item
+                .get("input")
+                .or_else(|| item.get("arguments"))
+                .cloned()
+                .unwrap_or(Value::Null);
+            out.push(ToolCall {
+                name,
+                args,
+                call_type: call_type.to_string(),
+            });
         }
     }
 }
@@ -263,6 +540,46 @@ fn extract_tool_calls_from_payload(value: &Value, out: &mut Vec<ToolCall>) {
     });
 }
 
+/// Extract from Goose `content[].type == "toolRequest"` format.
+fn extract_tool_calls_from_goose(value: &Value, out: &mut Vec<ToolCall>) {
+    for item in value
+        .get("content")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+    {
+        if item.get("type").and_then(Value::as_str) != Some("toolRequest") {
+            continue;
+        }
+        let call = item.pointer("/toolCall/value");
+        out.push(ToolCall {
+            name: call
+                .map_or("unknown", |call| tool_name(call, "name"))
+                .to_string(),
+            args: call
+                .and_then(|call| call.get("arguments"))
+                .cloned()
+                .unwrap_or(Value::Null),
+            call_type: "toolRequest".to_string(),
+        });
+    }
+}
+
+/// Extract from Cursor `toolFormerData` on a bubble.
+fn extract_tool_calls_from_cursor(value: &Value, out: &mut Vec<ToolCall>) {
+    let Some(tool) = value
+        .get("toolFormerData")
+        .filter(|tool| tool.get("name").is_some())
+    else {
+        return;
+    };
+    out.push(ToolCall {
+        name: tool_name(tool, "name").to_string(),
+        args: cursor_tool_args(tool),
+        call_type: "toolFormerData".to_string(),
+    });
+}
+
 /// Extract from Opencode `parts[].type == "tool"` format.
 fn extract_tool_calls_from_opencode(value: &Value, out: &mut Vec<ToolCall>) {
     let Some(parts) = value.get("parts").and_then(Value::as_array) else {
@@ -292,26 +609,88 @@ fn extract_tool_calls_from_opencode(value: &Value, out: &mut Vec<ToolCall>) {
     }
 }
 
-fn extract_timeline_entries(
+pub(crate) fn extract_timeline_entries(
     provider: ProviderKind,
     path: &Path,
     raw_jsonl: &str,
     session_id: &str,
     target_entry_id: Option<&str>,
+    options: &TimelineOptions,
 ) -> Result<Vec<TimelineEntry>> {
-    if provider == ProviderKind::Amp {
-        return Ok(messages_to_entries(extract_amp_messages(path, raw_jsonl)?));
-    }
-    if provider == ProviderKind::Gemini {
-        return Ok(messages_to_entries(extract_gemini_messages(
-            path, raw_jsonl,
-        )?));
-    }
-    if provider == ProviderKind::Pi {
-        return extract_pi_entries(path, raw_jsonl, session_id, target_entry_id);
+    extract_timeline_chunk(
+        provider,
+        path,
+        raw_jsonl,
+        session_id,
+        target_entry_id,
+        options,
+        &mut ChunkState::default(),
+    )
+}
+
+/// What extraction carries from one chunk of a growing thread to the next,
+/// so a thread read in pieces yields the entries a single read would.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct ChunkState {
+    /// Codex model from the latest `turn_context`.
+    pub codex_model: Option<String>,
+    /// Ids of tool calls whose result has not been read yet. Their results
+    /// belong to entries of an earlier chunk rather than becoming entries.
+    pub open_calls: Vec<String>,
+}
+
+/// [`extract_timeline_entries`] for text appended after the chunks that
+/// produced `state`.
+pub(crate) fn extract_timeline_chunk(
+    provider: ProviderKind,
+    path: &Path,
+    raw_jsonl: &str,
+    session_id: &str,
+    target_entry_id: Option<&str>,
+    options: &TimelineOptions,
+    state: &mut ChunkState,
+) -> Result<Vec<TimelineEntry>> {
+    let mut tools = options
+        .tools
+        .then(|| ToolTracker::resuming(options.max_tool_output, &state.open_calls));
+
+    let entries = if provider == ProviderKind::Amp {
+        extract_amp_entries(path, raw_jsonl, tools.as_mut())?
+    } else if let Some(flavor) = GeminiFlavor::of(provider) {
+        extract_gemini_entries(flavor, path, raw_jsonl, tools.as_mut())?
+    } else if provider == ProviderKind::Pi {
+        extract_pi_entries(path, raw_jsonl, session_id, target_entry_id, tools.as_mut())?
+    } else if matches!(provider, ProviderKind::Cline | ProviderKind::Roo) {
+        extract_cline_entries(path, raw_jsonl, tools.as_mut())?
+    } else if provider == ProviderKind::Aider {
+        extract_aider_entries(raw_jsonl, tools.as_mut())
+    } else {
+        extract_jsonl_entries(
+            provider,
+            path,
+            raw_jsonl,
+            &mut state.codex_model,
+            tools.as_mut(),
+        )?
+    };
+
+    if let Some(tools) = tools {
+        state.open_calls = tools.open_calls(&entries);
     }
+    Ok(entries)
+}
 
+fn extract_jsonl_entries(
+    provider: ProviderKind,
+    path: &Path,
+    raw_jsonl: &str,
+    codex_model: &mut Option<String>,
+    mut tools: Option<&mut ToolTracker>,
+) -> Result<Vec<TimelineEntry>> {
     let mut entries = Vec::new();
+    // Codex records the model in `turn_context` and token usage in a
+    // `token_count` event after each response, so both are carried forward.
+    let mut awaiting_usage = None::<usize>;
 
     for (line_idx, line) in raw_jsonl.lines().enumerate() {
         let line_no = line_idx + 1;
@@ -328,32 +707,68 @@ fn extract_timeline_entries(
             }
         })?;
 
+        if provider == ProviderKind::Codex {
+            if value.get("type").and_then(Value::as_str) == Some("turn_context") {
+                *codex_model = string_at(&value, "/payload/model");
+                continue;
+            }
+            if is_codex_token_count(&value) {
+                if let Some(usage) = codex_token_usage(&value)
+                    && let Some(TimelineEntry::Message(message)) =
+                        awaiting_usage.take().and_then(|idx| entries.get_mut(idx))
+                {
+                    message.usage = Some(usage);
+                }
+                continue;
+            }
+        }
+
         let extracted = match provider {
             ProviderKind::Amp => None,
             ProviderKind::Codex => extract_codex_entry(&value),
             ProviderKind::Claude => extract_claude_entry(&value),
-            ProviderKind::Gemini => None,
+            ProviderKind::Gemini | ProviderKind::Qwen => None,
             ProviderKind::Pi => None,
             ProviderKind::Opencode => extract_opencode_message(&value).map(TimelineEntry::Message),
+            ProviderKind::Goose => extract_goose_message(&value).map(TimelineEntry::Message),
+            ProviderKind::Cursor => extract_cursor_message(&value).map(TimelineEntry::Message),
+            ProviderKind::Cline | ProviderKind::Roo | ProviderKind::Aider => None,
         };
 
-        if let Some(entry) = extracted {
+        if let Some(mut entry) = extracted {
+            if provider == ProviderKind::Codex
+                && let TimelineEntry::Message(message) = &mut entry
+                && message.role == MessageRole::Assistant
+            {
+                message.model = codex_model.clone();
+                awaiting_usage = Some(entries.len());
+            }
             entries.push(entry);
         }
+
+        if let Some(tools) = tools.as_deref_mut() {
+            match provider {
+                ProviderKind::Codex => tools.codex_record(&value, &mut entries),
+                ProviderKind::Claude if !is_claude_compact_summary(&value) => {
+                    tools.content_items(value.pointer("/message/content"), &mut entries)
+                }
+                ProviderKind::Opencode => tools.opencode_parts(&value, &mut entries),
+                ProviderKind::Goose => tools.goose_content(value.get("content"), &mut entries),
+                ProviderKind::Cursor => tools.cursor_bubble(&value, &mut entries),
+                _ => {}
+            }
+        }
     }
 
     Ok(entries)
 }
 
-fn messages_to_entries(messages: Vec<ThreadMessage>) -> Vec<TimelineEntry> {
-    messages.into_iter().map(TimelineEntry::Message).collect()
-}
-
 fn extract_pi_entries(
     path: &Path,
     raw_jsonl: &str,
     session_id: &str,
     target_entry_id: Option<&str>,
+    mut tools: Option<&mut ToolTracker>,
 ) -> Result<Vec<TimelineEntry>> {
     let mut entries_by_id = HashMap::<String, Value>::new();
     let mut last_entry_id = None::<String>;
@@ -436,6 +851,9 @@ fn extract_pi_entries(
         if let Some(timeline_entry) = extract_pi_entry(entry) {
             entries.push(timeline_entry);
         }
+        if let Some(tools) = tools.as_deref_mut() {
+            tools.pi_entry(entry, &mut entries);
+        }
     }
 
     Ok(entries)
@@ -444,103 +862,1017 @@ fn extract_pi_entries(
 fn extract_pi_entry(value: &Value) -> Option<TimelineEntry> {
     let entry_type = value.get("type").and_then(Value::as_str)?;
 
-    if entry_type == "message" {
-        let message = value.get("message")?;
-        let role = message
-            .get("role")
-            .and_then(Value::as_str)
-            .and_then(parse_role)?;
-        let text = extract_text(message.get("content"));
-        if text.trim().is_empty() {
-            return None;
+    if entry_type == "message" {
+        let message = value.get("message")?;
+        let role = message
+            .get("role")
+            .and_then(Value::as_str)
+            .and_then(parse_role)?;
+        let text = extract_text(message.get("content"));
+        if text.trim().is_empty() {
+            return None;
+        }
+
+        let mut message_record = ThreadMessage::new(role, text);
+        message_record.timestamp = record_timestamp(value.get("timestamp"))
+            .or_else(|| record_timestamp(message.get("timestamp")));
+        message_record.model = string_at(message, "/model");
+        message_record.usage = message.get("usage").and_then(|usage| {
+            usage_from(
+                usage,
+                UsageKeys {
+                    input: "/input",
+                    output: "/output",
+                    cache_read: "/cacheRead",
+                    cache_write: "/cacheWrite",
+                    reasoning: "",
+                    total: "/totalTokens",
+                    cost: "/cost/total",
+                },
+            )
+        });
+        return Some(TimelineEntry::Message(message_record));
+    }
+
+    if entry_type == "compaction" || entry_type == "branch_summary" {
+        let summary = value
+            .get("summary")
+            .and_then(Value::as_str)
+            .map(ToString::to_string);
+        return Some(TimelineEntry::Compact { summary });
+    }
+
+    None
+}
+
+fn extract_amp_entries(
+    path: &Path,
+    raw_json: &str,
+    mut tools: Option<&mut ToolTracker>,
+) -> Result<Vec<TimelineEntry>> {
+    let value =
+        serde_json::from_str::<Value>(raw_json).map_err(|source| XurlError::InvalidJsonLine {
+            path: path.to_path_buf(),
+            line: 1,
+            source,
+        })?;
+
+    let mut entries = Vec::new();
+    for message in value
+        .get("messages")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+    {
+        let Some(role) = message
+            .get("role")
+            .and_then(Value::as_str)
+            .and_then(parse_role)
+        else {
+            continue;
+        };
+
+        let (text, reasoning) = extract_amp_text(message.get("content"));
+        if !text.trim().is_empty() {
+            let mut record = ThreadMessage::new(role, text);
+            record.reasoning = reasoning;
+            record.timestamp = record_timestamp(message.pointer("/meta/sentAt"))
+                .or_else(|| record_timestamp(message.pointer("/usage/timestamp")));
+            record.model = string_at(message, "/usage/model");
+            record.usage = message.get("usage").and_then(|usage| {
+                usage_from(
+                    usage,
+                    UsageKeys {
+                        input: "/inputTokens",
+                        output: "/outputTokens",
+                        cache_read: "/cacheReadInputTokens",
+                        cache_write: "/cacheCreationInputTokens",
+                        reasoning: "",
+                        total: "",
+                        cost: "",
+                    },
+                )
+            });
+            entries.push(TimelineEntry::Message(record));
+        }
+
+        if let Some(tools) = tools.as_deref_mut() {
+            tools.content_items(message.get("content"), &mut entries);
+        }
+    }
+
+    Ok(entries)
+}
+
+fn extract_gemini_entries(
+    flavor: &GeminiFlavor,
+    path: &Path,
+    raw_json: &str,
+    mut tools: Option<&mut ToolTracker>,
+) -> Result<Vec<TimelineEntry>> {
+    let value =
+        serde_json::from_str::<Value>(raw_json).map_err(|source| XurlError::InvalidJsonLine {
+            path: path.to_path_buf(),
+            line: 1,
+            source,
+        })?;
+
+    let mut entries = Vec::new();
+    for message in value
+        .get("messages")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+    {
+        let Some(role) = message
+            .get("type")
+            .and_then(Value::as_str)
+            .and_then(|role| parse_gemini_role(flavor, role))
+        else {
+            continue;
+        };
+
+        let text = extract_text(message.get("displayContent"));
+        let text = if text.trim().is_empty() {
+            extract_text(message.get("content"))
+        } else {
+            text
+        };
+
+        if !text.trim().is_empty() {
+            let mut record = ThreadMessage::new(role, text);
+            record.timestamp = record_timestamp(message.get("timestamp"));
+            record.model = string_at(message, "/model");
+            record.usage = message.get("tokens").and_then(|tokens| {
+                usage_from(
+                    tokens,
+                    UsageKeys {
+                        input: "/input",
+                        output: "/output",
+                        cache_read: "/cached",
+                        cache_write: "",
+                        reasoning: "/thoughts",
+                        total: "/total",
+                        cost: "",
+                    },
+                )
+            });
+            entries.push(TimelineEntry::Message(record));
+        }
+
+        if let Some(tools) = tools.as_deref_mut() {
+            tools.gemini_tool_calls(message, &mut entries);
+        }
+    }
+
+    Ok(entries)
+}
+
+/// Cline and Roo Code keep the API transcript as a JSON array of Anthropic
+/// messages and log checkpoints in the sibling `ui_messages.json`; each
+/// checkpoint follows the message it was taken after.
+fn extract_cline_entries(
+    path: &Path,
+    raw_json: &str,
+    mut tools: Option<&mut ToolTracker>,
+) -> Result<Vec<TimelineEntry>> {
+    let messages = parse_json_array(path, raw_json)?;
+    let mut checkpoints = cline_checkpoints(path, &messages);
+
+    let mut entries = Vec::new();
+    for (idx, message) in messages.iter().enumerate() {
+        if let Some(role) = message
+            .get("role")
+            .and_then(Value::as_str)
+            .and_then(parse_role)
+        {
+            let (text, reasoning) = extract_cline_text(role, message.get("content"));
+            if !text.is_empty() {
+                let mut record = ThreadMessage::new(role, text);
+                record.reasoning = reasoning;
+                record.timestamp = record_timestamp(message.get("ts"));
+                entries.push(TimelineEntry::Message(record));
+            }
+            if let Some(tools) = tools.as_deref_mut() {
+                tools.content_items(message.get("content"), &mut entries);
+                tools.cline_xml(role, message.get("content"), &mut entries);
+            }
+        }
+
+        for hash in checkpoints.remove(&idx).into_iter().flatten() {
+            entries.push(TimelineEntry::Checkpoint { hash });
+        }
+    }
+
+    Ok(entries)
+}
+
+/// Checkpoint hashes from `ui_messages.json`, keyed by the index of the API
+/// message they follow. Cline records that index on the UI message; Roo Code
+/// checkpoints are placed by timestamp instead.
+fn cline_checkpoints(path: &Path, messages: &[Value]) -> BTreeMap<usize, Vec<String>> {
+    let mut checkpoints = BTreeMap::<usize, Vec<String>>::new();
+    let Some(last) = messages.len().checked_sub(1) else {
+        return checkpoints;
+    };
+    let ui_messages = fs::read_to_string(path.with_file_name(UI_MESSAGES_FILE))
+        .ok()
+        .and_then(|raw| serde_json::from_str::<Value>(&raw).ok());
+
+    for ui in ui_messages
+        .as_ref()
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+    {
+        let hash = match ui.get("say").and_then(Value::as_str) {
+            Some("checkpoint_created") => string_at(ui, "/lastCheckpointHash"),
+            Some("checkpoint_saved") => string_at(ui, "/text"),
+            _ => None,
+        };
+        let Some(hash) = hash else {
+            continue;
+        };
+
+        let position = ui
+            .get("conversationHistoryIndex")
+            .and_then(Value::as_u64)
+            .map(|idx| idx as usize)
+            .or_else(|| {
+                let ts = ui.get("ts").and_then(Value::as_i64)?;
+                let before = messages
+                    .iter()
+                    .take_while(|message| {
+                        message
+                            .get("ts")
+                            .and_then(Value::as_i64)
+                            .is_some_and(|message_ts| message_ts <= ts)
+                    })
+                    .count();
+                Some(before.saturating_sub(1))
+            })
+            .unwrap_or(last)
+            .min(last);
+        checkpoints.entry(position).or_default().push(hash);
+    }
+    checkpoints
+}
+
+/// Message text without the `<environment_details>` block appended to user
+/// turns, plus any thinking or reasoning chunks. XML tool calls and their
+/// `Result:` blocks are left to the tool entries, except for the text of
+/// replies such as `attempt_completion`.
+fn extract_cline_text(role: MessageRole, content: Option<&Value>) -> (String, Vec<String>) {
+    let items = match content {
+        Some(Value::String(text)) => return (clean_cline_text(role, text), Vec::new()),
+        Some(Value::Array(items)) => items,
+        _ => return (String::new(), Vec::new()),
+    };
+
+    let mut chunks = Vec::new();
+    let mut reasoning = Vec::new();
+    let mut in_result = false;
+    for item in items {
+        let (text, is_reasoning) = match item.get("type").and_then(Value::as_str) {
+            Some("text") => (item.get("text").and_then(Value::as_str), false),
+            Some("thinking") => (item.get("thinking").and_then(Value::as_str), true),
+            Some("reasoning") => (item.get("text").and_then(Value::as_str), true),
+            _ => (None, false),
+        };
+        if role == MessageRole::User
+            && !is_reasoning
+            && let Some(text) = text
+        {
+            // A result header is followed by the output unless it carries it.
+            if std::mem::take(&mut in_result) {
+                continue;
+            }
+            if let Some((_, output)) = cline_tool_result(text) {
+                in_result = output.is_empty();
+                continue;
+            }
+        }
+        let text = text
+            .map(|text| clean_cline_text(role, text))
+            .unwrap_or_default();
+        if text.is_empty() {
+            continue;
+        }
+        if is_reasoning {
+            reasoning.push(text.clone());
+        }
+        chunks.push(text);
+    }
+
+    (chunks.join("\n\n"), reasoning)
+}
+
+fn clean_cline_text(role: MessageRole, text: &str) -> String {
+    let mut cleaned = text.to_string();
+    if role == MessageRole::Assistant {
+        for (name, args, range) in cline_xml_calls(text).into_iter().rev() {
+            let reply = CLINE_REPLY_PARAMS
+                .iter()
+                .find(|(tool, _)| *tool == name)
+                .and_then(|(_, param)| args.get(*param))
+                .and_then(Value::as_str)
+                .unwrap_or_default();
+            cleaned.replace_range(range, reply);
+        }
+    }
+    while let Some(start) = cleaned.find("<environment_details>") {
+        let end = cleaned[start..]
+            .find("</environment_details>")
+            .map_or(cleaned.len(), |end| {
+                start + end + "</environment_details>".len()
+            });
+        cleaned.replace_range(start..end, "");
+    }
+    for tag in ["<task>", "</task>", "<feedback>", "</feedback>"] {
+        cleaned = cleaned.replace(tag, "");
+    }
+    cleaned.trim().to_string()
+}
+
+/// `(name, args, range)` for each XML tool call in assistant text, e.g.
+/// `<read_file>\n<path>src/lib.rs</path>\n</read_file>`. Each parameter is a
+/// child element; a call cut off before its closing tag runs to the end.
+fn cline_xml_calls(text: &str) -> Vec<(&'static str, Value, Range<usize>)> {
+    let mut calls = Vec::new();
+    let mut offset = 0;
+    while let Some(open) = text[offset..].find('<').map(|open| offset + open) {
+        offset = open + 1;
+        let Some(name) = xml_tag(&text[open..])
+            .and_then(|tag| CLINE_XML_TOOLS.iter().find(|name| **name == tag))
+        else {
+            continue;
+        };
+        let body = open + name.len() + 2;
+        let close = format!("</{name}>");
+        let (inner, end) = match text[body..].find(&close) {
+            Some(at) => (&text[body..body + at], body + at + close.len()),
+            None => (&text[body..], text.len()),
+        };
+        calls.push((*name, Value::Object(xml_params(inner)), open..end));
+        offset = end;
+    }
+    calls
+}
+
+/// Child elements of an XML tool call as string arguments. File contents
+/// and diffs run to their last closing tag, since they may hold tags too.
+fn xml_params(inner: &str) -> serde_json::Map<String, Value> {
+    let mut params = serde_json::Map::new();
+    let mut offset = 0;
+    while let Some(open) = inner[offset..].find('<').map(|open| offset + open) {
+        offset = open + 1;
+        let Some(tag) = xml_tag(&inner[open..]) else {
+            continue;
+        };
+        let start = open + tag.len() + 2;
+        let close = format!("</{tag}>");
+        let at = if matches!(tag, "content" | "diff") {
+            inner[start..].rfind(&close)
+        } else {
+            inner[start..].find(&close)
+        };
+        let Some(at) = at else {
+            continue;
+        };
+        let value = &inner[start..start + at];
+        let value = if matches!(tag, "content" | "diff") {
+            let value = value.strip_prefix('\n').unwrap_or(value);
+            value.strip_suffix('\n').unwrap_or(value)
+        } else {
+            value.trim()
+        };
+        params.insert(tag.to_string(), Value::String(value.to_string()));
+        offset = start + at + close.len();
+    }
+    params
+}
+
+/// The name of the opening tag `text` starts with, if it is a plain
+/// `<snake_case>` tag.
+fn xml_tag(text: &str) -> Option<&str> {
+    let rest = text.strip_prefix('<')?;
+    let name = &rest[..rest.find('>')?];
+    (!name.is_empty()
+        && name
+            .chars()
+            .all(|ch| ch.is_ascii_lowercase() || ch.is_ascii_digit() || ch == '_'))
+    .then_some(name)
+}
+
+/// `(tool, output)` for a `[read_file for 'src/lib.rs'] Result:` block. The
+/// output is empty when it follows in the next text block.
+fn cline_tool_result(text: &str) -> Option<(&str, &str)> {
+    let header = text.trim_start().strip_prefix('[')?;
+    let end = header.find("] Result:")?;
+    let name = header[..end].split(' ').next()?;
+    if !CLINE_XML_TOOLS.contains(&name) {
+        return None;
+    }
+    Some((name, header[end + "] Result:".len()..].trim()))
+}
+
+/// The text blocks of a Cline message, in order.
+fn cline_texts(content: Option<&Value>) -> Vec<&str> {
+    match content {
+        Some(Value::String(text)) => vec![text.as_str()],
+        Some(Value::Array(items)) => items
+            .iter()
+            .filter(|item| item.get("type").and_then(Value::as_str) == Some("text"))
+            .filter_map(|item| item.get("text").and_then(Value::as_str))
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// A run of consecutive lines of one kind in an Aider chat log.
+enum AiderBlock {
+    User(Vec<String>),
+    Reply(Vec<String>),
+    Output(Vec<String>),
+}
+
+/// Aider's markdown chat log: `#### ` lines are the user's prompt, `> `
+/// lines are Aider's own output and everything else is the model's reply.
+/// SEARCH/REPLACE blocks in a reply become `edit` tool entries (`write` when
+/// SEARCH is empty), failed unless Aider reported `Applied edit to <path>`.
+fn extract_aider_entries(raw: &str, mut tools: Option<&mut ToolTracker>) -> Vec<TimelineEntry> {
+    let mut blocks = Vec::<AiderBlock>::new();
+    for line in raw.lines() {
+        if line.starts_with(AIDER_SESSION_HEADER) {
+            continue;
+        }
+        if let Some(prompt) = line.strip_prefix("####") {
+            let prompt = prompt.strip_prefix(' ').unwrap_or(prompt).to_string();
+            match blocks.last_mut() {
+                Some(AiderBlock::User(lines)) => lines.push(prompt),
+                _ => blocks.push(AiderBlock::User(vec![prompt])),
+            }
+        } else if line == ">" || line.starts_with("> ") {
+            // Not `>>>>>>> REPLACE`, which closes an edit block in a reply.
+            let output = line[1..].trim().to_string();
+            match blocks.last_mut() {
+                Some(AiderBlock::Output(lines)) => lines.push(output),
+                _ => blocks.push(AiderBlock::Output(vec![output])),
+            }
+        } else {
+            match blocks.last_mut() {
+                Some(AiderBlock::Reply(lines)) => lines.push(line.to_string()),
+                _ if line.trim().is_empty() => {}
+                _ => blocks.push(AiderBlock::Reply(vec![line.to_string()])),
+            }
+        }
+    }
+
+    let mut entries = Vec::new();
+    for (position, block) in blocks.iter().enumerate() {
+        let (role, lines) = match block {
+            AiderBlock::User(lines) => (MessageRole::User, lines),
+            AiderBlock::Reply(lines) => (MessageRole::Assistant, lines),
+            AiderBlock::Output(_) => continue,
+        };
+        let text = lines.join("\n").trim().to_string();
+        if !text.is_empty() {
+            entries.push(TimelineEntry::Message(ThreadMessage::new(
+                role,
+                text.clone(),
+            )));
+        }
+
+        let Some(tools) = tools.as_deref_mut() else {
+            continue;
+        };
+        if role != MessageRole::Assistant {
+            continue;
+        }
+        // Aider reports what it applied right after the reply.
+        let output = blocks[position + 1..]
+            .iter()
+            .map_while(|block| match block {
+                AiderBlock::Output(lines) => Some(lines),
+                _ => None,
+            })
+            .flatten()
+            .collect::<Vec<_>>();
+        for (path, search, replace) in aider_edit_blocks(&text) {
+            let applied = format!("Applied edit to {path}");
+            let (name, args) = if search.is_empty() {
+                ("write", json!({ "file_path": path, "content": replace }))
+            } else {
+                (
+                    "edit",
+                    json!({ "file_path": path, "old_string": search, "new_string": replace }),
+                )
+            };
+            if output.iter().any(|line| **line == applied) {
+                tools.finished(&mut entries, name, args, applied, false);
+            } else {
+                tools.finished(&mut entries, name, args, "Not applied".to_string(), true);
+            }
+        }
+    }
+    entries
+}
+
+/// `(path, search, replace)` for each SEARCH/REPLACE block in a reply. The
+/// path is the last plain line before the block, skipping code fences.
+fn aider_edit_blocks(reply: &str) -> Vec<(String, String, String)> {
+    let mut edits = Vec::new();
+    let mut path = None::<String>;
+    let mut current = None::<(Vec<&str>, Option<Vec<&str>>)>;
+
+    for line in reply.lines() {
+        let marker = line.trim();
+        match current.as_mut() {
+            None if marker.starts_with("<<<<<<< SEARCH") => current = Some((Vec::new(), None)),
+            None if marker.is_empty() || marker.starts_with("```") => {}
+            None => path = Some(marker.trim_matches(['`', '*', ':']).trim().to_string()),
+            Some((_, replace @ None)) if marker == "=======" => *replace = Some(Vec::new()),
+            Some((search, None)) => search.push(line),
+            Some((search, Some(replace))) if marker.starts_with(">>>>>>> REPLACE") => {
+                if let Some(path) = path.clone().filter(|path| !path.is_empty()) {
+                    edits.push((path, join_lines(search), join_lines(replace)));
+                }
+                current = None;
+            }
+            Some((_, Some(replace))) => replace.push(line),
+        }
+    }
+    edits
+}
+
+/// Lines joined back into file content, with the trailing newline edits
+/// of whole lines carry.
+fn join_lines(lines: &[&str]) -> String {
+    if lines.is_empty() {
+        String::new()
+    } else {
+        format!("{}\n", lines.join("\n"))
+    }
+}
+
+fn parse_json_array(path: &Path, raw_json: &str) -> Result<Vec<Value>> {
+    let value =
+        serde_json::from_str::<Value>(raw_json).map_err(|source| XurlError::InvalidJsonLine {
+            path: path.to_path_buf(),
+            line: 1,
+            source,
+        })?;
+    Ok(match value {
+        Value::Array(items) => items,
+        _ => Vec::new(),
+    })
+}
+
+/// Turns tool invocations into timeline entries and attaches each result to
+/// the invocation with the same call id. Results whose invocation is not in
+/// view are kept as entries of their own.
+struct ToolTracker {
+    max_output: usize,
+    by_call_id: HashMap<String, usize>,
+    /// Cline XML calls still waiting for their result, oldest first.
+    unanswered: Vec<usize>,
+    /// Calls made in an earlier chunk whose result is still to come.
+    earlier: HashSet<String>,
+}
+
+impl ToolTracker {
+    fn new(max_output: usize) -> Self {
+        Self::resuming(max_output, &[])
+    }
+
+    fn resuming(max_output: usize, open_calls: &[String]) -> Self {
+        Self {
+            max_output,
+            by_call_id: HashMap::new(),
+            unanswered: Vec::new(),
+            earlier: open_calls.iter().cloned().collect(),
+        }
+    }
+
+    /// Ids of calls still waiting for their result after `entries`.
+    fn open_calls(&self, entries: &[TimelineEntry]) -> Vec<String> {
+        let mut open = self
+            .by_call_id
+            .iter()
+            .filter(|(_, idx)| {
+                matches!(entries.get(**idx), Some(TimelineEntry::Tool(tool)) if tool.output.is_none())
+            })
+            .map(|(call_id, _)| call_id.clone())
+            .chain(self.earlier.iter().cloned())
+            .collect::<Vec<_>>();
+        open.sort();
+        open
+    }
+
+    fn call(
+        &mut self,
+        entries: &mut Vec<TimelineEntry>,
+        name: &str,
+        call_id: Option<&str>,
+        args: Value,
+    ) {
+        if let Some(call_id) = call_id {
+            self.by_call_id.insert(call_id.to_string(), entries.len());
+        }
+        entries.push(TimelineEntry::Tool(ToolEntry {
+            name: name.to_string(),
+            call_id: call_id.map(ToString::to_string),
+            args,
+            output: None,
+            is_error: false,
+            truncated: false,
+        }));
+    }
+
+    /// An invocation whose result is already known, without a call id.
+    fn finished(
+        &mut self,
+        entries: &mut Vec<TimelineEntry>,
+        name: &str,
+        args: Value,
+        output: String,
+        is_error: bool,
+    ) {
+        let (output, truncated) = truncate_chars(output, self.max_output);
+        entries.push(TimelineEntry::Tool(ToolEntry {
+            name: name.to_string(),
+            call_id: None,
+            args,
+            output: Some(output),
+            is_error,
+            truncated,
+        }));
+    }
+
+    fn result(
+        &mut self,
+        entries: &mut Vec<TimelineEntry>,
+        call_id: Option<&str>,
+        output: String,
+        is_error: bool,
+    ) {
+        if call_id.is_some_and(|call_id| self.earlier.remove(call_id)) {
+            return;
+        }
+        let (output, truncated) = truncate_chars(output, self.max_output);
+        let target = call_id
+            .and_then(|call_id| self.by_call_id.get(call_id))
+            .and_then(|idx| entries.get_mut(*idx));
+        if let Some(TimelineEntry::Tool(tool)) = target {
+            tool.output = Some(output);
+            tool.is_error = is_error;
+            tool.truncated = truncated;
+            return;
+        }
+
+        entries.push(TimelineEntry::Tool(ToolEntry {
+            name: "unknown".to_string(),
+            call_id: call_id.map(ToString::to_string),
+            args: Value::Null,
+            output: Some(output),
+            is_error,
+            truncated,
+        }));
+    }
+
+    /// Codex `function_call` / `custom_tool_call` and their `*_output` records.
+    fn codex_record(&mut self, value: &Value, entries: &mut Vec<TimelineEntry>) {
+        if value.get("type").and_then(Value::as_str) != Some("response_item") {
+            return;
+        }
+        let Some(payload) = value.get("payload") else {
+            return;
+        };
+        let call_id = payload.get("call_id").and_then(Value::as_str);
+
+        match payload.get("type").and_then(Value::as_str) {
+            Some("function_call") => {
+                let args = match payload.get("arguments") {
+                    Some(Value::String(raw)) => serde_json::from_str::<Value>(raw)
+                        .unwrap_or_else(|_| Value::String(raw.clone())),
+                    Some(args) => args.clone(),
+                    None => Value::Null,
+                };
+                self.call(entries, tool_name(payload, "name"), call_id, args);
+            }
+            Some("custom_tool_call") => {
+                let args = payload.get("input").cloned().unwrap_or(Value::Null);
+                self.call(entries, tool_name(payload, "name"), call_id, args);
+            }
+            Some("function_call_output" | "custom_tool_call_output") => {
+                let output = codex_tool_output(payload.get("output"));
+                self.result(entries, call_id, output, false);
+            }
+            _ => {}
+        }
+    }
+
+    /// Claude / Amp `tool_use` and `tool_result` content items.
+    fn content_items(&mut self, content: Option<&Value>, entries: &mut Vec<TimelineEntry>) {
+        for item in content.and_then(Value::as_array).into_iter().flatten() {
+            match item.get("type").and_then(Value::as_str) {
+                Some("tool_use") => {
+                    let args = item.get("input").cloned().unwrap_or(Value::Null);
+                    let call_id = item.get("id").and_then(Value::as_str);
+                    self.call(entries, tool_name(item, "name"), call_id, args);
+                }
+                Some("tool_result") => {
+                    let call_id = item
+                        .get("tool_use_id")
+                        .or_else(|| item.get("toolUseID"))
+                        .and_then(Value::as_str);
+                    let (output, is_error) = match item.get("run") {
+                        Some(run) => (
+                            value_text(run.get("result").or_else(|| run.get("error"))),
+                            run.get("status").and_then(Value::as_str) == Some("error"),
+                        ),
+                        None => (
+                            value_text(item.get("content")),
+                            item.get("is_error").and_then(Value::as_bool) == Some(true),
+                        ),
+                    };
+                    self.result(entries, call_id, output, is_error);
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Cline and older Roo Code XML tool calls in assistant text, and the
+    /// `[tool for '…'] Result:` blocks answering them in the next user
+    /// message. Neither has an id, so a result goes to the oldest unanswered
+    /// call of the same tool.
+    fn cline_xml(
+        &mut self,
+        role: MessageRole,
+        content: Option<&Value>,
+        entries: &mut Vec<TimelineEntry>,
+    ) {
+        let texts = cline_texts(content);
+        if role == MessageRole::Assistant {
+            for text in texts {
+                for (name, args, _) in cline_xml_calls(text) {
+                    self.unanswered.push(entries.len());
+                    self.call(entries, name, None, args);
+                }
+            }
+            return;
+        }
+
+        let mut texts = texts.into_iter();
+        while let Some(text) = texts.next() {
+            let Some((name, output)) = cline_tool_result(text) else {
+                continue;
+            };
+            let output = match output {
+                "" => texts.next().unwrap_or_default(),
+                output => output,
+            };
+            let is_error = CLINE_TOOL_ERRORS
+                .iter()
+                .any(|prefix| output.starts_with(prefix));
+            let call = self.unanswered.iter().position(|idx| {
+                matches!(entries.get(*idx), Some(TimelineEntry::Tool(tool)) if tool.name == name)
+            });
+            let target = call
+                .map(|position| self.unanswered.remove(position))
+                .and_then(|idx| entries.get_mut(idx));
+            if let Some(TimelineEntry::Tool(tool)) = target {
+                let (output, truncated) = truncate_chars(output.to_string(), self.max_output);
+                tool.output = Some(output);
+                tool.is_error = is_error;
+                tool.truncated = truncated;
+            } else {
+                self.finished(entries, name, Value::Null, output.to_string(), is_error);
+            }
+        }
+    }
+
+    /// OpenCode `tool` parts, which carry their result in `state`.
+    fn opencode_parts(&mut self, value: &Value, entries: &mut Vec<TimelineEntry>) {
+        for part in value
+            .get("parts")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+        {
+            if part.get("type").and_then(Value::as_str) != Some("tool") {
+                continue;
+            }
+            let call_id = part.get("callID").and_then(Value::as_str);
+            let args = part.pointer("/state/input").cloned().unwrap_or(Value::Null);
+            self.call(entries, tool_name(part, "tool"), call_id, args);
+
+            let is_error = part.pointer("/state/status").and_then(Value::as_str) == Some("error");
+            let output = part
+                .pointer("/state/output")
+                .or_else(|| part.pointer("/state/error"));
+            if output.is_some() {
+                self.result(entries, call_id, value_text(output), is_error);
+            }
+        }
+    }
+
+    /// Goose `toolRequest` / `toolResponse` content items. Both wrap their
+    /// payload as `{"status": "success", "value": ...}` or
+    /// `{"status": "error", "error": ...}`.
+    fn goose_content(&mut self, content: Option<&Value>, entries: &mut Vec<TimelineEntry>) {
+        for item in content.and_then(Value::as_array).into_iter().flatten() {
+            let call_id = item.get("id").and_then(Value::as_str);
+            match item.get("type").and_then(Value::as_str) {
+                Some("toolRequest") => {
+                    let call = item.pointer("/toolCall/value");
+                    let name = call.map_or("unknown", |call| tool_name(call, "name"));
+                    let args = call
+                        .and_then(|call| call.get("arguments"))
+                        .cloned()
+                        .unwrap_or(Value::Null);
+                    self.call(entries, name, call_id, args);
+                    if let Some(error) = item.pointer("/toolCall/error") {
+                        self.result(entries, call_id, value_text(Some(error)), true);
+                    }
+                }
+                Some("toolResponse") => {
+                    let result = item.get("toolResult");
+                    let (output, is_error) = match result.and_then(|result| result.get("value")) {
+                        // Newer releases store the whole MCP `CallToolResult`.
+                        Some(value @ Value::Object(_)) => (
+                            value_text(value.get("content")),
+                            value.get("isError").and_then(Value::as_bool) == Some(true),
+                        ),
+                        Some(value) => (value_text(Some(value)), false),
+                        None => (
+                            value_text(result.and_then(|result| result.get("error"))),
+                            true,
+                        ),
+                    };
+                    self.result(entries, call_id, output, is_error);
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Cursor `toolFormerData`, which carries a call and its result on the
+    /// same bubble.
+    fn cursor_bubble(&mut self, value: &Value, entries: &mut Vec<TimelineEntry>) {
+        let Some(tool) = value
+            .get("toolFormerData")
+            .filter(|tool| tool.get("name").is_some())
+        else {
+            return;
+        };
+        let call_id = tool.get("toolCallId").and_then(Value::as_str);
+        self.call(
+            entries,
+            tool_name(tool, "name"),
+            call_id,
+            cursor_tool_args(tool),
+        );
+
+        if let Some(result) = tool.get("result").filter(|result| !result.is_null()) {
+            let is_error = tool.get("status").and_then(Value::as_str) == Some("error");
+            self.result(entries, call_id, cursor_tool_output(result), is_error);
+        }
+    }
+
+    /// Pi `toolCall` content items and `toolResult` messages.
+    fn pi_entry(&mut self, value: &Value, entries: &mut Vec<TimelineEntry>) {
+        if value.get("type").and_then(Value::as_str) != Some("message") {
+            return;
+        }
+        let Some(message) = value.get("message") else {
+            return;
+        };
+
+        if message.get("role").and_then(Value::as_str) == Some("toolResult") {
+            let call_id = message.get("toolCallId").and_then(Value::as_str);
+            let is_error = message.get("isError").and_then(Value::as_bool) == Some(true);
+            self.result(
+                entries,
+                call_id,
+                value_text(message.get("content")),
+                is_error,
+            );
+            return;
         }
 
-        return Some(TimelineEntry::Message(ThreadMessage { role, text }));
+        for item in message
+            .get("content")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+        {
+            if item.get("type").and_then(Value::as_str) == Some("toolCall") {
+                let args = item.get("arguments").cloned().unwrap_or(Value::Null);
+                let call_id = item.get("id").and_then(Value::as_str);
+                self.call(entries, tool_name(item, "name"), call_id, args);
+            }
+        }
     }
 
-    if entry_type == "compaction" || entry_type == "branch_summary" {
-        let summary = value
-            .get("summary")
-            .and_then(Value::as_str)
-            .map(ToString::to_string);
-        return Some(TimelineEntry::Compact { summary });
+    /// Gemini `toolCalls`, which carry their result inline.
+    fn gemini_tool_calls(&mut self, message: &Value, entries: &mut Vec<TimelineEntry>) {
+        for call in message
+            .get("toolCalls")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+        {
+            let call_id = call.get("id").and_then(Value::as_str);
+            let args = call.get("args").cloned().unwrap_or(Value::Null);
+            self.call(entries, tool_name(call, "name"), call_id, args);
+
+            let response = call
+                .get("result")
+                .and_then(Value::as_array)
+                .and_then(|items| items.first())
+                .and_then(|item| item.pointer("/functionResponse/response"));
+            let output = response
+                .and_then(|response| response.get("output").or_else(|| response.get("error")))
+                .or_else(|| call.get("resultDisplay"));
+            if output.is_some() {
+                let is_error = call.get("status").and_then(Value::as_str) == Some("error");
+                self.result(entries, call_id, value_text(output), is_error);
+            }
+        }
     }
+}
 
-    None
+fn tool_name<'a>(value: &'a Value, key: &str) -> &'a str {
+    value.get(key).and_then(Value::as_str).unwrap_or("unknown")
 }
 
-fn extract_amp_messages(path: &Path, raw_json: &str) -> Result<Vec<ThreadMessage>> {
-    let value =
-        serde_json::from_str::<Value>(raw_json).map_err(|source| XurlError::InvalidJsonLine {
-            path: path.to_path_buf(),
-            line: 1,
-            source,
-        })?;
+/// Cursor records tool arguments as JSON text, first as streamed by the
+/// model (`rawArgs`) and then as validated (`params`).
+fn cursor_tool_args(tool: &Value) -> Value {
+    tool.get("rawArgs")
+        .or_else(|| tool.get("params"))
+        .map_or(Value::Null, json_in_string)
+}
 
-    let mut messages = Vec::new();
-    for message in value
-        .get("messages")
-        .and_then(Value::as_array)
-        .into_iter()
-        .flatten()
+/// Cursor results are JSON text too; terminal and file reads keep the text
+/// itself under `output` or `contents`.
+fn cursor_tool_output(result: &Value) -> String {
+    let result = json_in_string(result);
+    match ["output", "contents"]
+        .iter()
+        .find_map(|key| result.get(key).and_then(Value::as_str))
     {
-        let Some(role) = message
-            .get("role")
-            .and_then(Value::as_str)
-            .and_then(parse_role)
-        else {
-            continue;
-        };
+        Some(text) => text.to_string(),
+        None => value_text(Some(&result)),
+    }
+}
 
-        let text = extract_amp_text(message.get("content"));
-        if text.trim().is_empty() {
-            continue;
+/// The JSON encoded in a string value, or the value itself when it is not
+/// a string holding JSON.
+fn json_in_string(value: &Value) -> Value {
+    match value {
+        Value::String(raw) => {
+            serde_json::from_str::<Value>(raw).unwrap_or_else(|_| Value::String(raw.clone()))
         }
-
-        messages.push(ThreadMessage { role, text });
+        other => other.clone(),
     }
-
-    Ok(messages)
 }
 
-fn extract_gemini_messages(path: &Path, raw_json: &str) -> Result<Vec<ThreadMessage>> {
-    let value =
-        serde_json::from_str::<Value>(raw_json).map_err(|source| XurlError::InvalidJsonLine {
-            path: path.to_path_buf(),
-            line: 1,
-            source,
-        })?;
-
-    let mut messages = Vec::new();
-    for message in value
-        .get("messages")
-        .and_then(Value::as_array)
-        .into_iter()
-        .flatten()
+/// Codex wraps shell output as `{"output": ..., "metadata": ...}` encoded in a string.
+fn codex_tool_output(output: Option<&Value>) -> String {
+    if let Some(Value::String(raw)) = output
+        && let Ok(Value::Object(object)) = serde_json::from_str::<Value>(raw)
+        && let Some(inner) = object.get("output").and_then(Value::as_str)
     {
-        let Some(role) = message
-            .get("type")
-            .and_then(Value::as_str)
-            .and_then(parse_gemini_role)
-        else {
-            continue;
-        };
-
-        let text = extract_text(message.get("displayContent"));
-        let text = if text.trim().is_empty() {
-            extract_text(message.get("content"))
-        } else {
-            text
-        };
+        return inner.to_string();
+    }
+    value_text(output)
+}
 
-        if text.trim().is_empty() {
-            continue;
+/// Flatten a tool result (string, text items, or arbitrary JSON) into text.
+fn value_text(value: Option<&Value>) -> String {
+    match value {
+        None | Some(Value::Null) => String::new(),
+        Some(Value::String(text)) => text.clone(),
+        Some(Value::Array(_)) => {
+            let text = extract_text(value);
+            if text.is_empty() {
+                value.map(Value::to_string).unwrap_or_default()
+            } else {
+                text
+            }
         }
-
-        messages.push(ThreadMessage { role, text });
+        Some(other) => serde_json::to_string_pretty(other).unwrap_or_default(),
     }
+}
 
-    Ok(messages)
+fn truncate_chars(text: String, max_chars: usize) -> (String, bool) {
+    match text.char_indices().nth(max_chars) {
+        Some((cut, _)) => (text[..cut].to_string(), true),
+        None => (text, false),
+    }
 }
 
 fn extract_codex_message(value: &Value) -> Option<ThreadMessage> {
@@ -560,7 +1892,9 @@ fn extract_codex_message(value: &Value) -> Option<ThreadMessage> {
             return None;
         }
 
-        return Some(ThreadMessage { role, text });
+        let mut message = ThreadMessage::new(role, text);
+        message.timestamp = record_timestamp(value.get("timestamp"));
+        return Some(message);
     }
 
     if record_type == "event_msg"
@@ -581,10 +1915,9 @@ fn extract_codex_message(value: &Value) -> Option<ThreadMessage> {
             return None;
         }
 
-        return Some(ThreadMessage {
-            role: MessageRole::Assistant,
-            text,
-        });
+        let mut message = ThreadMessage::new(MessageRole::Assistant, text);
+        message.timestamp = record_timestamp(value.get("timestamp"));
+        return Some(message);
     }
 
     None
@@ -635,7 +1968,24 @@ fn extract_claude_message(value: &Value) -> Option<ThreadMessage> {
         return None;
     }
 
-    Some(ThreadMessage { role, text })
+    let mut record = ThreadMessage::new(role, text);
+    record.timestamp = record_timestamp(value.get("timestamp"));
+    record.model = string_at(message, "/model").filter(|model| model != "<synthetic>");
+    record.usage = message.get("usage").and_then(|usage| {
+        usage_from(
+            usage,
+            UsageKeys {
+                input: "/input_tokens",
+                output: "/output_tokens",
+                cache_read: "/cache_read_input_tokens",
+                cache_write: "/cache_creation_input_tokens",
+                reasoning: "",
+                total: "",
+                cost: "",
+            },
+        )
+    });
+    Some(record)
 }
 
 fn extract_claude_entry(value: &Value) -> Option<TimelineEntry> {
@@ -675,6 +2025,7 @@ fn extract_opencode_message(value: &Value) -> Option<ThreadMessage> {
     let role = parse_role(role)?;
 
     let mut chunks = Vec::new();
+    let mut reasoning = Vec::new();
     for part in value
         .get("parts")
         .and_then(Value::as_array)
@@ -693,6 +2044,9 @@ fn extract_opencode_message(value: &Value) -> Option<ThreadMessage> {
             && !text.trim().is_empty()
         {
             chunks.push(text.trim().to_string());
+            if part_type == "reasoning" {
+                reasoning.push(text.trim().to_string());
+            }
         }
     }
 
@@ -700,18 +2054,212 @@ fn extract_opencode_message(value: &Value) -> Option<ThreadMessage> {
         return None;
     }
 
-    Some(ThreadMessage {
-        role,
-        text: chunks.join("\n\n"),
-    })
+    let mut record = ThreadMessage::new(role, chunks.join("\n\n"));
+    record.reasoning = reasoning;
+    record.timestamp = record_timestamp(message.pointer("/time/created"));
+    record.model = string_at(message, "/modelID").or_else(|| string_at(message, "/model/modelID"));
+    record.usage = usage_from(
+        message,
+        UsageKeys {
+            input: "/tokens/input",
+            output: "/tokens/output",
+            cache_read: "/tokens/cache/read",
+            cache_write: "/tokens/cache/write",
+            reasoning: "/tokens/reasoning",
+            total: "",
+            cost: "/cost",
+        },
+    );
+    Some(record)
+}
+
+/// A Goose message line; the session metadata line and messages hidden from
+/// the user (such as compaction summaries) have no entry.
+fn extract_goose_message(value: &Value) -> Option<ThreadMessage> {
+    let role = parse_role(value.get("role").and_then(Value::as_str)?)?;
+    if value
+        .pointer("/metadata/userVisible")
+        .and_then(Value::as_bool)
+        == Some(false)
+    {
+        return None;
+    }
+
+    let (text, reasoning) = extract_amp_text(value.get("content"));
+    if text.trim().is_empty() {
+        return None;
+    }
+    let mut record = ThreadMessage::new(role, text);
+    record.reasoning = reasoning;
+    record.timestamp = record_timestamp(value.get("created"));
+    Some(record)
+}
+
+/// A Cursor bubble: `type` 1 is the user and 2 the assistant. Code blocks
+/// the assistant attached are appended unless its text already quotes
+/// them. The header line and bubbles that only carry a tool call have no
+/// entry.
+fn extract_cursor_message(value: &Value) -> Option<ThreadMessage> {
+    let role = match value.get("type").and_then(Value::as_i64)? {
+        1 => MessageRole::User,
+        2 => MessageRole::Assistant,
+        _ => return None,
+    };
+
+    let mut chunks = Vec::new();
+    let mut reasoning = Vec::new();
+    if let Some(thinking) = string_at(value, "/thinking/text") {
+        chunks.push(thinking.clone());
+        reasoning.push(thinking);
+    }
+    let text = value
+        .get("text")
+        .and_then(Value::as_str)
+        .map(str::trim)
+        .unwrap_or_default();
+    if !text.is_empty() {
+        chunks.push(text.to_string());
+    }
+    for block in value
+        .get("codeBlocks")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+    {
+        let Some(content) = block
+            .get("content")
+            .and_then(Value::as_str)
+            .map(str::trim_end)
+            .filter(|content| !content.trim().is_empty() && !text.contains(*content))
+        else {
+            continue;
+        };
+        let lang = block
+            .get("languageId")
+            .and_then(Value::as_str)
+            .unwrap_or_default();
+        chunks.push(match string_at(block, "/uri/path") {
+            Some(path) => format!("`{path}`\n\n{}", fenced(content, lang)),
+            None => fenced(content, lang),
+        });
+    }
+    if chunks.is_empty() {
+        return None;
+    }
+
+    let mut record = ThreadMessage::new(role, chunks.join("\n\n"));
+    record.reasoning = reasoning;
+    record.timestamp = record_timestamp(value.get("createdAt"));
+    record.model = string_at(value, "/modelInfo/modelName");
+    // Bubbles Cursor did not meter still carry a zeroed `tokenCount`.
+    record.usage = usage_from(
+        value,
+        UsageKeys {
+            input: "/tokenCount/inputTokens",
+            output: "/tokenCount/outputTokens",
+            cache_read: "",
+            cache_write: "",
+            reasoning: "",
+            total: "",
+            cost: "",
+        },
+    )
+    .filter(|usage| usage.input_tokens.unwrap_or(0) + usage.output_tokens.unwrap_or(0) > 0);
+    Some(record)
+}
+
+/// For a Codex `token_count` line, the usage it reports for the response
+/// before it; `None` for any other line.
+pub(crate) fn codex_token_count(line: &str) -> Option<Option<TokenUsage>> {
+    let value = serde_json::from_str::<Value>(line.trim()).ok()?;
+    is_codex_token_count(&value).then(|| codex_token_usage(&value))
+}
+
+fn is_codex_token_count(value: &Value) -> bool {
+    value.get("type").and_then(Value::as_str) == Some("event_msg")
+        && value.pointer("/payload/type").and_then(Value::as_str) == Some("token_count")
+}
+
+fn codex_token_usage(value: &Value) -> Option<TokenUsage> {
+    usage_from(
+        value.pointer("/payload/info/last_token_usage")?,
+        UsageKeys {
+            input: "/input_tokens",
+            output: "/output_tokens",
+            cache_read: "/cached_input_tokens",
+            cache_write: "",
+            reasoning: "/reasoning_output_tokens",
+            total: "/total_tokens",
+            cost: "",
+        },
+    )
+}
+
+/// JSON pointers for each usage field; an empty pointer means the provider
+/// does not record that field.
+struct UsageKeys {
+    input: &'static str,
+    output: &'static str,
+    cache_read: &'static str,
+    cache_write: &'static str,
+    reasoning: &'static str,
+    total: &'static str,
+    cost: &'static str,
+}
+
+fn usage_from(value: &Value, keys: UsageKeys) -> Option<TokenUsage> {
+    let count = |pointer: &str| {
+        (!pointer.is_empty())
+            .then(|| value.pointer(pointer))
+            .flatten()
+            .and_then(Value::as_u64)
+    };
+    let usage = TokenUsage {
+        input_tokens: count(keys.input),
+        output_tokens: count(keys.output),
+        cache_read_tokens: count(keys.cache_read),
+        cache_write_tokens: count(keys.cache_write),
+        reasoning_tokens: count(keys.reasoning),
+        total_tokens: count(keys.total),
+        cost: (!keys.cost.is_empty())
+            .then(|| value.pointer(keys.cost))
+            .flatten()
+            .and_then(Value::as_f64),
+    };
+
+    (!usage.is_empty()).then_some(usage)
+}
+
+fn string_at(value: &Value, pointer: &str) -> Option<String> {
+    value
+        .pointer(pointer)
+        .and_then(Value::as_str)
+        .map(str::trim)
+        .filter(|text| !text.is_empty())
+        .map(ToString::to_string)
+}
+
+/// Normalize an RFC 3339 string or an epoch (seconds or milliseconds) to
+/// RFC 3339. Values that are neither are dropped.
+fn record_timestamp(value: Option<&Value>) -> Option<String> {
+    match value? {
+        Value::String(text) => parse_rfc3339_epoch(text).map(|_| text.clone()),
+        Value::Number(number) => number
+            .as_i64()
+            .and_then(normalize_epoch)
+            .map(format_epoch_rfc3339),
+        _ => None,
+    }
 }
 
-fn extract_amp_text(content: Option<&Value>) -> String {
+/// Message text with thinking inline, plus the thinking chunks on their own.
+fn extract_amp_text(content: Option<&Value>) -> (String, Vec<String>) {
     let Some(items) = content.and_then(Value::as_array) else {
-        return String::new();
+        return (String::new(), Vec::new());
     };
 
     let mut chunks = Vec::new();
+    let mut reasoning = Vec::new();
     for item in items {
         let Some(item_type) = item.get("type").and_then(Value::as_str) else {
             continue;
@@ -730,13 +2278,14 @@ fn extract_amp_text(content: Option<&Value>) -> String {
                     && !thinking.trim().is_empty()
                 {
                     chunks.push(thinking.trim().to_string());
+                    reasoning.push(thinking.trim().to_string());
                 }
             }
             _ => {}
         }
     }
 
-    chunks.join("\n\n")
+    (chunks.join("\n\n"), reasoning)
 }
 
 fn parse_role(role: &str) -> Option<MessageRole> {
@@ -747,10 +2296,10 @@ fn parse_role(role: &str) -> Option<MessageRole> {
     }
 }
 
-fn parse_gemini_role(role: &str) -> Option<MessageRole> {
+fn parse_gemini_role(flavor: &GeminiFlavor, role: &str) -> Option<MessageRole> {
     match role {
         "user" => Some(MessageRole::User),
-        "gemini" => Some(MessageRole::Assistant),
+        _ if flavor.assistant_types.contains(&role) => Some(MessageRole::Assistant),
         _ => None,
     }
 }
@@ -812,7 +2361,7 @@ fn extract_text(content: Option<&Value>) -> String {
 mod tests {
     use std::path::Path;
 
-    use crate::model::ProviderKind;
+    use crate::model::{MessageRole, ProviderKind, TimelineOptions};
     use crate::render::{extract_messages, render_markdown};
     use crate::uri::ThreadUri;
 
@@ -821,7 +2370,13 @@ mod tests {
         let raw = r#"{"type":"response_item","payload":{"type":"message","role":"user","content":[{"type":"input_text","text":"hello"}]}}"#;
         let uri =
             ThreadUri::parse("codex://019c871c-b1f9-7f60-9c4f-87ed09f13592").expect("parse uri");
-        let output = render_markdown(&uri, Path::new("/tmp/mock"), raw).expect("render");
+        let output = render_markdown(
+            &uri,
+            Path::new("/tmp/mock"),
+            raw,
+            &TimelineOptions::default(),
+        )
+        .expect("render");
 
         assert!(output.starts_with("---\n"));
         assert!(output.contains("uri: 'agents://codex/019c871c-b1f9-7f60-9c4f-87ed09f13592'"));
@@ -864,6 +2419,7 @@ mod tests {
         assert_eq!(messages.len(), 2);
         assert_eq!(messages[0].text, "hello");
         assert_eq!(messages[1].text, "thinking\n\nworld");
+        assert_eq!(messages[1].reasoning, vec!["thinking"]);
     }
 
     #[test]
@@ -889,6 +2445,22 @@ mod tests {
         assert_eq!(messages[2].text, "step by step\n\ndone");
     }
 
+    #[test]
+    fn gemini_forks_use_their_own_assistant_role() {
+        let raw = r#"{"sessionId":"29d207db-ca7e-40ba-87f7-e14c9de60613","messages":[{"type":"user","content":"hello"},{"type":"qwen","content":"world"},{"type":"gemini","content":"older"}]}"#;
+
+        let qwen =
+            extract_messages(ProviderKind::Qwen, Path::new("/tmp/mock"), raw).expect("extract");
+        assert_eq!(qwen.len(), 3);
+        assert_eq!(qwen[1].role, MessageRole::Assistant);
+        assert_eq!(qwen[1].text, "world");
+
+        let gemini =
+            extract_messages(ProviderKind::Gemini, Path::new("/tmp/mock"), raw).expect("extract");
+        assert_eq!(gemini.len(), 2);
+        assert_eq!(gemini[1].text, "older");
+    }
+
     #[test]
     fn pi_default_leaf_renders_latest_branch() {
         let raw = r#"{"type":"session","version":3,"id":"12cb4c19-2774-4de4-a0d0-9fa32fbae29f","timestamp":"2026-02-23T13:00:12.780Z","cwd":"/tmp/project"}
@@ -901,7 +2473,13 @@ mod tests {
 {"type":"message","id":"g1b2c3d4","parentId":"f1b2c3d4","timestamp":"2026-02-23T13:00:19.000Z","message":{"role":"assistant","content":[{"type":"text","text":"branch two done"}]}}"#;
 
         let uri = ThreadUri::parse("pi://12cb4c19-2774-4de4-a0d0-9fa32fbae29f").expect("parse uri");
-        let output = render_markdown(&uri, Path::new("/tmp/mock"), raw).expect("render");
+        let output = render_markdown(
+            &uri,
+            Path::new("/tmp/mock"),
+            raw,
+            &TimelineOptions::default(),
+        )
+        .expect("render");
 
         assert!(output.contains("root"));
         assert!(output.contains("branch two"));
@@ -921,7 +2499,13 @@ mod tests {
 
         let uri = ThreadUri::parse("pi://12cb4c19-2774-4de4-a0d0-9fa32fbae29f/d1b2c3d4")
             .expect("parse uri");
-        let output = render_markdown(&uri, Path::new("/tmp/mock"), raw).expect("render");
+        let output = render_markdown(
+            &uri,
+            Path::new("/tmp/mock"),
+            raw,
+            &TimelineOptions::default(),
+        )
+        .expect("render");
 
         assert!(output.contains("branch one done"));
         assert!(!output.contains("branch two done"));
@@ -934,7 +2518,13 @@ mod tests {
 
         let uri = ThreadUri::parse("pi://12cb4c19-2774-4de4-a0d0-9fa32fbae29f/deadbeef")
             .expect("parse uri");
-        let err = render_markdown(&uri, Path::new("/tmp/mock"), raw).expect_err("must fail");
+        let err = render_markdown(
+            &uri,
+            Path::new("/tmp/mock"),
+            raw,
+            &TimelineOptions::default(),
+        )
+        .expect_err("must fail");
         assert!(format!("{err}").contains("entry not found"));
     }
 
@@ -946,7 +2536,13 @@ mod tests {
 
         let uri =
             ThreadUri::parse("codex://019c871c-b1f9-7f60-9c4f-87ed09f13592").expect("parse uri");
-        let output = render_markdown(&uri, Path::new("/tmp/mock"), raw).expect("render");
+        let output = render_markdown(
+            &uri,
+            Path::new("/tmp/mock"),
+            raw,
+            &TimelineOptions::default(),
+        )
+        .expect("render");
 
         assert!(output.contains("## 1. User"));
         assert!(output.contains("## 2. Context Compacted"));
@@ -961,7 +2557,13 @@ mod tests {
 
         let uri =
             ThreadUri::parse("claude://2823d1df-720a-4c31-ac55-ae8ba726721f").expect("parse uri");
-        let output = render_markdown(&uri, Path::new("/tmp/mock"), raw).expect("render");
+        let output = render_markdown(
+            &uri,
+            Path::new("/tmp/mock"),
+            raw,
+            &TimelineOptions::default(),
+        )
+        .expect("render");
 
         assert!(output.contains("## 1. Context Compacted"));
         assert!(output.contains("Summary: old conversation"));
@@ -1026,4 +2628,487 @@ mod tests {
         assert_eq!(calls[0].name, "read");
         assert_eq!(calls[0].call_type, "tool");
     }
+
+    #[test]
+    fn claude_captures_timestamp_model_and_usage() {
+        let raw = r#"{"type":"user","timestamp":"2026-02-23T00:00:01.000Z","message":{"role":"user","content":"hello"}}
+{"type":"assistant","timestamp":"2026-02-23T00:00:02.500Z","message":{"role":"assistant","model":"claude-opus-4-1","content":[{"type":"text","text":"done"}],"usage":{"input_tokens":12,"output_tokens":5,"cache_read_input_tokens":100,"cache_creation_input_tokens":0}}}"#;
+
+        let messages =
+            extract_messages(ProviderKind::Claude, Path::new("/tmp/mock"), raw).expect("extract");
+        assert_eq!(
+            messages[0].timestamp.as_deref(),
+            Some("2026-02-23T00:00:01.000Z")
+        );
+        assert_eq!(messages[0].usage, None);
+        assert_eq!(messages[1].model.as_deref(), Some("claude-opus-4-1"));
+        let usage = messages[1].usage.as_ref().expect("usage");
+        assert_eq!(usage.input_tokens, Some(12));
+        assert_eq!(usage.output_tokens, Some(5));
+        assert_eq!(usage.cache_read_tokens, Some(100));
+        assert_eq!(usage.cost, None);
+    }
+
+    #[test]
+    fn codex_attaches_turn_model_and_token_count_to_assistant_message() {
+        let raw = r#"{"timestamp":"2026-02-23T00:00:00Z","type":"turn_context","payload":{"model":"gpt-5-codex"}}
+{"timestamp":"2026-02-23T00:00:01Z","type":"response_item","payload":{"type":"message","role":"user","content":[{"type":"input_text","text":"hello"}]}}
+{"timestamp":"2026-02-23T00:00:02Z","type":"event_msg","payload":{"type":"token_count","info":null}}
+{"timestamp":"2026-02-23T00:00:03Z","type":"response_item","payload":{"type":"message","role":"assistant","content":[{"type":"output_text","text":"world"}]}}
+{"timestamp":"2026-02-23T00:00:04Z","type":"event_msg","payload":{"type":"token_count","info":{"last_token_usage":{"input_tokens":900,"cached_input_tokens":800,"output_tokens":40,"reasoning_output_tokens":8,"total_tokens":940}}}}"#;
+
+        let messages =
+            extract_messages(ProviderKind::Codex, Path::new("/tmp/mock"), raw).expect("extract");
+        assert_eq!(messages.len(), 2);
+        assert_eq!(messages[0].model, None);
+        assert_eq!(messages[0].usage, None);
+        assert_eq!(
+            messages[1].timestamp.as_deref(),
+            Some("2026-02-23T00:00:03Z")
+        );
+        assert_eq!(messages[1].model.as_deref(), Some("gpt-5-codex"));
+        let usage = messages[1].usage.as_ref().expect("usage");
+        assert_eq!(usage.cache_read_tokens, Some(800));
+        assert_eq!(usage.reasoning_tokens, Some(8));
+        assert_eq!(usage.total_tokens, Some(940));
+    }
+
+    #[test]
+    fn gemini_and_opencode_capture_usage_and_epoch_timestamps() {
+        let gemini = r#"{"messages":[{"type":"gemini","timestamp":"2026-01-08T11:55:00.000Z","model":"gemini-2.5-pro","content":"world","tokens":{"input":7042,"output":11,"cached":0,"thoughts":653,"tool":0,"total":7706}}]}"#;
+        let messages = extract_messages(ProviderKind::Gemini, Path::new("/tmp/mock"), gemini)
+            .expect("extract");
+        assert_eq!(messages[0].model.as_deref(), Some("gemini-2.5-pro"));
+        let usage = messages[0].usage.as_ref().expect("usage");
+        assert_eq!(usage.reasoning_tokens, Some(653));
+        assert_eq!(usage.total_tokens, Some(7706));
+
+        let opencode = r#"{"type":"message","id":"msg_2","message":{"role":"assistant","time":{"created":1768307088295,"completed":1768307091934},"modelID":"claude-sonnet-4","cost":0.0125,"tokens":{"input":8174,"output":164,"reasoning":0,"cache":{"read":2048,"write":0}}},"parts":[{"type":"text","text":"world"}]}"#;
+        let messages = extract_messages(ProviderKind::Opencode, Path::new("/tmp/mock"), opencode)
+            .expect("extract");
+        assert_eq!(
+            messages[0].timestamp.as_deref(),
+            Some("2026-01-13T12:24:48Z")
+        );
+        assert_eq!(messages[0].model.as_deref(), Some("claude-sonnet-4"));
+        let usage = messages[0].usage.as_ref().expect("usage");
+        assert_eq!(usage.cache_read_tokens, Some(2048));
+        assert_eq!(usage.cost, Some(0.0125));
+    }
+
+    #[test]
+    fn markdown_headings_show_message_metadata() {
+        let raw = r#"{"type":"assistant","timestamp":"txt_sanitized","message":{"role":"assistant","model":"claude-opus-4-1","content":"done","usage":{"input_tokens":12,"output_tokens":5,"cache_read_input_tokens":0}}}"#;
+        let uri = ThreadUri::parse("agents://claude/2823d1df-720a-4c31-ac55-ae8ba726721f")
+            .expect("parse uri");
+        let output = render_markdown(
+            &uri,
+            Path::new("/tmp/mock"),
+            raw,
+            &TimelineOptions::default(),
+        )
+        .expect("render");
+
+        assert!(output.contains("## 1. Assistant · claude-opus-4-1 · 12 in / 5 out tokens\n"));
+    }
+
+    fn tool_entries(provider: ProviderKind, raw: &str, max: usize) -> Vec<crate::model::ToolEntry> {
+        use crate::model::TimelineEntry;
+        use crate::render::extract_timeline_entries;
+
+        let options = TimelineOptions {
+            tools: true,
+            max_tool_output: max,
+            ..TimelineOptions::default()
+        };
+        extract_timeline_entries(provider, Path::new("/tmp/mock"), raw, "", None, &options)
+            .expect("extract")
+            .into_iter()
+            .filter_map(|entry| match entry {
+                TimelineEntry::Tool(tool) => Some(tool),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn role_and_thinking_options_filter_entries_but_keep_numbering() {
+        let raw = r#"{"type":"user","message":{"role":"user","content":"first"}}
+{"type":"assistant","message":{"role":"assistant","content":[{"type":"text","text":"reply"}]}}
+{"type":"user","message":{"role":"user","content":"second"}}"#;
+        let uri = ThreadUri::parse("agents://claude/2823d1df-720a-4c31-ac55-ae8ba726721f")
+            .expect("parse uri");
+        let options = TimelineOptions {
+            role: Some(MessageRole::User),
+            ..TimelineOptions::default()
+        };
+        let entries =
+            crate::render::extract_thread_entries(&uri, Path::new("/tmp/mock"), raw, &options)
+                .expect("extract");
+        assert_eq!(
+            entries.iter().map(|entry| entry.index).collect::<Vec<_>>(),
+            vec![1, 3]
+        );
+
+        let mut message =
+            crate::model::ThreadMessage::new(MessageRole::Assistant, "pondering\n\nanswer");
+        message.reasoning = vec!["pondering".to_string()];
+        assert_eq!(message.text_without_reasoning(), "answer");
+    }
+
+    #[test]
+    fn tools_are_skipped_unless_requested() {
+        let raw = r#"{"type":"assistant","message":{"role":"assistant","content":[{"type":"tool_use","id":"toolu_1","name":"Bash","input":{"command":"ls"}}]}}"#;
+        let uri = ThreadUri::parse("agents://claude/2823d1df-720a-4c31-ac55-ae8ba726721f")
+            .expect("parse uri");
+        let entries = crate::render::extract_thread_entries(
+            &uri,
+            Path::new("/tmp/mock"),
+            raw,
+            &TimelineOptions::default(),
+        )
+        .expect("extract");
+        assert!(entries.is_empty());
+    }
+
+    #[test]
+    fn claude_pairs_tool_use_with_result_in_order() {
+        use crate::model::TimelineEntry;
+        use crate::render::extract_timeline_entries;
+
+        let raw = r#"{"type":"user","message":{"role":"user","content":"list files"}}
+{"type":"assistant","message":{"role":"assistant","content":[{"type":"text","text":"checking"},{"type":"tool_use","id":"toolu_1","name":"Bash","input":{"command":"ls"}}]}}
+{"type":"user","message":{"role":"user","content":[{"type":"tool_result","tool_use_id":"toolu_1","content":[{"type":"text","text":"main.rs"}]}]}}
+{"type":"assistant","message":{"role":"assistant","content":[{"type":"text","text":"done"}]}}"#;
+        let options = TimelineOptions {
+            tools: true,
+            ..TimelineOptions::default()
+        };
+
+        let entries = extract_timeline_entries(
+            ProviderKind::Claude,
+            Path::new("/tmp/mock"),
+            raw,
+            "",
+            None,
+            &options,
+        )
+        .expect("extract");
+        assert_eq!(entries.len(), 4);
+        let TimelineEntry::Tool(tool) = &entries[2] else {
+            panic!("third entry must be the tool call");
+        };
+        assert_eq!(tool.name, "Bash");
+        assert_eq!(tool.call_id.as_deref(), Some("toolu_1"));
+        assert_eq!(tool.args["command"], "ls");
+        assert_eq!(tool.output.as_deref(), Some("main.rs"));
+        assert!(matches!(&entries[3], TimelineEntry::Message(message) if message.text == "done"));
+    }
+
+    #[test]
+    fn codex_pairs_calls_by_call_id_and_unwraps_shell_output() {
+        let raw = r#"{"type":"response_item","payload":{"type":"function_call","name":"exec_command","arguments":"{\"cmd\":\"ls\"}","call_id":"call_1"}}
+{"type":"response_item","payload":{"type":"custom_tool_call","name":"apply_patch","input":"*** Begin Patch","call_id":"call_2"}}
+{"type":"response_item","payload":{"type":"custom_tool_call_output","call_id":"call_2","output":"{\"output\":\"Success\",\"metadata\":{\"exit_code\":0}}"}}
+{"type":"response_item","payload":{"type":"function_call_output","call_id":"call_1","output":"main.rs\nlib.rs"}}
+{"type":"response_item","payload":{"type":"function_call_output","call_id":"call_missing","output":"orphan"}}"#;
+
+        let tools = tool_entries(ProviderKind::Codex, raw, 2000);
+        assert_eq!(tools.len(), 3);
+        assert_eq!(tools[0].args["cmd"], "ls");
+        assert_eq!(tools[0].output.as_deref(), Some("main.rs\nlib.rs"));
+        assert_eq!(tools[1].args, "*** Begin Patch");
+        assert_eq!(tools[1].output.as_deref(), Some("Success"));
+        assert_eq!(tools[2].name, "unknown");
+        assert_eq!(tools[2].output.as_deref(), Some("orphan"));
+    }
+
+    #[test]
+    fn amp_opencode_pi_and_gemini_report_tool_results() {
+        let amp = r#"{"messages":[{"role":"assistant","content":[{"type":"tool_use","id":"tool_1","name":"finder","input":{"query":"main"}}]},{"role":"user","content":[{"type":"tool_result","toolUseID":"tool_1","run":{"status":"error","error":"boom"}}]}]}"#;
+        let tools = tool_entries(ProviderKind::Amp, amp, 2000);
+        assert_eq!(tools.len(), 1);
+        assert!(tools[0].is_error);
+        assert_eq!(tools[0].output.as_deref(), Some("boom"));
+
+        let opencode = r#"{"type":"message","message":{"role":"assistant"},"parts":[{"type":"tool","callID":"toolu_1","tool":"read","state":{"status":"completed","input":{"filePath":"main.rs"},"output":"fn main() {}"}}]}"#;
+        let tools = tool_entries(ProviderKind::Opencode, opencode, 2000);
+        assert_eq!(tools[0].name, "read");
+        assert_eq!(tools[0].output.as_deref(), Some("fn main() {}"));
+
+        let pi = r#"{"type":"message","id":"a1","parentId":null,"message":{"role":"assistant","content":[{"type":"toolCall","id":"tc_1","name":"bash","arguments":{"command":"ls"}}]}}
+{"type":"message","id":"a2","parentId":"a1","message":{"role":"toolResult","toolCallId":"tc_1","toolName":"bash","content":[{"type":"text","text":"main.rs"}],"isError":false}}"#;
+        let tools = tool_entries(ProviderKind::Pi, pi, 2000);
+        assert_eq!(tools.len(), 1);
+        assert_eq!(tools[0].output.as_deref(), Some("main.rs"));
+
+        let gemini = r#"{"messages":[{"type":"gemini","content":"","toolCalls":[{"id":"c1","name":"list_directory","args":{"dir_path":"."},"result":[{"functionResponse":{"id":"c1","name":"list_directory","response":{"output":"src"}}}],"status":"success"}]}]}"#;
+        let tools = tool_entries(ProviderKind::Gemini, gemini, 2000);
+        assert_eq!(tools[0].args["dir_path"], ".");
+        assert_eq!(tools[0].output.as_deref(), Some("src"));
+    }
+
+    #[test]
+    fn tool_output_is_truncated_and_fenced() {
+        use crate::model::{ThreadEntry, TimelineEntry};
+        use crate::render::render_entry_markdown;
+
+        let raw = r#"{"type":"response_item","payload":{"type":"function_call","name":"exec_command","arguments":"{\"cmd\":\"cat README.md\"}","call_id":"call_1"}}
+{"type":"response_item","payload":{"type":"function_call_output","call_id":"call_1","output":"```rust\nfn main() {}\n```"}}"#;
+        let tools = tool_entries(ProviderKind::Codex, raw, 12);
+        assert!(tools[0].truncated);
+        assert_eq!(tools[0].output.as_deref(), Some("```rust\nfn m"));
+
+        let markdown = render_entry_markdown(&ThreadEntry {
+            index: 3,
+            entry: TimelineEntry::Tool(tools[0].clone()),
+        });
+        assert!(markdown.starts_with(
+            "## 3. Tool `exec_command`\n\n```json\n{\n  \"cmd\": \"cat README.md\"\n}\n```"
+        ));
+        assert!(markdown.contains("Output:\n\n````text\n```rust\nfn m\n````"));
+        assert!(markdown.contains("_Output truncated._"));
+    }
+
+    #[test]
+    fn cline_entries_clean_user_text_and_place_checkpoints() {
+        use std::fs;
+
+        use crate::model::TimelineEntry;
+        use crate::render::extract_timeline_entries;
+
+        let temp = tempfile::tempdir().expect("tempdir");
+        let path = temp.path().join("api_conversation_history.json");
+        let raw = r#"[{"role":"user","ts":1731234567890,"content":[{"type":"text","text":"<task>\nFix retries\n</task>"},{"type":"text","text":"<environment_details>\n# Current Working Directory (/tmp/project) Files\n</environment_details>"}]},{"role":"assistant","content":[{"type":"thinking","thinking":"look first"},{"type":"text","text":"Reading."},{"type":"tool_use","id":"toolu_1","name":"read_file","input":{"path":"src/lib.rs"}}]},{"role":"user","content":[{"type":"tool_result","tool_use_id":"toolu_1","content":"fn main() {}"}]},{"role":"assistant","content":"Done."}]"#;
+        fs::write(&path, raw).expect("write");
+        fs::write(
+            temp.path().join("ui_messages.json"),
+            r#"[{"ts":1,"type":"say","say":"text","text":"Fix retries"},{"ts":2,"type":"say","say":"checkpoint_created","lastCheckpointHash":"abc123","conversationHistoryIndex":2}]"#,
+        )
+        .expect("write");
+
+        let options = TimelineOptions {
+            tools: true,
+            ..TimelineOptions::default()
+        };
+        let entries = extract_timeline_entries(ProviderKind::Cline, &path, raw, "", None, &options)
+            .expect("extract");
+        assert_eq!(entries.len(), 5);
+        let TimelineEntry::Message(task) = &entries[0] else {
+            panic!("expected the task message");
+        };
+        assert_eq!(task.text, "Fix retries");
+        assert_eq!(task.timestamp.as_deref(), Some("2024-11-10T10:29:27Z"));
+        let TimelineEntry::Message(reply) = &entries[1] else {
+            panic!("expected the assistant reply");
+        };
+        assert_eq!(reply.reasoning, vec!["look first"]);
+        let TimelineEntry::Tool(tool) = &entries[2] else {
+            panic!("expected the tool call");
+        };
+        assert_eq!(tool.output.as_deref(), Some("fn main() {}"));
+        assert_eq!(
+            entries[3],
+            TimelineEntry::Checkpoint {
+                hash: "abc123".to_string()
+            }
+        );
+
+        let calls = crate::render::extract_tool_calls(ProviderKind::Roo, &path, raw)
+            .expect("extract tool calls");
+        assert_eq!(calls.len(), 2);
+        assert_eq!(calls[0].name, "read_file");
+    }
+
+    #[test]
+    fn cline_xml_tool_calls_pair_with_result_blocks() {
+        use serde_json::json;
+
+        use crate::model::TimelineEntry;
+        use crate::render::extract_timeline_entries;
+
+        let path = Path::new("api_conversation_history.json");
+        let raw = r##"[{"role":"user","content":[{"type":"text","text":"<task>\nShow the readme\n</task>"}]},{"role":"assistant","content":[{"type":"text","text":"Reading it.\n\n<read_file>\n<path>README.md</path>\n</read_file>"}]},{"role":"user","content":[{"type":"text","text":"[read_file for 'README.md'] Result:"},{"type":"text","text":"# Demo\n"},{"type":"text","text":"<environment_details>\nnone\n</environment_details>"}]},{"role":"assistant","content":[{"type":"text","text":"<write_to_file>\n<path>notes.md</path>\n<content>\n<b>bold</b>\n</content>\n</write_to_file>"}]},{"role":"user","content":[{"type":"text","text":"[write_to_file for 'notes.md'] Result:\nThe user denied this operation."}]},{"role":"assistant","content":[{"type":"text","text":"Done."}]}]"##;
+
+        let options = TimelineOptions {
+            tools: true,
+            ..TimelineOptions::default()
+        };
+        let entries = extract_timeline_entries(ProviderKind::Cline, path, raw, "", None, &options)
+            .expect("extract");
+        assert_eq!(entries.len(), 5);
+        let TimelineEntry::Message(reply) = &entries[1] else {
+            panic!("expected the assistant reply");
+        };
+        assert_eq!(reply.text, "Reading it.");
+        let TimelineEntry::Tool(read) = &entries[2] else {
+            panic!("expected the read call");
+        };
+        assert_eq!(read.name, "read_file");
+        assert_eq!(read.args, json!({"path": "README.md"}));
+        assert_eq!(read.output.as_deref(), Some("# Demo\n"));
+        assert!(!read.is_error);
+        let TimelineEntry::Tool(write) = &entries[3] else {
+            panic!("expected the write call");
+        };
+        assert_eq!(
+            write.args,
+            json!({"path": "notes.md", "content": "<b>bold</b>"})
+        );
+        assert_eq!(
+            write.output.as_deref(),
+            Some("The user denied this operation.")
+        );
+        assert!(write.is_error);
+
+        let plain = extract_timeline_entries(
+            ProviderKind::Cline,
+            path,
+            raw,
+            "",
+            None,
+            &TimelineOptions::default(),
+        )
+        .expect("extract");
+        assert_eq!(plain.len(), 3);
+
+        let calls = crate::render::extract_tool_calls(ProviderKind::Cline, path, raw)
+            .expect("extract tool calls");
+        assert_eq!(
+            calls
+                .iter()
+                .map(|call| call.name.as_str())
+                .collect::<Vec<_>>(),
+            vec!["read_file", "write_to_file"]
+        );
+    }
+
+    #[test]
+    fn aider_entries_split_prompts_replies_and_edit_blocks() {
+        use crate::model::TimelineEntry;
+        use crate::render::extract_timeline_entries;
+
+        let raw = "# aider chat started at 2024-05-01 10:00:00\n\n> Aider v0.50.0\n> Main model: gpt-4o\n\n#### rename the helper\n#### and add a readme\n\nRenaming it.\n\nsrc/lib.rs\n```rust\n<<<<<<< SEARCH\nfn old() {}\n=======\nfn new() {}\n>>>>>>> REPLACE\n```\n\nREADME.md\n```\n<<<<<<< SEARCH\n=======\n# Demo\n>>>>>>> REPLACE\n```\n\n> Tokens: 1k sent, 100 received.\n> Applied edit to src/lib.rs\n";
+        let options = TimelineOptions {
+            tools: true,
+            ..TimelineOptions::default()
+        };
+        let entries = extract_timeline_entries(
+            ProviderKind::Aider,
+            Path::new("a.md"),
+            raw,
+            "",
+            None,
+            &options,
+        )
+        .expect("extract");
+        assert_eq!(entries.len(), 4);
+        let TimelineEntry::Message(prompt) = &entries[0] else {
+            panic!("expected the user prompt");
+        };
+        assert_eq!(prompt.role, MessageRole::User);
+        assert_eq!(prompt.text, "rename the helper\nand add a readme");
+        let TimelineEntry::Message(reply) = &entries[1] else {
+            panic!("expected the assistant reply");
+        };
+        assert!(reply.text.starts_with("Renaming it."));
+        let TimelineEntry::Tool(edit) = &entries[2] else {
+            panic!("expected the edit");
+        };
+        assert_eq!(edit.name, "edit");
+        assert_eq!(edit.args["file_path"], "src/lib.rs");
+        assert_eq!(edit.args["new_string"], "fn new() {}\n");
+        assert!(!edit.is_error);
+        let TimelineEntry::Tool(write) = &entries[3] else {
+            panic!("expected the write");
+        };
+        assert_eq!(write.name, "write");
+        assert_eq!(write.args["content"], "# Demo\n");
+        assert!(write.is_error);
+
+        let calls = crate::render::extract_tool_calls(ProviderKind::Aider, Path::new("a.md"), raw)
+            .expect("extract tool calls");
+        assert_eq!(calls.len(), 2);
+    }
+
+    #[test]
+    fn goose_pairs_tool_requests_with_responses_and_skips_hidden_messages() {
+        let raw = r#"{"working_dir":"/tmp/project","description":"Fix it"}
+{"id":1,"role":"user","created":1760605923,"content":[{"type":"text","text":"fix the test"}]}
+{"id":2,"role":"assistant","created":1760605927,"content":[{"type":"thinking","thinking":"run it first","signature":"sig"},{"type":"text","text":"Running."},{"type":"toolRequest","id":"call_1","toolCall":{"status":"success","value":{"name":"developer__shell","arguments":{"command":"cargo test"}}}},{"type":"toolRequest","id":"call_2","toolCall":{"status":"error","error":"unknown tool"}}]}
+{"id":3,"role":"user","created":1760605930,"content":[{"type":"toolResponse","id":"call_1","toolResult":{"status":"success","value":[{"type":"text","text":"1 failed"}]}}]}
+{"id":4,"role":"user","created":1760605931,"content":[{"type":"text","text":"summary of the conversation"}],"metadata":{"userVisible":false,"agentVisible":true}}
+{"id":5,"role":"assistant","created":1760605932,"content":[{"type":"text","text":"Fixed."}]}"#;
+
+        let messages =
+            extract_messages(ProviderKind::Goose, Path::new("/tmp/mock"), raw).expect("extract");
+        assert_eq!(messages.len(), 3);
+        assert_eq!(messages[1].reasoning, vec!["run it first"]);
+        assert_eq!(
+            messages[0].timestamp.as_deref(),
+            Some("2025-10-16T09:12:03Z")
+        );
+        assert_eq!(messages[2].text, "Fixed.");
+
+        let tools = tool_entries(ProviderKind::Goose, raw, 2000);
+        assert_eq!(tools.len(), 2);
+        assert_eq!(tools[0].name, "developer__shell");
+        assert_eq!(tools[0].output.as_deref(), Some("1 failed"));
+        assert!(!tools[0].is_error);
+        assert_eq!(tools[1].name, "unknown");
+        assert!(tools[1].is_error);
+
+        let calls =
+            crate::render::extract_tool_calls(ProviderKind::Goose, Path::new("/tmp/mock"), raw)
+                .expect("extract tool calls");
+        assert_eq!(calls.len(), 2);
+        assert_eq!(calls[0].args["command"], "cargo test");
+    }
+
+    #[test]
+    fn cursor_bubbles_render_thinking_code_blocks_and_tools() {
+        let raw = r##"{"composerId":"5f0c2a4e-8b1d-4c3a-9e7f-1a2b3c4d5e6f","name":"Fix retries","cwd":"/tmp/project"}
+{"type":1,"bubbleId":"b1","text":"fix retries","createdAt":"2025-10-16T09:12:03.000Z","tokenCount":{"inputTokens":0,"outputTokens":0}}
+{"type":2,"bubbleId":"b2","text":"","thinking":{"text":"read it first"},"toolFormerData":{"tool":15,"name":"run_terminal_cmd","toolCallId":"toolu_1","status":"completed","rawArgs":"{\"command\":\"cargo test\"}","result":"{\"output\":\"1 failed\"}"}}
+{"type":2,"bubbleId":"b3","text":"Patched:\n\n```rust\nfn retry() {}\n```","modelInfo":{"modelName":"claude-4-sonnet"},"tokenCount":{"inputTokens":120,"outputTokens":30},"codeBlocks":[{"uri":{"path":"/tmp/project/src/lib.rs"},"languageId":"rust","content":"fn retry() {}"},{"uri":{"path":"/tmp/project/README.md"},"languageId":"markdown","content":"# Retries"}]}
+{"type":2,"bubbleId":"b4","toolFormerData":{"name":"edit_file","toolCallId":"toolu_2","status":"error","params":"{\"target_file\":\"src/lib.rs\"}","result":"permission denied"}}"##;
+
+        let messages =
+            extract_messages(ProviderKind::Cursor, Path::new("/tmp/mock"), raw).expect("extract");
+        assert_eq!(messages.len(), 3);
+        assert_eq!(messages[0].role, MessageRole::User);
+        assert_eq!(
+            messages[0].timestamp.as_deref(),
+            Some("2025-10-16T09:12:03.000Z")
+        );
+        assert_eq!(messages[0].usage, None);
+        assert_eq!(messages[1].reasoning, vec!["read it first"]);
+        assert_eq!(
+            messages[2].text,
+            "Patched:\n\n```rust\nfn retry() {}\n```\n\n`/tmp/project/README.md`\n\n```markdown\n# Retries\n```"
+        );
+        assert_eq!(messages[2].model.as_deref(), Some("claude-4-sonnet"));
+        assert_eq!(
+            messages[2]
+                .usage
+                .as_ref()
+                .and_then(|usage| usage.output_tokens),
+            Some(30)
+        );
+
+        let tools = tool_entries(ProviderKind::Cursor, raw, 2000);
+        assert_eq!(tools.len(), 2);
+        assert_eq!(tools[0].name, "run_terminal_cmd");
+        assert_eq!(tools[0].args["command"], "cargo test");
+        assert_eq!(tools[0].output.as_deref(), Some("1 failed"));
+        assert_eq!(tools[1].args["target_file"], "src/lib.rs");
+        assert_eq!(tools[1].output.as_deref(), Some("permission denied"));
+        assert!(tools[1].is_error);
+
+        let calls =
+            crate::render::extract_tool_calls(ProviderKind::Cursor, Path::new("/tmp/mock"), raw)
+                .expect("extract tool calls");
+        assert_eq!(calls.len(), 2);
+        assert_eq!(calls[1].name, "edit_file");
+    }
 }
@@ -0,0 +1,289 @@
+//! Running agent processes matched to the threads they are writing.
+//!
+//! Each process found by [`crate::process`] is matched to a thread in
+//! three steps: the thread file it holds open, a session id on its command
+//! line (`--resume <id>`), then the newest unclaimed thread recorded in the
+//! same working directory. Recent threads that no process claims and whose
+//! last entry is still waiting on the agent are reported as stale.
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::error::Result;
+use crate::model::{
+    MessageRole, ProviderKind, RunningAgent, RunningAgentsView, SessionSummary, StaleSession,
+    SubagentCounts, SubagentView, TimelineEntry, TimelineOptions,
+};
+use crate::process::{AgentProcess, discover_agent_pids};
+use crate::provider::ProviderRoots;
+use crate::render;
+use crate::service::{read_thread_text, resolve_subagent_view, session_thread_path};
+use crate::uri::ThreadUri;
+
+/// List running agents across providers, plus threads updated within
+/// `stale_window` that stopped mid-turn without a process.
+///
+/// Stale detection needs precise process discovery, so it only runs on
+/// Linux, and only for providers whose threads are written by a process
+/// of their own (see [`has_agent_process`]).
+pub fn list_running_agents(roots: &ProviderRoots, stale_window: Duration) -> RunningAgentsView {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|now| now.as_secs())
+        .unwrap_or_default();
+    let mut view = RunningAgentsView {
+        agents: Vec::new(),
+        stale: Vec::new(),
+        warnings: Vec::new(),
+    };
+
+    for provider in ProviderKind::ALL
+        .into_iter()
+        .filter(|provider| provider.listed_by_default())
+    {
+        let processes = discover_agent_pids(provider);
+        let mut sessions = match roots.list_sessions(provider) {
+            Ok(sessions) => sessions,
+            Err(err) => {
+                view.warnings
+                    .push(format!("failed to list {provider} sessions: {err}"));
+                Vec::new()
+            }
+        };
+        sessions.retain(|session| !session.archived && session.parent_session_id.is_none());
+        sessions.sort_by_key(|session| std::cmp::Reverse(session.updated_at));
+
+        let matched = match_processes(&processes, &sessions, roots.roots(provider));
+        let claimed = matched
+            .iter()
+            .filter_map(|(_, found)| found.map(|(session, _)| session.session_id.as_str()))
+            .collect::<HashSet<_>>();
+
+        if cfg!(target_os = "linux") && has_agent_process(provider) {
+            for session in &sessions {
+                if now.saturating_sub(session.updated_at) >= stale_window.as_secs()
+                    || claimed.contains(session.session_id.as_str())
+                {
+                    continue;
+                }
+                match waiting_on(session, roots) {
+                    Ok(Some(reason)) => view.stale.push(StaleSession {
+                        provider,
+                        uri: session.uri(),
+                        cwd: session.cwd.clone(),
+                        last_activity: session.updated_at,
+                        reason,
+                    }),
+                    Ok(None) => {}
+                    Err(err) => view
+                        .warnings
+                        .push(format!("failed to read {}: {err}", session.uri())),
+                }
+            }
+        }
+
+        for (process, found) in matched {
+            let subagents = found.and_then(|(session, _)| subagent_counts(session, roots));
+            view.agents.push(RunningAgent {
+                pid: process.pid,
+                provider,
+                command: process.command.clone(),
+                cwd: process.cwd.as_ref().map(|cwd| cwd.display().to_string()),
+                uptime_secs: process
+                    .started_at
+                    .map(|started| now.saturating_sub(started)),
+                uri: found.map(|(session, _)| session.uri()),
+                matched_by: found.map(|(_, how)| how.to_string()),
+                last_activity: found.map(|(session, _)| session.updated_at),
+                subagents,
+            });
+        }
+    }
+
+    view.stale
+        .sort_by_key(|stale| std::cmp::Reverse(stale.last_activity));
+    view
+}
+
+/// Whether the provider's threads are written by a process that
+/// [`discover_agent_pids`] can find. Cline and Roo Code run inside the VS
+/// Code extension host and Cursor composers inside the editor, so a missing
+/// process says nothing about whether one of their tasks is still running.
+fn has_agent_process(provider: ProviderKind) -> bool {
+    !matches!(
+        provider,
+        ProviderKind::Cline | ProviderKind::Roo | ProviderKind::Cursor
+    )
+}
+
+/// Pair each process with the thread it is writing. `sessions` must be
+/// newest first, so a cwd match picks the most recent unclaimed thread.
+fn match_processes<'a, 'p>(
+    processes: &'p [AgentProcess],
+    sessions: &'a [SessionSummary],
+    provider_roots: &[PathBuf],
+) -> Vec<(&'p AgentProcess, Option<(&'a SessionSummary, &'static str)>)> {
+    let mut claimed = HashSet::new();
+    let mut matched = processes
+        .iter()
+        .map(|process| {
+            let found = process
+                .session_file(provider_roots)
+                .and_then(|file| sessions.iter().find(|session| session.path == file))
+                .map(|session| (session, "open_file"))
+                .or_else(|| {
+                    sessions
+                        .iter()
+                        .find(|session| command_names(&process.command, &session.session_id))
+                        .map(|session| (session, "command"))
+                });
+            if let Some((session, _)) = found {
+                claimed.insert(session.session_id.as_str());
+            }
+            (process, found)
+        })
+        .collect::<Vec<_>>();
+
+    for (process, found) in &mut matched {
+        if found.is_some() {
+            continue;
+        }
+        let Some(cwd) = process.cwd.as_deref() else {
+            continue;
+        };
+        *found = sessions
+            .iter()
+            .find(|session| {
+                !claimed.contains(session.session_id.as_str())
+                    && session.cwd.as_deref().map(Path::new) == Some(cwd)
+            })
+            .map(|session| (session, "cwd"));
+        if let Some((session, _)) = found {
+            claimed.insert(session.session_id.as_str());
+        }
+    }
+    matched
+}
+
+/// Whether a command line passes `session_id` as a whole argument, either
+/// on its own or as `--flag=<id>`.
+fn command_names(command: &str, session_id: &str) -> bool {
+    command
+        .split(|ch: char| ch.is_whitespace() || ch == '=')
+        .any(|arg| arg == session_id)
+}
+
+/// Why a thread looks mid-turn: its last entry is a user message or a tool
+/// call without a result. `None` when the agent finished its turn.
+fn waiting_on(session: &SessionSummary, roots: &ProviderRoots) -> Result<Option<String>> {
+    let path = session_thread_path(session, roots)?;
+    let raw = read_thread_text(session.provider, &session.session_id, &path)?;
+    let uri = ThreadUri::parse(&session.uri())?;
+    let options = TimelineOptions {
+        tools: true,
+        ..TimelineOptions::default()
+    };
+    let entries = render::extract_thread_entries(&uri, &path, &raw, &options)?;
+
+    Ok(match entries.last().map(|entry| &entry.entry) {
+        Some(TimelineEntry::Message(message)) if message.role == MessageRole::User => {
+            Some("no reply to the last user message".to_string())
+        }
+        Some(TimelineEntry::Tool(tool)) if tool.output.is_none() => {
+            Some(format!("tool call `{}` has no result", tool.name))
+        }
+        _ => None,
+    })
+}
+
+fn subagent_counts(session: &SessionSummary, roots: &ProviderRoots) -> Option<SubagentCounts> {
+    if !matches!(session.provider, ProviderKind::Codex | ProviderKind::Claude) {
+        return None;
+    }
+    let uri = ThreadUri::parse(&session.uri()).ok()?;
+    let SubagentView::List(list) = resolve_subagent_view(&uri, roots, true).ok()? else {
+        return None;
+    };
+    Some(SubagentCounts {
+        running: list
+            .agents
+            .iter()
+            .filter(|agent| matches!(agent.status.as_str(), "running" | "pendingInit"))
+            .count(),
+        total: list.agents.len(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::match_processes;
+    use crate::model::{ProviderKind, SessionSummary};
+    use crate::process::AgentProcess;
+
+    fn process(pid: u32, command: &str, cwd: &str, open: &[&str]) -> AgentProcess {
+        AgentProcess {
+            pid,
+            provider: ProviderKind::Claude,
+            command: command.to_string(),
+            cwd: Some(PathBuf::from(cwd)),
+            started_at: None,
+            open_files: open.iter().map(PathBuf::from).collect(),
+        }
+    }
+
+    fn session(session_id: &str, cwd: &str, updated_at: u64) -> SessionSummary {
+        SessionSummary {
+            provider: ProviderKind::Claude,
+            session_id: session_id.to_string(),
+            path: PathBuf::from(format!("/claude/projects/-repo/{session_id}.jsonl")),
+            created_at: None,
+            updated_at,
+            cwd: Some(cwd.to_string()),
+            title: None,
+            archived: false,
+            parent_session_id: None,
+            message_count: None,
+        }
+    }
+
+    #[test]
+    fn matches_by_open_file_then_command_then_newest_unclaimed_cwd() {
+        let sessions = vec![
+            session("d", "/repo", 40),
+            session("c", "/repo", 30),
+            session("b", "/repo", 20),
+            session("a", "/other", 10),
+        ];
+        let processes = vec![
+            process(1, "claude", "/repo", &["/claude/projects/-repo/b.jsonl"]),
+            process(2, "claude --resume a", "/repo", &[]),
+            process(3, "claude", "/repo", &[]),
+            process(4, "claude", "/repo", &[]),
+            process(5, "claude", "/repo", &[]),
+        ];
+
+        let matched = match_processes(&processes, &sessions, &[PathBuf::from("/claude")]);
+        let summary = matched
+            .iter()
+            .map(|(process, found)| {
+                (
+                    process.pid,
+                    found.map(|(session, how)| (session.session_id.as_str(), how)),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            summary,
+            vec![
+                (1, Some(("b", "open_file"))),
+                (2, Some(("a", "command"))),
+                (3, Some(("d", "cwd"))),
+                (4, Some(("c", "cwd"))),
+                (5, None),
+            ]
+        );
+    }
+}
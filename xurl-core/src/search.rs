@@ -0,0 +1,283 @@
+//! Scan-based full-text search over rendered thread messages.
+//!
+//! Every candidate thread is read through the same extractors used for
+//! rendering, so matches are against message text rather than raw JSON.
+//! Each search reads every thread that survives the session filters; see
+//! [`crate::index`] for the persistent alternative.
+
+use crate::error::Result;
+use crate::model::{
+    MessageRole, SearchHit, SearchQuery, SearchResultsView, SessionListQuery, SessionSummary,
+    ThreadEntry, TimelineEntry, TimelineOptions,
+};
+use crate::provider::ProviderRoots;
+use crate::render;
+use crate::service::{filtered_sessions, read_thread_text, session_thread_path};
+use crate::uri::{ThreadUri, UriQuery};
+
+/// Bytes of context kept before the first match in a snippet.
+const SNIPPET_BEFORE: usize = 60;
+/// Maximum snippet length in bytes, before ellipses.
+const SNIPPET_LEN: usize = 200;
+
+/// A message whose text contains every search term.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct MessageMatch {
+    pub index: usize,
+    pub role: MessageRole,
+    pub timestamp: Option<String>,
+    pub snippet: String,
+    pub highlights: Vec<(usize, usize)>,
+}
+
+/// Search every thread matching the session filters in `query`, newest
+/// thread first and in timeline order within a thread.
+///
+/// Threads that fail to load are reported as warnings and skipped.
+pub fn search_threads(roots: &ProviderRoots, query: &SearchQuery) -> SearchResultsView {
+    let terms = parse_terms(&query.text);
+    let mut warnings = Vec::new();
+    let mut sessions = filtered_sessions(
+        roots,
+        &SessionListQuery {
+            providers: query.providers.clone(),
+            updated_after: query.updated_after,
+            cwd: query.cwd.clone(),
+            limit: None,
+        },
+        &mut warnings,
+    );
+    if let Some(before) = query.started_before {
+        sessions.retain(|session| session.created_at.unwrap_or(session.updated_at) <= before);
+    }
+
+    let mut hits = Vec::new();
+    let mut sessions_scanned = 0;
+    if !terms.is_empty() {
+        for session in &sessions {
+            if query.limit.is_some_and(|limit| hits.len() >= limit) {
+                break;
+            }
+            sessions_scanned += 1;
+
+            let uri = ThreadUri {
+                provider: session.provider,
+                session_id: session.session_id.clone(),
+                agent_id: None,
+                query: UriQuery::default(),
+                selection: None,
+            };
+            let entries = match read_session_entries(&uri, session, roots) {
+                Ok(entries) => entries,
+                Err(err) => {
+                    warnings.push(format!("failed to search {}: {err}", session.uri()));
+                    continue;
+                }
+            };
+
+            for found in match_entries(&entries, &terms, &query.roles) {
+                hits.push(SearchHit {
+                    uri: session.uri(),
+                    provider: session.provider,
+                    session_id: session.session_id.clone(),
+                    title: session.title.clone(),
+                    cwd: session.cwd.clone(),
+                    updated_at: session.updated_at,
+                    index: found.index,
+                    role: found.role,
+                    timestamp: found.timestamp,
+                    snippet: found.snippet,
+                    highlights: found.highlights,
+                });
+            }
+        }
+    }
+    if let Some(limit) = query.limit {
+        hits.truncate(limit);
+    }
+
+    SearchResultsView {
+        query: query.clone(),
+        indexed: false,
+        sessions_scanned,
+        hits,
+        warnings,
+    }
+}
+
+fn read_session_entries(
+    uri: &ThreadUri,
+    session: &SessionSummary,
+    roots: &ProviderRoots,
+) -> Result<Vec<ThreadEntry>> {
+    let path = session_thread_path(session, roots)?;
+    let raw = read_thread_text(session.provider, &session.session_id, &path)?;
+    render::extract_thread_entries(uri, &path, &raw, &TimelineOptions::default())
+}
+
+/// Split a query into lowercase terms, keeping `"quoted phrases"` whole.
+pub(crate) fn parse_terms(text: &str) -> Vec<String> {
+    text.split('"')
+        .enumerate()
+        .flat_map(|(position, part)| {
+            if position % 2 == 1 {
+                vec![part.trim().to_string()]
+            } else {
+                part.split_whitespace().map(ToString::to_string).collect()
+            }
+        })
+        .filter(|term| !term.is_empty())
+        .map(|term| term.to_ascii_lowercase())
+        .collect()
+}
+
+/// Messages containing every term, restricted to `roles` when non-empty.
+pub(crate) fn match_entries(
+    entries: &[ThreadEntry],
+    terms: &[String],
+    roles: &[MessageRole],
+) -> Vec<MessageMatch> {
+    let mut matches = Vec::new();
+    for entry in entries {
+        let TimelineEntry::Message(message) = &entry.entry else {
+            continue;
+        };
+        if !roles.is_empty() && !roles.contains(&message.role) {
+            continue;
+        }
+        let Some(ranges) = term_ranges(&message.text, terms) else {
+            continue;
+        };
+
+        let (snippet, highlights) = snippet(&message.text, &ranges);
+        matches.push(MessageMatch {
+            index: entry.index,
+            role: message.role,
+            timestamp: message.timestamp.clone(),
+            snippet,
+            highlights,
+        });
+    }
+    matches
+}
+
+/// Sorted, merged byte ranges of every term occurrence, or `None` if any
+/// term is missing. ASCII lowercasing keeps byte offsets intact.
+pub(crate) fn term_ranges(text: &str, terms: &[String]) -> Option<Vec<(usize, usize)>> {
+    let folded = text.to_ascii_lowercase();
+    let mut ranges = Vec::new();
+    for term in terms {
+        let before = ranges.len();
+        ranges.extend(
+            folded
+                .match_indices(term.as_str())
+                .map(|(start, found)| (start, start + found.len())),
+        );
+        if ranges.len() == before {
+            return None;
+        }
+    }
+
+    ranges.sort_unstable();
+    let mut merged: Vec<(usize, usize)> = Vec::with_capacity(ranges.len());
+    for (start, end) in ranges {
+        match merged.last_mut() {
+            Some(last) if start <= last.1 => last.1 = last.1.max(end),
+            _ => merged.push((start, end)),
+        }
+    }
+    Some(merged)
+}
+
+/// Cut a single-line excerpt around the first range and shift the ranges
+/// that fall inside it.
+pub(crate) fn snippet(text: &str, ranges: &[(usize, usize)]) -> (String, Vec<(usize, usize)>) {
+    let (first_start, first_end) = ranges[0];
+
+    let mut start = first_start.saturating_sub(SNIPPET_BEFORE);
+    while !text.is_char_boundary(start) {
+        start -= 1;
+    }
+    let mut end = (start + SNIPPET_LEN).max(first_end).min(text.len());
+    while !text.is_char_boundary(end) {
+        end += 1;
+    }
+
+    let prefix = if start > 0 { "…" } else { "" };
+    let suffix = if end < text.len() { "…" } else { "" };
+    // ASCII whitespace is replaced byte for byte so offsets stay valid.
+    let body = text[start..end]
+        .chars()
+        .map(|ch| if ch.is_ascii_whitespace() { ' ' } else { ch })
+        .collect::<String>();
+
+    let highlights = ranges
+        .iter()
+        .filter(|(range_start, range_end)| *range_start >= start && *range_end <= end)
+        .map(|(range_start, range_end)| {
+            (
+                range_start - start + prefix.len(),
+                range_end - start + prefix.len(),
+            )
+        })
+        .collect();
+
+    (format!("{prefix}{body}{suffix}"), highlights)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::ThreadMessage;
+
+    fn message(index: usize, role: MessageRole, text: &str) -> ThreadEntry {
+        ThreadEntry {
+            index,
+            entry: TimelineEntry::Message(ThreadMessage::new(role, text.to_string())),
+        }
+    }
+
+    #[test]
+    fn parses_terms_and_quoted_phrases() {
+        assert_eq!(
+            parse_terms(r#"S3 "retry bug"  fixed"#),
+            vec!["s3", "retry bug", "fixed"]
+        );
+        assert!(parse_terms("  ").is_empty());
+    }
+
+    #[test]
+    fn matches_messages_containing_every_term() {
+        let entries = vec![
+            message(1, MessageRole::User, "Please fix the S3 retry bug"),
+            message(2, MessageRole::Assistant, "The retry loop now backs off"),
+            message(3, MessageRole::Assistant, "Fixed: s3 RETRY handling"),
+        ];
+        let terms = parse_terms("s3 retry");
+
+        let found = match_entries(&entries, &terms, &[]);
+        assert_eq!(
+            found.iter().map(|found| found.index).collect::<Vec<_>>(),
+            vec![1, 3]
+        );
+        assert_eq!(found[0].snippet, "Please fix the S3 retry bug");
+        assert_eq!(found[0].highlights, vec![(15, 17), (18, 23)]);
+
+        let assistant_only = match_entries(&entries, &terms, &[MessageRole::Assistant]);
+        assert_eq!(assistant_only.len(), 1);
+        assert_eq!(assistant_only[0].index, 3);
+    }
+
+    #[test]
+    fn snippets_are_single_line_excerpts_around_the_first_match() {
+        let text = format!("{}\nneedle here\n{}", "a".repeat(100), "b".repeat(300));
+        let entries = vec![message(1, MessageRole::User, &text)];
+
+        let found = match_entries(&entries, &parse_terms("NEEDLE"), &[]);
+        let snippet = &found[0].snippet;
+        assert!(snippet.starts_with('…') && snippet.ends_with('…'));
+        assert!(!snippet.contains('\n'));
+        let (start, end) = found[0].highlights[0];
+        assert_eq!(&snippet[start..end], "needle");
+    }
+}
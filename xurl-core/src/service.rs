@@ -1,6 +1,6 @@
-use std::collections::{BTreeMap, BTreeSet};
 #[cfg(feature = "sqlite")]
 use std::collections::HashMap;
+use std::collections::{BTreeMap, BTreeSet, HashSet};
 use std::fs;
 use std::path::{Path, PathBuf};
 use std::time::UNIX_EPOCH;
@@ -9,21 +9,32 @@ use serde_json::Value;
 
 use crate::error::{Result, XurlError};
 use crate::model::{
-    PiEntryListItem, PiEntryListView, PiEntryQuery, ProviderKind, ResolvedThread, SubagentInfo,
-    SubagentDetailView, SubagentExcerptMessage, SubagentLifecycleEvent, SubagentListItem,
-    SubagentListView, SubagentQuery, SubagentRelation, SubagentThreadRef, SubagentView,
+    IndexStatus, MessageRole, PiEntryListItem, PiEntryListView, PiEntryQuery, ProviderKind,
+    ResolvedThread, RunningAgentsView, SearchResultsView, SessionListQuery, SessionListView,
+    SessionSummary, SubagentDetailView, SubagentExcerptMessage, SubagentInfo,
+    SubagentLifecycleEvent, SubagentListItem, SubagentListView, SubagentQuery, SubagentRelation,
+    SubagentThreadRef, SubagentView, ThreadDiffView, ThreadEntry, ThreadHead, ThreadHeadEntry,
+    ThreadHeadSubagent, ThreadView, TimelineOptions, TouchedFilesView,
 };
+use crate::provider::aider::AiderProvider;
 use crate::provider::amp::AmpProvider;
 use crate::provider::claude::ClaudeProvider;
+use crate::provider::cline::ClineProvider;
 #[cfg(feature = "sqlite")]
 use crate::provider::codex::CodexProvider;
+#[cfg(feature = "sqlite")]
+use crate::provider::cursor::CursorProvider;
 use crate::provider::gemini::GeminiProvider;
 #[cfg(feature = "sqlite")]
+use crate::provider::goose::GooseProvider;
+#[cfg(feature = "sqlite")]
 use crate::provider::opencode::OpencodeProvider;
 use crate::provider::pi::PiProvider;
 use crate::provider::{Provider, ProviderRoots};
 use crate::render;
-use crate::uri::ThreadUri;
+use crate::time::{format_duration_secs, format_epoch_rfc3339};
+use crate::uri::{ThreadUri, UriQuery};
+use crate::{diff, files};
 
 const STATUS_PENDING_INIT: &str = "pendingInit";
 const STATUS_RUNNING: &str = "running";
@@ -66,6 +77,7 @@ pub fn resolve_thread(uri: &ThreadUri, roots: &ProviderRoots) -> Result<Resolved
         ))),
         ProviderKind::Claude => ClaudeProvider::new(&roots.claude_root).resolve(&uri.session_id),
         ProviderKind::Gemini => GeminiProvider::new(&roots.gemini_root).resolve(&uri.session_id),
+        ProviderKind::Qwen => GeminiProvider::qwen(&roots.qwen_root).resolve(&uri.session_id),
         ProviderKind::Pi => PiProvider::new(&roots.pi_root).resolve(&uri.session_id),
         #[cfg(feature = "sqlite")]
         ProviderKind::Opencode => {
@@ -76,10 +88,150 @@ pub fn resolve_thread(uri: &ThreadUri, roots: &ProviderRoots) -> Result<Resolved
             "provider {} requires the 'sqlite' feature",
             uri.provider
         ))),
+        ProviderKind::Cline => ClineProvider::new(&roots.cline_root).resolve(&uri.session_id),
+        ProviderKind::Roo => ClineProvider::roo(&roots.roo_root).resolve(&uri.session_id),
+        ProviderKind::Aider => {
+            AiderProvider::new(roots.aider_roots.clone()).resolve(&uri.session_id)
+        }
+        #[cfg(feature = "sqlite")]
+        ProviderKind::Goose => GooseProvider::new(&roots.goose_root).resolve(&uri.session_id),
+        #[cfg(not(feature = "sqlite"))]
+        ProviderKind::Goose => Err(XurlError::InvalidMode(format!(
+            "provider {} requires the 'sqlite' feature",
+            uri.provider
+        ))),
+        #[cfg(feature = "sqlite")]
+        ProviderKind::Cursor => CursorProvider::new(&roots.cursor_root).resolve(&uri.session_id),
+        #[cfg(not(feature = "sqlite"))]
+        ProviderKind::Cursor => Err(XurlError::InvalidMode(format!(
+            "provider {} requires the 'sqlite' feature",
+            uri.provider
+        ))),
+    }
+}
+
+/// Enumerate threads across providers, newest first.
+///
+/// Provider failures are reported as warnings so one unreadable store does
+/// not hide the others. Message counts and fallback titles are only computed
+/// for sessions that survive filtering and the limit.
+pub fn list_sessions(roots: &ProviderRoots, query: &SessionListQuery) -> SessionListView {
+    let mut warnings = Vec::new();
+    let mut sessions = filtered_sessions(roots, query, &mut warnings);
+
+    for session in &mut sessions {
+        if let Err(err) = summarize_session_messages(session, roots) {
+            warnings.push(format!("failed to read {}: {err}", session.uri()));
+        }
+    }
+
+    SessionListView {
+        query: query.clone(),
+        sessions,
+        warnings,
+    }
+}
+
+/// [`list_sessions`] without reading any thread: titles and message counts
+/// are only what the provider's own listing records. Cheap enough to call
+/// for every thread.
+pub fn list_session_summaries(roots: &ProviderRoots, query: &SessionListQuery) -> SessionListView {
+    let mut warnings = Vec::new();
+    let sessions = filtered_sessions(roots, query, &mut warnings);
+    SessionListView {
+        query: query.clone(),
+        sessions,
+        warnings,
+    }
+}
+
+/// Gather, filter, sort and limit provider sessions without reading threads.
+pub(crate) fn filtered_sessions(
+    roots: &ProviderRoots,
+    query: &SessionListQuery,
+    warnings: &mut Vec<String>,
+) -> Vec<SessionSummary> {
+    let providers = if query.providers.is_empty() {
+        ProviderKind::ALL
+            .into_iter()
+            .filter(|provider| provider.listed_by_default())
+            .collect()
+    } else {
+        query.providers.clone()
+    };
+
+    let mut sessions = Vec::new();
+    for provider in providers {
+        match roots.list_sessions(provider) {
+            Ok(found) => sessions.extend(found),
+            Err(err) => warnings.push(format!("failed to list {provider} sessions: {err}")),
+        }
+    }
+
+    sessions.retain(|session| {
+        query
+            .updated_after
+            .is_none_or(|after| session.updated_at >= after)
+            && query.cwd.as_deref().is_none_or(|cwd| {
+                session
+                    .cwd
+                    .as_deref()
+                    .is_some_and(|session_cwd| Path::new(session_cwd).starts_with(cwd))
+            })
+    });
+    sessions.sort_by(|a, b| {
+        b.updated_at
+            .cmp(&a.updated_at)
+            .then_with(|| a.session_id.cmp(&b.session_id))
+    });
+
+    let mut seen = HashSet::new();
+    sessions.retain(|session| seen.insert((session.provider, session.session_id.clone())));
+    if let Some(limit) = query.limit {
+        sessions.truncate(limit);
+    }
+    sessions
+}
+
+/// The thread file behind a listed session.
+pub(crate) fn session_thread_path(
+    session: &SessionSummary,
+    roots: &ProviderRoots,
+) -> Result<PathBuf> {
+    // OpenCode, Goose and Cursor summaries point at the shared database, so
+    // the thread has to be materialized through the provider before it can be read.
+    if matches!(
+        session.provider,
+        ProviderKind::Opencode | ProviderKind::Goose | ProviderKind::Cursor
+    ) {
+        let uri = ThreadUri {
+            provider: session.provider,
+            session_id: session.session_id.clone(),
+            agent_id: None,
+            query: UriQuery::default(),
+            selection: None,
+        };
+        return Ok(resolve_thread(&uri, roots)?.path);
     }
+    Ok(session.path.clone())
 }
 
-fn read_thread_raw(path: &Path) -> Result<String> {
+fn summarize_session_messages(session: &mut SessionSummary, roots: &ProviderRoots) -> Result<()> {
+    let path = session_thread_path(session, roots)?;
+    let raw = read_thread_text(session.provider, &session.session_id, &path)?;
+    let messages = render::extract_messages(session.provider, &path, &raw)?;
+    session.message_count = Some(messages.len());
+    if session.title.is_none() {
+        session.title = messages
+            .iter()
+            .find(|message| message.role == MessageRole::User && !message.text.trim().is_empty())
+            .map(|message| truncate_preview(&message.text, 96));
+    }
+
+    Ok(())
+}
+
+pub(crate) fn read_thread_raw(path: &Path) -> Result<String> {
     let bytes = fs::read(path).map_err(|source| XurlError::Io {
         path: path.to_path_buf(),
         source,
@@ -96,9 +248,31 @@ fn read_thread_raw(path: &Path) -> Result<String> {
     })
 }
 
-pub fn render_thread_markdown(uri: &ThreadUri, resolved: &ResolvedThread) -> Result<String> {
-    let raw = read_thread_raw(&resolved.path)?;
-    let markdown = render::render_markdown(uri, &resolved.path, &raw)?;
+/// The transcript of a thread. An Aider thread is one run in a chat history
+/// shared by every run in the repository, so only its section is returned.
+pub(crate) fn read_thread_text(
+    provider: ProviderKind,
+    session_id: &str,
+    path: &Path,
+) -> Result<String> {
+    let raw = read_thread_raw(path)?;
+    if provider == ProviderKind::Aider {
+        return AiderProvider::session_text(path, &raw, session_id);
+    }
+    Ok(raw)
+}
+
+fn read_resolved_text(resolved: &ResolvedThread) -> Result<String> {
+    read_thread_text(resolved.provider, &resolved.session_id, &resolved.path)
+}
+
+pub fn render_thread_markdown(
+    uri: &ThreadUri,
+    resolved: &ResolvedThread,
+    options: &TimelineOptions,
+) -> Result<String> {
+    let raw = read_resolved_text(resolved)?;
+    let markdown = render::render_markdown(uri, &resolved.path, &raw, options)?;
     Ok(strip_frontmatter(markdown))
 }
 
@@ -107,18 +281,13 @@ pub fn render_thread_markdown(uri: &ThreadUri, resolved: &ResolvedThread) -> Res
 /// Includes messages, tool calls, and resolution metadata — suitable
 /// for machine consumption (monitoring, dashboards, etc.).
 pub fn resolve_thread_json(uri: &ThreadUri, resolved: &ResolvedThread) -> Result<Value> {
-    let raw = read_thread_raw(&resolved.path)?;
+    let raw = read_resolved_text(resolved)?;
     let messages = render::extract_messages(uri.provider, &resolved.path, &raw)?;
     let tool_calls = render::extract_tool_calls(uri.provider, &resolved.path, &raw)?;
 
     let messages_json: Vec<Value> = messages
         .iter()
-        .map(|m| {
-            serde_json::json!({
-                "role": m.role.to_string(),
-                "text": m.text,
-            })
-        })
+        .map(|m| serde_json::to_value(m).unwrap_or(Value::Null))
         .collect();
 
     let tool_calls_json: Vec<Value> = tool_calls
@@ -248,113 +417,291 @@ fn list_codex_subagents(resolved_main: &ResolvedThread) -> Vec<SubagentInfo> {
     infos
 }
 
-pub fn render_thread_head_markdown(uri: &ThreadUri, roots: &ProviderRoots) -> Result<String> {
-    let mut output = String::new();
-    output.push_str("---\n");
-    push_yaml_string(&mut output, "uri", &uri.as_agents_string());
-    push_yaml_string(&mut output, "provider", &uri.provider.to_string());
-    push_yaml_string(&mut output, "session_id", &uri.session_id);
+/// Resolve the header fields shown in frontmatter (`-I`) for a thread URI.
+pub fn resolve_thread_head(uri: &ThreadUri, roots: &ProviderRoots) -> Result<ThreadHead> {
+    let mut head = ThreadHead {
+        uri: uri.as_agents_string(),
+        provider: uri.provider,
+        session_id: uri.session_id.clone(),
+        thread_source: String::new(),
+        mode: String::new(),
+        agent_id: None,
+        subagent_uri: None,
+        status: None,
+        status_source: None,
+        child_thread_id: None,
+        child_thread_source: None,
+        child_last_updated_at: None,
+        entry_id: None,
+        subagents: None,
+        entries: None,
+        warnings: Vec::new(),
+    };
+    let mut warnings = Vec::new();
 
     match (uri.provider, uri.agent_id.as_deref()) {
         (ProviderKind::Codex | ProviderKind::Claude, None) => {
             let resolved_main = resolve_thread(uri, roots)?;
-            push_yaml_string(
-                &mut output,
-                "thread_source",
-                &resolved_main.path.display().to_string(),
-            );
-            push_yaml_string(&mut output, "mode", "subagent_index");
-
-            let view = resolve_subagent_view(uri, roots, true)?;
-            let mut warnings = resolved_main.metadata.warnings.clone();
-
-            if let SubagentView::List(list) = view {
-                render_subagents_head(&mut output, &list);
+            head.thread_source = resolved_main.path.display().to_string();
+            head.mode = "subagent_index".to_string();
+            warnings.extend(resolved_main.metadata.warnings);
+
+            if let SubagentView::List(list) = resolve_subagent_view(uri, roots, true)? {
+                head.subagents = Some(subagent_head_items(&list));
                 warnings.extend(list.warnings);
             }
-
-            render_warnings(&mut output, &warnings);
         }
         (ProviderKind::Pi, None) => {
             let resolved = resolve_thread(uri, roots)?;
-            push_yaml_string(
-                &mut output,
-                "thread_source",
-                &resolved.path.display().to_string(),
-            );
-            push_yaml_string(&mut output, "mode", "pi_entry_index");
+            head.thread_source = resolved.path.display().to_string();
+            head.mode = "pi_entry_index".to_string();
 
             let list = resolve_pi_entry_list_view(uri, roots)?;
-            render_pi_entries_head(&mut output, &list);
-            render_warnings(&mut output, &list.warnings);
+            head.entries = Some(pi_entry_head_items(&list));
+            warnings.extend(list.warnings);
         }
         (ProviderKind::Codex | ProviderKind::Claude, Some(_)) => {
             let main_uri = main_thread_uri(uri);
             let resolved_main = resolve_thread(&main_uri, roots)?;
 
-            let view = resolve_subagent_view(uri, roots, false)?;
-            if let SubagentView::Detail(detail) = view {
-                let thread_source = detail
+            if let SubagentView::Detail(detail) = resolve_subagent_view(uri, roots, false)? {
+                head.thread_source = detail
                     .child_thread
                     .as_ref()
                     .and_then(|thread| thread.path.as_deref())
                     .map(ToString::to_string)
                     .unwrap_or_else(|| resolved_main.path.display().to_string());
-                push_yaml_string(&mut output, "thread_source", &thread_source);
-                push_yaml_string(&mut output, "mode", "subagent_detail");
+                head.mode = "subagent_detail".to_string();
 
                 if let Some(agent_id) = &detail.query.agent_id {
-                    push_yaml_string(&mut output, "agent_id", agent_id);
-                    push_yaml_string(
-                        &mut output,
-                        "subagent_uri",
-                        &agents_thread_uri(
-                            &detail.query.provider,
-                            &detail.query.main_thread_id,
-                            Some(agent_id),
-                        ),
-                    );
+                    head.agent_id = Some(agent_id.clone());
+                    head.subagent_uri = Some(agents_thread_uri(
+                        &detail.query.provider,
+                        &detail.query.main_thread_id,
+                        Some(agent_id),
+                    ));
                 }
-                push_yaml_string(&mut output, "status", &detail.status);
-                push_yaml_string(&mut output, "status_source", &detail.status_source);
+                head.status = Some(detail.status.clone());
+                head.status_source = Some(detail.status_source.clone());
 
                 if let Some(child_thread) = &detail.child_thread {
-                    push_yaml_string(&mut output, "child_thread_id", &child_thread.thread_id);
-                    if let Some(path) = &child_thread.path {
-                        push_yaml_string(&mut output, "child_thread_source", path);
-                    }
-                    if let Some(last_updated_at) = &child_thread.last_updated_at {
-                        push_yaml_string(&mut output, "child_last_updated_at", last_updated_at);
-                    }
+                    head.child_thread_id = Some(child_thread.thread_id.clone());
+                    head.child_thread_source = child_thread.path.clone();
+                    head.child_last_updated_at = child_thread.last_updated_at.clone();
                 }
 
-                render_warnings(&mut output, &detail.warnings);
+                warnings.extend(detail.warnings);
             }
         }
         (ProviderKind::Pi, Some(entry_id)) => {
             let resolved = resolve_thread(uri, roots)?;
-            push_yaml_string(
-                &mut output,
-                "thread_source",
-                &resolved.path.display().to_string(),
-            );
-            push_yaml_string(&mut output, "mode", "pi_entry");
-            push_yaml_string(&mut output, "entry_id", entry_id);
+            head.thread_source = resolved.path.display().to_string();
+            head.mode = "pi_entry".to_string();
+            head.entry_id = Some(entry_id.to_string());
         }
         _ => {
             let resolved = resolve_thread(uri, roots)?;
-            push_yaml_string(
-                &mut output,
-                "thread_source",
-                &resolved.path.display().to_string(),
-            );
-            push_yaml_string(&mut output, "mode", "thread");
-            render_warnings(&mut output, &resolved.metadata.warnings);
+            head.thread_source = resolved.path.display().to_string();
+            head.mode = "thread".to_string();
+            warnings.extend(resolved.metadata.warnings);
         }
     }
 
+    head.warnings = warnings
+        .into_iter()
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect();
+    Ok(head)
+}
+
+pub fn render_thread_head_markdown(uri: &ThreadUri, roots: &ProviderRoots) -> Result<String> {
+    let head = resolve_thread_head(uri, roots)?;
+    Ok(render_thread_head_yaml(&head))
+}
+
+fn render_thread_head_yaml(head: &ThreadHead) -> String {
+    let mut output = String::new();
     output.push_str("---\n");
-    Ok(output)
+    push_yaml_string(&mut output, "uri", &head.uri);
+    push_yaml_string(&mut output, "provider", &head.provider.to_string());
+    push_yaml_string(&mut output, "session_id", &head.session_id);
+    push_yaml_string(&mut output, "thread_source", &head.thread_source);
+    push_yaml_string(&mut output, "mode", &head.mode);
+
+    let optional_fields = [
+        ("agent_id", &head.agent_id),
+        ("subagent_uri", &head.subagent_uri),
+        ("status", &head.status),
+        ("status_source", &head.status_source),
+        ("child_thread_id", &head.child_thread_id),
+        ("child_thread_source", &head.child_thread_source),
+        ("child_last_updated_at", &head.child_last_updated_at),
+        ("entry_id", &head.entry_id),
+    ];
+    for (key, value) in optional_fields {
+        if let Some(value) = value {
+            push_yaml_string(&mut output, key, value);
+        }
+    }
+
+    if let Some(subagents) = &head.subagents {
+        render_subagents_head(&mut output, subagents);
+    }
+    if let Some(entries) = &head.entries {
+        render_pi_entries_head(&mut output, entries);
+    }
+    render_warnings(&mut output, &head.warnings);
+
+    output.push_str("---\n");
+    output
+}
+
+/// Resolve the full machine-readable view for a thread URI.
+///
+/// Subagent drill-down URIs carry the detail view instead of a timeline.
+pub fn resolve_thread_view(
+    uri: &ThreadUri,
+    roots: &ProviderRoots,
+    options: &TimelineOptions,
+) -> Result<ThreadView> {
+    let head = resolve_thread_head(uri, roots)?;
+
+    if matches!(uri.provider, ProviderKind::Codex | ProviderKind::Claude) && uri.agent_id.is_some()
+    {
+        let subagent = match resolve_subagent_view(uri, roots, false)? {
+            SubagentView::Detail(detail) => Some(detail),
+            SubagentView::List(_) => None,
+        };
+        return Ok(ThreadView {
+            head,
+            timeline: None,
+            subagent,
+        });
+    }
+
+    let resolved = resolve_thread(uri, roots)?;
+    let raw = read_resolved_text(&resolved)?;
+    let mut timeline = render::extract_thread_entries(uri, &resolved.path, &raw, options)?;
+    if let Some(selection) = uri.selection {
+        timeline = selection.apply(timeline)?;
+    }
+    Ok(ThreadView {
+        head,
+        timeline: Some(timeline),
+        subagent: None,
+    })
+}
+
+/// Resolve the files a thread's tool calls read, created, modified or deleted.
+///
+/// Paths are made relative to the session cwd when the provider records one.
+pub fn resolve_touched_files(uri: &ThreadUri, roots: &ProviderRoots) -> Result<TouchedFilesView> {
+    let (entries, cwd) = resolve_tool_timeline(uri, roots, "files")?;
+    Ok(TouchedFilesView {
+        uri: uri.as_agents_string(),
+        files: files::extract_touched_files(&entries, cwd.as_deref().map(Path::new)),
+        cwd,
+    })
+}
+
+/// Reconstruct the edits a thread made as per-file unified diffs.
+pub fn resolve_thread_diff(uri: &ThreadUri, roots: &ProviderRoots) -> Result<ThreadDiffView> {
+    let (entries, cwd) = resolve_tool_timeline(uri, roots, "diff")?;
+    Ok(ThreadDiffView {
+        uri: uri.as_agents_string(),
+        files: diff::extract_file_diffs(&entries, cwd.as_deref().map(Path::new)),
+        cwd,
+    })
+}
+
+/// Timeline with tool entries plus the session cwd, for tool-derived views.
+fn resolve_tool_timeline(
+    uri: &ThreadUri,
+    roots: &ProviderRoots,
+    command: &str,
+) -> Result<(Vec<ThreadEntry>, Option<String>)> {
+    if matches!(uri.provider, ProviderKind::Codex | ProviderKind::Claude) && uri.agent_id.is_some()
+    {
+        return Err(XurlError::InvalidMode(format!(
+            "{command} requires a main thread URI; inspect a child thread by its own session id"
+        )));
+    }
+    if uri.selection.is_some() {
+        return Err(XurlError::InvalidMode(format!(
+            "{command} reads the whole thread; drop the #fragment from the URI"
+        )));
+    }
+
+    let resolved = resolve_thread(uri, roots)?;
+    let raw = read_resolved_text(&resolved)?;
+    let options = TimelineOptions {
+        tools: true,
+        ..TimelineOptions::default()
+    };
+    let entries = render::extract_thread_entries(uri, &resolved.path, &raw, &options)?;
+
+    // A store that cannot be listed only costs us cwd-relative paths.
+    let cwd = roots
+        .list_sessions(uri.provider)
+        .ok()
+        .and_then(|sessions| {
+            sessions
+                .into_iter()
+                .find(|session| session.session_id == uri.session_id)
+        })
+        .and_then(|session| session.cwd);
+
+    Ok((entries, cwd))
+}
+
+/// Concatenate per-file patches into one diff, with notes as `#` lines
+/// between sections (which `git apply` skips).
+pub fn render_thread_diff(view: &ThreadDiffView) -> String {
+    let mut output = String::new();
+    for file in &view.files {
+        for note in &file.notes {
+            output.push_str(&format!("# {}: {note}\n", file.path));
+        }
+        output.push_str(&file.patch);
+    }
+    output
+}
+
+pub fn render_touched_files_markdown(view: &TouchedFilesView) -> String {
+    let mut output = String::new();
+    output.push_str("# Files\n\n");
+    output.push_str(&format!("- URI: `{}`\n", view.uri));
+    output.push_str(&format!(
+        "- Cwd: `{}`\n",
+        view.cwd.as_deref().unwrap_or("unknown")
+    ));
+    output.push_str(&format!("- Count: `{}`\n\n", view.files.len()));
+
+    if view.files.is_empty() {
+        output.push_str("_No files found._\n");
+        return output;
+    }
+
+    for (index, file) in view.files.iter().enumerate() {
+        output.push_str(&format!("## {}. `{}`\n\n", index + 1, file.path));
+        output.push_str(&format!("- Change: `{}`\n", file.change));
+        let touches = file
+            .touches
+            .iter()
+            .map(|touch| {
+                format!(
+                    "{} by `{}` (turn {}, entry {})",
+                    touch.change, touch.tool, touch.turn, touch.entry
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("; ");
+        output.push_str(&format!("- Touches: {touches}\n"));
+        output.push('\n');
+    }
+
+    output
 }
 
 pub fn resolve_subagent_view(
@@ -368,6 +715,12 @@ pub fn resolve_subagent_view(
         ));
     }
 
+    if !list && uri.selection.is_some() {
+        return Err(XurlError::InvalidMode(
+            "subagent views are not timelines; drop the #fragment from the URI".to_string(),
+        ));
+    }
+
     if !list && uri.agent_id.is_none() {
         return Err(XurlError::InvalidMode(
             "subagent drill-down requires agents://<provider>/<main_thread_id>/<agent_id>"
@@ -412,59 +765,83 @@ fn render_warnings(output: &mut String, warnings: &[String]) {
     }
 }
 
-fn render_subagents_head(output: &mut String, list: &SubagentListView) {
+fn subagent_head_items(list: &SubagentListView) -> Vec<ThreadHeadSubagent> {
+    list.agents
+        .iter()
+        .map(|agent| ThreadHeadSubagent {
+            agent_id: agent.agent_id.clone(),
+            uri: agents_thread_uri(
+                &list.query.provider,
+                &list.query.main_thread_id,
+                Some(&agent.agent_id),
+            ),
+            status: agent.status.clone(),
+            status_source: agent.status_source.clone(),
+            last_update: agent.last_update.clone(),
+            thread_source: agent
+                .child_thread
+                .as_ref()
+                .and_then(|thread| thread.path.clone()),
+        })
+        .collect()
+}
+
+fn pi_entry_head_items(list: &PiEntryListView) -> Vec<ThreadHeadEntry> {
+    list.entries
+        .iter()
+        .map(|entry| ThreadHeadEntry {
+            entry_id: entry.entry_id.clone(),
+            uri: agents_thread_uri(
+                &list.query.provider,
+                &list.query.session_id,
+                Some(&entry.entry_id),
+            ),
+            entry_type: entry.entry_type.clone(),
+            parent_id: entry.parent_id.clone(),
+            timestamp: entry.timestamp.clone(),
+            preview: entry.preview.clone(),
+            is_leaf: entry.is_leaf,
+        })
+        .collect()
+}
+
+fn render_subagents_head(output: &mut String, subagents: &[ThreadHeadSubagent]) {
     output.push_str("subagents:\n");
-    if list.agents.is_empty() {
+    if subagents.is_empty() {
         output.push_str("  []\n");
         return;
     }
 
-    for agent in &list.agents {
+    for agent in subagents {
         output.push_str(&format!(
             "  - agent_id: '{}'\n",
             yaml_single_quoted(&agent.agent_id)
         ));
-        output.push_str(&format!(
-            "    uri: '{}'\n",
-            yaml_single_quoted(&agents_thread_uri(
-                &list.query.provider,
-                &list.query.main_thread_id,
-                Some(&agent.agent_id),
-            ))
-        ));
+        push_yaml_string_with_indent(output, 4, "uri", &agent.uri);
         push_yaml_string_with_indent(output, 4, "status", &agent.status);
         push_yaml_string_with_indent(output, 4, "status_source", &agent.status_source);
         if let Some(last_update) = &agent.last_update {
             push_yaml_string_with_indent(output, 4, "last_update", last_update);
         }
-        if let Some(child_thread) = &agent.child_thread
-            && let Some(path) = &child_thread.path
-        {
-            push_yaml_string_with_indent(output, 4, "thread_source", path);
+        if let Some(thread_source) = &agent.thread_source {
+            push_yaml_string_with_indent(output, 4, "thread_source", thread_source);
         }
     }
 }
 
-fn render_pi_entries_head(output: &mut String, list: &PiEntryListView) {
+fn render_pi_entries_head(output: &mut String, entries: &[ThreadHeadEntry]) {
     output.push_str("entries:\n");
-    if list.entries.is_empty() {
+    if entries.is_empty() {
         output.push_str("  []\n");
         return;
     }
 
-    for entry in &list.entries {
+    for entry in entries {
         output.push_str(&format!(
             "  - entry_id: '{}'\n",
             yaml_single_quoted(&entry.entry_id)
         ));
-        output.push_str(&format!(
-            "    uri: '{}'\n",
-            yaml_single_quoted(&agents_thread_uri(
-                &list.query.provider,
-                &list.query.session_id,
-                Some(&entry.entry_id),
-            ))
-        ));
+        push_yaml_string_with_indent(output, 4, "uri", &entry.uri);
         push_yaml_string_with_indent(output, 4, "entry_type", &entry.entry_type);
         if let Some(parent_id) = &entry.parent_id {
             push_yaml_string_with_indent(output, 4, "parent_id", parent_id);
@@ -1529,6 +1906,8 @@ fn main_thread_uri(uri: &ThreadUri) -> ThreadUri {
         provider: uri.provider,
         session_id: uri.session_id.clone(),
         agent_id: None,
+        query: UriQuery::default(),
+        selection: None,
     }
 }
 
@@ -1628,6 +2007,275 @@ fn render_subagent_list_markdown(view: &SubagentListView) -> String {
     output
 }
 
+pub fn render_session_list_markdown(view: &SessionListView) -> String {
+    let mut output = String::new();
+    output.push_str("# Threads\n\n");
+    let providers = if view.query.providers.is_empty() {
+        "all".to_string()
+    } else {
+        view.query
+            .providers
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+    output.push_str(&format!("- Providers: `{providers}`\n"));
+    if let Some(after) = view.query.updated_after {
+        output.push_str(&format!(
+            "- Updated Since: `{}`\n",
+            format_epoch_rfc3339(after)
+        ));
+    }
+    if let Some(cwd) = &view.query.cwd {
+        output.push_str(&format!("- Cwd: `{}`\n", cwd.display()));
+    }
+    output.push_str(&format!("- Count: `{}`\n\n", view.sessions.len()));
+
+    if view.sessions.is_empty() {
+        output.push_str("_No threads found._\n");
+        return output;
+    }
+
+    for (index, session) in view.sessions.iter().enumerate() {
+        output.push_str(&format!("## {}. `{}`\n\n", index + 1, session.uri()));
+        if let Some(title) = &session.title {
+            output.push_str(&format!("- Title: {title}\n"));
+        }
+        output.push_str(&format!("- Provider: `{}`\n", session.provider));
+        output.push_str(&format!(
+            "- Cwd: `{}`\n",
+            session.cwd.as_deref().unwrap_or("unknown")
+        ));
+        if let Some(created_at) = session.created_at {
+            output.push_str(&format!(
+                "- Created: `{}`\n",
+                format_epoch_rfc3339(created_at)
+            ));
+        }
+        output.push_str(&format!(
+            "- Updated: `{}`\n",
+            format_epoch_rfc3339(session.updated_at)
+        ));
+        if let Some(count) = session.message_count {
+            output.push_str(&format!("- Messages: `{count}`\n"));
+        }
+        if let Some(parent) = &session.parent_session_id {
+            output.push_str(&format!(
+                "- Parent: `{}`\n",
+                agents_thread_uri(&session.provider.to_string(), parent, None)
+            ));
+        }
+        if session.archived {
+            output.push_str("- Archived: `yes`\n");
+        }
+        output.push_str(&format!("- Thread Path: `{}`\n", session.path.display()));
+        output.push('\n');
+    }
+
+    output
+}
+
+pub fn render_search_markdown(view: &SearchResultsView) -> String {
+    let mut output = String::new();
+    output.push_str("# Search\n\n");
+    output.push_str(&format!("- Query: `{}`\n", view.query.text));
+    let providers = if view.query.providers.is_empty() {
+        "all".to_string()
+    } else {
+        view.query
+            .providers
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+    output.push_str(&format!("- Providers: `{providers}`\n"));
+    if !view.query.roles.is_empty() {
+        let roles = view
+            .query
+            .roles
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join(", ");
+        output.push_str(&format!("- Roles: `{roles}`\n"));
+    }
+    if let Some(after) = view.query.updated_after {
+        output.push_str(&format!(
+            "- Updated Since: `{}`\n",
+            format_epoch_rfc3339(after)
+        ));
+    }
+    if let Some(before) = view.query.started_before {
+        output.push_str(&format!(
+            "- Started Before: `{}`\n",
+            format_epoch_rfc3339(before)
+        ));
+    }
+    if let Some(cwd) = &view.query.cwd {
+        output.push_str(&format!("- Cwd: `{}`\n", cwd.display()));
+    }
+    let source = if view.indexed { "index" } else { "scan" };
+    output.push_str(&format!("- Source: `{source}`\n"));
+    output.push_str(&format!("- Threads Scanned: `{}`\n", view.sessions_scanned));
+    output.push_str(&format!("- Count: `{}`\n\n", view.hits.len()));
+
+    if view.hits.is_empty() {
+        output.push_str("_No matches found._\n");
+        return output;
+    }
+
+    for (index, hit) in view.hits.iter().enumerate() {
+        output.push_str(&format!("## {}. `{}`\n\n", index + 1, hit.uri));
+        if let Some(title) = &hit.title {
+            output.push_str(&format!("- Title: {title}\n"));
+        }
+        output.push_str(&format!("- Message: `{}` ({})\n", hit.index, hit.role));
+        if let Some(timestamp) = &hit.timestamp {
+            output.push_str(&format!("- Timestamp: `{timestamp}`\n"));
+        }
+        output.push_str(&format!(
+            "- Cwd: `{}`\n",
+            hit.cwd.as_deref().unwrap_or("unknown")
+        ));
+        output.push_str(&format!(
+            "- Updated: `{}`\n\n",
+            format_epoch_rfc3339(hit.updated_at)
+        ));
+        output.push_str(&format!(
+            "> {}\n\n",
+            highlight_markdown(&hit.snippet, &hit.highlights)
+        ));
+    }
+
+    output
+}
+
+pub fn render_index_status_markdown(status: &IndexStatus) -> String {
+    let mut output = String::new();
+    output.push_str("# Search Index\n\n");
+    output.push_str(&format!("- Path: `{}`\n", status.path.display()));
+    if !status.exists {
+        output.push_str("\n_No index found. Run `xurl index rebuild` to create one._\n");
+        return output;
+    }
+    output.push_str(&format!("- Size: `{}` bytes\n", status.size_bytes));
+    output.push_str(&format!("- Threads: `{}`\n", status.threads));
+    output.push_str(&format!("- Messages: `{}`\n", status.messages));
+    output.push_str(&format!(
+        "- Last Refresh: `{}`\n",
+        status
+            .last_refresh
+            .map_or_else(|| "never".to_string(), format_epoch_rfc3339)
+    ));
+    output.push_str(&format!("- Pending: `{}`\n\n", status.pending));
+
+    if status.providers.is_empty() {
+        output.push_str("_No threads indexed._\n");
+        return output;
+    }
+
+    for provider in &status.providers {
+        output.push_str(&format!("## `{}`\n\n", provider.provider));
+        output.push_str(&format!("- Threads: `{}`\n", provider.threads));
+        output.push_str(&format!("- Messages: `{}`\n\n", provider.messages));
+    }
+
+    output
+}
+
+pub fn render_running_agents_markdown(view: &RunningAgentsView) -> String {
+    let mut output = String::new();
+    output.push_str("# Running Agents\n\n");
+    output.push_str(&format!("- Count: `{}`\n", view.agents.len()));
+    output.push_str(&format!("- Stale: `{}`\n\n", view.stale.len()));
+
+    if view.agents.is_empty() {
+        output.push_str("_No running agents found._\n\n");
+    }
+    for (index, agent) in view.agents.iter().enumerate() {
+        output.push_str(&format!(
+            "## {}. `{}` · pid {}\n\n",
+            index + 1,
+            agent.provider,
+            agent.pid
+        ));
+        output.push_str(&format!(
+            "- Thread: `{}`\n",
+            agent.uri.as_deref().unwrap_or("unknown")
+        ));
+        if let Some(matched_by) = &agent.matched_by {
+            output.push_str(&format!("- Matched By: `{matched_by}`\n"));
+        }
+        output.push_str(&format!(
+            "- Cwd: `{}`\n",
+            agent.cwd.as_deref().unwrap_or("unknown")
+        ));
+        if let Some(uptime) = agent.uptime_secs {
+            output.push_str(&format!("- Uptime: `{}`\n", format_duration_secs(uptime)));
+        }
+        if let Some(last_activity) = agent.last_activity {
+            output.push_str(&format!(
+                "- Last Activity: `{}`\n",
+                format_epoch_rfc3339(last_activity)
+            ));
+        }
+        if let Some(subagents) = agent.subagents {
+            output.push_str(&format!(
+                "- Subagents: `{}` running / `{}` total\n",
+                subagents.running, subagents.total
+            ));
+        }
+        output.push_str(&format!(
+            "- Command: `{}`\n\n",
+            command_preview(&agent.command)
+        ));
+    }
+
+    if !view.stale.is_empty() {
+        output.push_str("# Stale Threads\n\n");
+    }
+    for (index, stale) in view.stale.iter().enumerate() {
+        output.push_str(&format!("## {}. `{}`\n\n", index + 1, stale.uri));
+        output.push_str(&format!("- Reason: {}\n", stale.reason));
+        output.push_str(&format!(
+            "- Cwd: `{}`\n",
+            stale.cwd.as_deref().unwrap_or("unknown")
+        ));
+        output.push_str(&format!(
+            "- Last Activity: `{}`\n\n",
+            format_epoch_rfc3339(stale.last_activity)
+        ));
+    }
+
+    output
+}
+
+/// A command line on one line, cut to a readable length.
+fn command_preview(command: &str) -> String {
+    const MAX_CHARS: usize = 120;
+    let line = command.split_whitespace().collect::<Vec<_>>().join(" ");
+    match line.char_indices().nth(MAX_CHARS) {
+        Some((cut, _)) => format!("{}…", &line[..cut]),
+        None => line,
+    }
+}
+
+fn highlight_markdown(snippet: &str, highlights: &[(usize, usize)]) -> String {
+    let mut output = String::with_capacity(snippet.len() + highlights.len() * 4);
+    let mut cursor = 0;
+    for &(start, end) in highlights {
+        output.push_str(&snippet[cursor..start]);
+        output.push_str("**");
+        output.push_str(&snippet[start..end]);
+        output.push_str("**");
+        cursor = end;
+    }
+    output.push_str(&snippet[cursor..]);
+    output
+}
+
 fn render_subagent_detail_markdown(view: &SubagentDetailView) -> String {
     let main_thread_uri = agents_thread_uri(&view.query.provider, &view.query.main_thread_id, None);
     let mut output = String::new();
@@ -0,0 +1,174 @@
+//! Minimal UTC timestamp helpers.
+//!
+//! Provider stores mix epoch seconds, epoch milliseconds and RFC 3339
+//! strings. These helpers convert between them without pulling in a
+//! full date/time dependency.
+
+/// Format epoch seconds as an RFC 3339 UTC timestamp (`YYYY-MM-DDTHH:MM:SSZ`).
+pub fn format_epoch_rfc3339(epoch_secs: u64) -> String {
+    let days = (epoch_secs / 86_400) as i64;
+    let secs_of_day = epoch_secs % 86_400;
+    let (year, month, day) = civil_from_days(days);
+
+    format!(
+        "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}Z",
+        secs_of_day / 3600,
+        (secs_of_day % 3600) / 60,
+        secs_of_day % 60
+    )
+}
+
+/// Format a duration compactly using its two largest units, e.g. `45s`,
+/// `12m`, `3h 5m` or `2d 4h`.
+pub fn format_duration_secs(secs: u64) -> String {
+    let units = [(86_400, "d"), (3600, "h"), (60, "m"), (1, "s")];
+    let mut parts = Vec::new();
+    let mut rest = secs;
+    for (size, suffix) in units {
+        if rest >= size || (parts.is_empty() && size == 1) {
+            parts.push(format!("{}{suffix}", rest / size));
+            rest %= size;
+        } else if !parts.is_empty() {
+            break;
+        }
+        if parts.len() == 2 {
+            break;
+        }
+    }
+    parts.join(" ")
+}
+
+/// Parse an RFC 3339 timestamp (or a bare `YYYY-MM-DD` date) into epoch seconds.
+///
+/// Fractional seconds are truncated and numeric offsets are applied.
+/// Returns `None` for anything that does not look like a timestamp.
+pub fn parse_rfc3339_epoch(input: &str) -> Option<u64> {
+    let input = input.trim();
+    let bytes = input.as_bytes();
+    if bytes.len() < 10 || bytes[4] != b'-' || bytes[7] != b'-' {
+        return None;
+    }
+
+    let year = input.get(0..4)?.parse::<i64>().ok()?;
+    let month = input.get(5..7)?.parse::<u32>().ok()?;
+    let day = input.get(8..10)?.parse::<u32>().ok()?;
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return None;
+    }
+
+    let days = days_from_civil(year, month, day);
+    let mut secs = days * 86_400;
+
+    let rest = &input[10..];
+    if rest.is_empty() {
+        return u64::try_from(secs).ok();
+    }
+
+    let rest = rest.strip_prefix(['T', 't', ' '])?;
+    let hour = rest.get(0..2)?.parse::<i64>().ok()?;
+    let minute = rest.get(3..5)?.parse::<i64>().ok()?;
+    let second = rest.get(6..8)?.parse::<i64>().ok()?;
+    secs += hour * 3600 + minute * 60 + second;
+
+    let mut tail = rest.get(8..)?;
+    if let Some(fraction) = tail.strip_prefix('.') {
+        let digits = fraction
+            .find(|ch: char| !ch.is_ascii_digit())
+            .unwrap_or(fraction.len());
+        tail = &fraction[digits..];
+    }
+
+    match tail {
+        "" | "Z" | "z" => {}
+        offset => {
+            let sign = match offset.as_bytes().first()? {
+                b'+' => 1,
+                b'-' => -1,
+                _ => return None,
+            };
+            let offset_hours = offset.get(1..3)?.parse::<i64>().ok()?;
+            let offset_minutes = offset.get(4..6)?.parse::<i64>().ok()?;
+            secs -= sign * (offset_hours * 3600 + offset_minutes * 60);
+        }
+    }
+
+    u64::try_from(secs).ok()
+}
+
+/// Interpret a numeric timestamp that may be either epoch seconds or epoch
+/// milliseconds (OpenCode and Pi use milliseconds).
+pub fn normalize_epoch(raw: i64) -> Option<u64> {
+    let raw = u64::try_from(raw).ok()?;
+    if raw > 100_000_000_000 {
+        Some(raw / 1000)
+    } else {
+        Some(raw)
+    }
+}
+
+// Algorithms from Howard Hinnant's `chrono`-compatible date library.
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let yoe = year.rem_euclid(400);
+    let month = i64::from(month);
+    let mp = if month > 2 { month - 3 } else { month + 9 };
+    let doy = (153 * mp + 2) / 5 + i64::from(day) - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe - 719_468
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formats_epoch() {
+        assert_eq!(format_epoch_rfc3339(0), "1970-01-01T00:00:00Z");
+        assert_eq!(format_epoch_rfc3339(1_771_851_612), "2026-02-23T13:00:12Z");
+    }
+
+    #[test]
+    fn formats_durations_with_two_units() {
+        assert_eq!(format_duration_secs(0), "0s");
+        assert_eq!(format_duration_secs(45), "45s");
+        assert_eq!(format_duration_secs(720), "12m");
+        assert_eq!(format_duration_secs(3 * 3600 + 5 * 60 + 7), "3h 5m");
+        assert_eq!(format_duration_secs(2 * 86_400 + 30), "2d");
+        assert_eq!(format_duration_secs(2 * 86_400 + 4 * 3600), "2d 4h");
+    }
+
+    #[test]
+    fn parses_rfc3339_variants() {
+        assert_eq!(
+            parse_rfc3339_epoch("2026-02-23T13:00:12.780Z"),
+            Some(1_771_851_612)
+        );
+        assert_eq!(
+            parse_rfc3339_epoch("2026-02-23T15:00:12+02:00"),
+            Some(1_771_851_612)
+        );
+        assert_eq!(parse_rfc3339_epoch("2026-02-23"), Some(1_771_804_800));
+        assert_eq!(parse_rfc3339_epoch("txt_8957d7aea4e7ec47"), None);
+    }
+
+    #[test]
+    fn normalizes_millisecond_epochs() {
+        assert_eq!(normalize_epoch(1_768_307_088_294), Some(1_768_307_088));
+        assert_eq!(normalize_epoch(1_768_307_088), Some(1_768_307_088));
+        assert_eq!(normalize_epoch(-1), None);
+    }
+}
@@ -1,10 +1,11 @@
+use std::fmt;
 use std::str::FromStr;
 
 use once_cell::sync::Lazy;
 use regex::Regex;
 
 use crate::error::{Result, XurlError};
-use crate::model::ProviderKind;
+use crate::model::{MessageRole, ProviderKind, ThreadEntry, TimelineOptions};
 
 static SESSION_ID_RE: Lazy<Regex> = Lazy::new(|| {
     Regex::new(r"(?i)^[0-9a-f]{8}-[0-9a-f]{4}-[0-9a-f]{4}-[0-9a-f]{4}-[0-9a-f]{12}$")
@@ -16,6 +17,28 @@ static AMP_SESSION_ID_RE: Lazy<Regex> = Lazy::new(|| {
 });
 static OPENCODE_SESSION_ID_RE: Lazy<Regex> =
     Lazy::new(|| Regex::new(r"^ses_[0-9A-Za-z]+$").expect("valid regex"));
+/// Cline task ids are creation times in epoch milliseconds; Roo Code
+/// moved from those to UUIDs.
+static CLINE_TASK_ID_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r"(?i)^(?:[0-9]{10,16}|[0-9a-f]{8}-[0-9a-f]{4}-[0-9a-f]{4}-[0-9a-f]{4}-[0-9a-f]{12})$",
+    )
+    .expect("valid regex")
+});
+/// Aider session ids are derived by xurl: the run's start time plus a hash
+/// of its chat history path.
+static AIDER_SESSION_ID_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?i)^[0-9]{8}-[0-9]{6}-[0-9a-f]{8}$").expect("valid regex"));
+/// Goose names sessions `YYYYMMDD_<n>`; releases before its session
+/// database used `YYYYMMDD_HHMMSS`.
+static GOOSE_SESSION_ID_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^[0-9]{8}_[0-9]{1,6}$").expect("valid regex"));
+static SESSION_PREFIX_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?i)^[0-9a-f][0-9a-f-]{5,34}$").expect("valid regex"));
+static AMP_SESSION_PREFIX_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?i)^t-[0-9a-f][0-9a-f-]{5,34}$").expect("valid regex"));
+static OPENCODE_SESSION_PREFIX_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^ses_[0-9A-Za-z]{6,}$").expect("valid regex"));
 static PI_SHORT_ENTRY_ID_RE: Lazy<Regex> =
     Lazy::new(|| Regex::new(r"(?i)^[0-9a-f]{8}$").expect("valid regex"));
 
@@ -24,6 +47,259 @@ pub struct ThreadUri {
     pub provider: ProviderKind,
     pub session_id: String,
     pub agent_id: Option<String>,
+    /// View options from the `?...` query.
+    pub query: UriQuery,
+    /// Timeline entries picked by the `#...` fragment.
+    pub selection: Option<EntrySelection>,
+}
+
+/// Query keys accepted on thread URIs, in canonical order.
+const QUERY_KEYS: [&str; 6] = [
+    "format",
+    "head",
+    "max_tool_output",
+    "roles",
+    "thinking",
+    "tools",
+];
+
+/// View options carried in a URI query such as `?tools=1&roles=user`.
+///
+/// Each key mirrors a CLI flag; unset keys leave the flag's default alone.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct UriQuery {
+    /// `format=markdown|json|jsonl|html`, like `--format`.
+    pub format: Option<UriFormat>,
+    /// `head=1`, like `--head`.
+    pub head: Option<bool>,
+    /// `max_tool_output=<chars>`, like `--max-tool-output`.
+    pub max_tool_output: Option<usize>,
+    /// `roles=user` or `roles=assistant`, like `--role`. Naming both roles
+    /// is the same as no filter.
+    pub role: Option<MessageRole>,
+    /// `thinking=0`, like `--no-thinking`.
+    pub thinking: Option<bool>,
+    /// `tools=1`, like `--tools`.
+    pub tools: Option<bool>,
+}
+
+/// Output formats selectable with `?format=`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UriFormat {
+    Markdown,
+    Json,
+    Jsonl,
+    Html,
+}
+
+impl fmt::Display for UriFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Markdown => write!(f, "markdown"),
+            Self::Json => write!(f, "json"),
+            Self::Jsonl => write!(f, "jsonl"),
+            Self::Html => write!(f, "html"),
+        }
+    }
+}
+
+impl UriQuery {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// `base` with every option set in the query applied on top.
+    pub fn timeline_options(&self, base: TimelineOptions) -> TimelineOptions {
+        TimelineOptions {
+            tools: self.tools.unwrap_or(base.tools),
+            max_tool_output: self.max_tool_output.unwrap_or(base.max_tool_output),
+            role: self.role.or(base.role),
+            thinking: self.thinking.unwrap_or(base.thinking),
+        }
+    }
+}
+
+impl fmt::Display for UriQuery {
+    /// Keys in canonical (alphabetical) order, booleans as `1`/`0`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let flag = |value: bool| if value { "1" } else { "0" };
+        let pairs = [
+            self.format.map(|format| format.to_string()),
+            self.head.map(|head| flag(head).to_string()),
+            self.max_tool_output.map(|max| max.to_string()),
+            self.role.map(|role| role.to_string()),
+            self.thinking.map(|thinking| flag(thinking).to_string()),
+            self.tools.map(|tools| flag(tools).to_string()),
+        ];
+        let mut separator = "";
+        for (key, value) in QUERY_KEYS.iter().zip(pairs) {
+            if let Some(value) = value {
+                write!(f, "{separator}{key}={value}")?;
+                separator = "&";
+            }
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for UriQuery {
+    type Err = XurlError;
+
+    fn from_str(query: &str) -> Result<Self> {
+        let mut parsed = Self::default();
+        let mut seen = Vec::new();
+        for pair in query.split('&').filter(|pair| !pair.is_empty()) {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            let (key, value) = (percent_decode(key), percent_decode(value));
+            let (key, value) = (key.as_str(), value.as_str());
+            if !QUERY_KEYS.contains(&key) {
+                return Err(XurlError::InvalidUri(format!(
+                    "unknown query parameter `{key}`; supported: {}",
+                    QUERY_KEYS.join(", ")
+                )));
+            }
+            if seen.iter().any(|seen| seen == key) {
+                return Err(XurlError::InvalidUri(format!(
+                    "query parameter `{key}` is given more than once"
+                )));
+            }
+            seen.push(key.to_string());
+
+            let invalid = |expected: &str| {
+                XurlError::InvalidUri(format!(
+                    "invalid value `{value}` for query parameter `{key}`; expected {expected}"
+                ))
+            };
+            let flag = || match value {
+                "1" | "true" => Ok(true),
+                "0" | "false" => Ok(false),
+                _ => Err(invalid("1 or 0")),
+            };
+            match key {
+                "format" => {
+                    parsed.format = Some(match value {
+                        "markdown" => UriFormat::Markdown,
+                        "json" => UriFormat::Json,
+                        "jsonl" => UriFormat::Jsonl,
+                        "html" => UriFormat::Html,
+                        _ => return Err(invalid("markdown, json, jsonl or html")),
+                    });
+                }
+                "head" => parsed.head = Some(flag()?),
+                "max_tool_output" => {
+                    parsed.max_tool_output =
+                        Some(value.parse().map_err(|_| invalid("a character count"))?);
+                }
+                "roles" => {
+                    let mut roles = Vec::new();
+                    for role in value.split(',') {
+                        roles.push(match role {
+                            "user" => MessageRole::User,
+                            "assistant" => MessageRole::Assistant,
+                            _ => return Err(invalid("user, assistant or user,assistant")),
+                        });
+                    }
+                    let both = roles.contains(&MessageRole::User)
+                        && roles.contains(&MessageRole::Assistant);
+                    parsed.role = if both { None } else { roles.first().copied() };
+                }
+                "thinking" => parsed.thinking = Some(flag()?),
+                _ => parsed.tools = Some(flag()?),
+            }
+        }
+        Ok(parsed)
+    }
+}
+
+/// Timeline entries addressed by a URI fragment, using the thread's
+/// 1-based entry numbering.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EntrySelection {
+    /// `#12`
+    Entry(usize),
+    /// `#12-20`, inclusive.
+    Range(usize, usize),
+    /// `#last` or `#last-5`: the final N entries.
+    Last(usize),
+}
+
+impl EntrySelection {
+    /// Keep only the selected entries, with their original numbering.
+    pub fn apply(&self, entries: Vec<ThreadEntry>) -> Result<Vec<ThreadEntry>> {
+        let count = entries.len();
+        let selected = match *self {
+            Self::Entry(index) => entries
+                .into_iter()
+                .filter(|entry| entry.index == index)
+                .collect::<Vec<_>>(),
+            Self::Range(start, end) => entries
+                .into_iter()
+                .filter(|entry| (start..=end).contains(&entry.index))
+                .collect(),
+            Self::Last(last) => entries
+                .into_iter()
+                .skip(count.saturating_sub(last))
+                .collect(),
+        };
+
+        if selected.is_empty() {
+            return Err(XurlError::EntryOutOfRange {
+                selection: self.to_string(),
+                count,
+            });
+        }
+        Ok(selected)
+    }
+}
+
+impl fmt::Display for EntrySelection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Entry(index) => write!(f, "{index}"),
+            Self::Range(start, end) => write!(f, "{start}-{end}"),
+            Self::Last(1) => write!(f, "last"),
+            Self::Last(last) => write!(f, "last-{last}"),
+        }
+    }
+}
+
+impl FromStr for EntrySelection {
+    type Err = XurlError;
+
+    /// Accepts `12`, `12-20`, `last`, `last-5`, and the `msg-12` anchors
+    /// used by HTML output.
+    fn from_str(fragment: &str) -> Result<Self> {
+        let invalid = || {
+            XurlError::InvalidUri(format!(
+                "invalid fragment `#{fragment}`; expected #<n>, #<n>-<m>, #last or #last-<n>"
+            ))
+        };
+        let position = |value: &str| {
+            value
+                .parse::<usize>()
+                .ok()
+                .filter(|value| *value > 0)
+                .ok_or_else(invalid)
+        };
+
+        let target = fragment.strip_prefix("msg-").unwrap_or(fragment);
+        if target == "last" {
+            return Ok(Self::Last(1));
+        }
+        if let Some(last) = target.strip_prefix("last-") {
+            return Ok(Self::Last(position(last)?));
+        }
+        match target.split_once('-') {
+            Some((start, end)) => {
+                let (start, end) = (position(start)?, position(end)?);
+                if start > end {
+                    return Err(invalid());
+                }
+                Ok(Self::Range(start, end))
+            }
+            None => Ok(Self::Entry(position(target)?)),
+        }
+    }
 }
 
 impl ThreadUri {
@@ -32,19 +308,39 @@ impl ThreadUri {
     }
 
     pub fn as_agents_string(&self) -> String {
-        match &self.agent_id {
+        let uri = match &self.agent_id {
             Some(agent_id) => format!(
                 "agents://{}/{}/{}",
                 self.provider, self.session_id, agent_id
             ),
             None => format!("agents://{}/{}", self.provider, self.session_id),
-        }
+        };
+        self.with_suffix(uri)
     }
 
     pub fn as_string(&self) -> String {
-        match &self.agent_id {
+        let uri = match &self.agent_id {
             Some(agent_id) => format!("{}://{}/{}", self.provider, self.session_id, agent_id),
             None => format!("{}://{}", self.provider, self.session_id),
+        };
+        self.with_suffix(uri)
+    }
+
+    /// The same thread without a fragment.
+    pub fn without_selection(&self) -> Self {
+        Self {
+            selection: None,
+            ..self.clone()
+        }
+    }
+
+    fn with_suffix(&self, mut uri: String) -> String {
+        if !self.query.is_empty() {
+            uri = format!("{uri}?{}", self.query);
+        }
+        match self.selection {
+            Some(selection) => format!("{uri}#{selection}"),
+            None => uri,
         }
     }
 }
@@ -53,6 +349,14 @@ impl FromStr for ThreadUri {
     type Err = XurlError;
 
     fn from_str(input: &str) -> Result<Self> {
+        let (input, selection) = match input.split_once('#') {
+            Some((input, fragment)) => (input, Some(fragment.parse::<EntrySelection>()?)),
+            None => (input, None),
+        };
+        let (input, query) = match input.split_once('?') {
+            Some((input, query)) => (input, query.parse::<UriQuery>()?),
+            None => (input, UriQuery::default()),
+        };
         let (scheme, target) = input
             .split_once("://")
             .ok_or_else(|| XurlError::InvalidUri(input.to_string()))?;
@@ -76,8 +380,14 @@ impl FromStr for ThreadUri {
                 .unwrap_or(provider_target),
             ProviderKind::Claude
             | ProviderKind::Gemini
+            | ProviderKind::Qwen
             | ProviderKind::Pi
-            | ProviderKind::Opencode => provider_target,
+            | ProviderKind::Opencode
+            | ProviderKind::Cline
+            | ProviderKind::Roo
+            | ProviderKind::Aider
+            | ProviderKind::Goose
+            | ProviderKind::Cursor => provider_target,
         };
 
         let (id, agent_id) = match provider {
@@ -96,7 +406,15 @@ impl FromStr for ThreadUri {
 
                 (main_id, agent_id)
             }
-            ProviderKind::Amp | ProviderKind::Gemini | ProviderKind::Opencode => {
+            ProviderKind::Amp
+            | ProviderKind::Gemini
+            | ProviderKind::Qwen
+            | ProviderKind::Opencode
+            | ProviderKind::Cline
+            | ProviderKind::Roo
+            | ProviderKind::Aider
+            | ProviderKind::Goose
+            | ProviderKind::Cursor => {
                 if normalized_target.contains('/') {
                     return Err(XurlError::InvalidUri(input.to_string()));
                 }
@@ -111,7 +429,9 @@ impl FromStr for ThreadUri {
             ProviderKind::Codex
             | ProviderKind::Claude
             | ProviderKind::Gemini
+            | ProviderKind::Qwen
             | ProviderKind::Pi
+            | ProviderKind::Cursor
                 if !SESSION_ID_RE.is_match(id) =>
             {
                 return Err(XurlError::InvalidSessionId(id.to_string()));
@@ -119,6 +439,15 @@ impl FromStr for ThreadUri {
             ProviderKind::Opencode if !OPENCODE_SESSION_ID_RE.is_match(id) => {
                 return Err(XurlError::InvalidSessionId(id.to_string()));
             }
+            ProviderKind::Cline | ProviderKind::Roo if !CLINE_TASK_ID_RE.is_match(id) => {
+                return Err(XurlError::InvalidSessionId(id.to_string()));
+            }
+            ProviderKind::Aider if !AIDER_SESSION_ID_RE.is_match(id) => {
+                return Err(XurlError::InvalidSessionId(id.to_string()));
+            }
+            ProviderKind::Goose if !GOOSE_SESSION_ID_RE.is_match(id) => {
+                return Err(XurlError::InvalidSessionId(id.to_string()));
+            }
             _ => {}
         }
 
@@ -127,7 +456,13 @@ impl FromStr for ThreadUri {
             ProviderKind::Codex
             | ProviderKind::Claude
             | ProviderKind::Gemini
-            | ProviderKind::Pi => id.to_ascii_lowercase(),
+            | ProviderKind::Qwen
+            | ProviderKind::Pi
+            | ProviderKind::Cline
+            | ProviderKind::Roo
+            | ProviderKind::Aider
+            | ProviderKind::Cursor => id.to_ascii_lowercase(),
+            ProviderKind::Goose => id.to_string(),
             ProviderKind::Opencode => id.to_string(),
         };
 
@@ -147,26 +482,84 @@ impl FromStr for ThreadUri {
             provider,
             session_id,
             agent_id,
+            query,
+            selection,
         })
     }
 }
 
 fn parse_provider(scheme: &str) -> Result<ProviderKind> {
-    match scheme {
-        "amp" => Ok(ProviderKind::Amp),
-        "codex" => Ok(ProviderKind::Codex),
-        "claude" => Ok(ProviderKind::Claude),
-        "gemini" => Ok(ProviderKind::Gemini),
-        "pi" => Ok(ProviderKind::Pi),
-        "opencode" => Ok(ProviderKind::Opencode),
-        _ => Err(XurlError::UnsupportedScheme(scheme.to_string())),
+    scheme.parse()
+}
+
+/// Decode `%XX` escapes in a URI component. The query is split on `&` and
+/// `=` before its parts are decoded, so escaped separators stay literal.
+pub(crate) fn percent_decode(raw: &str) -> String {
+    let bytes = raw.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+    while index < bytes.len() {
+        let escaped = bytes
+            .get(index + 1..index + 3)
+            .filter(|_| bytes[index] == b'%')
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match escaped {
+            Some(byte) => {
+                decoded.push(byte);
+                index += 3;
+            }
+            None => {
+                decoded.push(bytes[index]);
+                index += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+/// Whether `id` is a well-formed full session id for `provider`.
+pub(crate) fn is_session_id(provider: ProviderKind, id: &str) -> bool {
+    match provider {
+        ProviderKind::Amp => AMP_SESSION_ID_RE.is_match(id),
+        ProviderKind::Codex
+        | ProviderKind::Claude
+        | ProviderKind::Gemini
+        | ProviderKind::Qwen
+        | ProviderKind::Pi
+        | ProviderKind::Cursor => SESSION_ID_RE.is_match(id),
+        ProviderKind::Opencode => OPENCODE_SESSION_ID_RE.is_match(id),
+        ProviderKind::Cline | ProviderKind::Roo => CLINE_TASK_ID_RE.is_match(id),
+        ProviderKind::Aider => AIDER_SESSION_ID_RE.is_match(id),
+        ProviderKind::Goose => GOOSE_SESSION_ID_RE.is_match(id),
+    }
+}
+
+/// Whether `id` could be a short prefix of a session id for `provider`:
+/// at least six id characters after any fixed prefix.
+pub(crate) fn is_session_prefix(provider: ProviderKind, id: &str) -> bool {
+    match provider {
+        ProviderKind::Amp => AMP_SESSION_PREFIX_RE.is_match(id),
+        ProviderKind::Codex
+        | ProviderKind::Claude
+        | ProviderKind::Gemini
+        | ProviderKind::Qwen
+        | ProviderKind::Pi
+        | ProviderKind::Cline
+        | ProviderKind::Roo
+        | ProviderKind::Aider
+        | ProviderKind::Goose
+        | ProviderKind::Cursor => SESSION_PREFIX_RE.is_match(id),
+        ProviderKind::Opencode => OPENCODE_SESSION_PREFIX_RE.is_match(id),
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::ThreadUri;
-    use crate::model::ProviderKind;
+    use super::{EntrySelection, ThreadUri, UriFormat};
+    use crate::model::{
+        MessageRole, ProviderKind, ThreadEntry, ThreadMessage, TimelineEntry, TimelineOptions,
+    };
 
     #[test]
     fn parse_valid_uri() {
@@ -186,6 +579,47 @@ mod tests {
         assert_eq!(uri.agent_id, None);
     }
 
+    #[test]
+    fn parse_cline_and_roo_task_uris() {
+        let uri = ThreadUri::parse("agents://cline/1731234567890").expect("parse should succeed");
+        assert_eq!(uri.provider, ProviderKind::Cline);
+        assert_eq!(uri.session_id, "1731234567890");
+
+        let uri = ThreadUri::parse("roo://0198A1B2-C3D4-7E5F-8A9B-0C1D2E3F4A5B")
+            .expect("parse should succeed");
+        assert_eq!(uri.provider, ProviderKind::Roo);
+        assert_eq!(uri.session_id, "0198a1b2-c3d4-7e5f-8a9b-0c1d2e3f4a5b");
+
+        assert!(ThreadUri::parse("agents://cline/task-1").is_err());
+        assert!(ThreadUri::parse("agents://cline/1731234567890/child").is_err());
+    }
+
+    #[test]
+    fn parse_goose_session_uris() {
+        let uri = ThreadUri::parse("agents://goose/20251016_1").expect("parse should succeed");
+        assert_eq!(uri.provider, ProviderKind::Goose);
+        assert_eq!(uri.session_id, "20251016_1");
+
+        let uri = ThreadUri::parse("goose://20250325_143022").expect("parse should succeed");
+        assert_eq!(uri.session_id, "20250325_143022");
+
+        assert!(ThreadUri::parse("agents://goose/my-session").is_err());
+        assert!(ThreadUri::parse("agents://goose/20251016_1/child").is_err());
+    }
+
+    #[test]
+    fn parse_cursor_composer_uris() {
+        let uri = ThreadUri::parse("agents://cursor/5F0C2A4E-8B1D-4C3A-9E7F-1A2B3C4D5E6F")
+            .expect("parse should succeed");
+        assert_eq!(uri.provider, ProviderKind::Cursor);
+        assert_eq!(uri.session_id, "5f0c2a4e-8b1d-4c3a-9e7f-1a2b3c4d5e6f");
+
+        assert!(ThreadUri::parse("agents://cursor/composer-1").is_err());
+        assert!(
+            ThreadUri::parse("agents://cursor/5f0c2a4e-8b1d-4c3a-9e7f-1a2b3c4d5e6f/child").is_err()
+        );
+    }
+
     #[test]
     fn parse_codex_deeplink_uri() {
         let uri = ThreadUri::parse("codex://threads/019c871c-b1f9-7f60-9c4f-87ed09f13592")
@@ -266,14 +700,14 @@ mod tests {
 
     #[test]
     fn parse_rejects_invalid_scheme() {
-        let err = ThreadUri::parse("cursor://019c871c-b1f9-7f60-9c4f-87ed09f13592")
+        let err = ThreadUri::parse("windsurf://019c871c-b1f9-7f60-9c4f-87ed09f13592")
             .expect_err("must reject unsupported scheme");
         assert!(format!("{err}").contains("unsupported scheme"));
     }
 
     #[test]
     fn parse_rejects_invalid_agents_provider() {
-        let err = ThreadUri::parse("agents://cursor/019c871c-b1f9-7f60-9c4f-87ed09f13592")
+        let err = ThreadUri::parse("agents://windsurf/019c871c-b1f9-7f60-9c4f-87ed09f13592")
             .expect_err("must reject unsupported provider");
         assert!(format!("{err}").contains("unsupported scheme"));
     }
@@ -326,4 +760,131 @@ mod tests {
             .expect_err("must reject nested path");
         assert!(format!("{err}").contains("invalid uri"));
     }
+
+    #[test]
+    fn parse_and_round_trip_fragments() {
+        for (fragment, selection) in [
+            ("12", EntrySelection::Entry(12)),
+            ("12-20", EntrySelection::Range(12, 20)),
+            ("last", EntrySelection::Last(1)),
+            ("last-5", EntrySelection::Last(5)),
+        ] {
+            let input = format!("agents://codex/019c871c-b1f9-7f60-9c4f-87ed09f13592#{fragment}");
+            let uri = ThreadUri::parse(&input).expect("parse should succeed");
+            assert_eq!(uri.selection, Some(selection));
+            assert_eq!(uri.as_agents_string(), input);
+        }
+
+        let uri = ThreadUri::parse("codex://019C871C-B1F9-7F60-9C4F-87ED09F13592#msg-3")
+            .expect("parse should succeed");
+        assert_eq!(
+            uri.as_agents_string(),
+            "agents://codex/019c871c-b1f9-7f60-9c4f-87ed09f13592#3"
+        );
+        assert_eq!(uri.without_selection().selection, None);
+    }
+
+    #[test]
+    fn parse_rejects_malformed_fragments() {
+        for fragment in ["", "0", "20-12", "last-0", "first", "1-", "-3"] {
+            let err = ThreadUri::parse(&format!(
+                "agents://codex/019c871c-b1f9-7f60-9c4f-87ed09f13592#{fragment}"
+            ))
+            .expect_err("must reject fragment");
+            assert!(format!("{err}").contains("invalid fragment"), "{fragment}");
+        }
+    }
+
+    #[test]
+    fn selection_keeps_original_numbering() {
+        let entries = (1..=6)
+            .map(|index| ThreadEntry {
+                index,
+                entry: TimelineEntry::Message(ThreadMessage::new(MessageRole::User, "hi")),
+            })
+            .collect::<Vec<_>>();
+        let indexes = |selection: EntrySelection| {
+            selection
+                .apply(entries.clone())
+                .expect("selection")
+                .iter()
+                .map(|entry| entry.index)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(indexes(EntrySelection::Entry(4)), vec![4]);
+        assert_eq!(indexes(EntrySelection::Range(5, 9)), vec![5, 6]);
+        assert_eq!(indexes(EntrySelection::Last(2)), vec![5, 6]);
+        assert_eq!(indexes(EntrySelection::Last(10)).len(), 6);
+        let err = EntrySelection::Entry(7)
+            .apply(entries.clone())
+            .expect_err("out of range");
+        assert!(format!("{err}").contains("the thread has 6"));
+    }
+
+    #[test]
+    fn parse_query_and_print_it_canonically() {
+        let uri = ThreadUri::parse(
+            "codex://019c871c-b1f9-7f60-9c4f-87ed09f13592?tools=true&thinking=0&roles=user&format=json#last-2",
+        )
+        .expect("parse should succeed");
+        assert_eq!(uri.query.format, Some(UriFormat::Json));
+        assert_eq!(uri.query.role, Some(MessageRole::User));
+        assert_eq!(uri.query.tools, Some(true));
+        assert_eq!(uri.selection, Some(EntrySelection::Last(2)));
+        assert_eq!(
+            uri.as_agents_string(),
+            "agents://codex/019c871c-b1f9-7f60-9c4f-87ed09f13592?format=json&roles=user&thinking=0&tools=1#last-2"
+        );
+
+        let options = uri.query.timeline_options(TimelineOptions::default());
+        assert!(options.tools && !options.thinking);
+        assert_eq!(options.role, Some(MessageRole::User));
+
+        let both = ThreadUri::parse(
+            "agents://codex/019c871c-b1f9-7f60-9c4f-87ed09f13592?roles=user,assistant",
+        )
+        .expect("parse should succeed");
+        assert!(both.query.is_empty());
+        assert_eq!(
+            both.as_agents_string(),
+            "agents://codex/019c871c-b1f9-7f60-9c4f-87ed09f13592"
+        );
+
+        let escaped = ThreadUri::parse(
+            "agents://codex/019c871c-b1f9-7f60-9c4f-87ed09f13592?roles=user%2Cassistant&t%6Fols=1",
+        )
+        .expect("parse should succeed");
+        assert_eq!(escaped.query.role, None);
+        assert_eq!(escaped.query.tools, Some(true));
+        let err = ThreadUri::parse(
+            "agents://codex/019c871c-b1f9-7f60-9c4f-87ed09f13592?roles=user%26tools%3D1",
+        )
+        .expect_err("escaped separators stay in the value");
+        assert!(format!("{err}").contains("invalid value `user&tools=1`"));
+    }
+
+    #[test]
+    fn parse_rejects_bad_query_parameters() {
+        for (query, message) in [
+            (
+                "verbose=1",
+                "unknown query parameter `verbose`; supported: format, head",
+            ),
+            ("tools=1&tools=0", "`tools` is given more than once"),
+            (
+                "tools=yes",
+                "invalid value `yes` for query parameter `tools`",
+            ),
+            ("roles=system", "expected user, assistant or user,assistant"),
+            ("format=xml", "expected markdown, json, jsonl or html"),
+            ("max_tool_output=-1", "expected a character count"),
+        ] {
+            let err = ThreadUri::parse(&format!(
+                "agents://codex/019c871c-b1f9-7f60-9c4f-87ed09f13592?{query}"
+            ))
+            .expect_err("must reject query");
+            assert!(format!("{err}").contains(message), "{query}: {err}");
+        }
+    }
 }